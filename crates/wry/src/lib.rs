@@ -2320,6 +2320,21 @@ pub fn get_last_composition_controller_ptr() -> isize {
   LAST_COMP_CONTROLLER_PTR.load(std::sync::atomic::Ordering::SeqCst)
 }
 
+/// Host callback for the WebView2 `ProcessFailed` event, see [`set_process_failed_handler`].
+#[cfg(target_os = "windows")]
+static PROCESS_FAILED_HANDLER: std::sync::OnceLock<fn(i32)> = std::sync::OnceLock::new();
+
+/// Register a callback invoked on the WebView2 UI thread whenever a WebView2
+/// process (browser, renderer, GPU, ...) fails. The argument is the raw
+/// `COREWEBVIEW2_PROCESS_FAILED_KIND`. Only the first registration is kept.
+///
+/// When the browser process exits the composition controller is dead, so
+/// [`get_last_composition_controller_ptr`] is reset to 0 before the callback runs.
+#[cfg(target_os = "windows")]
+pub fn set_process_failed_handler(handler: fn(i32)) {
+  let _ = PROCESS_FAILED_HANDLER.set(handler);
+}
+
 /// Send a mouse input event via the WebView2 composition controller.
 ///
 /// This is a free function that takes a raw COM pointer, allowing it to be called
@@ -2419,6 +2434,84 @@ pub unsafe fn set_controller_bounds_raw(
   Ok(())
 }
 
+/// Show or hide the WebView2 controller via a raw composition controller pointer.
+///
+/// A hidden controller stops rendering and lets WebView2 throttle the page, which is
+/// used while a native video covers the whole webview.
+///
+/// # Safety
+/// `comp_ptr` must be a valid `ICoreWebView2CompositionController` COM pointer and
+/// this must be called on the thread that created the webview.
+#[cfg(target_os = "windows")]
+pub unsafe fn set_controller_visible_raw(
+  comp_ptr: isize,
+  visible: bool,
+) -> std::result::Result<(), String> {
+  use webview2_com::Microsoft::Web::WebView2::Win32::*;
+  use windows::core::Interface;
+
+  if comp_ptr == 0 {
+    return Err("Null composition controller".to_string());
+  }
+
+  let comp = std::mem::ManuallyDrop::new(
+    ICoreWebView2CompositionController::from_raw(comp_ptr as *mut std::ffi::c_void)
+  );
+  let controller: ICoreWebView2Controller =
+    comp.cast().map_err(|e| format!("QI for ICoreWebView2Controller failed: {}", e))?;
+  controller
+    .SetIsVisible(visible)
+    .map_err(|e| format!("SetIsVisible failed: {}", e))
+}
+
+/// Clear profile browsing data through the WebView2 composition controller.
+///
+/// `kinds` is a raw `COREWEBVIEW2_BROWSING_DATA_KINDS` bit set. `done` runs on the
+/// WebView2 UI thread once the data is cleared.
+///
+/// # Safety
+/// `comp_ptr` must be a valid `ICoreWebView2CompositionController` COM pointer and
+/// this must be called on the thread that created the webview.
+#[cfg(target_os = "windows")]
+pub unsafe fn clear_browsing_data_raw(
+  comp_ptr: isize,
+  kinds: i32,
+  done: Box<dyn FnOnce(std::result::Result<(), String>) + Send>,
+) -> std::result::Result<(), String> {
+  use webview2_com::Microsoft::Web::WebView2::Win32::*;
+  use webview2_com::ClearBrowsingDataCompletedHandler;
+  use windows::core::Interface;
+
+  if comp_ptr == 0 {
+    return Err("Null composition controller".to_string());
+  }
+
+  let comp = std::mem::ManuallyDrop::new(
+    ICoreWebView2CompositionController::from_raw(comp_ptr as *mut std::ffi::c_void)
+  );
+  let controller: ICoreWebView2Controller =
+    comp.cast().map_err(|e| format!("QI for ICoreWebView2Controller failed: {}", e))?;
+  let profile = controller
+    .CoreWebView2()
+    .and_then(|webview| webview.cast::<ICoreWebView2_13>())
+    .and_then(|webview| webview.Profile())
+    .and_then(|profile| profile.cast::<ICoreWebView2Profile2>())
+    .map_err(|e| format!("Profile unavailable: {}", e))?;
+
+  let mut done = Some(done);
+  profile
+    .ClearBrowsingData(
+      COREWEBVIEW2_BROWSING_DATA_KINDS(kinds),
+      &ClearBrowsingDataCompletedHandler::create(Box::new(move |result| {
+        if let Some(done) = done.take() {
+          done(result.map_err(|e| e.to_string()));
+        }
+        Ok(())
+      })),
+    )
+    .map_err(|e| format!("ClearBrowsingData failed: {}", e))
+}
+
 /// Additional methods on `WebView` that are specific to Linux.
 #[cfg(gtk)]
 pub trait WebViewExtUnix: Sized {
@@ -735,6 +735,26 @@ impl InnerWebView {
       token,
     )?;
 
+    // Report browser/renderer/GPU process failures to the host
+    webview.add_ProcessFailed(
+      &ProcessFailedEventHandler::create(Box::new(move |_, args| {
+        let Some(args) = args else {
+          return Ok(());
+        };
+
+        let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND::default();
+        args.ProcessFailedKind(&mut kind)?;
+        if kind == COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED {
+          crate::LAST_COMP_CONTROLLER_PTR.store(0, std::sync::atomic::Ordering::SeqCst);
+        }
+        if let Some(handler) = crate::PROCESS_FAILED_HANDLER.get() {
+          handler(kind.0);
+        }
+        Ok(())
+      })),
+      token,
+    )?;
+
     // Document title changed handler
     if let Some(document_title_changed_handler) = attributes.document_title_changed_handler.take() {
       webview.add_DocumentTitleChanged(
@@ -0,0 +1,138 @@
+//! OS accessibility preferences: reduced motion, high contrast and disabled
+//! transparency.
+//!
+//! Polled every few seconds and emitted as `accessibility-prefs-changed`, so
+//! scenes can tone themselves down. With `pause_on_reduce_motion` the backend
+//! also holds an automatic pause (see `desktop_actions::auto_pause`) while
+//! reduced motion is requested.
+
+use log::{error, info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use typeshare::typeshare;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const PAUSE_SOURCE: &str = "reduce motion";
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static PAUSE_ON_REDUCE_MOTION: AtomicBool = AtomicBool::new(false);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityPrefs {
+    /// Animations are turned off in the OS ("Animation effects")
+    pub reduce_motion: bool,
+    /// A high contrast theme is active
+    pub high_contrast: bool,
+    /// Transparency effects are turned off
+    pub transparency_disabled: bool,
+}
+
+/// Current preferences; all false where they cannot be determined.
+pub fn get() -> AccessibilityPrefs {
+    query().unwrap_or_default()
+}
+
+/// Pause the wallpaper while reduced motion is requested (`true`).
+pub fn configure(pause_on_reduce_motion: bool) {
+    let changed = PAUSE_ON_REDUCE_MOTION.swap(pause_on_reduce_motion, Ordering::SeqCst)
+        != pause_on_reduce_motion;
+    // Before `start` the watcher applies the policy itself
+    if changed && RUNNING.load(Ordering::SeqCst) {
+        apply_policy(get());
+    }
+}
+
+fn apply_policy(prefs: AccessibilityPrefs) {
+    let hold = prefs.reduce_motion && PAUSE_ON_REDUCE_MOTION.load(Ordering::SeqCst);
+    if let Err(e) = crate::desktop_actions::auto_pause(PAUSE_SOURCE, hold) {
+        warn!("[accessibility] Cannot update pause: {}", e);
+    }
+}
+
+/// Start the `accessibility-prefs-changed` watcher.
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut last = get();
+        apply_policy(last);
+        while RUNNING.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = get();
+            if current == last {
+                continue;
+            }
+            info!(
+                "[accessibility] Reduce motion {}, high contrast {}, transparency {}",
+                current.reduce_motion, current.high_contrast, !current.transparency_disabled
+            );
+            apply_policy(current);
+            if let Err(e) = app.emit_app_event(&AppEvent::AccessibilityPrefsChanged(current)) {
+                error!("[accessibility] Failed to emit event: {}", e);
+            }
+            last = current;
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+fn query() -> Option<AccessibilityPrefs> {
+    use windows::core::w;
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+    use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST,
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    let mut animations = BOOL(1);
+    let mut contrast = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        ..Default::default()
+    };
+    let mut transparency: u32 = 1;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut animations as *mut BOOL as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .ok()?;
+        SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            contrast.cbSize,
+            Some(&mut contrast as *mut HIGHCONTRASTW as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .ok()?;
+        // Missing on older builds: transparency is on
+        let _ = RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+            w!("EnableTransparency"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut transparency as *mut u32 as *mut _),
+            Some(&mut size),
+        );
+    }
+    Some(AccessibilityPrefs {
+        reduce_motion: !animations.as_bool(),
+        high_contrast: contrast.dwFlags.contains(HCF_HIGHCONTRASTON),
+        transparency_disabled: transparency == 0,
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn query() -> Option<AccessibilityPrefs> {
+    None
+}
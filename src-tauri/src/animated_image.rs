@@ -0,0 +1,695 @@
+//! Native animated image wallpaper — GIF / APNG / animated WebP without Chromium.
+//!
+//! Frames are decoded lazily with the `image` crate on a background thread,
+//! at most `PREFETCH_FRAMES` ahead of playback, and the file is decoded again
+//! from the start on every loop. Memory stays at a few frames whatever the
+//! animation's length. Each frame is uploaded into a single Direct2D bitmap
+//! and drawn, scaled by the GPU, into a flip-model DXGI swap chain on a child
+//! window of the WorkerW layer.
+
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use typeshare::typeshare;
+
+/// Largest frame side; every Direct3D 10+ GPU supports textures this big.
+const MAX_FRAME_DIMENSION: u32 = 8192;
+/// Frames decoded ahead of the one on screen
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const PREFETCH_FRAMES: usize = 2;
+/// Browsers clamp GIF delays below 20ms to 100ms; match that behaviour.
+const MIN_FRAME_DELAY_MS: u32 = 20;
+const DEFAULT_FRAME_DELAY_MS: u32 = 100;
+
+/// FPS cap set by the active profile, on top of the per-image cap (0 = none)
+static FPS_CAP: AtomicU32 = AtomicU32::new(0);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageFit {
+    /// Fill the target, cropping the overflow
+    #[default]
+    Cover,
+    /// Fit entirely inside the target, letterboxed
+    Contain,
+    /// Fill the target ignoring aspect ratio
+    Stretch,
+    /// Native size, centered
+    Center,
+}
+
+/// One decoded frame in top-down BGRA order.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+struct Frame {
+    bgra: Vec<u8>,
+    width: u32,
+    height: u32,
+    /// `u32::MAX` for still images
+    delay_ms: u32,
+}
+
+/// Frames of one pass over the file, decoded as they are pulled.
+type Frames = Box<dyn Iterator<Item = AppResult<Frame>>>;
+
+fn bgra_frame(mut buf: image::RgbaImage, delay_ms: u32) -> AppResult<Frame> {
+    if buf.width() > MAX_FRAME_DIMENSION || buf.height() > MAX_FRAME_DIMENSION {
+        return Err(AppError::Validation(format!(
+            "Images larger than {}x{} cannot be rendered natively",
+            MAX_FRAME_DIMENSION, MAX_FRAME_DIMENSION
+        )));
+    }
+    // RGBA → BGRA in place
+    for px in buf.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+    Ok(Frame {
+        width: buf.width(),
+        height: buf.height(),
+        bgra: buf.into_raw(),
+        delay_ms,
+    })
+}
+
+/// Minimum frame delay for an FPS cap (0 = uncapped).
+fn cap_delay_ms(fps_cap: u32) -> u32 {
+    1000u32.checked_div(fps_cap).map_or(0, |d| d.max(1))
+}
+
+/// Cap every native animation at `cap` frames per second (0 = uncapped).
+/// Running renderers pick it up from their next frame.
+pub fn set_fps_cap(cap: u32) {
+    let cap = crate::system_monitor::refresh_rate_for(None)
+        .map_or(cap, |hz| crate::system_monitor::snap_fps_cap(cap, hz));
+    FPS_CAP.store(cap, Ordering::SeqCst);
+    log::info!("[animated_image] Global fps cap {}", cap);
+}
+
+fn animated(frames: image::Frames<'static>, fps_cap: Option<u32>) -> Frames {
+    let min_delay = cap_delay_ms(fps_cap.unwrap_or(0));
+    Box::new(frames.map(move |frame| {
+        let min_delay = min_delay.max(cap_delay_ms(FPS_CAP.load(Ordering::SeqCst)));
+        let frame =
+            frame.map_err(|e| AppError::Validation(format!("Cannot decode frame: {}", e)))?;
+        let (num, den) = frame.delay().numer_denom_ms();
+        let raw_delay = num.checked_div(den).unwrap_or(0);
+        let delay_ms = if raw_delay < MIN_FRAME_DELAY_MS {
+            DEFAULT_FRAME_DELAY_MS
+        } else {
+            raw_delay
+        }
+        .max(min_delay);
+        bgra_frame(frame.into_buffer(), delay_ms)
+    }))
+}
+
+fn still(path: &Path) -> AppResult<Frames> {
+    let img = image::open(path)
+        .map_err(|e| AppError::Validation(format!("Cannot decode image: {}", e)))?;
+    let frame = bgra_frame(img.into_rgba8(), u32::MAX)?;
+    Ok(Box::new(std::iter::once(Ok(frame))))
+}
+
+/// Start decoding a GIF, APNG or WebP (static images yield one frame).
+fn open_frames(path: &Path, fps_cap: Option<u32>) -> AppResult<Frames> {
+    use image::codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder};
+    use image::AnimationDecoder;
+    use std::io::BufReader;
+
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let reader = || -> AppResult<BufReader<std::fs::File>> {
+        Ok(BufReader::new(std::fs::File::open(path)?))
+    };
+    let invalid =
+        |e: image::ImageError| AppError::Validation(format!("Cannot decode image: {}", e));
+
+    match ext.as_str() {
+        "gif" => Ok(animated(
+            GifDecoder::new(reader()?).map_err(invalid)?.into_frames(),
+            fps_cap,
+        )),
+        "png" | "apng" => {
+            let dec = PngDecoder::new(reader()?).map_err(invalid)?;
+            if dec.is_apng().map_err(invalid)? {
+                Ok(animated(
+                    dec.apng().map_err(invalid)?.into_frames(),
+                    fps_cap,
+                ))
+            } else {
+                still(path)
+            }
+        }
+        "webp" => {
+            let dec = WebPDecoder::new(reader()?).map_err(invalid)?;
+            if dec.has_animation() {
+                Ok(animated(dec.into_frames(), fps_cap))
+            } else {
+                still(path)
+            }
+        }
+        _ => Err(AppError::Validation(
+            "Unsupported animated image format (use GIF, APNG or WebP)".into(),
+        )),
+    }
+}
+
+/// Decode `path` over and over into `tx` until the receiver is dropped. The
+/// bounded channel keeps decoding `PREFETCH_FRAMES` ahead of playback.
+/// Errors are sent once and end decoding.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn decode_loop(
+    path: std::path::PathBuf,
+    fps_cap: Option<u32>,
+    tx: std::sync::mpsc::SyncSender<AppResult<Frame>>,
+) {
+    loop {
+        let frames = match open_frames(&path, fps_cap) {
+            Ok(frames) => frames,
+            Err(e) => {
+                let _ = tx.send(Err(e));
+                return;
+            }
+        };
+        let mut count = 0usize;
+        for frame in frames {
+            let failed = frame.is_err();
+            if tx.send(frame).is_err() || failed {
+                return;
+            }
+            count += 1;
+        }
+        // A still image (or single-frame animation) has nothing to loop
+        if count <= 1 {
+            return;
+        }
+    }
+}
+
+/// Destination rectangle `(x, y, w, h)` for an image inside a `cw`×`ch` client area.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn fit_rect(fit: ImageFit, cw: i32, ch: i32, iw: i32, ih: i32) -> (i32, i32, i32, i32) {
+    if iw <= 0 || ih <= 0 {
+        return (0, 0, cw, ch);
+    }
+    let scale = |s: f64| {
+        let w = (iw as f64 * s).round() as i32;
+        let h = (ih as f64 * s).round() as i32;
+        ((cw - w) / 2, (ch - h) / 2, w, h)
+    };
+    let sx = cw as f64 / iw as f64;
+    let sy = ch as f64 / ih as f64;
+    match fit {
+        ImageFit::Stretch => (0, 0, cw, ch),
+        ImageFit::Cover => scale(sx.max(sy)),
+        ImageFit::Contain => scale(sx.min(sy)),
+        ImageFit::Center => scale(1.0),
+    }
+}
+
+// ============================================================================
+// Windows: Direct2D renderer window
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+mod host {
+    use super::{fit_rect, Frame, ImageFit};
+    use crate::error::{AppError, AppResult};
+    use log::warn;
+    use std::cell::RefCell;
+    use std::mem::ManuallyDrop;
+    use std::path::PathBuf;
+    use std::sync::mpsc::{Receiver, TryRecvError};
+    use std::sync::Mutex;
+    use windows::core::Interface;
+    use windows::Win32::Foundation::{HMODULE, HWND, LPARAM, LRESULT, RECT, WPARAM};
+    use windows::Win32::Graphics::Direct2D::Common::{
+        D2D1_ALPHA_MODE_IGNORE, D2D1_COLOR_F, D2D1_PIXEL_FORMAT, D2D_RECT_F, D2D_SIZE_U,
+    };
+    use windows::Win32::Graphics::Direct2D::{
+        D2D1CreateFactory, ID2D1Bitmap1, ID2D1DeviceContext, ID2D1Factory1, D2D1_BITMAP_OPTIONS,
+        D2D1_BITMAP_OPTIONS_CANNOT_DRAW, D2D1_BITMAP_OPTIONS_NONE, D2D1_BITMAP_OPTIONS_TARGET,
+        D2D1_BITMAP_PROPERTIES1, D2D1_DEVICE_CONTEXT_OPTIONS_NONE,
+        D2D1_FACTORY_TYPE_SINGLE_THREADED, D2D1_INTERPOLATION_MODE_LINEAR,
+    };
+    use windows::Win32::Graphics::Direct3D::{
+        D3D_DRIVER_TYPE_HARDWARE, D3D_FEATURE_LEVEL_10_0, D3D_FEATURE_LEVEL_10_1,
+        D3D_FEATURE_LEVEL_11_0,
+    };
+    use windows::Win32::Graphics::Direct3D11::{
+        D3D11CreateDevice, ID3D11Device, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_SDK_VERSION,
+    };
+    use windows::Win32::Graphics::Dxgi::Common::{
+        DXGI_ALPHA_MODE_IGNORE, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_UNKNOWN, DXGI_SAMPLE_DESC,
+    };
+    use windows::Win32::Graphics::Dxgi::{
+        IDXGIDevice, IDXGIFactory2, IDXGISurface, IDXGISwapChain1, DXGI_PRESENT,
+        DXGI_SCALING_STRETCH, DXGI_SWAP_CHAIN_DESC1, DXGI_SWAP_CHAIN_FLAG,
+        DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL, DXGI_USAGE_RENDER_TARGET_OUTPUT,
+    };
+    use windows::Win32::Graphics::Gdi::{BeginPaint, EndPaint, PAINTSTRUCT};
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    const FRAME_TIMER_ID: usize = 1;
+    /// Retry delay when the decoder has not caught up with playback
+    const DECODE_WAIT_MS: u32 = 5;
+
+    fn bitmap_properties(options: D2D1_BITMAP_OPTIONS) -> D2D1_BITMAP_PROPERTIES1 {
+        D2D1_BITMAP_PROPERTIES1 {
+            pixelFormat: D2D1_PIXEL_FORMAT {
+                format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                alphaMode: D2D1_ALPHA_MODE_IGNORE,
+            },
+            // 96 DPI: one DIP per physical pixel
+            dpiX: 96.0,
+            dpiY: 96.0,
+            bitmapOptions: options,
+            colorContext: ManuallyDrop::new(None),
+        }
+    }
+
+    /// Device resources; dropped and rebuilt when the GPU device is lost.
+    struct Gpu {
+        context: ID2D1DeviceContext,
+        swap_chain: IDXGISwapChain1,
+        /// Swap chain buffer size
+        size: (u32, u32),
+        /// The frame on screen, at its native size
+        bitmap: ID2D1Bitmap1,
+        bitmap_size: (u32, u32),
+    }
+
+    impl Gpu {
+        unsafe fn new(hwnd: HWND, frame: &Frame) -> windows::core::Result<Self> {
+            let mut device: Option<ID3D11Device> = None;
+            D3D11CreateDevice(
+                None,
+                D3D_DRIVER_TYPE_HARDWARE,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                Some(&[
+                    D3D_FEATURE_LEVEL_11_0,
+                    D3D_FEATURE_LEVEL_10_1,
+                    D3D_FEATURE_LEVEL_10_0,
+                ]),
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                None,
+            )?;
+            let device = device.ok_or_else(windows::core::Error::empty)?;
+            let dxgi_device: IDXGIDevice = device.cast()?;
+
+            let factory: ID2D1Factory1 =
+                D2D1CreateFactory(D2D1_FACTORY_TYPE_SINGLE_THREADED, None)?;
+            let context = factory
+                .CreateDevice(&dxgi_device)?
+                .CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)?;
+
+            let size = client_size(hwnd);
+            let dxgi_factory: IDXGIFactory2 = dxgi_device.GetAdapter()?.GetParent()?;
+            let swap_chain = dxgi_factory.CreateSwapChainForHwnd(
+                &device,
+                hwnd,
+                &DXGI_SWAP_CHAIN_DESC1 {
+                    Width: size.0,
+                    Height: size.1,
+                    Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                    SampleDesc: DXGI_SAMPLE_DESC {
+                        Count: 1,
+                        Quality: 0,
+                    },
+                    BufferUsage: DXGI_USAGE_RENDER_TARGET_OUTPUT,
+                    BufferCount: 2,
+                    Scaling: DXGI_SCALING_STRETCH,
+                    SwapEffect: DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL,
+                    AlphaMode: DXGI_ALPHA_MODE_IGNORE,
+                    ..Default::default()
+                },
+                None,
+                None,
+            )?;
+
+            let bitmap = create_bitmap(&context, frame)?;
+            Ok(Self {
+                context,
+                swap_chain,
+                size,
+                bitmap,
+                bitmap_size: (frame.width, frame.height),
+            })
+        }
+
+        unsafe fn upload(&mut self, frame: &Frame) -> windows::core::Result<()> {
+            if self.bitmap_size != (frame.width, frame.height) {
+                self.bitmap = create_bitmap(&self.context, frame)?;
+                self.bitmap_size = (frame.width, frame.height);
+                return Ok(());
+            }
+            self.bitmap
+                .CopyFromMemory(None, frame.bgra.as_ptr().cast(), frame.width * 4)
+        }
+
+        unsafe fn render(&mut self, hwnd: HWND, fit: ImageFit) -> windows::core::Result<()> {
+            let size = client_size(hwnd);
+            if size != self.size {
+                self.swap_chain.ResizeBuffers(
+                    0,
+                    size.0,
+                    size.1,
+                    DXGI_FORMAT_UNKNOWN,
+                    DXGI_SWAP_CHAIN_FLAG(0),
+                )?;
+                self.size = size;
+            }
+            let surface: IDXGISurface = self.swap_chain.GetBuffer(0)?;
+            let target = self.context.CreateBitmapFromDxgiSurface(
+                &surface,
+                Some(&bitmap_properties(
+                    D2D1_BITMAP_OPTIONS_TARGET | D2D1_BITMAP_OPTIONS_CANNOT_DRAW,
+                )),
+            )?;
+            self.context.SetTarget(&target);
+            self.context.BeginDraw();
+            self.context.Clear(Some(&D2D1_COLOR_F {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            }));
+            let (iw, ih) = (self.bitmap_size.0 as i32, self.bitmap_size.1 as i32);
+            let (x, y, w, h) = fit_rect(fit, size.0 as i32, size.1 as i32, iw, ih);
+            self.context.DrawBitmap(
+                &self.bitmap,
+                Some(&D2D_RECT_F {
+                    left: x as f32,
+                    top: y as f32,
+                    right: (x + w) as f32,
+                    bottom: (y + h) as f32,
+                }),
+                1.0,
+                D2D1_INTERPOLATION_MODE_LINEAR,
+                None,
+                None,
+            );
+            let drawn = self.context.EndDraw(None, None);
+            // The swap chain cannot resize while its buffer is the target
+            self.context.SetTarget(None);
+            drawn?;
+            self.swap_chain.Present(1, DXGI_PRESENT(0)).ok()
+        }
+    }
+
+    unsafe fn create_bitmap(
+        context: &ID2D1DeviceContext,
+        frame: &Frame,
+    ) -> windows::core::Result<ID2D1Bitmap1> {
+        context.CreateBitmap(
+            D2D_SIZE_U {
+                width: frame.width,
+                height: frame.height,
+            },
+            Some(frame.bgra.as_ptr().cast()),
+            frame.width * 4,
+            &bitmap_properties(D2D1_BITMAP_OPTIONS_NONE),
+        )
+    }
+
+    unsafe fn client_size(hwnd: HWND) -> (u32, u32) {
+        let mut client = RECT::default();
+        let _ = GetClientRect(hwnd, &mut client);
+        (client.right.max(1) as u32, client.bottom.max(1) as u32)
+    }
+
+    struct Renderer {
+        frames: Receiver<AppResult<Frame>>,
+        current: Frame,
+        fit: ImageFit,
+        gpu: Option<Gpu>,
+    }
+
+    impl Renderer {
+        unsafe fn draw(&mut self, hwnd: HWND) {
+            if self.gpu.is_none() {
+                match Gpu::new(hwnd, &self.current) {
+                    Ok(gpu) => self.gpu = Some(gpu),
+                    Err(e) => {
+                        warn!("[animated_image] GPU setup failed: {}", e);
+                        return;
+                    }
+                }
+            }
+            if let Some(gpu) = self.gpu.as_mut() {
+                if let Err(e) = gpu.render(hwnd, self.fit) {
+                    // Device lost or removed: rebuilt on the next frame
+                    warn!("[animated_image] Render failed: {}", e);
+                    self.gpu = None;
+                }
+            }
+        }
+
+        unsafe fn show(&mut self, hwnd: HWND, frame: Frame) {
+            self.current = frame;
+            if let Some(gpu) = self.gpu.as_mut() {
+                if gpu.upload(&self.current).is_err() {
+                    self.gpu = None;
+                }
+            }
+            self.draw(hwnd);
+        }
+    }
+
+    thread_local! {
+        static RENDERER: RefCell<Option<Renderer>> = const { RefCell::new(None) };
+    }
+
+    /// (monitor, host HWND) of each running renderer.
+    pub static RENDERERS: Mutex<Vec<(Option<u32>, isize)>> = Mutex::new(Vec::new());
+
+    unsafe fn schedule(hwnd: HWND, delay_ms: u32) {
+        // Still images never advance
+        if delay_ms != u32::MAX {
+            SetTimer(hwnd, FRAME_TIMER_ID, delay_ms, None);
+        }
+    }
+
+    unsafe extern "system" fn image_wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wp: WPARAM,
+        lp: LPARAM,
+    ) -> LRESULT {
+        match msg {
+            WM_NCHITTEST => LRESULT(HTTRANSPARENT as isize),
+            // Everything is drawn through the swap chain
+            WM_ERASEBKGND => LRESULT(1),
+            WM_PAINT => {
+                let mut ps = PAINTSTRUCT::default();
+                BeginPaint(hwnd, &mut ps);
+                let _ = EndPaint(hwnd, &ps);
+                RENDERER.with(|r| {
+                    if let Some(r) = r.borrow_mut().as_mut() {
+                        r.draw(hwnd);
+                    }
+                });
+                LRESULT(0)
+            }
+            WM_TIMER if wp.0 == FRAME_TIMER_ID => {
+                let next = RENDERER.with(|r| {
+                    let mut r = r.borrow_mut();
+                    let r = r.as_mut()?;
+                    match r.frames.try_recv() {
+                        Ok(Ok(frame)) => {
+                            let delay = frame.delay_ms;
+                            r.show(hwnd, frame);
+                            Some(delay)
+                        }
+                        Err(TryRecvError::Empty) => Some(DECODE_WAIT_MS),
+                        Ok(Err(e)) => {
+                            warn!("[animated_image] Decoding stopped: {}", e);
+                            None
+                        }
+                        Err(TryRecvError::Disconnected) => None,
+                    }
+                });
+                match next {
+                    Some(delay) => schedule(hwnd, delay),
+                    None => {
+                        let _ = KillTimer(hwnd, FRAME_TIMER_ID);
+                    }
+                }
+                LRESULT(0)
+            }
+            WM_CLOSE => {
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_DESTROY => {
+                // Dropping the receiver stops the decoder thread
+                RENDERER.with(|r| r.borrow_mut().take());
+                PostQuitMessage(0);
+                LRESULT(0)
+            }
+            _ => DefWindowProcW(hwnd, msg, wp, lp),
+        }
+    }
+
+    pub fn spawn(
+        path: PathBuf,
+        fps_cap: Option<u32>,
+        fit: ImageFit,
+        rect: RECT,
+    ) -> AppResult<isize> {
+        let parent = crate::window_layer::mouse_hook::get_target_parent_hwnd();
+        if parent == 0 {
+            return Err(AppError::WindowLayer("Desktop layer not ready".into()));
+        }
+        let (frame_tx, frames) = std::sync::mpsc::sync_channel(super::PREFETCH_FRAMES);
+        std::thread::spawn(move || super::decode_loop(path, fps_cap, frame_tx));
+        // The first frame validates the file before anything is shown
+        let first = frames
+            .recv()
+            .map_err(|_| AppError::Validation("Image has no frames".into()))??;
+
+        let (tx, rx) = std::sync::mpsc::channel::<AppResult<isize>>();
+        std::thread::spawn(move || unsafe {
+            let cls = windows::core::w!("MWP_AnimatedImage");
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(image_wnd_proc),
+                lpszClassName: cls,
+                ..Default::default()
+            };
+            let _ = RegisterClassW(&wc);
+
+            let hwnd = match CreateWindowExW(
+                WS_EX_NOACTIVATE,
+                cls,
+                windows::core::w!(""),
+                WS_CHILD | WS_VISIBLE | WS_CLIPSIBLINGS,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                HWND(parent as *mut _),
+                None,
+                None,
+                None,
+            ) {
+                Ok(h) => h,
+                Err(e) => {
+                    let _ = tx.send(Err(AppError::WindowLayer(format!(
+                        "CreateWindow failed: {}",
+                        e
+                    ))));
+                    return;
+                }
+            };
+            // Above the WebView, like a replacing native video
+            let _ = SetWindowPos(
+                hwnd,
+                HWND_TOP,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            );
+
+            let first_delay = first.delay_ms;
+            RENDERER.with(|r| {
+                let mut r = r.borrow_mut();
+                let renderer = r.insert(Renderer {
+                    frames,
+                    current: first,
+                    fit,
+                    gpu: None,
+                });
+                renderer.draw(hwnd);
+            });
+            schedule(hwnd, first_delay);
+            let _ = tx.send(Ok(hwnd.0 as isize));
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).into() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+
+        rx.recv()
+            .map_err(|_| AppError::WindowLayer("Renderer thread exited".into()))?
+    }
+
+    pub fn close(hwnd: isize) {
+        unsafe {
+            let _ = PostMessageW(HWND(hwnd as *mut _), WM_CLOSE, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Render an animated image natively on one monitor (None = whole virtual desktop).
+#[cfg(target_os = "windows")]
+pub fn apply(path: &str, fit: ImageFit, fps: Option<u32>, monitor: Option<u32>) -> AppResult<()> {
+    let file = Path::new(path)
+        .canonicalize()
+        .map_err(|_| AppError::Validation(format!("Image not found: {}", path)))?;
+    let fps = fps.map(|cap| {
+        crate::system_monitor::refresh_rate_for(monitor)
+            .map_or(cap, |hz| crate::system_monitor::snap_fps_cap(cap, hz))
+    });
+    let rect = crate::window_layer::layer_rect(monitor)?;
+    let hwnd = host::spawn(file.clone(), fps, fit, rect)?;
+    // Replaced only once the new renderer is up
+    let _ = stop(monitor);
+    log::info!(
+        "[animated_image] Rendering {} ({:?}, fps cap {:?})",
+        file.display(),
+        fit,
+        fps
+    );
+    host::RENDERERS
+        .lock()
+        .map_err(|_| AppError::WindowLayer("Renderer registry poisoned".into()))?
+        .push((monitor, hwnd));
+    Ok(())
+}
+
+/// Remove renderers on `monitor` (None = all).
+#[cfg(target_os = "windows")]
+pub fn stop(monitor: Option<u32>) -> AppResult<()> {
+    let mut renderers = host::RENDERERS
+        .lock()
+        .map_err(|_| AppError::WindowLayer("Renderer registry poisoned".into()))?;
+    renderers.retain(|(m, hwnd)| {
+        let matches = monitor.is_none() || *m == monitor || m.is_none();
+        if matches {
+            host::close(*hwnd);
+        }
+        !matches
+    });
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn apply(path: &str, _fit: ImageFit, fps: Option<u32>, _monitor: Option<u32>) -> AppResult<()> {
+    open_frames(Path::new(path), fps)?
+        .next()
+        .transpose()?
+        .ok_or_else(|| AppError::Validation("Image has no frames".into()))?;
+    Err(AppError::WindowLayer(
+        "Not supported on this platform".into(),
+    ))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn stop(_monitor: Option<u32>) -> AppResult<()> {
+    Ok(())
+}
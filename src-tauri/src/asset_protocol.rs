@@ -0,0 +1,207 @@
+//! `mywallpaper-asset://` — read-only access to local folders the user granted.
+//!
+//! Wallpapers have no `file://` access. The app page grants a folder (photos,
+//! music covers) with `grant_asset_dir` and hands the returned base URL to the
+//! wallpaper, which then loads `<base>/<relative path>`. Only files inside a
+//! granted folder are served (no traversal, no symlinks out of it), with MIME
+//! detection and Range support from the library protocol.
+//!
+//! Grant ids are random, so a base URL is a capability only its holder can
+//! use. A grant made for a wallpaper records the wallpaper's origin and is only
+//! served to pages of that origin (by `Referer`/`Origin`); an ownerless grant
+//! is served to the app only. Local packs all share the `mwlocal` origin, so
+//! this separates them from remote wallpapers but not from each other. Grants
+//! persist in `asset_grants.json`; the hash-keyed `asset_dirs.json` of older
+//! versions is not carried over, so those folders have to be granted again.
+
+use crate::error::{AppError, AppResult};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::http::{header, Request, Response, StatusCode};
+use typeshare::typeshare;
+
+pub const PROTOCOL_SCHEME: &str = "mywallpaper-asset";
+const ASSET_GRANTS_FILE: &str = "asset_grants.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Grant {
+    /// Canonical folder
+    path: PathBuf,
+    /// Wallpaper the folder was granted to; `None` for the app itself
+    #[serde(default)]
+    wallpaper_id: Option<String>,
+    /// Origin of that wallpaper's page
+    #[serde(default)]
+    origin: Option<String>,
+}
+
+/// Grant id → grant
+static GRANTS: RwLock<BTreeMap<String, Grant>> = RwLock::new(BTreeMap::new());
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetDir {
+    pub id: String,
+    pub path: String,
+    /// Wallpaper allowed to load from it; `None` for the app itself
+    pub wallpaper_id: Option<String>,
+    /// Base URL; append the file path relative to the folder
+    pub url: String,
+}
+
+fn grants_error() -> AppError {
+    AppError::Storage("Asset grants poisoned".into())
+}
+
+fn persist(grants: &BTreeMap<String, Grant>) {
+    if let Err(e) = crate::storage::save_json(ASSET_GRANTS_FILE, grants) {
+        error!("[asset_protocol] Failed to save grants: {}", e);
+    }
+}
+
+/// Base URL of grant `id`. WebView2 maps custom schemes to `http://<scheme>.localhost/`.
+fn base_url(id: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("http://{}.localhost/{}", PROTOCOL_SCHEME, id)
+    } else {
+        format!("{}://localhost/{}", PROTOCOL_SCHEME, id)
+    }
+}
+
+fn asset_dir(id: &str, grant: &Grant) -> AssetDir {
+    AssetDir {
+        id: id.to_string(),
+        path: grant.path.to_string_lossy().into_owned(),
+        wallpaper_id: grant.wallpaper_id.clone(),
+        url: base_url(id),
+    }
+}
+
+pub fn init() {
+    let loaded: BTreeMap<String, Grant> = crate::storage::load_json(ASSET_GRANTS_FILE);
+    if let Ok(mut grants) = GRANTS.write() {
+        info!("[asset_protocol] Loaded {} granted folder(s)", loaded.len());
+        *grants = loaded;
+    }
+}
+
+/// Grant read access to folder `path`, to wallpaper `wallpaper_id` loaded
+/// from `wallpaper_url`, or to the app when both are `None`. Granting the same
+/// folder to the same owner again returns the existing grant.
+pub fn grant(
+    path: &str,
+    wallpaper_id: Option<&str>,
+    wallpaper_url: Option<&str>,
+) -> AppResult<AssetDir> {
+    let dir = Path::new(path)
+        .canonicalize()
+        .ok()
+        .filter(|d| d.is_dir())
+        .ok_or_else(|| AppError::Validation(format!("Not a folder: {}", path)))?;
+    let origin = match (wallpaper_id, wallpaper_url) {
+        (None, None) => None,
+        (Some(_), Some(url)) => Some(
+            url::Url::parse(url)
+                .ok()
+                .as_ref()
+                .and_then(crate::navigation::origin_of)
+                .ok_or_else(|| AppError::Validation(format!("Invalid wallpaper URL: {}", url)))?,
+        ),
+        _ => {
+            return Err(AppError::Validation(
+                "A wallpaper grant needs both the wallpaper id and URL".into(),
+            ))
+        }
+    };
+    let new = Grant {
+        path: dir,
+        wallpaper_id: wallpaper_id.map(str::to_string),
+        origin,
+    };
+
+    let mut grants = GRANTS.write().map_err(|_| grants_error())?;
+    if let Some((id, grant)) = grants.iter().find(|(_, g)| **g == new) {
+        return Ok(asset_dir(id, grant));
+    }
+    let id = crate::crypto::random_hex(16)?;
+    info!(
+        "[asset_protocol] Granted {} to {}",
+        new.path.display(),
+        new.wallpaper_id.as_deref().unwrap_or("the app")
+    );
+    let dir = asset_dir(&id, &new);
+    grants.insert(id, new);
+    persist(&grants);
+    Ok(dir)
+}
+
+pub fn revoke(id: &str) -> AppResult<()> {
+    let mut grants = GRANTS.write().map_err(|_| grants_error())?;
+    if grants.remove(id).is_some() {
+        persist(&grants);
+    }
+    Ok(())
+}
+
+pub fn list() -> AppResult<Vec<AssetDir>> {
+    let grants = GRANTS.read().map_err(|_| grants_error())?;
+    Ok(grants
+        .iter()
+        .map(|(id, grant)| asset_dir(id, grant))
+        .collect())
+}
+
+/// Origin of the page making `request`: its `Referer`, else its `Origin`.
+fn request_origin(request: &Request<Vec<u8>>) -> Option<String> {
+    let header_url = |name| {
+        let value = request.headers().get(name)?.to_str().ok()?;
+        url::Url::parse(value).ok()
+    };
+    header_url(header::REFERER)
+        .or_else(|| header_url(header::ORIGIN))
+        .as_ref()
+        .and_then(crate::navigation::origin_of)
+}
+
+/// `mywallpaper-asset://` protocol entry point: `/<grant id>/<relative path>`.
+/// Blocks on file I/O; call it off the main thread.
+pub fn handle_protocol_request(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    use crate::library::{percent_decode, resolve_in_root, serve_path, status_response};
+
+    let path = request.uri().path().trim_start_matches('/');
+    let (id, rel) = path.split_once('/').unwrap_or((path, ""));
+    let grant = match GRANTS.read() {
+        Ok(grants) => grants.get(id).cloned(),
+        Err(_) => return status_response(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let Some(grant) = grant else {
+        return status_response(StatusCode::NOT_FOUND);
+    };
+    let origin = request_origin(request);
+    let allowed = match (&grant.origin, &origin) {
+        (Some(owner), Some(origin)) => owner == origin,
+        (None, Some(origin)) => crate::navigation::APP_ORIGINS.contains(&origin.as_str()),
+        (_, None) => false,
+    };
+    let Some(origin) = origin.filter(|_| allowed) else {
+        return status_response(StatusCode::FORBIDDEN);
+    };
+    let Some(file) =
+        percent_decode(rel).and_then(|rel| resolve_in_root(&grant.path, Path::new(&rel)))
+    else {
+        return status_response(StatusCode::NOT_FOUND);
+    };
+    let mut response = serve_path(&file, request);
+    // Wallpaper frames are cross-origin: allow fetch() and WebGL textures
+    // for the owning page only
+    if let Ok(value) = header::HeaderValue::from_str(&origin) {
+        let headers = response.headers_mut();
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+        headers.insert(header::VARY, header::HeaderValue::from_static("Origin"));
+    }
+    response
+}
@@ -0,0 +1,316 @@
+//! Ducks or mutes wallpaper audio while other applications play sound.
+//!
+//! The audio sessions of the default render device are polled: a session that
+//! belongs to another process, is active and has a non-silent peak meter
+//! counts as "other audio". Session notifications alone only report
+//! active/inactive, and players keep their session active while paused, so
+//! the meter is what tells actual sound apart. Wallpaper audio is every session
+//! of this process (native video) and of its children (the WebView2 renderers);
+//! it is changed through `ISimpleAudioVolume`, so it follows whatever the page
+//! plays, and restored to the user's mixer settings afterwards.
+
+use crate::settings::{AudioDuckingMode, AudioDuckingPolicy};
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Meter peak above which a session counts as audible
+const AUDIBLE_PEAK: f32 = 0.01;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Wallpaper sessions are held down right now
+static DUCKED: AtomicBool = AtomicBool::new(false);
+static POLICY: Mutex<Option<AudioDuckingPolicy>> = Mutex::new(None);
+static APP_HANDLE: std::sync::OnceLock<tauri::AppHandle> = std::sync::OnceLock::new();
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+    let policy = POLICY.lock().ok().and_then(|p| *p);
+    if let Some(policy) = policy {
+        configure(policy);
+    }
+}
+
+/// Apply a new policy; the monitor runs only while the mode is not `Off`.
+pub fn configure(policy: AudioDuckingPolicy) {
+    if let Ok(mut current) = POLICY.lock() {
+        *current = Some(policy);
+    }
+    if policy.mode == AudioDuckingMode::Off || APP_HANDLE.get().is_none() {
+        return;
+    }
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("[audio_ducking] Monitor started ({:?})", policy.mode);
+    std::thread::spawn(monitor::run);
+}
+
+/// Restore ducked sessions before exiting: Windows remembers per-app session
+/// volumes across launches.
+pub fn shutdown() {
+    if let Ok(mut current) = POLICY.lock() {
+        let policy = current.unwrap_or_default();
+        *current = Some(AudioDuckingPolicy {
+            mode: AudioDuckingMode::Off,
+            ..policy
+        });
+    }
+    let deadline = std::time::Instant::now() + POLL_INTERVAL * 3;
+    while RUNNING.load(Ordering::SeqCst) && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn current_policy() -> AudioDuckingPolicy {
+    POLICY.lock().ok().and_then(|p| *p).unwrap_or_default()
+}
+
+pub fn is_ducking() -> bool {
+    DUCKED.load(Ordering::SeqCst)
+}
+
+fn emit_ducking(active: bool) {
+    use crate::events::{AppEvent, EmitAppEvent};
+    DUCKED.store(active, Ordering::SeqCst);
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit_app_event(&AppEvent::AudioDucking { active }) {
+            log::error!("[audio_ducking] Failed to emit event: {}", e);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) use monitor::own_process_tree;
+
+#[cfg(target_os = "windows")]
+mod monitor {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+    use std::time::Instant;
+    use windows::core::Interface;
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::Media::Audio::Endpoints::IAudioMeterInformation;
+    use windows::Win32::Media::Audio::{
+        eMultimedia, eRender, AudioSessionStateActive, IAudioSessionControl2,
+        IAudioSessionManager2, IMMDeviceEnumerator, ISimpleAudioVolume, MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    struct Session {
+        id: String,
+        volume: ISimpleAudioVolume,
+    }
+
+    /// Volume and mute of a wallpaper session before it was ducked
+    type Saved = (f32, bool);
+
+    /// This process and all of its descendants.
+    pub unsafe fn own_process_tree() -> HashSet<u32> {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+            TH32CS_SNAPPROCESS,
+        };
+
+        let mut tree = HashSet::from([std::process::id()]);
+        let Ok(snap) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return tree;
+        };
+        let mut parents = Vec::new();
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        if Process32FirstW(snap, &mut entry).is_ok() {
+            loop {
+                parents.push((entry.th32ProcessID, entry.th32ParentProcessID));
+                if Process32NextW(snap, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+        let _ = CloseHandle(snap);
+        // Walk down until no new children turn up
+        loop {
+            let before = tree.len();
+            for (pid, parent) in &parents {
+                if *pid != 0 && tree.contains(parent) {
+                    tree.insert(*pid);
+                }
+            }
+            if tree.len() == before {
+                return tree;
+            }
+        }
+    }
+
+    /// Wallpaper sessions and whether any other session is audible.
+    unsafe fn scan(enumerator: &IMMDeviceEnumerator) -> Option<(Vec<Session>, bool)> {
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eRender, eMultimedia)
+            .ok()?;
+        let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None).ok()?;
+        let sessions = manager.GetSessionEnumerator().ok()?;
+        let own = own_process_tree();
+
+        let mut wallpaper = Vec::new();
+        let mut other_audible = false;
+        for i in 0..sessions.GetCount().ok()? {
+            let Ok(control) = sessions.GetSession(i) else {
+                continue;
+            };
+            let Ok(control) = control.cast::<IAudioSessionControl2>() else {
+                continue;
+            };
+            let Ok(pid) = control.GetProcessId() else {
+                continue;
+            };
+            if own.contains(&pid) {
+                let Ok(id) = control.GetSessionInstanceIdentifier() else {
+                    continue;
+                };
+                let id_string = id.to_string().unwrap_or_default();
+                CoTaskMemFree(Some(id.0 as *const _));
+                if let Ok(volume) = control.cast::<ISimpleAudioVolume>() {
+                    wallpaper.push(Session {
+                        id: id_string,
+                        volume,
+                    });
+                }
+                continue;
+            }
+            // System sounds (notification dings) are too short to duck for
+            if other_audible || control.IsSystemSoundsSession().0 == 0 {
+                continue;
+            }
+            let active = control
+                .GetState()
+                .is_ok_and(|s| s == AudioSessionStateActive);
+            let muted = control
+                .cast::<ISimpleAudioVolume>()
+                .and_then(|v| v.GetMute())
+                .is_ok_and(|m| m.as_bool());
+            let peak = control
+                .cast::<IAudioMeterInformation>()
+                .and_then(|m| m.GetPeakValue())
+                .unwrap_or(0.0);
+            other_audible = active && !muted && peak > AUDIBLE_PEAK;
+        }
+        Some((wallpaper, other_audible))
+    }
+
+    unsafe fn duck(
+        sessions: &[Session],
+        saved: &mut HashMap<String, Saved>,
+        policy: &AudioDuckingPolicy,
+    ) {
+        for session in sessions {
+            if saved.contains_key(&session.id) {
+                continue;
+            }
+            let (Ok(volume), Ok(muted)) =
+                (session.volume.GetMasterVolume(), session.volume.GetMute())
+            else {
+                continue;
+            };
+            let result = match policy.mode {
+                AudioDuckingMode::Mute => {
+                    session.volume.SetMute(BOOL::from(true), std::ptr::null())
+                }
+                _ => session
+                    .volume
+                    .SetMasterVolume(volume * policy.duck_volume, std::ptr::null()),
+            };
+            if result.is_ok() {
+                saved.insert(session.id.clone(), (volume, muted.as_bool()));
+            }
+        }
+    }
+
+    unsafe fn restore(sessions: &[Session], saved: &mut HashMap<String, Saved>) {
+        for session in sessions {
+            if let Some((volume, muted)) = saved.get(&session.id) {
+                let _ = session.volume.SetMasterVolume(*volume, std::ptr::null());
+                let _ = session.volume.SetMute(BOOL::from(*muted), std::ptr::null());
+            }
+        }
+        saved.clear();
+    }
+
+    pub fn run() {
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: Option<IMMDeviceEnumerator> =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).ok();
+            let Some(enumerator) = enumerator else {
+                log::warn!("[audio_ducking] No audio device enumerator");
+                RUNNING.store(false, Ordering::SeqCst);
+                return;
+            };
+
+            let mut saved: HashMap<String, Saved> = HashMap::new();
+            let mut last_audible: Option<Instant> = None;
+            loop {
+                let policy = current_policy();
+                let Some((sessions, other_audible)) = scan(&enumerator) else {
+                    std::thread::sleep(POLL_INTERVAL);
+                    continue;
+                };
+                if policy.mode == AudioDuckingMode::Off {
+                    if last_audible.is_some() {
+                        restore(&sessions, &mut saved);
+                        emit_ducking(false);
+                    }
+                    RUNNING.store(false, Ordering::SeqCst);
+                    // Re-enabled meanwhile: `configure` saw the flag still set
+                    if current_policy().mode == AudioDuckingMode::Off
+                        || RUNNING.swap(true, Ordering::SeqCst)
+                    {
+                        info!("[audio_ducking] Monitor stopped");
+                        return;
+                    }
+                    continue;
+                }
+
+                if other_audible {
+                    if last_audible.is_none() {
+                        info!("[audio_ducking] Other audio playing, {:?}", policy.mode);
+                        emit_ducking(true);
+                    }
+                    last_audible = Some(Instant::now());
+                }
+                match last_audible {
+                    Some(at)
+                        if !other_audible
+                            && at.elapsed()
+                                >= Duration::from_millis(policy.restore_after_ms as u64) =>
+                    {
+                        info!("[audio_ducking] Other audio stopped, restoring");
+                        restore(&sessions, &mut saved);
+                        last_audible = None;
+                        emit_ducking(false);
+                    }
+                    // New wallpaper sessions (e.g. a page starting audio) are ducked too
+                    Some(_) => duck(&sessions, &mut saved, &policy),
+                    None => {}
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod monitor {
+    use super::*;
+
+    pub fn run() {
+        log::warn!("[audio_ducking] Audio ducking is only supported on Windows");
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,295 @@
+//! Audio spectrum for audio-reactive wallpapers.
+//!
+//! Two sources share one capture path (WASAPI shared mode): the default output
+//! device through loopback, and the default microphone. Each subscribed source
+//! gets its own capture thread and emits `audio-spectrum` about 30 times per
+//! second, tagged with its source so wallpapers can tell them apart. The
+//! microphone is only opened while subscribed, which wallpapers can only do
+//! with the `microphone` capability.
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use typeshare::typeshare;
+
+const TICK: Duration = Duration::from_millis(33);
+/// FFT window (power of two)
+const WINDOW: usize = 1024;
+const BANDS: usize = 64;
+const MIN_FREQ: f32 = 20.0;
+const MAX_FREQ: f32 = 16_000.0;
+/// Levels at or below this are reported as 0
+const FLOOR_DB: f32 = -80.0;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioSource {
+    /// What the speakers play (default output device)
+    Loopback,
+    /// Default recording device
+    Microphone,
+}
+
+impl AudioSource {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioSpectrum {
+    pub source: AudioSource,
+    /// Log-spaced bands from 20 Hz to 16 kHz, each 0-1
+    pub bands: Vec<f32>,
+    /// Peak sample amplitude of the last window (0-1)
+    pub peak: f32,
+}
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static SUBSCRIBED: [AtomicBool; 2] = [AtomicBool::new(false), AtomicBool::new(false)];
+static RUNNING: [AtomicBool; 2] = [AtomicBool::new(false), AtomicBool::new(false)];
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Start or stop `audio-spectrum` events for `source`.
+pub fn set_subscribed(source: AudioSource, subscribed: bool) {
+    let i = source.index();
+    if SUBSCRIBED[i].swap(subscribed, Ordering::SeqCst) == subscribed {
+        return;
+    }
+    info!(
+        "[audio_spectrum] {:?} {}",
+        source,
+        if subscribed {
+            "subscribed"
+        } else {
+            "unsubscribed"
+        }
+    );
+    if subscribed && !RUNNING[i].swap(true, Ordering::SeqCst) {
+        std::thread::spawn(move || run(source));
+    }
+}
+
+fn run(source: AudioSource) {
+    let i = source.index();
+    loop {
+        if let Err(e) = capture::run(source, &SUBSCRIBED[i]) {
+            warn!("[audio_spectrum] {:?} capture failed: {}", source, e);
+            SUBSCRIBED[i].store(false, Ordering::SeqCst);
+        }
+        RUNNING[i].store(false, Ordering::SeqCst);
+        // Resubscribed while stopping: `set_subscribed` saw the flag still set
+        if !SUBSCRIBED[i].load(Ordering::SeqCst) || RUNNING[i].swap(true, Ordering::SeqCst) {
+            return;
+        }
+    }
+}
+
+fn emit(spectrum: AudioSpectrum) {
+    use crate::events::{AppEvent, EmitAppEvent};
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit_app_event(&AppEvent::AudioSpectrum(spectrum)) {
+            error!("[audio_spectrum] Failed to emit event: {}", e);
+        }
+    }
+}
+
+/// In-place iterative radix-2 FFT over (re, im) pairs.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Band levels of the last `WINDOW` mono samples.
+fn spectrum(source: AudioSource, samples: &[f32], sample_rate: u32) -> AudioSpectrum {
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs())).min(1.0);
+    // Hann window
+    let mut re: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let w = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / WINDOW as f32).cos();
+            s * w
+        })
+        .collect();
+    let mut im = vec![0.0; WINDOW];
+    fft(&mut re, &mut im);
+
+    let bin_hz = sample_rate as f32 / WINDOW as f32;
+    let max_freq = MAX_FREQ.min(sample_rate as f32 / 2.0);
+    let ratio = (max_freq / MIN_FREQ).powf(1.0 / BANDS as f32);
+    let bands = (0..BANDS)
+        .map(|band| {
+            let lo = MIN_FREQ * ratio.powi(band as i32);
+            let hi = lo * ratio;
+            let first = ((lo / bin_hz) as usize).max(1);
+            let last = ((hi / bin_hz) as usize).clamp(first, WINDOW / 2 - 1);
+            let magnitude = (first..=last)
+                .map(|k| (re[k] * re[k] + im[k] * im[k]).sqrt())
+                .fold(0.0f32, f32::max);
+            // Hann halves the amplitude; scale so a full-scale sine reads 0 dB
+            let db = 20.0 * (magnitude * 4.0 / WINDOW as f32).max(1e-9).log10();
+            ((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0)
+        })
+        .collect();
+    AudioSpectrum {
+        source,
+        bands,
+        peak,
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod capture {
+    use super::*;
+    use std::collections::VecDeque;
+    use windows::Win32::Media::Audio::{
+        eCapture, eConsole, eRender, IAudioCaptureClient, IAudioClient, IMMDeviceEnumerator,
+        MMDeviceEnumerator, AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED,
+        AUDCLNT_STREAMFLAGS_LOOPBACK, WAVEFORMATEX,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    /// Shared-mode buffer: 1s, in 100ns units
+    const BUFFER_DURATION: i64 = 10_000_000;
+
+    /// Capture `source` until `subscribed` is cleared.
+    pub fn run(source: AudioSource, subscribed: &AtomicBool) -> windows::core::Result<()> {
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+            let (flow, flags) = match source {
+                AudioSource::Loopback => (eRender, AUDCLNT_STREAMFLAGS_LOOPBACK),
+                AudioSource::Microphone => (eCapture, 0),
+            };
+            let device = enumerator.GetDefaultAudioEndpoint(flow, eConsole)?;
+            let client: IAudioClient = device.Activate(CLSCTX_ALL, None)?;
+
+            let format_ptr = client.GetMixFormat()?;
+            let format: WAVEFORMATEX = std::ptr::read_unaligned(format_ptr);
+            let result = client.Initialize(
+                AUDCLNT_SHAREMODE_SHARED,
+                flags,
+                BUFFER_DURATION,
+                0,
+                format_ptr,
+                None,
+            );
+            CoTaskMemFree(Some(format_ptr as *const _));
+            result?;
+
+            let channels = format.nChannels.max(1) as usize;
+            let bits = format.wBitsPerSample;
+            let rate = format.nSamplesPerSec;
+            if bits != 32 && bits != 16 {
+                return Err(windows::core::Error::new(
+                    windows::Win32::Foundation::E_NOTIMPL,
+                    format!("Unsupported {}-bit mix format", bits),
+                ));
+            }
+            let capture: IAudioCaptureClient = client.GetService()?;
+            client.Start()?;
+            info!(
+                "[audio_spectrum] Capturing {:?} ({} Hz, {} ch)",
+                source, rate, channels
+            );
+
+            let mut window: VecDeque<f32> = VecDeque::from(vec![0.0; WINDOW]);
+            let silence_per_tick = (rate as f32 * TICK.as_secs_f32()) as usize;
+            while subscribed.load(Ordering::SeqCst) {
+                std::thread::sleep(TICK);
+                let mut received = false;
+                while capture.GetNextPacketSize()? > 0 {
+                    let mut data = std::ptr::null_mut();
+                    let mut frames = 0u32;
+                    let mut buffer_flags = 0u32;
+                    capture.GetBuffer(&mut data, &mut frames, &mut buffer_flags, None, None)?;
+                    let silent = buffer_flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0;
+                    let count = frames as usize * channels;
+                    let mut push = |sample: f32| {
+                        window.pop_front();
+                        window.push_back(sample);
+                    };
+                    // Downmix to mono
+                    if silent || data.is_null() {
+                        (0..frames).for_each(|_| push(0.0));
+                    } else if bits == 32 {
+                        let samples = std::slice::from_raw_parts(data as *const f32, count);
+                        for frame in samples.chunks_exact(channels) {
+                            push(frame.iter().sum::<f32>() / channels as f32);
+                        }
+                    } else {
+                        let samples = std::slice::from_raw_parts(data as *const i16, count);
+                        for frame in samples.chunks_exact(channels) {
+                            let sum: f32 = frame.iter().map(|s| *s as f32 / 32768.0).sum();
+                            push(sum / channels as f32);
+                        }
+                    }
+                    capture.ReleaseBuffer(frames)?;
+                    received = true;
+                }
+                // Loopback delivers no packets while nothing plays
+                if !received {
+                    for _ in 0..silence_per_tick.min(WINDOW) {
+                        window.pop_front();
+                        window.push_back(0.0);
+                    }
+                }
+                let samples: Vec<f32> = window.iter().copied().collect();
+                emit(spectrum(source, &samples, rate));
+            }
+            let _ = client.Stop();
+            info!("[audio_spectrum] Stopped {:?}", source);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod capture {
+    use super::*;
+
+    pub fn run(_source: AudioSource, _subscribed: &AtomicBool) -> Result<(), String> {
+        Err("Audio capture is only supported on Windows".into())
+    }
+}
@@ -0,0 +1,272 @@
+//! Backup — settings, profiles and library metadata in a single `.zip`.
+//!
+//! Archive layout:
+//! - `backup.json` — format version, app version and the frontend settings blob
+//! - `config/<file>.json` — the user-facing stores in `CONFIG_FILES`. Secrets
+//!   (local API key), grants (permissions, asset folders) and per-machine
+//!   state or caches are never exported nor restored.
+//! - `library/<pack>/wallpaper.json` — manifest of each local pack (assets are not included)
+
+use crate::error::{AppError, AppResult};
+use crate::library;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use typeshare::typeshare;
+
+const BACKUP_VERSION: u32 = 1;
+const BACKUP_META_FILE: &str = "backup.json";
+const CONFIG_PREFIX: &str = "config/";
+const LIBRARY_PREFIX: &str = "library/";
+const SETTINGS_FILE: &str = "settings.json";
+const PROFILES_FILE: &str = "profiles.json";
+const OFFLINE_FILE: &str = "offline.json";
+const WALLPAPER_SETTINGS_FILE: &str = "wallpaper_settings.json";
+const WIDGETS_FILE: &str = "widgets.json";
+/// Config files a backup carries; anything else in an archive is ignored.
+const CONFIG_FILES: &[&str] = &[
+    SETTINGS_FILE,
+    PROFILES_FILE,
+    OFFLINE_FILE,
+    WALLPAPER_SETTINGS_FILE,
+    WIDGETS_FILE,
+];
+/// Refuse single archive entries larger than this (nothing we write comes close).
+const MAX_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupMeta {
+    version: u32,
+    app_version: String,
+    /// Unix timestamp (seconds)
+    created_at: u64,
+    /// Opaque frontend settings, restored as-is
+    #[serde(default)]
+    settings: Option<serde_json::Value>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub app_version: String,
+    pub created_at: u64,
+    /// Frontend settings stored in the backup, for the frontend to re-apply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<serde_json::Value>,
+    /// Config files written back (export: files included)
+    pub config_files: Vec<String>,
+    /// Library packs described in the backup
+    pub library_packs: Vec<String>,
+    /// Packs from the backup that are not installed locally and need re-importing
+    pub missing_packs: Vec<String>,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn zip_err(e: zip::result::ZipError) -> AppError {
+    AppError::Storage(format!("Backup archive: {}", e))
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Write a backup archive to `path`. `settings` is the frontend's own settings blob.
+pub fn export_backup(path: &Path, settings: Option<serde_json::Value>) -> AppResult<BackupSummary> {
+    use zip::write::SimpleFileOptions;
+
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let meta = BackupMeta {
+        version: BACKUP_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: now_secs(),
+        settings,
+    };
+
+    // Write next to the target and rename so a failed export never clobbers an old backup
+    let tmp = path.with_extension("zip.tmp");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&tmp)?);
+    let result = (|| -> AppResult<(Vec<String>, Vec<String>)> {
+        writer
+            .start_file(BACKUP_META_FILE, options)
+            .map_err(zip_err)?;
+        writer.write_all(&serde_json::to_vec_pretty(&meta)?)?;
+
+        let mut config_files = Vec::new();
+        for name in CONFIG_FILES {
+            let bytes = match std::fs::read(crate::storage::config_path(name)?) {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            writer
+                .start_file(format!("{}{}", CONFIG_PREFIX, name), options)
+                .map_err(zip_err)?;
+            writer.write_all(&bytes)?;
+            config_files.push(name.to_string());
+        }
+
+        let mut packs = Vec::new();
+        if library::library_root().is_ok() {
+            for wallpaper in library::list_local_wallpapers()? {
+                let dir = library::library_root()?.join(&wallpaper.id);
+                let Some(manifest) = library::read_manifest(&dir) else {
+                    continue;
+                };
+                writer
+                    .start_file(
+                        format!(
+                            "{}{}/{}",
+                            LIBRARY_PREFIX,
+                            wallpaper.id,
+                            library::MANIFEST_FILE
+                        ),
+                        options,
+                    )
+                    .map_err(zip_err)?;
+                writer.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+                packs.push(wallpaper.id);
+            }
+        }
+        Ok((config_files, packs))
+    })();
+
+    let (config_files, library_packs) = match result.and_then(|r| {
+        writer.finish().map_err(zip_err)?;
+        Ok(r)
+    }) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&tmp, path)?;
+    info!(
+        "[backup] Exported {} config file(s) and {} pack manifest(s) to {}",
+        config_files.len(),
+        library_packs.len(),
+        path.display()
+    );
+
+    Ok(BackupSummary {
+        app_version: meta.app_version,
+        created_at: meta.created_at,
+        settings: None,
+        config_files,
+        library_packs,
+        missing_packs: Vec::new(),
+    })
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+fn read_entry(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> AppResult<Vec<u8>> {
+    let entry = archive.by_name(name).map_err(zip_err)?;
+    if entry.size() > MAX_ENTRY_BYTES {
+        return Err(AppError::Storage(format!(
+            "Backup entry too large: {}",
+            name
+        )));
+    }
+    let mut buf = Vec::with_capacity(entry.size() as usize);
+    entry.take(MAX_ENTRY_BYTES).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Restore a backup archive. Config files are replaced, in-memory state is
+/// reloaded, and the frontend settings are returned for the caller to apply.
+pub fn import_backup(app: &tauri::AppHandle, path: &Path) -> AppResult<BackupSummary> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?).map_err(zip_err)?;
+
+    let meta: BackupMeta = serde_json::from_slice(&read_entry(&mut archive, BACKUP_META_FILE)?)
+        .map_err(|e| AppError::Validation(format!("Not a MyWallpaper backup: {}", e)))?;
+    if meta.version > BACKUP_VERSION {
+        return Err(AppError::Validation(format!(
+            "Backup format v{} requires a newer app version",
+            meta.version
+        )));
+    }
+
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+
+    // Validate every config file before writing any, so a bad archive changes nothing
+    let mut configs: Vec<(String, serde_json::Value)> = Vec::new();
+    for entry_name in &names {
+        let Some(file) = entry_name.strip_prefix(CONFIG_PREFIX) else {
+            continue;
+        };
+        if !CONFIG_FILES.contains(&file) {
+            warn!("[backup] Skipping unexpected entry '{}'", entry_name);
+            continue;
+        }
+        let value: serde_json::Value =
+            serde_json::from_slice(&read_entry(&mut archive, entry_name)?)
+                .map_err(|e| AppError::Validation(format!("Corrupt {} in backup: {}", file, e)))?;
+        configs.push((file.to_string(), value));
+    }
+
+    // Settings go through the same validation as the settings page, first, so
+    // rejected settings leave everything else untouched
+    let mut config_files = Vec::new();
+    if let Some((file, value)) = configs.iter().find(|(f, _)| f == SETTINGS_FILE) {
+        let settings: crate::settings::BackendSettings = serde_json::from_value(value.clone())
+            .map_err(|e| AppError::Validation(format!("Corrupt {} in backup: {}", file, e)))?;
+        crate::settings::update(settings)?;
+        config_files.push(file.clone());
+    }
+    for (file, value) in configs.iter().filter(|(f, _)| f != SETTINGS_FILE) {
+        crate::storage::save_json(file, value)?;
+        config_files.push(file.clone());
+        match file.as_str() {
+            PROFILES_FILE => crate::profiles::reload(),
+            OFFLINE_FILE => crate::offline::init(),
+            WALLPAPER_SETTINGS_FILE => crate::wallpaper_settings::init(),
+            WIDGETS_FILE => crate::widgets::reload(app)?,
+            _ => {}
+        }
+    }
+
+    let mut library_packs: Vec<String> = names
+        .iter()
+        .filter_map(|n| n.strip_prefix(LIBRARY_PREFIX))
+        .filter_map(|rest| rest.strip_suffix(&format!("/{}", library::MANIFEST_FILE)))
+        .filter(|id| library::validate_pack_id(id).is_ok())
+        .map(str::to_string)
+        .collect();
+    library_packs.sort();
+    let missing_packs = library_packs
+        .iter()
+        .filter(|id| !library::pack_exists(id))
+        .cloned()
+        .collect();
+
+    info!(
+        "[backup] Restored {} config file(s) from {} (created by v{})",
+        config_files.len(),
+        path.display(),
+        meta.app_version
+    );
+    Ok(BackupSummary {
+        app_version: meta.app_version,
+        created_at: meta.created_at,
+        settings: meta.settings,
+        config_files,
+        library_packs,
+        missing_packs,
+    })
+}
@@ -0,0 +1,239 @@
+//! Real display brightness, for scheduled dimming and ambient lighting scenes.
+//!
+//! The backlight is set over DDC/CI when the monitor supports it. Otherwise
+//! (most laptop panels, monitors with DDC/CI turned off) the monitor's gamma
+//! ramp is scaled instead, which darkens the picture without saving power.
+//! Scaled ramps are reset to linear on exit. Windows rejects ramps that stray
+//! too far from linear, so the gamma fallback cannot go fully dark.
+
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use typeshare::typeshare;
+
+/// How the brightness was applied
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BrightnessMethod {
+    /// Monitor backlight over DDC/CI
+    Ddc,
+    /// Scaled gamma ramp
+    Gamma,
+}
+
+/// Display devices whose gamma ramp is not linear, `None` until the exit
+/// hook that resets them is registered.
+static GAMMA_ADJUSTED: Mutex<Option<BTreeSet<String>>> = Mutex::new(None);
+
+/// Set the brightness (0-1) of monitor `monitor` (`DisplayInfo` order).
+pub fn set(monitor: u32, level: f32) -> AppResult<BrightnessMethod> {
+    if !(0.0..=1.0).contains(&level) {
+        return Err(AppError::Validation(
+            "Brightness must be between 0 and 1".into(),
+        ));
+    }
+    let method = display::set(monitor, level)?;
+    info!(
+        "[brightness] Monitor {} set to {:.2} ({:?})",
+        monitor, level, method
+    );
+    Ok(method)
+}
+
+/// Remember whether `device` has a non-linear gamma ramp.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn track_gamma(device: &str, adjusted: bool) {
+    let Ok(mut guard) = GAMMA_ADJUSTED.lock() else {
+        return;
+    };
+    let devices = guard.get_or_insert_with(|| {
+        crate::shutdown::on_exit("Resetting display gamma", restore);
+        BTreeSet::new()
+    });
+    if adjusted {
+        devices.insert(device.to_string());
+    } else {
+        devices.remove(device);
+    }
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn gamma_adjusted(device: &str) -> bool {
+    GAMMA_ADJUSTED
+        .lock()
+        .is_ok_and(|d| d.as_ref().is_some_and(|d| d.contains(device)))
+}
+
+/// Reset every gamma ramp changed by `set` to linear.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn restore() {
+    let devices = GAMMA_ADJUSTED
+        .lock()
+        .map(|mut d| d.as_mut().map(std::mem::take).unwrap_or_default())
+        .unwrap_or_default();
+    for device in devices {
+        if let Err(e) = display::set_gamma(&device, 1.0) {
+            warn!("[brightness] Could not reset gamma of {}: {}", device, e);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod display {
+    use super::{gamma_adjusted, track_gamma, BrightnessMethod};
+    use crate::error::{AppError, AppResult};
+    use log::debug;
+    use windows::core::PCWSTR;
+    use windows::Win32::Devices::Display::{
+        DestroyPhysicalMonitors, GetMonitorBrightness, GetNumberOfPhysicalMonitorsFromHMONITOR,
+        GetPhysicalMonitorsFromHMONITOR, SetMonitorBrightness, PHYSICAL_MONITOR,
+    };
+    use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        CreateDCW, DeleteDC, EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
+    };
+    use windows::Win32::UI::ColorSystem::SetDeviceGammaRamp;
+
+    fn display_err(e: windows::core::Error) -> AppError {
+        AppError::Display(e.to_string())
+    }
+
+    pub fn set(monitor: u32, level: f32) -> AppResult<BrightnessMethod> {
+        let hm = monitors()
+            .get(monitor as usize)
+            .copied()
+            .ok_or_else(|| AppError::Validation(format!("No monitor {}", monitor)))?;
+        let device = device_name(hm)?;
+        match set_ddc(hm, level) {
+            Ok(()) => {
+                // Undo an earlier fallback on this monitor
+                if gamma_adjusted(&device) {
+                    let _ = set_gamma(&device, 1.0);
+                }
+                Ok(BrightnessMethod::Ddc)
+            }
+            Err(e) => {
+                debug!("[brightness] DDC/CI unavailable on {}: {}", device, e);
+                set_gamma(&device, level)?;
+                Ok(BrightnessMethod::Gamma)
+            }
+        }
+    }
+
+    /// Monitor handles in `DisplayInfo` order.
+    fn monitors() -> Vec<HMONITOR> {
+        unsafe extern "system" fn cb(
+            hm: HMONITOR,
+            _hdc: HDC,
+            _rect: *mut RECT,
+            lp: LPARAM,
+        ) -> BOOL {
+            (*(lp.0 as *mut Vec<HMONITOR>)).push(hm);
+            BOOL(1)
+        }
+        let mut out: Vec<HMONITOR> = Vec::new();
+        unsafe {
+            let _ = EnumDisplayMonitors(
+                HDC::default(),
+                None,
+                Some(cb),
+                LPARAM(&mut out as *mut _ as isize),
+            );
+        }
+        out
+    }
+
+    /// GDI device name (`\\.\DISPLAY1`) of a monitor.
+    fn device_name(hm: HMONITOR) -> AppResult<String> {
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !unsafe { GetMonitorInfoW(hm, &mut info.monitorInfo) }.as_bool() {
+            return Err(AppError::Display("Monitor is gone".into()));
+        }
+        let len = info
+            .szDevice
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(info.szDevice.len());
+        Ok(String::from_utf16_lossy(&info.szDevice[..len]))
+    }
+
+    /// Set the backlight of the physical monitors behind `hm`.
+    fn set_ddc(hm: HMONITOR, level: f32) -> AppResult<()> {
+        unsafe {
+            let mut count = 0u32;
+            GetNumberOfPhysicalMonitorsFromHMONITOR(hm, &mut count).map_err(display_err)?;
+            if count == 0 {
+                return Err(AppError::Display("No physical monitor".into()));
+            }
+            let mut physical = vec![std::mem::zeroed::<PHYSICAL_MONITOR>(); count as usize];
+            GetPhysicalMonitorsFromHMONITOR(hm, &mut physical).map_err(display_err)?;
+            let mut result = Err(AppError::Display(
+                "Monitor does not support DDC/CI brightness".into(),
+            ));
+            for monitor in &physical {
+                let handle = monitor.hPhysicalMonitor;
+                let (mut min, mut current, mut max) = (0u32, 0u32, 0u32);
+                if GetMonitorBrightness(handle, &mut min, &mut current, &mut max) == 0 || max <= min
+                {
+                    continue;
+                }
+                let value = min + ((max - min) as f32 * level).round() as u32;
+                if value == current || SetMonitorBrightness(handle, value) != 0 {
+                    result = Ok(());
+                }
+            }
+            let _ = DestroyPhysicalMonitors(&physical);
+            result
+        }
+    }
+
+    /// Scale the gamma ramp of display `device`; 1 restores a linear ramp.
+    pub fn set_gamma(device: &str, level: f32) -> AppResult<()> {
+        let name: Vec<u16> = device.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut ramp = [[0u16; 256]; 3];
+        for channel in ramp.iter_mut() {
+            for (i, value) in channel.iter_mut().enumerate() {
+                *value = ((i * 257) as f32 * level).round() as u16;
+            }
+        }
+        let applied = unsafe {
+            let hdc = CreateDCW(PCWSTR::null(), PCWSTR(name.as_ptr()), PCWSTR::null(), None);
+            if hdc.is_invalid() {
+                return Err(AppError::Display(format!("Cannot open {}", device)));
+            }
+            let applied = SetDeviceGammaRamp(hdc, ramp.as_ptr().cast()).as_bool();
+            let _ = DeleteDC(hdc);
+            applied
+        };
+        if !applied {
+            return Err(AppError::Display(format!(
+                "{} rejected the gamma ramp (level too low?)",
+                device
+            )));
+        }
+        track_gamma(device, level < 1.0);
+        Ok(())
+    }
+}
+
+/// DisplayServices (macOS) and ddcutil (Linux) are left for the Linux/macOS
+/// port.
+#[cfg(not(target_os = "windows"))]
+mod display {
+    use super::BrightnessMethod;
+    use crate::error::{AppError, AppResult};
+
+    pub fn set(_monitor: u32, _level: f32) -> AppResult<BrightnessMethod> {
+        Err(AppError::Display(
+            "Display brightness control is only supported on Windows".into(),
+        ))
+    }
+
+    pub fn set_gamma(_device: &str, _level: f32) -> AppResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+//! WebView user data folder: storage usage, selective clearing and relocation.
+//!
+//! WebView2 keeps cookies, caches and site storage in `EBWebView` under the
+//! user data folder, by default the app's local data directory. The
+//! `browserDataDir` setting moves it to another drive: `prepare` runs before
+//! the webview exists, moves the previous folder's data over and points
+//! WebView2 at it through `WEBVIEW2_USER_DATA_FOLDER`, so a change applies on
+//! the next launch.
+
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use typeshare::typeshare;
+
+const PROFILE_DIR: &str = "EBWebView";
+/// Remembers the folder in use, so a relocation knows where to move data from
+const LOCATION_FILE: &str = "browser_data.json";
+
+/// User data folder resolved by `prepare`
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BrowserDataKind {
+    Cookies,
+    /// HTTP disk cache and Cache Storage
+    Cache,
+    IndexedDb,
+    LocalStorage,
+    ServiceWorkers,
+    /// Everything in the profile, including history and settings
+    All,
+}
+
+impl BrowserDataKind {
+    /// `COREWEBVIEW2_BROWSING_DATA_KINDS` bits.
+    fn webview2_bits(self) -> i32 {
+        match self {
+            Self::Cookies => 0x40,
+            Self::Cache => 0x100 | 0x10,
+            Self::IndexedDb => 0x2,
+            Self::LocalStorage => 0x4,
+            Self::ServiceWorkers => 0x8000,
+            Self::All => 0x4000,
+        }
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserStorageUsage {
+    /// User data folder (holds `EBWebView`)
+    pub path: String,
+    pub total_bytes: u64,
+    pub cookies_bytes: u64,
+    pub cache_bytes: u64,
+    pub indexed_db_bytes: u64,
+    pub local_storage_bytes: u64,
+    pub service_workers_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Location {
+    path: PathBuf,
+}
+
+/// Validate a relocation target: an absolute folder path (None = default).
+pub fn normalize_dir(dir: Option<&str>) -> AppResult<Option<String>> {
+    let Some(dir) = dir.map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+    let path = Path::new(dir);
+    if !path.is_absolute() {
+        return Err(AppError::Validation(
+            "Browser data folder must be an absolute path".into(),
+        ));
+    }
+    if path.is_file() {
+        return Err(AppError::Validation(format!("{} is a file", dir)));
+    }
+    Ok(Some(dir.to_string()))
+}
+
+fn default_dir() -> Option<PathBuf> {
+    let local = std::env::var_os("LOCALAPPDATA")?;
+    Some(PathBuf::from(local).join(crate::cli::APP_IDENTIFIER))
+}
+
+/// Resolve the user data folder, move data after a relocation and point
+/// WebView2 at it. Runs before Tauri starts.
+pub fn prepare() {
+    let Some(config_dir) = crate::cli::config_dir() else {
+        return;
+    };
+    let configured = crate::cli::read_json(&config_dir, "settings.json")
+        .and_then(|s| s.get("browserDataDir")?.as_str().map(PathBuf::from));
+    let Some(target) = configured.or_else(default_dir) else {
+        return;
+    };
+
+    let location_path = config_dir.join(LOCATION_FILE);
+    let previous = std::fs::read(&location_path)
+        .ok()
+        .and_then(|b| serde_json::from_slice::<Location>(&b).ok())
+        .map(|l| l.path)
+        .or_else(default_dir);
+    let mut in_use = target.clone();
+    if let Some(previous) = previous.filter(|p| *p != target) {
+        if let Err(e) = move_profile(&previous, &target) {
+            warn!(
+                "[browser_data] Keeping {}: cannot move data: {}",
+                previous.display(),
+                e
+            );
+            in_use = previous;
+        }
+    }
+
+    std::env::set_var("WEBVIEW2_USER_DATA_FOLDER", &in_use);
+    if let Ok(bytes) = serde_json::to_vec(&Location {
+        path: in_use.clone(),
+    }) {
+        let _ = std::fs::create_dir_all(&config_dir);
+        let _ = std::fs::write(&location_path, bytes);
+    }
+    let _ = DATA_DIR.set(in_use);
+}
+
+/// Move `EBWebView` from `from` to `to` (rename, or copy across drives).
+fn move_profile(from: &Path, to: &Path) -> std::io::Result<()> {
+    let source = from.join(PROFILE_DIR);
+    let dest = to.join(PROFILE_DIR);
+    if !source.is_dir() || dest.exists() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(to)?;
+    if std::fs::rename(&source, &dest).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_dir(&source, &dest) {
+        let _ = std::fs::remove_dir_all(&dest);
+        return Err(e);
+    }
+    std::fs::remove_dir_all(&source)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn data_dir() -> AppResult<PathBuf> {
+    DATA_DIR
+        .get()
+        .cloned()
+        .or_else(default_dir)
+        .ok_or_else(|| AppError::Storage("Browser data folder unknown".into()))
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Disk usage of the user data folder, by kind (the default profile).
+pub fn usage() -> AppResult<BrowserStorageUsage> {
+    let root = data_dir()?;
+    let profile = root.join(PROFILE_DIR).join("Default");
+    let size = |parts: &[&str]| -> u64 { parts.iter().map(|p| dir_size(&profile.join(p))).sum() };
+    Ok(BrowserStorageUsage {
+        path: root.to_string_lossy().into_owned(),
+        total_bytes: dir_size(&root.join(PROFILE_DIR)),
+        cookies_bytes: size(&[
+            "Network/Cookies",
+            "Network/Cookies-journal",
+            "Cookies",
+            "Cookies-journal",
+        ]),
+        cache_bytes: size(&["Cache", "Code Cache", "GPUCache"]),
+        indexed_db_bytes: size(&["IndexedDB"]),
+        local_storage_bytes: size(&["Local Storage", "Session Storage"]),
+        service_workers_bytes: size(&["Service Worker"]),
+    })
+}
+
+/// Clear `kinds` from the desktop webview's profile; blocks until done.
+pub fn clear(app: &tauri::AppHandle, kinds: &[BrowserDataKind]) -> AppResult<()> {
+    if kinds.is_empty() {
+        return Ok(());
+    }
+    let bits = kinds.iter().fold(0, |bits, k| bits | k.webview2_bits());
+    info!("[browser_data] Clearing {:?}", kinds);
+    webview::clear(app, bits)
+}
+
+#[cfg(target_os = "windows")]
+mod webview {
+    use super::*;
+    use std::time::Duration;
+
+    const CLEAR_TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub fn clear(app: &tauri::AppHandle, bits: i32) -> AppResult<()> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        // WebView2 must be called on the thread that created it
+        app.run_on_main_thread(move || {
+            let ptr = crate::window_layer::mouse_hook::get_comp_controller_ptr();
+            let done = tx.clone();
+            let started = unsafe {
+                wry::clear_browsing_data_raw(
+                    ptr,
+                    bits,
+                    Box::new(move |result| {
+                        let _ = done.send(result);
+                    }),
+                )
+            };
+            if let Err(e) = started {
+                let _ = tx.send(Err(e));
+            }
+        })?;
+        rx.recv_timeout(CLEAR_TIMEOUT)
+            .map_err(|_| AppError::Storage("Clearing browser data timed out".into()))?
+            .map_err(AppError::Storage)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod webview {
+    use super::*;
+
+    pub fn clear(_app: &tauri::AppHandle, _bits: i32) -> AppResult<()> {
+        Err(AppError::Storage(
+            "Clearing browser data is only supported on Windows".into(),
+        ))
+    }
+}
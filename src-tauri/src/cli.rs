@@ -0,0 +1,408 @@
+//! CLI subcommands that control a running instance:
+//! `mywallpaper status | pause | resume | next | apply <url-or-id>`.
+//!
+//! Commands go through the local REST API when it is enabled, so results can be
+//! printed. Otherwise they are forwarded through the single-instance channel
+//! (fire-and-forget; `status` needs the API).
+//!
+//! The running instance parses every forwarded argument list — subcommands,
+//! `--pause`/`--resume`/`--next`/`--apply <url|id>` flags, deep links and file
+//! paths — runs the matching actions and emits it as `second-instance`.
+//!
+//! A normal launch accepts startup flags (`--monitor`, `--url`, `--paused`,
+//! `--profile`, `--windowed`), see `LaunchOptions`.
+
+use crate::desktop_actions::DesktopAction;
+use crate::events::{AppEvent, EmitAppEvent};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use typeshare::typeshare;
+
+/// Must match `identifier` in tauri.conf.json (config directory name).
+pub(crate) const APP_IDENTIFIER: &str = "com.mywallpaper.desktop";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const USAGE: &str = "Usage: mywallpaper <command>
+
+Commands:
+  status              Show the running instance's state
+  pause               Pause the wallpaper
+  resume              Resume the wallpaper
+  next                Switch to the next wallpaper
+  apply <url|id>      Apply a remote wallpaper URL or a local wallpaper id
+
+Launch options:
+  --monitor <n>       Cover only monitor n (0-based) instead of all monitors
+  --url <url>         Start with this remote wallpaper
+  --paused            Start paused
+  --profile <id|name> Activate this profile on startup
+  --windowed          Open in a normal window instead of the desktop (debugging)";
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "target", rename_all = "camelCase")]
+pub enum CliCommand {
+    Status,
+    Pause,
+    Resume,
+    Next,
+    Apply(String),
+}
+
+/// Parse process arguments (program name included). `None` when the first
+/// argument is not a subcommand (normal launch, deep link, `--minimized`, …).
+pub fn parse(args: &[String]) -> Option<Result<CliCommand, String>> {
+    let command = args.get(1)?;
+    let arg = args.get(2);
+    Some(match command.as_str() {
+        "status" => Ok(CliCommand::Status),
+        "pause" => Ok(CliCommand::Pause),
+        "resume" => Ok(CliCommand::Resume),
+        "next" => Ok(CliCommand::Next),
+        "apply" => arg
+            .map(|a| CliCommand::Apply(a.clone()))
+            .ok_or_else(|| "apply: missing <url|id>".to_string()),
+        "help" | "--help" | "-h" => Err(String::new()),
+        _ => return None,
+    })
+}
+
+fn is_url(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
+/// Startup flags of a normal launch. Also exposed to the page as
+/// `window.__MW_INIT__.launch`.
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchOptions {
+    /// Restrict the layer to one monitor (`DisplayInfo` order)
+    pub monitor: Option<u32>,
+    /// Remote wallpaper to load instead of the last one
+    pub url: Option<String>,
+    pub paused: bool,
+    /// Profile id or name
+    pub profile: Option<String>,
+    /// Normal window instead of the desktop layer
+    pub windowed: bool,
+}
+
+static LAUNCH: OnceLock<LaunchOptions> = OnceLock::new();
+
+/// Options of the current launch (defaults when none were given).
+pub fn launch_options() -> &'static LaunchOptions {
+    LAUNCH.get_or_init(LaunchOptions::default)
+}
+
+/// Parse launch flags (program name included). Other arguments (deep links,
+/// `--minimized`, …) are left alone.
+pub fn parse_launch(args: &[String]) -> Result<LaunchOptions, String> {
+    let mut options = LaunchOptions::default();
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| rest.next().cloned())
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| format!("{}: missing value", flag))
+        };
+        match flag {
+            "--monitor" => {
+                let raw = value()?;
+                let monitor = raw
+                    .parse()
+                    .map_err(|_| format!("--monitor: invalid index '{}'", raw))?;
+                options.monitor = Some(monitor);
+            }
+            "--url" => {
+                let url = value()?;
+                if !is_url(&url) {
+                    return Err(format!("--url: not an http(s) URL: {}", url));
+                }
+                options.url = Some(url);
+            }
+            "--profile" => options.profile = Some(value()?),
+            "--paused" => options.paused = true,
+            "--windowed" => options.windowed = true,
+            _ => {}
+        }
+    }
+    Ok(options)
+}
+
+// ============================================================================
+// Client side (second process)
+// ============================================================================
+
+/// The GUI-subsystem binary has no console of its own: borrow the caller's.
+fn attach_console() {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// App config directory, resolved like Tauri's `app_config_dir` (no AppHandle here).
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(std::env::var_os("HOME")?).join("Library/Application Support")
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?
+    };
+    Some(base.join(APP_IDENTIFIER))
+}
+
+pub(crate) fn read_json(dir: &std::path::Path, file: &str) -> Option<Value> {
+    serde_json::from_slice(&std::fs::read(dir.join(file)).ok()?).ok()
+}
+
+/// (port, api key) when the local API is enabled.
+fn local_api_endpoint() -> Option<(u16, String)> {
+    let dir = config_dir()?;
+    let settings = read_json(&dir, "settings.json")?;
+    if settings.get("localApiEnabled")?.as_bool() != Some(true) {
+        return None;
+    }
+    let port = settings
+        .get("localApiPort")
+        .and_then(Value::as_u64)
+        .and_then(|p| u16::try_from(p).ok())
+        .unwrap_or(crate::local_api::DEFAULT_PORT);
+    let key = read_json(&dir, "local_api.json")?
+        .get("apiKey")?
+        .as_str()?
+        .to_string();
+    Some((port, key))
+}
+
+fn call_local_api(port: u16, key: &str, command: &CliCommand) -> Result<Value, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = |path: &str| format!("http://127.0.0.1:{}{}", port, path);
+    let request = match command {
+        CliCommand::Status => client.get(url("/status")),
+        CliCommand::Pause => client
+            .post(url("/pause"))
+            .json(&serde_json::json!({ "paused": true })),
+        CliCommand::Resume => client
+            .post(url("/pause"))
+            .json(&serde_json::json!({ "paused": false })),
+        CliCommand::Next => client.post(url("/next")),
+        CliCommand::Apply(target) if is_url(target) => client
+            .post(url("/wallpaper"))
+            .json(&serde_json::json!({ "url": target })),
+        CliCommand::Apply(id) => client
+            .post(url("/wallpaper"))
+            .json(&serde_json::json!({ "id": id })),
+    };
+    let response = request
+        .bearer_auth(key)
+        .send()
+        .map_err(|e| format!("MyWallpaper is not running or unreachable ({})", e))?;
+    let status = response.status();
+    let body: Value = response.json().unwrap_or(Value::Null);
+    if status.is_success() {
+        Ok(body)
+    } else {
+        Err(body
+            .get("error")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("HTTP {}", status)))
+    }
+}
+
+/// Handle a CLI invocation. Returns the exit code when the command was fully
+/// handled here (or the launch flags are invalid), or `None` to continue
+/// startup so the single-instance plugin forwards the arguments to the running
+/// instance.
+pub fn run(args: &[String]) -> Option<i32> {
+    let Some(parsed) = parse(args) else {
+        return match parse_launch(args) {
+            Ok(options) => {
+                let _ = LAUNCH.set(options);
+                None
+            }
+            Err(message) => {
+                attach_console();
+                eprintln!("{}\n\n{}", message, USAGE);
+                Some(2)
+            }
+        };
+    };
+    attach_console();
+    let command = match parsed {
+        Ok(c) => c,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("{}\n", message);
+            }
+            eprintln!("{}", USAGE);
+            return Some(2);
+        }
+    };
+
+    match local_api_endpoint() {
+        Some((port, key)) => match call_local_api(port, &key, &command) {
+            Ok(body) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&body).unwrap_or_default()
+                );
+                Some(0)
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                Some(1)
+            }
+        },
+        None if command == CliCommand::Status => {
+            eprintln!("Error: `status` needs the local API (enable it in MyWallpaper settings)");
+            Some(1)
+        }
+        None => {
+            println!("Forwarded to the running instance (enable the local API to see results)");
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Server side (running instance, via single-instance)
+// ============================================================================
+
+/// Arguments of a second launch, as understood by the running instance.
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondInstance {
+    /// Raw arguments, program name excluded
+    pub args: Vec<String>,
+    pub cwd: String,
+    /// Subcommand or control flag; the last one wins
+    pub command: Option<CliCommand>,
+    /// `mywallpaper://` links
+    pub links: Vec<String>,
+    /// Existing files, made absolute against `cwd`
+    pub files: Vec<String>,
+    /// Arguments matching none of the above
+    pub unknown: Vec<String>,
+}
+
+/// Flags the launcher itself passes (autostart); not worth reporting.
+const LAUNCH_FLAGS: [&str; 1] = ["--minimized"];
+
+/// Sort forwarded arguments (program name included) into a `SecondInstance`.
+pub fn parse_forwarded(args: &[String], cwd: &str) -> SecondInstance {
+    let mut parsed = SecondInstance {
+        args: args.iter().skip(1).cloned().collect(),
+        cwd: cwd.to_string(),
+        ..Default::default()
+    };
+    if let Some(Ok(command)) = parse(args) {
+        parsed.command = Some(command);
+        return parsed;
+    }
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        let command = match arg.as_str() {
+            "--pause" | "--paused" => Some(CliCommand::Pause),
+            "--resume" => Some(CliCommand::Resume),
+            "--next" => Some(CliCommand::Next),
+            "--apply" | "--url" => match rest.next() {
+                Some(target) => Some(CliCommand::Apply(target.clone())),
+                None => {
+                    parsed.unknown.push(arg.clone());
+                    None
+                }
+            },
+            a if a.starts_with("--apply=") || a.starts_with("--url=") => a
+                .split_once('=')
+                .map(|(_, target)| CliCommand::Apply(target.into())),
+            a if LAUNCH_FLAGS.contains(&a) => None,
+            a if a.starts_with("mywallpaper:") => {
+                parsed.links.push(arg.clone());
+                None
+            }
+            a => {
+                let path = Path::new(cwd).join(a);
+                if path.is_file() {
+                    parsed.files.push(path.to_string_lossy().into_owned());
+                } else {
+                    parsed.unknown.push(arg.clone());
+                }
+                None
+            }
+        };
+        if command.is_some() {
+            parsed.command = command;
+        }
+    }
+    parsed
+}
+
+fn run_command(command: &CliCommand) {
+    log::info!("[cli] Forwarded command: {:?}", command);
+    let result = match command {
+        CliCommand::Status => Ok(()),
+        CliCommand::Pause => crate::desktop_actions::set_paused(true),
+        CliCommand::Resume => crate::desktop_actions::set_paused(false),
+        CliCommand::Next => crate::desktop_actions::run(&DesktopAction::NextWallpaper),
+        CliCommand::Apply(target) if is_url(target) => {
+            crate::local_api::apply_wallpaper(None, Some(target)).map(|_| ())
+        }
+        CliCommand::Apply(id) => crate::local_api::apply_wallpaper(Some(id), None).map(|_| ()),
+    };
+    if let Err(e) = result {
+        log::warn!("[cli] {:?} failed: {}", command, e);
+    }
+}
+
+/// Act on arguments forwarded by a second instance and report them to the
+/// frontend. `.mwpack` files are installed; other files are left to the frontend.
+pub fn handle_second_instance(app: &tauri::AppHandle, args: Vec<String>, cwd: String) {
+    let parsed = parse_forwarded(&args, &cwd);
+    if let Some(command) = &parsed.command {
+        run_command(command);
+    }
+    crate::deep_link::handle_links(app, parsed.links.iter().cloned());
+    let packs: Vec<String> = parsed
+        .files
+        .iter()
+        .filter(|f| {
+            Path::new(f)
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("mwpack"))
+        })
+        .cloned()
+        .collect();
+    if !packs.is_empty() {
+        std::thread::spawn(move || {
+            for pack in packs {
+                if let Err(e) = crate::mwpack::install(&pack) {
+                    log::warn!("[cli] Installing {} failed: {}", pack, e);
+                }
+            }
+        });
+    }
+    if !parsed.unknown.is_empty() {
+        log::debug!("[cli] Unrecognized arguments: {:?}", parsed.unknown);
+    }
+    let _ = app.emit_app_event(&AppEvent::SecondInstance(parsed));
+}
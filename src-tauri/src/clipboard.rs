@@ -0,0 +1,379 @@
+//! Clipboard access for productivity widgets (notes, color pickers, …).
+//!
+//! Text is exchanged as UTF-16 (`CF_UNICODETEXT`), images as PNG: reads take
+//! the registered "PNG" format when an app put one there and convert the
+//! `CF_DIB` bitmap otherwise; writes put both. While subscribed, the clipboard
+//! sequence number is polled and changes are emitted as `clipboard-changed`
+//! with the available formats only, never the content. Subscribers that lose
+//! the clipboard capability are dropped at the next change.
+
+use crate::error::{AppError, AppResult};
+use crate::permissions::{self, Capability};
+use log::{error, info};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use typeshare::typeshare;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Images larger than this (either side) are refused on write
+const MAX_IMAGE_SIZE: u32 = 16384;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Subscribed wallpaper ids; "" is the app itself
+static SUBSCRIBERS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardFormats {
+    pub text: bool,
+    pub image: bool,
+}
+
+pub fn read_text() -> AppResult<Option<String>> {
+    native::read_text()
+}
+
+pub fn write_text(text: &str) -> AppResult<()> {
+    native::write_text(text)
+}
+
+/// Clipboard image as PNG, None when the clipboard holds no image.
+pub fn read_image() -> AppResult<Option<Vec<u8>>> {
+    native::read_image()
+}
+
+/// Put an image on the clipboard. `data` is base64 of any supported image
+/// format, optionally as a `data:` URL (e.g., from `canvas.toDataURL()`).
+pub fn write_image(data: &str) -> AppResult<()> {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    let encoded = match data.split_once(";base64,") {
+        Some((prefix, rest)) if prefix.starts_with("data:") => rest,
+        _ => data,
+    };
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|_| AppError::Validation("Image must be base64 encoded".into()))?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| AppError::Validation(format!("Unsupported image: {}", e)))?
+        .into_rgba8();
+    if image.width() > MAX_IMAGE_SIZE || image.height() > MAX_IMAGE_SIZE {
+        return Err(AppError::Validation(format!(
+            "Clipboard images are limited to {}x{}",
+            MAX_IMAGE_SIZE, MAX_IMAGE_SIZE
+        )));
+    }
+    native::write_image(&image)
+}
+
+pub fn formats() -> ClipboardFormats {
+    native::formats()
+}
+
+/// Add or remove a `clipboard-changed` subscriber (`None` = the app).
+pub fn set_subscribed(wallpaper_id: Option<&str>, subscribed: bool) {
+    let Ok(mut subscribers) = SUBSCRIBERS.lock() else {
+        return;
+    };
+    let subscribers = subscribers.get_or_insert_with(HashSet::new);
+    let id = wallpaper_id.unwrap_or_default().to_string();
+    let changed = if subscribed {
+        subscribers.insert(id)
+    } else {
+        subscribers.remove(&id)
+    };
+    if changed {
+        info!(
+            "[clipboard] {} subscriber(s) for changes",
+            subscribers.len()
+        );
+    }
+}
+
+/// Drop wallpapers whose capability was revoked; true while anyone is left.
+fn has_permitted_subscribers() -> bool {
+    let Ok(mut subscribers) = SUBSCRIBERS.lock() else {
+        return false;
+    };
+    let Some(subscribers) = subscribers.as_mut() else {
+        return false;
+    };
+    subscribers.retain(|id| id.is_empty() || permissions::is_granted(id, Capability::Clipboard));
+    !subscribers.is_empty()
+}
+
+/// Start the change watcher (idle until someone subscribes).
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut last = native::sequence_number();
+        while RUNNING.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = native::sequence_number();
+            if current == last {
+                continue;
+            }
+            last = current;
+            if !has_permitted_subscribers() {
+                continue;
+            }
+            if let Err(e) = app.emit_app_event(&AppEvent::ClipboardChanged(formats())) {
+                error!("[clipboard] Failed to emit event: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+mod native {
+    use super::ClipboardFormats;
+    use crate::error::{AppError, AppResult};
+    use windows::core::w;
+    use windows::Win32::Foundation::{HANDLE, HGLOBAL};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, GetClipboardData, GetClipboardSequenceNumber,
+        IsClipboardFormatAvailable, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{
+        GlobalAlloc, GlobalFree, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+    };
+    use windows::Win32::System::Ole::{CF_DIB, CF_UNICODETEXT};
+
+    const OPEN_ATTEMPTS: u32 = 10;
+    const BITMAPINFOHEADER_SIZE: usize = 40;
+    const BI_BITFIELDS: u32 = 3;
+
+    /// Open clipboard, closed on drop.
+    struct Clipboard;
+
+    impl Clipboard {
+        /// Another app may hold the clipboard for a moment; retry briefly.
+        fn open() -> AppResult<Self> {
+            for _ in 0..OPEN_ATTEMPTS {
+                if unsafe { OpenClipboard(None) }.is_ok() {
+                    return Ok(Self);
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            Err(AppError::Clipboard(
+                "The clipboard is in use by another application".into(),
+            ))
+        }
+
+        /// Copy of the data in `format`, None when not available.
+        fn get(&self, format: u32) -> Option<Vec<u8>> {
+            unsafe {
+                let handle = GetClipboardData(format).ok()?;
+                let global = HGLOBAL(handle.0);
+                let ptr = GlobalLock(global) as *const u8;
+                if ptr.is_null() {
+                    return None;
+                }
+                let bytes = std::slice::from_raw_parts(ptr, GlobalSize(global)).to_vec();
+                let _ = GlobalUnlock(global);
+                Some(bytes)
+            }
+        }
+
+        /// Hand `bytes` to the clipboard, which then owns the memory.
+        fn set(&self, format: u32, bytes: &[u8]) -> AppResult<()> {
+            let err = |e: windows::core::Error| AppError::Clipboard(e.to_string());
+            unsafe {
+                let global = GlobalAlloc(GMEM_MOVEABLE, bytes.len()).map_err(err)?;
+                let ptr = GlobalLock(global) as *mut u8;
+                if ptr.is_null() {
+                    let _ = GlobalFree(global);
+                    return Err(AppError::Clipboard("Out of memory".into()));
+                }
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+                let _ = GlobalUnlock(global);
+                if let Err(e) = SetClipboardData(format, HANDLE(global.0)) {
+                    let _ = GlobalFree(global);
+                    return Err(err(e));
+                }
+            }
+            Ok(())
+        }
+
+        fn clear(&self) -> AppResult<()> {
+            unsafe { EmptyClipboard() }.map_err(|e| AppError::Clipboard(e.to_string()))
+        }
+    }
+
+    impl Drop for Clipboard {
+        fn drop(&mut self) {
+            let _ = unsafe { CloseClipboard() };
+        }
+    }
+
+    /// Registered by browsers and Office; keeps transparency, unlike `CF_DIB`.
+    fn png_format() -> u32 {
+        unsafe { RegisterClipboardFormatW(w!("PNG")) }
+    }
+
+    fn available(format: u32) -> bool {
+        unsafe { IsClipboardFormatAvailable(format) }.is_ok()
+    }
+
+    pub fn sequence_number() -> u32 {
+        unsafe { GetClipboardSequenceNumber() }
+    }
+
+    pub fn formats() -> ClipboardFormats {
+        ClipboardFormats {
+            text: available(CF_UNICODETEXT.0 as u32),
+            image: available(png_format()) || available(CF_DIB.0 as u32),
+        }
+    }
+
+    pub fn read_text() -> AppResult<Option<String>> {
+        let clipboard = Clipboard::open()?;
+        let Some(bytes) = clipboard.get(CF_UNICODETEXT.0 as u32) else {
+            return Ok(None);
+        };
+        let wide: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect();
+        Ok(Some(String::from_utf16_lossy(&wide)))
+    }
+
+    pub fn write_text(text: &str) -> AppResult<()> {
+        let bytes: Vec<u8> = text
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let clipboard = Clipboard::open()?;
+        clipboard.clear()?;
+        clipboard.set(CF_UNICODETEXT.0 as u32, &bytes)
+    }
+
+    pub fn read_image() -> AppResult<Option<Vec<u8>>> {
+        let clipboard = Clipboard::open()?;
+        if let Some(png) = clipboard.get(png_format()) {
+            return Ok(Some(png));
+        }
+        let Some(dib) = clipboard.get(CF_DIB.0 as u32) else {
+            return Ok(None);
+        };
+        drop(clipboard);
+        let image =
+            image::load_from_memory_with_format(&dib_to_bmp(&dib)?, image::ImageFormat::Bmp)
+                .map_err(|e| AppError::Clipboard(format!("Unreadable bitmap: {}", e)))?;
+        let mut png = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| AppError::Clipboard(format!("Cannot encode image: {}", e)))?;
+        Ok(Some(png.into_inner()))
+    }
+
+    /// Prefix a packed DIB with the BMP file header.
+    fn dib_to_bmp(dib: &[u8]) -> AppResult<Vec<u8>> {
+        let u32_at = |i: usize| {
+            dib.get(i..i + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let invalid = || AppError::Clipboard("Invalid bitmap on the clipboard".into());
+        let header_size = u32_at(0).ok_or_else(invalid)? as usize;
+        let bit_count = dib.get(14..16).ok_or_else(invalid)?;
+        let bit_count = u16::from_le_bytes([bit_count[0], bit_count[1]]);
+        let compression = u32_at(16).ok_or_else(invalid)?;
+        let colors_used = u32_at(32).ok_or_else(invalid)? as usize;
+        let palette = match colors_used {
+            0 if bit_count <= 8 => (1usize << bit_count) * 4,
+            n => n * 4,
+        };
+        // Color masks follow a plain BITMAPINFOHEADER
+        let masks = if compression == BI_BITFIELDS && header_size == BITMAPINFOHEADER_SIZE {
+            12
+        } else {
+            0
+        };
+        let offset = 14 + header_size + masks + palette;
+        let mut bmp = Vec::with_capacity(14 + dib.len());
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&((14 + dib.len()) as u32).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]);
+        bmp.extend_from_slice(&(offset as u32).to_le_bytes());
+        bmp.extend_from_slice(dib);
+        Ok(bmp)
+    }
+
+    /// 32-bit bottom-up DIB of `image`.
+    fn to_dib(image: &image::RgbaImage) -> Vec<u8> {
+        let (width, height) = image.dimensions();
+        let mut dib = Vec::with_capacity(BITMAPINFOHEADER_SIZE + (width * height * 4) as usize);
+        dib.extend_from_slice(&(BITMAPINFOHEADER_SIZE as u32).to_le_bytes());
+        dib.extend_from_slice(&(width as i32).to_le_bytes());
+        dib.extend_from_slice(&(height as i32).to_le_bytes());
+        dib.extend_from_slice(&1u16.to_le_bytes()); // planes
+        dib.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+        dib.extend_from_slice(&0u32.to_le_bytes()); // BI_RGB
+        dib.extend_from_slice(&(width * height * 4).to_le_bytes());
+        dib.extend_from_slice(&[0; 16]); // resolution, palette
+        for row in image.rows().rev() {
+            for pixel in row {
+                let [r, g, b, a] = pixel.0;
+                dib.extend_from_slice(&[b, g, r, a]);
+            }
+        }
+        dib
+    }
+
+    pub fn write_image(image: &image::RgbaImage) -> AppResult<()> {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| AppError::Clipboard(format!("Cannot encode image: {}", e)))?;
+        let dib = to_dib(image);
+        let clipboard = Clipboard::open()?;
+        clipboard.clear()?;
+        clipboard.set(png_format(), png.get_ref())?;
+        clipboard.set(CF_DIB.0 as u32, &dib)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod native {
+    use super::ClipboardFormats;
+    use crate::error::{AppError, AppResult};
+
+    fn unsupported() -> AppError {
+        AppError::Clipboard("Clipboard access is not available on this platform yet".into())
+    }
+
+    pub fn sequence_number() -> u32 {
+        0
+    }
+
+    pub fn formats() -> ClipboardFormats {
+        ClipboardFormats::default()
+    }
+
+    pub fn read_text() -> AppResult<Option<String>> {
+        Err(unsupported())
+    }
+
+    pub fn write_text(_text: &str) -> AppResult<()> {
+        Err(unsupported())
+    }
+
+    pub fn read_image() -> AppResult<Option<Vec<u8>>> {
+        Err(unsupported())
+    }
+
+    pub fn write_image(_image: &image::RgbaImage) -> AppResult<()> {
+        Err(unsupported())
+    }
+}
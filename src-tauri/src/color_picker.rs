@@ -0,0 +1,140 @@
+//! Screen color picker: a crosshair over the whole screen, returning the color
+//! under the cursor on click.
+//!
+//! The crosshair is a nearly transparent topmost window spanning the virtual
+//! screen, which only exists to own the cursor and the click. It is hidden
+//! before the pixel is read so it never tints the result.
+
+use crate::error::{AppError, AppResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PICKING: AtomicBool = AtomicBool::new(false);
+
+/// Block until the user clicks (`#rrggbb`) or cancels with Esc/right-click (None).
+pub fn pick() -> AppResult<Option<String>> {
+    if PICKING.swap(true, Ordering::SeqCst) {
+        return Err(AppError::Validation(
+            "A color pick is already in progress".into(),
+        ));
+    }
+    let result = crosshair::run();
+    PICKING.store(false, Ordering::SeqCst);
+    Ok(result?.map(|[r, g, b]| format!("#{:02x}{:02x}{:02x}", r, g, b)))
+}
+
+#[cfg(target_os = "windows")]
+mod crosshair {
+    use crate::error::{AppError, AppResult};
+    use std::cell::Cell;
+    use windows::core::w;
+    use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, WPARAM};
+    use windows::Win32::Graphics::Dwm::DwmFlush;
+    use windows::Win32::Graphics::Gdi::{GetDC, GetPixel, ReleaseDC, CLR_INVALID};
+    use windows::Win32::UI::Input::KeyboardAndMouse::VK_ESCAPE;
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    thread_local! {
+        /// COLORREF picked by the last click on this thread
+        static PICKED: Cell<Option<u32>> = const { Cell::new(None) };
+    }
+
+    unsafe fn pixel_at(hwnd: HWND, pt: POINT) -> Option<u32> {
+        let _ = ShowWindow(hwnd, SW_HIDE);
+        // Wait for the compositor to present a frame without the crosshair
+        let _ = DwmFlush();
+        let dc = GetDC(None);
+        let color = GetPixel(dc, pt.x, pt.y);
+        let _ = ReleaseDC(None, dc);
+        (color.0 != CLR_INVALID).then_some(color.0)
+    }
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        match msg {
+            WM_SETCURSOR => {
+                if let Ok(cross) = LoadCursorW(None, IDC_CROSS) {
+                    SetCursor(cross);
+                }
+                LRESULT(1)
+            }
+            WM_LBUTTONDOWN => {
+                let mut pt = POINT::default();
+                if GetCursorPos(&mut pt).is_ok() {
+                    PICKED.with(|p| p.set(pixel_at(hwnd, pt)));
+                }
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_RBUTTONDOWN => {
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_KEYDOWN if wparam.0 == VK_ESCAPE.0 as usize => {
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_DESTROY => {
+                PostQuitMessage(0);
+                LRESULT(0)
+            }
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+
+    pub fn run() -> AppResult<Option<[u8; 3]>> {
+        unsafe {
+            PICKED.with(|p| p.set(None));
+            let cls = w!("MWP_ColorPicker");
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                lpszClassName: cls,
+                ..Default::default()
+            };
+            let _ = RegisterClassW(&wc);
+            let hwnd = CreateWindowExW(
+                WS_EX_TOPMOST | WS_EX_LAYERED | WS_EX_TOOLWINDOW,
+                cls,
+                w!(""),
+                WS_POPUP,
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                GetSystemMetrics(SM_CYVIRTUALSCREEN),
+                None,
+                None,
+                None,
+                None,
+            )
+            .map_err(|e| AppError::WindowLayer(e.to_string()))?;
+            // Alpha 0 would let clicks fall through; 1 is invisible but hit-testable
+            let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), 1, LWA_ALPHA);
+            let _ = ShowWindow(hwnd, SW_SHOW);
+            let _ = SetForegroundWindow(hwnd);
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+            // COLORREF is 0x00BBGGRR
+            Ok(PICKED
+                .with(|p| p.take())
+                .map(|c| [c as u8, (c >> 8) as u8, (c >> 16) as u8]))
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod crosshair {
+    use crate::error::{AppError, AppResult};
+
+    pub fn run() -> AppResult<Option<[u8; 3]>> {
+        Err(AppError::WindowLayer(
+            "Screen color picking is only supported on Windows".into(),
+        ))
+    }
+}
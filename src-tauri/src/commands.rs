@@ -2,6 +2,7 @@
 
 use crate::error::{AppError, AppResult};
 use crate::events::{AppEvent, EmitAppEvent};
+use crate::permissions::{self, Capability};
 use crate::system_monitor;
 use log::info;
 use serde::Serialize;
@@ -141,21 +142,6 @@ fn validate_update_version(current: &str, candidate: &str) -> AppResult<()> {
     Ok(())
 }
 
-const ALLOWED_DEEP_LINK_ACTIONS: &[&str] = &["callback", "auth", "oauth", "login", "app"];
-
-pub fn validate_deep_link(raw: &str) -> Option<String> {
-    let parsed = url::Url::parse(raw).ok()?;
-    if parsed.scheme() != "mywallpaper" {
-        return None;
-    }
-    if let Some(host) = parsed.host_str() {
-        if !host.is_empty() && !ALLOWED_DEEP_LINK_ACTIONS.contains(&host) {
-            return None;
-        }
-    }
-    Some(parsed.to_string())
-}
-
 // ============================================================================
 // Commands
 // ============================================================================
@@ -172,13 +158,331 @@ pub fn get_system_info() -> SystemInfo {
 }
 
 #[tauri::command]
-pub fn get_system_data(categories: Vec<String>) -> system_monitor::SystemData {
-    system_monitor::collect_system_data(system_monitor::parse_categories(&categories))
+pub async fn get_gpu_info() -> AppResult<crate::gpu::GpuReport> {
+    Ok(tauri::async_runtime::spawn_blocking(crate::gpu::get_gpu_info).await?)
+}
+
+/// Try a wallpaper in a regular window before applying it to the desktop.
+// Async: creating a window from a sync command deadlocks on Windows
+#[tauri::command]
+pub async fn open_preview(
+    app: tauri::AppHandle,
+    url: String,
+    monitor: Option<u32>,
+) -> AppResult<()> {
+    crate::preview::open(&app, &url, monitor)
+}
+
+#[tauri::command]
+pub fn close_preview(app: tauri::AppHandle) -> AppResult<()> {
+    crate::preview::close(&app)
+}
+
+#[tauri::command]
+pub async fn open_hub_window(app: tauri::AppHandle, section: Option<String>) -> AppResult<()> {
+    crate::hub::open(&app, section.as_deref())
+}
+
+#[tauri::command]
+pub fn close_hub_window(app: tauri::AppHandle) -> AppResult<()> {
+    crate::hub::close(&app)
+}
+
+#[tauri::command]
+pub fn list_widgets() -> crate::widgets::WidgetList {
+    crate::widgets::list()
 }
 
+/// Insert or replace a desktop widget (matched by id).
+// Async: creating a window from a sync command deadlocks on Windows
 #[tauri::command]
-pub fn subscribe_system_data(categories: Vec<String>) {
+pub async fn save_widget(
+    app: tauri::AppHandle,
+    widget: crate::widgets::DesktopWidget,
+) -> AppResult<()> {
+    crate::widgets::save(&app, widget)
+}
+
+#[tauri::command]
+pub fn delete_widget(app: tauri::AppHandle, id: String) -> AppResult<()> {
+    crate::widgets::delete(&app, &id)
+}
+
+#[tauri::command]
+pub async fn set_widget_visible(app: tauri::AppHandle, id: String, visible: bool) -> AppResult<()> {
+    crate::widgets::set_visible(&app, &id, visible)
+}
+
+#[tauri::command]
+pub fn set_widget_edit_mode(app: tauri::AppHandle, editing: bool) -> AppResult<()> {
+    crate::widgets::set_edit_mode(&app, editing)
+}
+
+/// Called from a widget page on mouse down while in edit mode.
+#[tauri::command]
+pub fn start_widget_drag(window: tauri::WebviewWindow) -> AppResult<()> {
+    crate::widgets::start_drag(&window)
+}
+
+// `wallpaper_id` is set when the app relays a request from wallpaper content;
+// see permissions.rs.
+
+#[tauri::command]
+pub fn get_system_data(
+    webview: tauri::Webview,
+    categories: Vec<String>,
+    wallpaper_id: Option<String>,
+) -> AppResult<system_monitor::SystemData> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::SystemStats)?;
+    Ok(system_monitor::collect_system_data(
+        system_monitor::parse_categories(&categories),
+    ))
+}
+
+#[tauri::command]
+pub fn subscribe_system_data(
+    webview: tauri::Webview,
+    categories: Vec<String>,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::SystemStats)?;
     system_monitor::set_poll_mask(system_monitor::parse_categories(&categories));
+    Ok(())
+}
+
+/// Enable/disable `render-stats` events (presented FPS, dropped frames).
+#[tauri::command]
+pub fn subscribe_render_stats(enabled: bool) {
+    crate::render_stats::set_subscribed(enabled);
+}
+
+/// Enable/disable `cursor-position` events, `rate` per second at most
+/// (default 30, up to 60).
+#[tauri::command]
+pub fn subscribe_cursor_position(enabled: bool, rate: Option<u32>) -> AppResult<()> {
+    crate::cursor::set_subscribed(enabled, rate)
+}
+
+/// Enable/disable `audio-spectrum` events for an audio source.
+#[tauri::command]
+pub fn subscribe_audio_spectrum(
+    webview: tauri::Webview,
+    source: crate::audio_spectrum::AudioSource,
+    enabled: bool,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    if enabled && source == crate::audio_spectrum::AudioSource::Microphone {
+        permissions::require(&webview, wallpaper_id.as_deref(), Capability::Microphone)?;
+    }
+    crate::audio_spectrum::set_subscribed(source, enabled);
+    Ok(())
+}
+
+/// Set the wallpaper audio level (0-1) on top of the scene's own mixing;
+/// saved in backend settings.
+#[tauri::command]
+pub fn set_wallpaper_volume(app: tauri::AppHandle, level: f32) -> AppResult<()> {
+    crate::wallpaper_volume::set(&app, level)
+}
+
+/// Dim and/or warm the wallpaper (level 0-1, temperature 1000-6500 K);
+/// the dimming schedule in backend settings takes precedence while active.
+#[tauri::command]
+pub fn set_dimming(level: f32, temperature: Option<u32>) -> AppResult<()> {
+    crate::dimming::set(crate::dimming::Dimming {
+        level,
+        temperature: temperature.unwrap_or(crate::dimming::NEUTRAL_KELVIN),
+    })
+}
+
+/// Serve folder `path` over `mywallpaper-asset://` to wallpaper `wallpaper_id`
+/// (loaded from `wallpaper_url`), or to the app when both are omitted;
+/// returns its base URL.
+#[tauri::command]
+pub fn grant_asset_dir(
+    webview: tauri::Webview,
+    path: String,
+    wallpaper_id: Option<String>,
+    wallpaper_url: Option<String>,
+) -> AppResult<crate::asset_protocol::AssetDir> {
+    permissions::require_trusted(&webview)?;
+    crate::asset_protocol::grant(&path, wallpaper_id.as_deref(), wallpaper_url.as_deref())
+}
+
+#[tauri::command]
+pub fn revoke_asset_dir(webview: tauri::Webview, id: String) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    crate::asset_protocol::revoke(&id)
+}
+
+#[tauri::command]
+pub fn list_asset_dirs(webview: tauri::Webview) -> AppResult<Vec<crate::asset_protocol::AssetDir>> {
+    permissions::require_trusted(&webview)?;
+    crate::asset_protocol::list()
+}
+
+#[tauri::command]
+pub async fn get_browser_storage_usage() -> AppResult<crate::browser_data::BrowserStorageUsage> {
+    tauri::async_runtime::spawn_blocking(crate::browser_data::usage).await?
+}
+
+/// Clear cookies, caches or site storage of the desktop webview.
+#[tauri::command]
+pub async fn clear_browser_storage(
+    webview: tauri::Webview,
+    app: tauri::AppHandle,
+    kinds: Vec<crate::browser_data::BrowserDataKind>,
+) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || crate::browser_data::clear(&app, &kinds)).await?
+}
+
+#[tauri::command]
+pub fn get_power_mode() -> crate::power_mode::PowerMode {
+    crate::power_mode::get()
+}
+
+/// Seconds since the last keyboard or mouse input.
+#[tauri::command]
+pub fn get_idle_seconds() -> u64 {
+    crate::idle::get_idle_seconds()
+}
+
+#[tauri::command]
+pub fn get_accessibility_prefs() -> crate::accessibility::AccessibilityPrefs {
+    crate::accessibility::get()
+}
+
+#[tauri::command]
+pub fn get_connection_cost() -> crate::metered::ConnectionCost {
+    crate::metered::get()
+}
+
+#[tauri::command]
+pub fn get_locale_info() -> crate::locale::LocaleInfo {
+    crate::locale::get_locale_info()
+}
+
+/// Installed font families; file paths only for the app itself.
+#[tauri::command]
+pub async fn list_system_fonts(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+) -> AppResult<Vec<crate::fonts::FontFamily>> {
+    let with_paths = wallpaper_id.is_none() && permissions::is_trusted(webview.label());
+    tauri::async_runtime::spawn_blocking(move || crate::fonts::list_system_fonts(with_paths))
+        .await?
+}
+
+#[tauri::command]
+pub async fn clipboard_read_text(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+) -> AppResult<Option<String>> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Clipboard)?;
+    tauri::async_runtime::spawn_blocking(crate::clipboard::read_text).await?
+}
+
+#[tauri::command]
+pub async fn clipboard_write_text(
+    webview: tauri::Webview,
+    text: String,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Clipboard)?;
+    tauri::async_runtime::spawn_blocking(move || crate::clipboard::write_text(&text)).await?
+}
+
+/// Clipboard image as PNG bytes; empty when the clipboard holds no image.
+#[tauri::command]
+pub async fn clipboard_read_image(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+) -> AppResult<tauri::ipc::Response> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Clipboard)?;
+    tauri::async_runtime::spawn_blocking(|| {
+        let png = crate::clipboard::read_image()?.unwrap_or_default();
+        Ok(tauri::ipc::Response::new(png))
+    })
+    .await?
+}
+
+/// `image`: base64 (or a base64 `data:` URL) of a PNG, JPEG, WebP, … image.
+#[tauri::command]
+pub async fn clipboard_write_image(
+    webview: tauri::Webview,
+    image: String,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Clipboard)?;
+    tauri::async_runtime::spawn_blocking(move || crate::clipboard::write_image(&image)).await?
+}
+
+/// Enable/disable `clipboard-changed` events for the caller.
+#[tauri::command]
+pub fn subscribe_clipboard(
+    webview: tauri::Webview,
+    enabled: bool,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    if enabled {
+        permissions::require(&webview, wallpaper_id.as_deref(), Capability::Clipboard)?;
+    }
+    crate::clipboard::set_subscribed(wallpaper_id.as_deref(), enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_location(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+) -> AppResult<crate::location::Location> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Location)?;
+    tauri::async_runtime::spawn_blocking(crate::location::get_location).await?
+}
+
+/// Sunrise, sunset, golden hour, sun elevation and moon phase for `date`
+/// ("YYYY-MM-DD", today when omitted) at the current location.
+#[tauri::command]
+pub async fn get_sun_info(
+    webview: tauri::Webview,
+    app: tauri::AppHandle,
+    date: Option<String>,
+    wallpaper_id: Option<String>,
+) -> AppResult<crate::sun::SunInfo> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Location)?;
+    tauri::async_runtime::spawn_blocking(move || crate::sun::get_sun_info(&app, date.as_deref()))
+        .await?
+}
+
+#[tauri::command]
+pub fn get_system_volume(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+) -> AppResult<crate::system_monitor::AudioInfo> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::SystemStats)?;
+    crate::system_volume::get()
+}
+
+#[tauri::command]
+pub fn set_system_volume(
+    webview: tauri::Webview,
+    level: f32,
+    muted: Option<bool>,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::SystemVolume)?;
+    crate::system_volume::set(level, muted)
+}
+
+#[tauri::command]
+pub fn set_desktop_icons_visible(
+    webview: tauri::Webview,
+    visible: bool,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::DesktopIcons)?;
+    crate::window_layer::set_desktop_icons_visible(visible)
 }
 
 fn build_updater(
@@ -253,6 +557,7 @@ pub async fn download_and_install_update(
 
 #[tauri::command]
 pub fn restart_app(app: tauri::AppHandle) {
+    crate::shutdown::run("Restarting");
     app.restart();
 }
 
@@ -265,6 +570,45 @@ pub async fn open_oauth_in_browser(app: tauri::AppHandle, url: String) -> AppRes
         .map_err(|e| AppError::OAuth(format!("Failed to open browser: {}", e)))
 }
 
+/// Loopback + PKCE sign-in; resolves with the token response.
+#[tauri::command]
+pub async fn start_oauth_flow(
+    app: tauri::AppHandle,
+    request: crate::oauth::OAuthFlowRequest,
+) -> AppResult<serde_json::Value> {
+    tauri::async_runtime::spawn_blocking(move || crate::oauth::run_flow(&app, request)).await?
+}
+
+#[tauri::command]
+pub fn cancel_oauth_flow() {
+    crate::oauth::cancel();
+}
+
+// The credential store may block (Secret Service unlock prompt): keep it off
+// the main thread.
+
+#[tauri::command]
+pub async fn secure_store_set(
+    webview: tauri::Webview,
+    key: String,
+    value: String,
+) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || crate::secure_store::set(&key, &value)).await?
+}
+
+#[tauri::command]
+pub async fn secure_store_get(webview: tauri::Webview, key: String) -> AppResult<Option<String>> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || crate::secure_store::get(&key)).await?
+}
+
+#[tauri::command]
+pub async fn secure_store_delete(webview: tauri::Webview, key: String) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || crate::secure_store::delete(&key)).await?
+}
+
 #[tauri::command]
 pub fn reload_window(app: tauri::AppHandle) -> AppResult<()> {
     app.emit_app_event(&AppEvent::ReloadApp)?;
@@ -272,22 +616,29 @@ pub fn reload_window(app: tauri::AppHandle) -> AppResult<()> {
 }
 
 #[tauri::command]
-pub fn get_media_info() -> AppResult<crate::media::MediaInfo> {
+pub fn get_media_info(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+) -> AppResult<crate::media::MediaInfo> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::MediaControl)?;
     crate::media::get_media_info()
 }
 
 #[tauri::command]
-pub fn media_play_pause() -> AppResult<()> {
+pub fn media_play_pause(webview: tauri::Webview, wallpaper_id: Option<String>) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::MediaControl)?;
     crate::media::media_play_pause()
 }
 
 #[tauri::command]
-pub fn media_next() -> AppResult<()> {
+pub fn media_next(webview: tauri::Webview, wallpaper_id: Option<String>) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::MediaControl)?;
     crate::media::media_next()
 }
 
 #[tauri::command]
-pub fn media_prev() -> AppResult<()> {
+pub fn media_prev(webview: tauri::Webview, wallpaper_id: Option<String>) -> AppResult<()> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::MediaControl)?;
     crate::media::media_prev()
 }
 
@@ -295,3 +646,530 @@ pub fn media_prev() -> AppResult<()> {
 pub fn update_discord_presence(details: String, state: String) -> AppResult<()> {
     crate::discord::update_presence(&details, &state)
 }
+
+#[tauri::command]
+pub fn list_profiles() -> crate::profiles::ProfileList {
+    crate::profiles::list_profiles()
+}
+
+#[tauri::command]
+pub fn save_profile(profile: crate::profiles::Profile) -> AppResult<()> {
+    crate::profiles::save_profile(profile)
+}
+
+#[tauri::command]
+pub fn delete_profile(id: String) -> AppResult<()> {
+    crate::profiles::delete_profile(&id)
+}
+
+#[tauri::command]
+pub fn apply_profile(app: tauri::AppHandle, id: String) -> AppResult<()> {
+    crate::profiles::apply_profile(&app, &id, false)
+}
+
+#[tauri::command]
+pub async fn list_local_wallpapers() -> AppResult<Vec<crate::library::LocalWallpaper>> {
+    tauri::async_runtime::spawn_blocking(crate::library::list_local_wallpapers).await?
+}
+
+#[tauri::command]
+pub fn apply_local_wallpaper(
+    app: tauri::AppHandle,
+    id: String,
+) -> AppResult<crate::library::LocalWallpaper> {
+    crate::library::apply_local_wallpaper(&app, &id)
+}
+
+/// Connected monitors with their HDR state, in `monitor_rects` order.
+#[tauri::command]
+pub async fn get_monitors() -> AppResult<Vec<crate::system_monitor::DisplayInfo>> {
+    Ok(tauri::async_runtime::spawn_blocking(|| {
+        crate::system_monitor::collect_display_info().unwrap_or_default()
+    })
+    .await?)
+}
+
+/// Set the real brightness (0-1) of a monitor (`get_monitors` order) over
+/// DDC/CI, or by scaling its gamma ramp when DDC/CI is unavailable.
+#[tauri::command]
+pub async fn set_monitor_brightness(
+    webview: tauri::Webview,
+    monitor_id: u32,
+    level: f32,
+    wallpaper_id: Option<String>,
+) -> AppResult<crate::brightness::BrightnessMethod> {
+    permissions::require(
+        &webview,
+        wallpaper_id.as_deref(),
+        Capability::DisplayBrightness,
+    )?;
+    // DDC/CI round trips take tens of milliseconds per monitor
+    tauri::async_runtime::spawn_blocking(move || crate::brightness::set(monitor_id, level)).await?
+}
+
+/// Capture the current wallpaper frame as PNG bytes; with `path`, the PNG is
+/// also written there (app only, not on behalf of a wallpaper).
+#[tauri::command]
+pub async fn capture_wallpaper_screenshot(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+    path: Option<String>,
+    max_size: Option<u32>,
+) -> AppResult<tauri::ipc::Response> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Screenshots)?;
+    if wallpaper_id.is_some() && path.is_some() {
+        return Err(AppError::Permission(
+            "Wallpapers cannot save screenshots to disk".into(),
+        ));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let png = crate::screenshot::capture_png(max_size)?;
+        if let Some(path) = path {
+            crate::screenshot::save_png(std::path::Path::new(&path), &png)?;
+        }
+        Ok(tauri::ipc::Response::new(png))
+    })
+    .await?
+}
+
+/// Let the user click anywhere on screen; returns the color there (`#rrggbb`),
+/// or None when cancelled.
+#[tauri::command]
+pub async fn pick_screen_color(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+) -> AppResult<Option<String>> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Screenshots)?;
+    tauri::async_runtime::spawn_blocking(crate::color_picker::pick).await?
+}
+
+/// Dominant/average colors of the current frame, or of local pack `source_id`.
+#[tauri::command]
+pub async fn get_wallpaper_palette(
+    webview: tauri::Webview,
+    wallpaper_id: Option<String>,
+    source_id: Option<String>,
+) -> AppResult<crate::palette::WallpaperPalette> {
+    if source_id.is_none() {
+        permissions::require(&webview, wallpaper_id.as_deref(), Capability::Screenshots)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || crate::palette::palette(source_id.as_deref()))
+        .await?
+}
+
+/// Show an OS notification; returns the id reported in `notification-action`.
+#[tauri::command]
+pub async fn show_notification(
+    webview: tauri::Webview,
+    app: tauri::AppHandle,
+    notification: crate::notifications::NotificationRequest,
+    wallpaper_id: Option<String>,
+) -> AppResult<String> {
+    permissions::require(&webview, wallpaper_id.as_deref(), Capability::Notifications)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::notifications::show(&app, wallpaper_id.as_deref(), &notification)
+    })
+    .await?
+}
+
+/// Load a remote wallpaper behind a crossfade/slide transition.
+#[tauri::command]
+pub async fn apply_wallpaper(
+    app: tauri::AppHandle,
+    url: String,
+    transition: Option<crate::transition::Transition>,
+) -> AppResult<String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::transition::apply_url(&app, &url, &transition.unwrap_or_default())
+    })
+    .await?
+}
+
+/// The page finished rendering newly applied content.
+#[tauri::command]
+pub fn notify_wallpaper_ready() {
+    crate::transition::content_ready();
+}
+
+/// Subscribed Wallpaper Engine Workshop items, without importing them.
+#[tauri::command]
+pub async fn scan_workshop_items() -> AppResult<Vec<crate::wallpaper_engine::WorkshopItem>> {
+    Ok(tauri::async_runtime::spawn_blocking(crate::wallpaper_engine::scan_workshop).await?)
+}
+
+/// Import the given project folders, or every discoverable project when omitted.
+#[tauri::command]
+pub async fn import_wallpaper_engine_projects(
+    paths: Option<Vec<String>>,
+) -> AppResult<Vec<crate::library::ImportResult>> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::wallpaper_engine::import_projects(paths.unwrap_or_default())
+    })
+    .await?
+}
+
+/// Install a `.mwpack` from a local path or an HTTPS URL.
+#[tauri::command]
+pub async fn install_pack(
+    webview: tauri::Webview,
+    path_or_url: String,
+) -> AppResult<crate::library::LocalWallpaper> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || crate::mwpack::install(&path_or_url)).await?
+}
+
+#[tauri::command]
+pub async fn uninstall_pack(webview: tauri::Webview, id: String) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || crate::mwpack::uninstall(&id)).await?
+}
+
+/// Check a `.mwpack` or project folder against the pack format before publishing.
+#[tauri::command]
+pub async fn validate_pack(path: String) -> AppResult<crate::mwpack::PackReport> {
+    tauri::async_runtime::spawn_blocking(move || crate::mwpack::validate(&path)).await?
+}
+
+#[tauri::command]
+pub fn list_downloads() -> Vec<crate::downloads::DownloadInfo> {
+    crate::downloads::list()
+}
+
+#[tauri::command]
+pub fn cancel_download(id: u64) -> AppResult<()> {
+    crate::downloads::cancel(id)
+}
+
+#[tauri::command]
+pub fn set_tray_menu(
+    app: tauri::AppHandle,
+    items: Vec<crate::tray::TrayMenuItem>,
+) -> AppResult<()> {
+    crate::tray::set_custom_items(&app, items)
+}
+
+#[tauri::command]
+pub async fn import_lively_wallpapers(
+    paths: Vec<String>,
+) -> AppResult<Vec<crate::library::ImportResult>> {
+    tauri::async_runtime::spawn_blocking(move || crate::lively::import_wallpapers(paths)).await?
+}
+
+#[tauri::command]
+pub async fn play_native_video(
+    path: String,
+    monitor: Option<u32>,
+    looping: Option<bool>,
+    volume: Option<f32>,
+    layer: Option<crate::native_video::VideoLayer>,
+) -> AppResult<()> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::native_video::play(
+            &path,
+            monitor,
+            looping.unwrap_or(true),
+            volume.unwrap_or(0.0),
+            layer.unwrap_or_default(),
+        )
+    })
+    .await?
+}
+
+#[tauri::command]
+pub fn pause_native_video(monitor: Option<u32>) -> AppResult<()> {
+    crate::native_video::set_playing(monitor, false)
+}
+
+#[tauri::command]
+pub fn resume_native_video(monitor: Option<u32>) -> AppResult<()> {
+    crate::native_video::set_playing(monitor, true)
+}
+
+#[tauri::command]
+pub fn set_native_video_loop(monitor: Option<u32>, looping: bool) -> AppResult<()> {
+    crate::native_video::set_looping(monitor, looping)
+}
+
+#[tauri::command]
+pub fn set_native_video_volume(monitor: Option<u32>, volume: f32) -> AppResult<()> {
+    crate::native_video::set_volume(monitor, volume)
+}
+
+#[tauri::command]
+pub fn stop_native_video(monitor: Option<u32>) -> AppResult<()> {
+    crate::native_video::stop(monitor)
+}
+
+#[tauri::command]
+pub fn get_native_video_status() -> Vec<crate::native_video::VideoStatus> {
+    crate::native_video::status()
+}
+
+#[tauri::command]
+pub async fn apply_animated_image(
+    path: String,
+    fit: Option<crate::animated_image::ImageFit>,
+    fps: Option<u32>,
+    monitor: Option<u32>,
+) -> AppResult<()> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::animated_image::apply(&path, fit.unwrap_or_default(), fps, monitor)
+    })
+    .await?
+}
+
+#[tauri::command]
+pub fn stop_animated_image(monitor: Option<u32>) -> AppResult<()> {
+    crate::animated_image::stop(monitor)
+}
+
+#[tauri::command]
+pub async fn cache_remote_wallpaper(
+    wallpaper_id: String,
+    url: String,
+    assets: Option<Vec<String>>,
+) -> AppResult<crate::offline::OfflineEntry> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::offline::cache_remote_wallpaper(&wallpaper_id, &url, &assets.unwrap_or_default())
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn pin_offline(
+    wallpaper_id: String,
+    url: String,
+    assets: Option<Vec<String>>,
+) -> AppResult<crate::offline::OfflineEntry> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::offline::pin_offline(&wallpaper_id, &url, &assets.unwrap_or_default())
+    })
+    .await?
+}
+
+#[tauri::command]
+pub fn unpin_offline(wallpaper_id: String) -> AppResult<()> {
+    crate::offline::unpin_offline(&wallpaper_id)
+}
+
+#[tauri::command]
+pub fn list_offline_wallpapers() -> Vec<crate::offline::OfflineEntry> {
+    crate::offline::list_offline()
+}
+
+#[tauri::command]
+pub async fn resolve_wallpaper_url(wallpaper_id: String, url: String) -> AppResult<String> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        crate::offline::resolve_wallpaper_url(&wallpaper_id, &url)
+    })
+    .await?)
+}
+
+#[tauri::command]
+pub async fn export_backup(
+    webview: tauri::Webview,
+    path: String,
+    settings: Option<serde_json::Value>,
+) -> AppResult<crate::backup::BackupSummary> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::backup::export_backup(std::path::Path::new(&path), settings)
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn import_backup(
+    webview: tauri::Webview,
+    app: tauri::AppHandle,
+    path: String,
+) -> AppResult<crate::backup::BackupSummary> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::backup::import_backup(&app, std::path::Path::new(&path))
+    })
+    .await?
+}
+
+#[tauri::command]
+pub fn get_backend_settings() -> crate::settings::BackendSettings {
+    crate::settings::get()
+}
+
+#[tauri::command]
+pub fn set_backend_settings(
+    webview: tauri::Webview,
+    settings: crate::settings::BackendSettings,
+) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    crate::settings::update(settings)
+}
+
+/// Replace the extra origins the webview may navigate to; returns them normalized.
+#[tauri::command]
+pub fn set_allowed_origins(
+    webview: tauri::Webview,
+    origins: Vec<String>,
+) -> AppResult<Vec<String>> {
+    permissions::require_trusted(&webview)?;
+    let mut settings = crate::settings::get();
+    settings.allowed_origins = origins;
+    crate::settings::update(settings)?;
+    Ok(crate::settings::get().allowed_origins)
+}
+
+#[tauri::command]
+pub fn list_shortcuts() -> Vec<crate::shortcuts::ShortcutInfo> {
+    crate::shortcuts::list()
+}
+
+#[tauri::command]
+pub fn register_action_shortcut(
+    accelerator: String,
+    action: crate::desktop_actions::DesktopAction,
+) -> AppResult<crate::shortcuts::ShortcutInfo> {
+    crate::shortcuts::register(&accelerator, action)
+}
+
+#[tauri::command]
+pub fn unregister_action_shortcut(accelerator: String) -> AppResult<()> {
+    crate::shortcuts::unregister(&accelerator)
+}
+
+#[tauri::command]
+pub fn run_desktop_action(action: crate::desktop_actions::DesktopAction) -> AppResult<()> {
+    crate::desktop_actions::run(&action)
+}
+
+#[tauri::command]
+pub async fn get_recent_logs(
+    app: tauri::AppHandle,
+    lines: Option<usize>,
+    level: Option<String>,
+) -> AppResult<Vec<String>> {
+    let min_level = level.as_deref().map(crate::logs::parse_level).transpose()?;
+    let dir = crate::logs::log_dir(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::logs::recent_logs(&dir, lines.unwrap_or(crate::logs::DEFAULT_LINES), min_level)
+    })
+    .await?
+}
+
+#[tauri::command]
+pub fn get_crash_status() -> AppResult<crate::crash::CrashStatus> {
+    crate::crash::status()
+}
+
+#[tauri::command]
+pub async fn upload_crash_report(webview: tauri::Webview, id: String) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || crate::crash::upload_report(&id)).await?
+}
+
+#[tauri::command]
+pub fn delete_crash_report(id: String) -> AppResult<()> {
+    crate::crash::delete_report(&id)
+}
+
+#[tauri::command]
+pub async fn export_diagnostics(
+    webview: tauri::Webview,
+    app: tauri::AppHandle,
+    path: String,
+) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::diagnostics::export_diagnostics(&app, std::path::Path::new(&path))
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn health_check() -> AppResult<crate::health::HealthReport> {
+    Ok(tauri::async_runtime::spawn_blocking(crate::health::health_check).await?)
+}
+
+/// Record a performance trace for `duration_secs` and return the file path.
+#[tauri::command]
+pub async fn start_trace_capture(
+    webview: tauri::Webview,
+    app: tauri::AppHandle,
+    duration_secs: u64,
+) -> AppResult<String> {
+    permissions::require_trusted(&webview)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::trace::capture(&app, std::time::Duration::from_secs(duration_secs))
+            .map(|p| p.to_string_lossy().into_owned())
+    })
+    .await?
+}
+
+#[tauri::command]
+pub fn report_frontend_error(payload: crate::frontend_errors::FrontendError) {
+    crate::frontend_errors::report(payload)
+}
+
+#[tauri::command]
+pub fn list_wallpaper_permissions(
+    webview: tauri::Webview,
+) -> AppResult<std::collections::BTreeMap<String, permissions::WallpaperPermissions>> {
+    permissions::require_trusted(&webview)?;
+    Ok(permissions::list())
+}
+
+/// `granted: None` forgets the decision so the wallpaper is prompted again.
+#[tauri::command]
+pub fn set_wallpaper_permission(
+    webview: tauri::Webview,
+    wallpaper_id: String,
+    capability: Capability,
+    granted: Option<bool>,
+) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    permissions::set(&wallpaper_id, capability, granted)
+}
+
+#[tauri::command]
+pub fn revoke_wallpaper_permissions(
+    webview: tauri::Webview,
+    wallpaper_id: String,
+) -> AppResult<()> {
+    permissions::require_trusted(&webview)?;
+    permissions::revoke_all(&wallpaper_id)
+}
+
+/// Stored options of wallpaper `id` (null when none were saved).
+#[tauri::command]
+pub fn get_wallpaper_settings(
+    webview: tauri::Webview,
+    id: String,
+    wallpaper_id: Option<String>,
+) -> AppResult<serde_json::Value> {
+    permissions::require_self(&webview, wallpaper_id.as_deref(), &id)?;
+    crate::wallpaper_settings::get(&id)
+}
+
+/// Replace the options of wallpaper `id`; null deletes them.
+#[tauri::command]
+pub fn set_wallpaper_settings(
+    webview: tauri::Webview,
+    app: tauri::AppHandle,
+    id: String,
+    settings: serde_json::Value,
+    wallpaper_id: Option<String>,
+) -> AppResult<()> {
+    permissions::require_self(&webview, wallpaper_id.as_deref(), &id)?;
+    crate::wallpaper_settings::set(&app, &id, settings)
+}
+
+#[tauri::command]
+pub fn get_local_api_info(webview: tauri::Webview) -> AppResult<crate::local_api::LocalApiInfo> {
+    permissions::require_trusted(&webview)?;
+    crate::local_api::info()
+}
+
+#[tauri::command]
+pub fn regenerate_local_api_key(webview: tauri::Webview) -> AppResult<String> {
+    permissions::require_trusted(&webview)?;
+    crate::local_api::regenerate_key()
+}
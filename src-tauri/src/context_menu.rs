@@ -0,0 +1,81 @@
+//! Desktop context menu — native popup on empty-desktop right-click that merges
+//! standard desktop entries with the MyWallpaper actions from the tray menu.
+
+use log::warn;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::OnceLock;
+use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::{AppHandle, Manager, PhysicalPosition};
+
+const MENU_REFRESH: &str = "desktop-refresh";
+const MENU_PERSONALIZE: &str = "desktop-personalize";
+const MENU_DISPLAY: &str = "desktop-display-settings";
+const MENU_NATIVE: &str = "desktop-native-menu";
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+// Screen position of the last popup, for the "more options" fallback
+static LAST_X: AtomicI32 = AtomicI32::new(0);
+static LAST_Y: AtomicI32 = AtomicI32::new(0);
+
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+fn popup(app: &AppHandle, x: i32, y: i32) -> tauri::Result<()> {
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(());
+    };
+    let refresh = MenuItemBuilder::with_id(MENU_REFRESH, "Refresh").build(app)?;
+    let personalize = MenuItemBuilder::with_id(MENU_PERSONALIZE, "Personalize").build(app)?;
+    let display = MenuItemBuilder::with_id(MENU_DISPLAY, "Display settings").build(app)?;
+    let native = MenuItemBuilder::with_id(MENU_NATIVE, "Show more options").build(app)?;
+
+    let builder = MenuBuilder::new(app).item(&refresh).separator();
+    let menu = crate::tray::with_wallpaper_actions(app, builder)?
+        .separator()
+        .item(&display)
+        .item(&personalize)
+        .separator()
+        .item(&native)
+        .build()?;
+
+    // Popup coordinates are relative to the window; the wallpaper window spans
+    // the virtual desktop, so subtract its origin
+    let origin = window.outer_position()?;
+    window.popup_menu_at(&menu, PhysicalPosition::new(x - origin.x, y - origin.y))
+}
+
+/// Show the menu at screen position `(x, y)`. Called from the mouse dispatch window.
+pub fn show_at(x: i32, y: i32) {
+    let Some(app) = APP_HANDLE.get().cloned() else {
+        return;
+    };
+    LAST_X.store(x, Ordering::Relaxed);
+    LAST_Y.store(y, Ordering::Relaxed);
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        if let Err(e) = popup(&handle, x, y) {
+            warn!("[context_menu] Popup failed: {}", e);
+        }
+    });
+}
+
+/// Handle ids owned by the context menu (shared wallpaper actions are handled by the tray).
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
+    use tauri_plugin_opener::OpenerExt;
+    let open = |uri: &str| {
+        if let Err(e) = app.opener().open_url(uri, None::<&str>) {
+            warn!("[context_menu] Cannot open {}: {}", uri, e);
+        }
+    };
+    match id {
+        MENU_REFRESH => crate::window_layer::refresh_desktop(),
+        MENU_PERSONALIZE => open("ms-settings:personalization"),
+        MENU_DISPLAY => open("ms-settings:display"),
+        MENU_NATIVE => crate::window_layer::show_native_desktop_menu(
+            LAST_X.load(Ordering::Relaxed),
+            LAST_Y.load(Ordering::Relaxed),
+        ),
+        _ => {}
+    }
+}
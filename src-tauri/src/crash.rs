@@ -0,0 +1,372 @@
+//! Crash reporting — local crash reports with breadcrumbs, opt-in upload.
+//!
+//! - Rust panics (release builds abort) write `crash-<ts>.txt` from the panic hook.
+//! - Native crashes on Windows write `crash-<ts>.dmp` (minidump) + `.txt` from an
+//!   unhandled exception filter.
+//! - A `session.lock` marker left behind by a previous run means it did not exit
+//!   cleanly (crash, kill, power loss); surfaced as `lastRunCrashed`.
+//!
+//! Nothing leaves the machine unless the user calls `upload_crash_report`.
+
+use crate::error::{AppError, AppResult};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use typeshare::typeshare;
+
+const SESSION_MARKER: &str = "session.lock";
+const MAX_BREADCRUMBS: usize = 64;
+/// Older reports are deleted at startup.
+const MAX_REPORTS: usize = 10;
+const UPLOAD_TIMEOUT_SECS: u64 = 60;
+/// HTTPS crash collector, injected by release builds. Minidumps hold process
+/// memory (tokens included), so the destination is never taken from IPC.
+const UPLOAD_ENDPOINT: Option<&str> = option_env!("MYWALLPAPER_CRASH_UPLOAD_URL");
+
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+static LAST_RUN_CRASHED: AtomicBool = AtomicBool::new(false);
+static BREADCRUMBS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    /// File stem, e.g. `crash-1718000000`
+    pub id: String,
+    pub created_at: u64,
+    pub has_minidump: bool,
+    pub size_bytes: u64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashStatus {
+    /// The previous run did not shut down cleanly
+    pub last_run_crashed: bool,
+    pub reports: Vec<CrashReport>,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn crash_dir() -> AppResult<&'static PathBuf> {
+    CRASH_DIR
+        .get()
+        .ok_or_else(|| AppError::Storage("Crash reporter not initialized".into()))
+}
+
+// ============================================================================
+// Breadcrumbs
+// ============================================================================
+
+/// Record a notable event; the most recent ones are written into crash reports.
+pub fn breadcrumb(category: &str, message: &str) {
+    if let Ok(mut crumbs) = BREADCRUMBS.lock() {
+        if crumbs.len() == MAX_BREADCRUMBS {
+            crumbs.pop_front();
+        }
+        crumbs.push_back(format!("[{}] [{}] {}", now_secs(), category, message));
+    }
+}
+
+/// Report text: header, crash details, breadcrumbs. Never blocks on the
+/// breadcrumb lock — the crashing thread may hold it.
+fn report_text(kind: &str, details: &str) -> String {
+    let mut text = format!(
+        "MyWallpaper Desktop v{} ({}/{})\nKind: {}\nTime: {}\n\n{}\n\nBreadcrumbs (oldest first):\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        kind,
+        now_secs(),
+        details
+    );
+    match BREADCRUMBS.try_lock() {
+        Ok(crumbs) => crumbs.iter().for_each(|c| {
+            text.push_str(c);
+            text.push('\n');
+        }),
+        Err(_) => text.push_str("(unavailable)\n"),
+    }
+    text
+}
+
+fn write_report(stem: &str, kind: &str, details: &str) {
+    let Some(dir) = CRASH_DIR.get() else {
+        return;
+    };
+    let _ = std::fs::write(
+        dir.join(format!("{}.txt", stem)),
+        report_text(kind, details),
+    );
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let thread = std::thread::current();
+        let details = format!(
+            "Thread: {}\n{}\n\nBacktrace:\n{}",
+            thread.name().unwrap_or("<unnamed>"),
+            panic_info,
+            std::backtrace::Backtrace::force_capture()
+        );
+        write_report(&format!("crash-{}", now_secs()), "panic", &details);
+        // Only when the panic ends the process
+        if cfg!(panic = "abort") {
+            crate::exit_wallpaper::on_crash();
+        }
+        default_hook(panic_info);
+    }));
+}
+
+#[cfg(target_os = "windows")]
+mod native {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Diagnostics::Debug::{
+        MiniDumpWithIndirectlyReferencedMemory, MiniDumpWriteDump, SetUnhandledExceptionFilter,
+        EXCEPTION_POINTERS, MINIDUMP_EXCEPTION_INFORMATION,
+    };
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId,
+    };
+
+    const EXCEPTION_CONTINUE_SEARCH: i32 = 0;
+
+    /// Last-chance filter: write a minidump + report, then let Windows terminate us.
+    unsafe extern "system" fn exception_filter(info: *const EXCEPTION_POINTERS) -> i32 {
+        use std::os::windows::io::AsRawHandle;
+        let Some(dir) = super::CRASH_DIR.get() else {
+            return EXCEPTION_CONTINUE_SEARCH;
+        };
+        let stem = format!("crash-{}", super::now_secs());
+        if let Ok(file) = std::fs::File::create(dir.join(format!("{}.dmp", stem))) {
+            let exception = MINIDUMP_EXCEPTION_INFORMATION {
+                ThreadId: GetCurrentThreadId(),
+                ExceptionPointers: info as *mut _,
+                ClientPointers: false.into(),
+            };
+            let _ = MiniDumpWriteDump(
+                GetCurrentProcess(),
+                GetCurrentProcessId(),
+                HANDLE(file.as_raw_handle()),
+                MiniDumpWithIndirectlyReferencedMemory,
+                Some(&exception as *const _),
+                None,
+                None,
+            );
+        }
+        let code = if info.is_null() || (*info).ExceptionRecord.is_null() {
+            0
+        } else {
+            (*(*info).ExceptionRecord).ExceptionCode.0 as u32
+        };
+        super::write_report(
+            &stem,
+            "native exception",
+            &format!("Exception code: 0x{:08X}", code),
+        );
+        EXCEPTION_CONTINUE_SEARCH
+    }
+
+    pub fn install() {
+        unsafe {
+            SetUnhandledExceptionFilter(Some(exception_filter));
+        }
+    }
+}
+
+// ============================================================================
+// Lifecycle
+// ============================================================================
+
+/// Keep the newest `MAX_REPORTS` reports (a report is all files sharing a stem).
+fn prune_reports(dir: &Path) {
+    let Ok(reports) = list_reports_in(dir) else {
+        return;
+    };
+    for report in reports.iter().skip(MAX_REPORTS) {
+        for ext in ["txt", "dmp"] {
+            let _ = std::fs::remove_file(dir.join(format!("{}.{}", report.id, ext)));
+        }
+    }
+}
+
+/// Install the crash handlers and detect an unclean previous shutdown.
+/// Must run early in setup; `mark_clean_exit` must run on normal exit.
+pub fn init(app: &tauri::AppHandle) -> AppResult<()> {
+    use tauri::Manager;
+    let dir = app.path().app_data_dir()?.join("crashes");
+    std::fs::create_dir_all(&dir)?;
+
+    let marker = dir.join(SESSION_MARKER);
+    if marker.exists() {
+        LAST_RUN_CRASHED.store(true, Ordering::SeqCst);
+        warn!("[crash] Previous session did not exit cleanly");
+    }
+    std::fs::write(&marker, std::process::id().to_string())?;
+    prune_reports(&dir);
+    let _ = CRASH_DIR.set(dir);
+
+    install_panic_hook();
+    #[cfg(target_os = "windows")]
+    native::install();
+    breadcrumb("app", &format!("started v{}", env!("CARGO_PKG_VERSION")));
+    info!("[crash] Crash reporter ready");
+    Ok(())
+}
+
+/// Remove the session marker: this run ended normally.
+pub fn mark_clean_exit() {
+    if let Some(dir) = CRASH_DIR.get() {
+        let _ = std::fs::remove_file(dir.join(SESSION_MARKER));
+    }
+}
+
+// ============================================================================
+// Reports
+// ============================================================================
+
+fn list_reports_in(dir: &Path) -> AppResult<Vec<CrashReport>> {
+    let mut reports: Vec<CrashReport> = Vec::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(ts) = stem.strip_prefix("crash-") else {
+            continue;
+        };
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        let is_dump = path.extension().is_some_and(|x| x == "dmp");
+        match reports.iter_mut().find(|r| r.id == stem) {
+            Some(report) => {
+                report.size_bytes += size;
+                report.has_minidump |= is_dump;
+            }
+            None => reports.push(CrashReport {
+                id: stem.to_string(),
+                created_at: ts.parse().unwrap_or(0),
+                has_minidump: is_dump,
+                size_bytes: size,
+            }),
+        }
+    }
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(reports)
+}
+
+pub fn status() -> AppResult<CrashStatus> {
+    Ok(CrashStatus {
+        last_run_crashed: LAST_RUN_CRASHED.load(Ordering::SeqCst),
+        reports: list_reports_in(crash_dir()?)?,
+    })
+}
+
+fn validate_report_id(id: &str) -> AppResult<()> {
+    let valid = id
+        .strip_prefix("crash-")
+        .is_some_and(|ts| !ts.is_empty() && ts.bytes().all(|b| b.is_ascii_digit()));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "Invalid crash report id: {}",
+            id
+        )))
+    }
+}
+
+pub fn delete_report(id: &str) -> AppResult<()> {
+    validate_report_id(id)?;
+    let dir = crash_dir()?;
+    for ext in ["txt", "dmp"] {
+        let path = dir.join(format!("{}.{}", id, ext));
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Zip a report's files and POST them to `UPLOAD_ENDPOINT`. User-initiated.
+pub fn upload_report(id: &str) -> AppResult<()> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    validate_report_id(id)?;
+    let endpoint = UPLOAD_ENDPOINT.ok_or_else(|| {
+        AppError::Validation("Crash upload is not available in this build".into())
+    })?;
+    let url = url::Url::parse(endpoint)
+        .ok()
+        .filter(|u| u.scheme() == "https")
+        .ok_or_else(|| AppError::Validation("Crash upload URL must use HTTPS".into()))?;
+
+    let dir = crash_dir()?;
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let mut found = false;
+    for ext in ["txt", "dmp"] {
+        let name = format!("{}.{}", id, ext);
+        let Ok(bytes) = std::fs::read(dir.join(&name)) else {
+            continue;
+        };
+        writer
+            .start_file(name, options)
+            .map_err(|e| AppError::Storage(format!("Crash archive: {}", e)))?;
+        writer.write_all(&bytes)?;
+        found = true;
+    }
+    if !found {
+        return Err(AppError::Validation(format!(
+            "Crash report not found: {}",
+            id
+        )));
+    }
+    let body = writer
+        .finish()
+        .map_err(|e| AppError::Storage(format!("Crash archive: {}", e)))?
+        .into_inner();
+
+    let response = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(UPLOAD_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/zip")
+        .header("X-MyWallpaper-Version", env!("CARGO_PKG_VERSION"))
+        .header("X-MyWallpaper-Crash-Id", id)
+        .body(body)
+        .send()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        error!(
+            "[crash] Upload of {} failed: HTTP {}",
+            id,
+            response.status()
+        );
+        return Err(AppError::Network(format!(
+            "Upload failed: HTTP {}",
+            response.status()
+        )));
+    }
+    info!("[crash] Uploaded {}", id);
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Random tokens and constant-time comparison, shared by the local API key,
+//! the OAuth flow and asset grant ids.
+
+use crate::error::{AppError, AppResult};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+fn random_bytes(len: usize) -> AppResult<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| AppError::Storage(format!("No system randomness: {}", e)))?;
+    Ok(bytes)
+}
+
+/// `len` random bytes as lowercase hex.
+pub fn random_hex(len: usize) -> AppResult<String> {
+    Ok(random_bytes(len)?
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// `len` random bytes as unpadded URL-safe base64.
+pub fn random_urlsafe(len: usize) -> AppResult<String> {
+    Ok(URL_SAFE_NO_PAD.encode(random_bytes(len)?))
+}
+
+/// Compare secrets without leaking where they differ through timing.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,150 @@
+//! Cursor position stream, for parallax in non-interactive mode.
+//!
+//! Without interactive mode the page receives no input and cannot track the
+//! cursor itself. While subscribed, the position recorded by the low-level
+//! mouse hook (asked from the system under the Raw Input backend) is sampled
+//! at up to `rate` Hz and emitted as `cursor-position` whenever it moved.
+//! Nothing is emitted while the session is locked.
+
+use crate::error::{AppError, AppResult};
+use log::{error, info};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Duration;
+use typeshare::typeshare;
+
+pub const DEFAULT_RATE: u32 = 30;
+pub const MAX_RATE: u32 = 60;
+const IDLE_INTERVAL: Duration = Duration::from_millis(500);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static SUBSCRIBED: AtomicBool = AtomicBool::new(false);
+static RATE: AtomicU32 = AtomicU32::new(DEFAULT_RATE);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPosition {
+    /// Page pixels: physical pixels relative to the wallpaper window's top-left
+    pub x: i32,
+    pub y: i32,
+    /// Monitor under the cursor, in `DisplayInfo` order
+    pub monitor: u32,
+    /// Position within that monitor, 0-1 from its top-left corner
+    pub monitor_x: f32,
+    pub monitor_y: f32,
+}
+
+/// Start the sampling thread (idle until `set_subscribed(true, ..)`).
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut sampler = sampler::Sampler::default();
+        let mut last: Option<CursorPosition> = None;
+        while RUNNING.load(Ordering::SeqCst) {
+            if !SUBSCRIBED.load(Ordering::Relaxed) {
+                last = None;
+                std::thread::sleep(IDLE_INTERVAL);
+                continue;
+            }
+            let rate = RATE.load(Ordering::Relaxed).clamp(1, MAX_RATE);
+            std::thread::sleep(Duration::from_millis(1000 / rate as u64));
+            let Some(position) = sampler.sample() else {
+                continue;
+            };
+            if last.replace(position) == Some(position) {
+                continue;
+            }
+            if let Err(e) = app.emit_app_event(&AppEvent::CursorPosition(position)) {
+                error!("[cursor] Failed to emit event: {}", e);
+            }
+        }
+    });
+}
+
+/// Enable/disable `cursor-position` events, at most `rate` per second.
+pub fn set_subscribed(subscribed: bool, rate: Option<u32>) -> AppResult<()> {
+    let rate = rate.unwrap_or(DEFAULT_RATE);
+    if !(1..=MAX_RATE).contains(&rate) {
+        return Err(AppError::Validation(format!(
+            "Cursor position rate must be between 1 and {} Hz",
+            MAX_RATE
+        )));
+    }
+    RATE.store(rate, Ordering::Relaxed);
+    if SUBSCRIBED.swap(subscribed, Ordering::SeqCst) != subscribed {
+        info!(
+            "[cursor] {}",
+            if subscribed {
+                format!("Subscribed at {} Hz", rate)
+            } else {
+                "Unsubscribed".to_string()
+            }
+        );
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod sampler {
+    use super::CursorPosition;
+    use std::time::{Duration, Instant};
+    use windows::Win32::Foundation::RECT;
+
+    /// Monitor layout is re-read this often rather than on every sample
+    const LAYOUT_REFRESH: Duration = Duration::from_secs(2);
+
+    #[derive(Default)]
+    pub struct Sampler {
+        monitors: Vec<RECT>,
+        origin: (i32, i32),
+        refreshed: Option<Instant>,
+    }
+
+    impl Sampler {
+        pub fn sample(&mut self) -> Option<CursorPosition> {
+            if !crate::window_layer::is_session_active() {
+                return None;
+            }
+            if self.refreshed.is_none_or(|t| t.elapsed() >= LAYOUT_REFRESH) {
+                self.monitors = crate::window_layer::monitor_rects();
+                self.origin = crate::window_layer::layer_origin();
+                self.refreshed = Some(Instant::now());
+            }
+            let (x, y) = crate::window_layer::mouse_hook::cursor_pos()?;
+            let (index, rect) = self
+                .monitors
+                .iter()
+                .enumerate()
+                .find(|(_, r)| x >= r.left && x < r.right && y >= r.top && y < r.bottom)?;
+            let width = (rect.right - rect.left).max(1) as f32;
+            let height = (rect.bottom - rect.top).max(1) as f32;
+            Some(CursorPosition {
+                x: x - self.origin.0,
+                y: y - self.origin.1,
+                monitor: index as u32,
+                monitor_x: (x - rect.left) as f32 / width,
+                monitor_y: (y - rect.top) as f32 / height,
+            })
+        }
+    }
+}
+
+/// Left for the Linux/macOS port; never emits.
+#[cfg(not(target_os = "windows"))]
+mod sampler {
+    use super::CursorPosition;
+
+    #[derive(Default)]
+    pub struct Sampler;
+
+    impl Sampler {
+        pub fn sample(&mut self) -> Option<CursorPosition> {
+            None
+        }
+    }
+}
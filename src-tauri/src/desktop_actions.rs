@@ -0,0 +1,228 @@
+//! Desktop actions — user-configurable reactions to desktop gestures
+//! (double-click, middle-button swipes and button chords on empty desktop space).
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use typeshare::typeshare;
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static PAUSED: AtomicBool = AtomicBool::new(false);
+/// Automatic pause sources currently holding a pause (quiet hours, pause rules)
+static AUTO_PAUSE: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+/// The current pause was started by an automatic source, not the user
+static AUTO_PAUSED: AtomicBool = AtomicBool::new(false);
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum DesktopAction {
+    /// Do nothing (gesture passes through unchanged)
+    #[default]
+    None,
+    /// Show/hide desktop icons
+    ToggleIcons,
+    /// Pause/resume the wallpaper
+    TogglePause,
+    /// Open the hub window
+    OpenHub,
+    /// Ask the frontend to advance to the next wallpaper (it owns playlists)
+    NextWallpaper,
+    /// Ask the frontend to go back to the previous wallpaper
+    PreviousWallpaper,
+    /// Switch between interface (overlay) mode and wallpaper mode
+    ToggleInterfaceMode,
+    /// Launch a program (no shell is involved)
+    RunCommand {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// Mouse gestures recognized on empty desktop space (WH_MOUSE_LL backend only).
+/// Discriminants are the codes posted by the mouse hook.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DesktopGesture {
+    /// Middle-button drag, dominant direction
+    MiddleSwipeUp = 1,
+    MiddleSwipeDown = 2,
+    MiddleSwipeLeft = 3,
+    MiddleSwipeRight = 4,
+    /// Left and right buttons pressed together
+    LeftRightChord = 5,
+}
+
+impl DesktopGesture {
+    pub fn from_code(code: usize) -> Option<Self> {
+        Some(match code {
+            1 => Self::MiddleSwipeUp,
+            2 => Self::MiddleSwipeDown,
+            3 => Self::MiddleSwipeLeft,
+            4 => Self::MiddleSwipeRight,
+            5 => Self::LeftRightChord,
+            _ => return None,
+        })
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GestureBinding {
+    pub gesture: DesktopGesture,
+    pub action: DesktopAction,
+}
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+pub fn validate(action: &DesktopAction) -> AppResult<()> {
+    match action {
+        DesktopAction::RunCommand { program, .. } if program.trim().is_empty() => Err(
+            AppError::Validation("Command program must not be empty".into()),
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn app() -> AppResult<&'static tauri::AppHandle> {
+    APP_HANDLE
+        .get()
+        .ok_or_else(|| AppError::Validation("Desktop actions not initialized".into()))
+}
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+/// Set the paused state explicitly (user request; overrides automatic pauses
+/// until their sources release and hold again).
+pub fn set_paused(paused: bool) -> AppResult<()> {
+    AUTO_PAUSED.store(false, Ordering::SeqCst);
+    apply_paused(paused)
+}
+
+fn apply_paused(paused: bool) -> AppResult<()> {
+    PAUSED.store(paused, Ordering::SeqCst);
+    // Native players have no frontend to pause them
+    let _ = crate::native_video::set_playing(None, !paused);
+    app()?.emit_app_event(&AppEvent::WallpaperPaused { paused })?;
+    Ok(())
+}
+
+/// Toggle the paused state; returns the new value.
+pub fn toggle_pause() -> AppResult<bool> {
+    let paused = !is_paused();
+    set_paused(paused)?;
+    Ok(paused)
+}
+
+/// Hold (`hold`) or release an automatic pause for `source`. The first holder
+/// pauses unless the wallpaper is already paused; the last release resumes
+/// only a pause the holders started.
+pub fn auto_pause(source: &'static str, hold: bool) -> AppResult<()> {
+    let mut holders = AUTO_PAUSE
+        .lock()
+        .map_err(|_| AppError::Validation("Pause state poisoned".into()))?;
+    let changed = if hold {
+        holders.insert(source)
+    } else {
+        holders.remove(source)
+    };
+    if !changed {
+        return Ok(());
+    }
+    info!(
+        "[desktop_actions] Auto-pause {} by {}",
+        if hold { "held" } else { "released" },
+        source
+    );
+    if hold && holders.len() == 1 && !is_paused() {
+        AUTO_PAUSED.store(true, Ordering::SeqCst);
+        apply_paused(true)?;
+    } else if holders.is_empty() && AUTO_PAUSED.swap(false, Ordering::SeqCst) && is_paused() {
+        apply_paused(false)?;
+    }
+    Ok(())
+}
+
+/// Show the hub window (optionally on a section).
+pub fn open_hub(section: Option<&str>) -> AppResult<()> {
+    crate::hub::open(app()?, section)
+}
+
+pub fn run(action: &DesktopAction) -> AppResult<()> {
+    match action {
+        DesktopAction::None => {}
+        DesktopAction::ToggleIcons => {
+            crate::window_layer::toggle_desktop_icons()?;
+        }
+        DesktopAction::TogglePause => {
+            let paused = toggle_pause()?;
+            info!(
+                "[desktop_actions] Wallpaper {}",
+                if paused { "paused" } else { "resumed" }
+            );
+        }
+        DesktopAction::OpenHub => open_hub(None)?,
+        DesktopAction::NextWallpaper => app()?.emit_app_event(&AppEvent::NextWallpaper)?,
+        DesktopAction::PreviousWallpaper => app()?.emit_app_event(&AppEvent::PreviousWallpaper)?,
+        DesktopAction::ToggleInterfaceMode => {
+            let interface = crate::window_layer::toggle_interface_mode()?;
+            info!(
+                "[desktop_actions] {} mode",
+                if interface { "Interface" } else { "Wallpaper" }
+            );
+        }
+        DesktopAction::RunCommand { program, args } => {
+            std::process::Command::new(program)
+                .args(args)
+                .spawn()
+                .map_err(|e| AppError::Validation(format!("Cannot run '{}': {}", program, e)))?;
+            info!("[desktop_actions] Launched '{}'", program);
+        }
+    }
+    Ok(())
+}
+
+/// Called from the mouse dispatch window: notify the frontend, then run the bound action.
+pub fn on_desktop_gesture(gesture: DesktopGesture) {
+    info!("[desktop_actions] Gesture {:?}", gesture);
+    if let Ok(app) = app() {
+        let _ = app.emit_app_event(&AppEvent::DesktopGesture { gesture });
+    }
+    let Some(action) = crate::settings::get()
+        .gesture_bindings
+        .into_iter()
+        .find(|b| b.gesture == gesture)
+        .map(|b| b.action)
+    else {
+        return;
+    };
+    std::thread::spawn(move || {
+        if let Err(e) = run(&action) {
+            warn!("[desktop_actions] Gesture action failed: {}", e);
+        }
+    });
+}
+
+/// Called from the mouse dispatch window; runs off the UI thread.
+pub fn on_desktop_double_click() {
+    let action = crate::settings::get().double_click_action;
+    if action == DesktopAction::None {
+        return;
+    }
+    std::thread::spawn(move || {
+        if let Err(e) = run(&action) {
+            warn!("[desktop_actions] Double-click action failed: {}", e);
+        }
+    });
+}
@@ -0,0 +1,162 @@
+//! Diagnostics bundle — a single `.zip` for support tickets.
+//!
+//! Archive layout:
+//! - `summary.json` — app/OS/WebView2 versions, monitors, GPUs, desktop detection, input stats, crashes
+//! - `desktop-windows.json` — Progman/WorkerW window tree at export time
+//! - `config/<file>.json` — config files (settings, profiles, …) with secrets redacted
+//! - `logs/<file>.log` — rotated log files
+
+use crate::error::{AppError, AppResult};
+use log::info;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+
+const REDACTED: &str = "[redacted]";
+/// Key fragments (lowercase) whose values never leave the machine.
+const SECRET_KEYS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "apikey",
+    "api_key",
+    "authorization",
+    "cookie",
+    "session",
+];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitorInfo {
+    name: Option<String>,
+    position: (i32, i32),
+    size: (u32, u32),
+    scale_factor: f64,
+    primary: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticsSummary {
+    created_at: u64,
+    system: crate::commands::SystemInfo,
+    webview_version: Option<String>,
+    monitors: Vec<MonitorInfo>,
+    gpu: crate::gpu::GpuReport,
+    desktop_detection: Option<crate::window_layer::DesktopDetectionReport>,
+    input: crate::window_layer::InputDiagnostics,
+    crashes: Option<crate::crash::CrashStatus>,
+}
+
+fn zip_err(e: zip::result::ZipError) -> AppError {
+    AppError::Storage(format!("Diagnostics archive: {}", e))
+}
+
+/// Replace values of secret-looking keys, recursively.
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SECRET_KEYS.iter().any(|s| key.contains(s)) {
+                    *v = Value::String(REDACTED.into());
+                } else {
+                    redact(v);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn monitors(app: &tauri::AppHandle) -> Vec<MonitorInfo> {
+    let primary = app.primary_monitor().ok().flatten().map(|m| *m.position());
+    app.available_monitors()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| MonitorInfo {
+            name: m.name().cloned(),
+            position: (m.position().x, m.position().y),
+            size: (m.size().width, m.size().height),
+            scale_factor: m.scale_factor(),
+            primary: primary == Some(*m.position()),
+        })
+        .collect()
+}
+
+/// Write the diagnostics bundle to `path`.
+pub fn export_diagnostics(app: &tauri::AppHandle, path: &Path) -> AppResult<()> {
+    use zip::write::SimpleFileOptions;
+
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let summary = DiagnosticsSummary {
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        system: crate::commands::get_system_info(),
+        webview_version: tauri::webview_version().ok(),
+        monitors: monitors(app),
+        gpu: crate::gpu::get_gpu_info(),
+        desktop_detection: crate::window_layer::last_detection(),
+        input: crate::window_layer::get_input_diagnostics(),
+        crashes: crate::crash::status().ok(),
+    };
+
+    let tmp = path.with_extension("zip.tmp");
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&tmp)?);
+    let result = (|| -> AppResult<()> {
+        writer
+            .start_file("summary.json", options)
+            .map_err(zip_err)?;
+        writer.write_all(&serde_json::to_vec_pretty(&summary)?)?;
+
+        writer
+            .start_file("desktop-windows.json", options)
+            .map_err(zip_err)?;
+        writer.write_all(&serde_json::to_vec_pretty(
+            &crate::window_layer::desktop_window_tree(),
+        )?)?;
+
+        for name in crate::storage::list_json_files().unwrap_or_default() {
+            let Ok(bytes) = std::fs::read(crate::storage::config_path(&name)?) else {
+                continue;
+            };
+            // Unparseable files are skipped rather than shipped unredacted
+            let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+                continue;
+            };
+            redact(&mut value);
+            writer
+                .start_file(format!("config/{}", name), options)
+                .map_err(zip_err)?;
+            writer.write_all(&serde_json::to_vec_pretty(&value)?)?;
+        }
+
+        let log_dir = crate::logs::log_dir(app)?;
+        for log in crate::logs::log_files(&log_dir).unwrap_or_default() {
+            let (Some(name), Ok(bytes)) = (
+                log.file_name().and_then(|n| n.to_str()),
+                std::fs::read(&log),
+            ) else {
+                continue;
+            };
+            writer
+                .start_file(format!("logs/{}", name), options)
+                .map_err(zip_err)?;
+            writer.write_all(&bytes)?;
+        }
+        writer.finish().map_err(zip_err)?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, path)?;
+    info!("[diagnostics] Exported bundle to {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,314 @@
+//! Dimming and warm color-temperature overlay over the wallpaper, independent
+//! of the OS night light (icons and other windows are not affected).
+//!
+//! A layered child window above the layer window blends a solid color over
+//! the wallpaper. One alpha-blended color cannot multiply each channel
+//! separately, so it is chosen to be exact for white: whites come out dimmed
+//! and warmed as requested, pure dimming (neutral temperature) keeps blacks
+//! black, and a warm tint lifts them slightly. The settings schedule takes
+//! over from `set_dimming` while its window is active.
+
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use typeshare::typeshare;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// Neutral white point; no tint at or above it
+pub const NEUTRAL_KELVIN: u32 = 6500;
+pub const MIN_KELVIN: u32 = 1000;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Dimming {
+    /// 0 = untouched, 1 = black
+    pub level: f32,
+    /// Color temperature in Kelvin (1000-6500; 6500 = no tint)
+    pub temperature: u32,
+}
+
+impl Dimming {
+    pub const NONE: Dimming = Dimming {
+        level: 0.0,
+        temperature: NEUTRAL_KELVIN,
+    };
+
+    fn is_none(&self) -> bool {
+        self.level <= 0.0 && self.temperature >= NEUTRAL_KELVIN
+    }
+}
+
+static MANUAL: Mutex<Dimming> = Mutex::new(Dimming::NONE);
+/// Last dimming shown by the overlay
+static APPLIED: Mutex<Dimming> = Mutex::new(Dimming::NONE);
+
+pub fn validate(dimming: &Dimming) -> AppResult<()> {
+    if !(0.0..=1.0).contains(&dimming.level)
+        || !(MIN_KELVIN..=NEUTRAL_KELVIN).contains(&dimming.temperature)
+    {
+        return Err(AppError::Validation(
+            "Dimming level must be 0-1 and temperature 1000-6500 K".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Set the dimming used outside the scheduled window.
+pub fn set(dimming: Dimming) -> AppResult<()> {
+    validate(&dimming)?;
+    *MANUAL
+        .lock()
+        .map_err(|_| AppError::WindowLayer("Dimming state poisoned".into()))? = dimming;
+    refresh()
+}
+
+/// Start re-evaluating the schedule.
+pub fn init() {
+    std::thread::spawn(|| loop {
+        if let Err(e) = refresh() {
+            warn!("[dimming] {}", e);
+        }
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+fn effective() -> Dimming {
+    let schedule = crate::settings::get().dimming_schedule;
+    let scheduled = schedule.enabled
+        && crate::quiet_hours::local_minute().is_some_and(|m| {
+            crate::quiet_hours::in_window(m, schedule.start_minute, schedule.end_minute)
+        });
+    if scheduled {
+        schedule.dimming
+    } else {
+        MANUAL.lock().map(|d| *d).unwrap_or(Dimming::NONE)
+    }
+}
+
+/// Bring the overlay in line with the manual value and the schedule.
+pub fn refresh() -> AppResult<()> {
+    let target = effective();
+    let mut applied = APPLIED
+        .lock()
+        .map_err(|_| AppError::WindowLayer("Dimming state poisoned".into()))?;
+    if *applied == target {
+        return Ok(());
+    }
+    if target.is_none() {
+        overlay::hide();
+    } else {
+        let (color, alpha) = blend(&target);
+        overlay::show(color, alpha)?;
+    }
+    info!(
+        "[dimming] Level {:.2}, {} K",
+        target.level, target.temperature
+    );
+    *applied = target;
+    Ok(())
+}
+
+/// Normalized white point of a black body at `kelvin` (Tanner Helland's fit).
+fn white_point(kelvin: u32) -> [f32; 3] {
+    let t = kelvin as f32 / 100.0;
+    let red = if t <= 66.0 {
+        1.0
+    } else {
+        (329.698_73 * (t - 60.0).powf(-0.133_204_76) / 255.0).min(1.0)
+    };
+    let green = if t <= 66.0 {
+        (99.470_8 * t.ln() - 161.119_57) / 255.0
+    } else {
+        288.122_16 * (t - 60.0).powf(-0.075_514_85) / 255.0
+    };
+    let blue = if t >= 66.0 {
+        1.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        (138.517_73 * (t - 10.0).ln() - 305.044_8) / 255.0
+    };
+    [red, green, blue].map(|c| c.clamp(0.0, 1.0))
+}
+
+/// Overlay color (COLORREF, 0x00BBGGRR) and alpha turning white into the
+/// dimmed, tinted white.
+fn blend(dimming: &Dimming) -> (u32, u8) {
+    let neutral = white_point(NEUTRAL_KELVIN);
+    let tint = white_point(dimming.temperature);
+    let target: [f32; 3] =
+        std::array::from_fn(|i| (tint[i] / neutral[i]).min(1.0) * (1.0 - dimming.level));
+    let darkest = target.iter().copied().fold(1.0f32, f32::min);
+    let alpha = 1.0 - darkest;
+    let color = target.map(|m| {
+        if alpha <= f32::EPSILON {
+            0
+        } else {
+            (((m - darkest) / alpha).clamp(0.0, 1.0) * 255.0).round() as u32
+        }
+    });
+    (
+        color[0] | (color[1] << 8) | (color[2] << 16),
+        (alpha * 255.0).round() as u8,
+    )
+}
+
+#[cfg(target_os = "windows")]
+mod overlay {
+    use crate::error::{AppError, AppResult};
+    use crate::window_layer::mouse_hook;
+    use std::sync::atomic::{AtomicIsize, AtomicU32, Ordering};
+    use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM};
+    use windows::Win32::Graphics::Gdi::{
+        BeginPaint, CreateSolidBrush, DeleteObject, EndPaint, FillRect, InvalidateRect,
+        ScreenToClient, PAINTSTRUCT,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    const WM_MWP_DIM_UPDATE: u32 = WM_APP + 60;
+
+    static HWND_RAW: AtomicIsize = AtomicIsize::new(0);
+    static COLOR: AtomicU32 = AtomicU32::new(0);
+    static ALPHA: AtomicU32 = AtomicU32::new(0);
+
+    /// Cover the layer window, above it in the layer parent.
+    unsafe fn cover_layer(hwnd: HWND) {
+        let layer = HWND(mouse_hook::get_webview_hwnd() as *mut _);
+        let parent = HWND(mouse_hook::get_target_parent_hwnd() as *mut _);
+        let mut rect = RECT::default();
+        if layer.is_invalid() || GetWindowRect(layer, &mut rect).is_err() {
+            return;
+        }
+        let mut origin = POINT {
+            x: rect.left,
+            y: rect.top,
+        };
+        let _ = ScreenToClient(parent, &mut origin);
+        let _ = SetWindowPos(
+            hwnd,
+            HWND_TOP,
+            origin.x,
+            origin.y,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+            SWP_NOACTIVATE | SWP_SHOWWINDOW,
+        );
+    }
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        match msg {
+            WM_MWP_DIM_UPDATE => {
+                let alpha = ALPHA.load(Ordering::SeqCst) as u8;
+                let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+                cover_layer(hwnd);
+                let _ = InvalidateRect(hwnd, None, true);
+                LRESULT(0)
+            }
+            WM_ERASEBKGND => LRESULT(1),
+            WM_PAINT => {
+                let mut ps = PAINTSTRUCT::default();
+                let hdc = BeginPaint(hwnd, &mut ps);
+                let brush = CreateSolidBrush(COLORREF(COLOR.load(Ordering::SeqCst)));
+                FillRect(hdc, &ps.rcPaint, brush);
+                let _ = DeleteObject(brush);
+                let _ = EndPaint(hwnd, &ps);
+                LRESULT(0)
+            }
+            WM_DESTROY => {
+                HWND_RAW.store(0, Ordering::SeqCst);
+                PostQuitMessage(0);
+                LRESULT(0)
+            }
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+
+    pub fn show(color: u32, alpha: u8) -> AppResult<()> {
+        COLOR.store(color, Ordering::SeqCst);
+        ALPHA.store(alpha as u32, Ordering::SeqCst);
+        let existing = HWND(HWND_RAW.load(Ordering::SeqCst) as *mut _);
+        unsafe {
+            if !existing.is_invalid() && IsWindow(existing).as_bool() {
+                let _ = PostMessageW(existing, WM_MWP_DIM_UPDATE, WPARAM(0), LPARAM(0));
+                return Ok(());
+            }
+        }
+        let parent_raw = mouse_hook::get_target_parent_hwnd();
+        if parent_raw == 0 {
+            return Err(AppError::WindowLayer("Desktop layer not ready".into()));
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || unsafe {
+            let cls = windows::core::w!("MWP_DimmingOverlay");
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                lpszClassName: cls,
+                ..Default::default()
+            };
+            let _ = RegisterClassW(&wc);
+            let created = CreateWindowExW(
+                WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_NOACTIVATE,
+                cls,
+                windows::core::w!(""),
+                WS_CHILD,
+                0,
+                0,
+                0,
+                0,
+                HWND(parent_raw as *mut _),
+                None,
+                None,
+                None,
+            );
+            let hwnd = match created {
+                Ok(hwnd) => hwnd,
+                Err(e) => {
+                    let _ = tx.send(Err(AppError::WindowLayer(e.to_string())));
+                    return;
+                }
+            };
+            HWND_RAW.store(hwnd.0 as isize, Ordering::SeqCst);
+            let _ = PostMessageW(hwnd, WM_MWP_DIM_UPDATE, WPARAM(0), LPARAM(0));
+            let _ = tx.send(Ok(()));
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+        rx.recv_timeout(std::time::Duration::from_secs(1))
+            .map_err(|_| AppError::WindowLayer("Dimming overlay timed out".into()))?
+    }
+
+    pub fn hide() {
+        let hwnd = HWND(HWND_RAW.load(Ordering::SeqCst) as *mut _);
+        if !hwnd.is_invalid() {
+            unsafe {
+                let _ = PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod overlay {
+    use crate::error::{AppError, AppResult};
+
+    pub fn show(_color: u32, _alpha: u8) -> AppResult<()> {
+        Err(AppError::WindowLayer(
+            "Dimming is only supported on Windows".into(),
+        ))
+    }
+
+    pub fn hide() {}
+}
@@ -0,0 +1,519 @@
+//! Shared download manager for large files (wallpaper packs, offline
+//! snapshots, the WebView2 bootstrapper).
+//!
+//! At most `maxConcurrentDownloads` transfers run at once; the others wait in
+//! FIFO order. Data goes to `<dest>.part` and is renamed into place once
+//! complete. A dropped connection is resumed with a `Range` request guarded by
+//! `If-Range`, so a changed file restarts from scratch instead of being
+//! spliced. `downloadBandwidthLimitKbps` caps the combined rate of all
+//! transfers. Labelled downloads are listed by `list_downloads` and report
+//! `download-progress`.
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use log::{info, warn};
+use reqwest::header;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use typeshare::typeshare;
+
+pub const DEFAULT_MAX_CONCURRENT: u8 = 3;
+pub const MAX_CONCURRENT_LIMIT: u8 = 8;
+const CONNECT_TIMEOUT_SECS: u64 = 15;
+/// A transfer without data for this long counts as interrupted
+const IDLE_TIMEOUT_SECS: u64 = 30;
+const RETRIES: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const CHUNK_BYTES: usize = 64 * 1024;
+/// Queued downloads re-check for cancellation this often
+const QUEUE_POLL: Duration = Duration::from_millis(500);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadState {
+    Queued,
+    Active,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadInfo {
+    pub id: u64,
+    pub label: String,
+    pub url: String,
+    pub state: DownloadState,
+    pub received_bytes: u64,
+    /// None until the server reports a length
+    pub total_bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+struct Tracked {
+    info: DownloadInfo,
+    cancel: Arc<AtomicBool>,
+}
+
+struct Slots {
+    active: usize,
+    limit: usize,
+    queue: VecDeque<u64>,
+}
+
+/// Token bucket shared by all transfers
+struct Bucket {
+    tokens: f64,
+    last: Option<Instant>,
+}
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static DOWNLOADS: Mutex<BTreeMap<u64, Tracked>> = Mutex::new(BTreeMap::new());
+static SLOTS: Mutex<Slots> = Mutex::new(Slots {
+    active: 0,
+    limit: DEFAULT_MAX_CONCURRENT as usize,
+    queue: VecDeque::new(),
+});
+static SLOTS_CHANGED: Condvar = Condvar::new();
+/// Bandwidth cap in bytes per second, 0 = unlimited
+static RATE: AtomicU64 = AtomicU64::new(0);
+static BUCKET: Mutex<Bucket> = Mutex::new(Bucket {
+    tokens: 0.0,
+    last: None,
+});
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Apply the bandwidth cap (KiB/s, 0 = unlimited) and concurrency limit.
+pub fn configure(bandwidth_limit_kbps: u32, max_concurrent: u8) {
+    RATE.store(u64::from(bandwidth_limit_kbps) * 1024, Ordering::Relaxed);
+    if let Ok(mut slots) = SLOTS.lock() {
+        slots.limit = usize::from(max_concurrent.clamp(1, MAX_CONCURRENT_LIMIT));
+    }
+    SLOTS_CHANGED.notify_all();
+}
+
+pub fn list() -> Vec<DownloadInfo> {
+    DOWNLOADS
+        .lock()
+        .map(|d| d.values().map(|t| t.info.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Cancel a queued or running download.
+pub fn cancel(id: u64) -> AppResult<()> {
+    let downloads = DOWNLOADS
+        .lock()
+        .map_err(|_| AppError::Network("Download registry poisoned".into()))?;
+    let tracked = downloads
+        .get(&id)
+        .ok_or_else(|| AppError::Validation(format!("Unknown download: {}", id)))?;
+    tracked.cancel.store(true, Ordering::SeqCst);
+    drop(downloads);
+    SLOTS_CHANGED.notify_all();
+    Ok(())
+}
+
+/// Result of a completed download.
+pub struct Downloaded {
+    pub bytes: u64,
+    /// Lowercased `Content-Type`, empty when absent
+    pub content_type: String,
+}
+
+type ProgressFn<'a> = &'a dyn Fn(u64, Option<u64>) -> bool;
+
+/// A download of `url` into `dest`, run on the calling thread.
+pub struct Download<'a> {
+    url: &'a str,
+    dest: &'a Path,
+    label: Option<&'a str>,
+    max_bytes: u64,
+    on_progress: Option<ProgressFn<'a>>,
+}
+
+impl<'a> Download<'a> {
+    pub fn new(url: &'a str, dest: &'a Path) -> Self {
+        Self {
+            url,
+            dest,
+            label: None,
+            max_bytes: u64::MAX,
+            on_progress: None,
+        }
+    }
+
+    /// List the download in `list_downloads` and report its progress.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Called with (received, total) as data arrives; return false to cancel.
+    pub fn on_progress(mut self, on_progress: ProgressFn<'a>) -> Self {
+        self.on_progress = Some(on_progress);
+        self
+    }
+
+    /// Wait for a slot, transfer (resuming after interruptions) and move the
+    /// file into place. Blocks until done.
+    pub fn run(self) -> AppResult<Downloaded> {
+        let parsed = url::Url::parse(self.url)
+            .map_err(|_| AppError::Validation(format!("Invalid download URL: {}", self.url)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(AppError::Validation(format!(
+                "Unsupported download URL: {}",
+                self.url
+            )));
+        }
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut tracker = Tracker::register(id, self.label, self.url, &cancel);
+        let result = Slot::acquire(id, &cancel).and_then(|_slot| {
+            let client = reqwest::blocking::Client::builder()
+                .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+                .timeout(Duration::from_secs(IDLE_TIMEOUT_SECS))
+                .user_agent(concat!("MyWallpaper-Desktop/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .map_err(|e| AppError::Network(e.to_string()))?;
+            self.transfer(&client, &mut tracker, &cancel)
+        });
+
+        match &result {
+            Ok(done) => tracker.update(true, |i| {
+                i.state = DownloadState::Completed;
+                i.received_bytes = done.bytes;
+            }),
+            Err(_) if cancel.load(Ordering::SeqCst) => {
+                tracker.update(true, |i| i.state = DownloadState::Cancelled)
+            }
+            Err(e) => tracker.update(true, |i| {
+                i.state = DownloadState::Failed;
+                i.error = Some(e.to_string());
+            }),
+        }
+        result
+    }
+
+    fn transfer(
+        &self,
+        client: &reqwest::blocking::Client,
+        tracker: &mut Tracker,
+        cancel: &AtomicBool,
+    ) -> AppResult<Downloaded> {
+        let part = part_path(self.dest);
+        let _ = std::fs::remove_file(&part);
+        // ETag or Last-Modified of the first response, for If-Range
+        let mut validator = None;
+        let mut attempt = 0;
+        loop {
+            let outcome = self.fetch_into(client, &part, &mut validator, tracker, cancel);
+            match outcome {
+                Ok(Fetch::Done(done)) => {
+                    std::fs::rename(&part, self.dest)?;
+                    return Ok(done);
+                }
+                Ok(Fetch::Interrupted(reason)) if attempt < RETRIES => {
+                    attempt += 1;
+                    warn!(
+                        "[downloads] {} interrupted ({}), resuming ({}/{})",
+                        self.url, reason, attempt, RETRIES
+                    );
+                    std::thread::sleep(RETRY_DELAY * attempt);
+                }
+                Ok(Fetch::Interrupted(reason)) => {
+                    let _ = std::fs::remove_file(&part);
+                    return Err(AppError::Network(format!("{}: {}", self.url, reason)));
+                }
+                Err(e) => {
+                    let _ = std::fs::remove_file(&part);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// One request, appending to `part` when the server honours the range.
+    fn fetch_into(
+        &self,
+        client: &reqwest::blocking::Client,
+        part: &Path,
+        validator: &mut Option<String>,
+        tracker: &mut Tracker,
+        cancel: &AtomicBool,
+    ) -> AppResult<Fetch> {
+        let offset = std::fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+        let mut request = client.get(self.url);
+        if let Some(validator) = validator.as_deref().filter(|_| offset > 0) {
+            request = request
+                .header(header::RANGE, format!("bytes={}-", offset))
+                .header(header::IF_RANGE, validator);
+        }
+        let response = match request.send() {
+            Ok(r) => r,
+            Err(e) => return Ok(Fetch::Interrupted(e.to_string())),
+        };
+        let status = response.status();
+        if status.is_server_error() {
+            return Ok(Fetch::Interrupted(status.to_string()));
+        }
+        let mut response = response
+            .error_for_status()
+            .map_err(|e| AppError::Network(format!("{}: {}", self.url, e)))?;
+
+        let resumed = offset > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+        let start = if resumed { offset } else { 0 };
+        if !resumed {
+            let headers = response.headers();
+            *validator = headers
+                .get(header::ETAG)
+                .filter(|v| !v.as_bytes().starts_with(b"W/"))
+                .or_else(|| headers.get(header::LAST_MODIFIED))
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+        }
+        let total = response.content_length().map(|l| l + start);
+        if total.is_some_and(|t| t > self.max_bytes) {
+            return Err(self.too_large());
+        }
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        let mut file = if resumed {
+            std::fs::OpenOptions::new().append(true).open(part)?
+        } else {
+            std::fs::File::create(part)?
+        };
+        let mut received = start;
+        tracker.update(true, |i| {
+            i.state = DownloadState::Active;
+            i.received_bytes = received;
+            i.total_bytes = total;
+        });
+        let mut buf = vec![0u8; CHUNK_BYTES];
+        loop {
+            if cancel.load(Ordering::SeqCst) {
+                return Err(cancelled(self.url));
+            }
+            let n = match response.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) => return Ok(Fetch::Interrupted(e.to_string())),
+            };
+            throttle(n);
+            received += n as u64;
+            if received > self.max_bytes {
+                return Err(self.too_large());
+            }
+            file.write_all(&buf[..n])?;
+            tracker.update(false, |i| i.received_bytes = received);
+            if self.on_progress.is_some_and(|f| !f(received, total)) {
+                cancel.store(true, Ordering::SeqCst);
+            }
+        }
+        file.flush()?;
+        if total.is_some_and(|t| received < t) {
+            return Ok(Fetch::Interrupted("connection closed early".into()));
+        }
+        Ok(Fetch::Done(Downloaded {
+            bytes: received,
+            content_type,
+        }))
+    }
+
+    fn too_large(&self) -> AppError {
+        AppError::Network(format!(
+            "{}: larger than {} bytes",
+            self.url, self.max_bytes
+        ))
+    }
+}
+
+enum Fetch {
+    Done(Downloaded),
+    /// Network failure worth resuming from
+    Interrupted(String),
+}
+
+fn cancelled(url: &str) -> AppError {
+    AppError::Network(format!("{}: download cancelled", url))
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Registry entry of a labelled download; removed on drop.
+struct Tracker {
+    id: u64,
+    tracked: bool,
+    last_emit: Option<Instant>,
+}
+
+impl Tracker {
+    fn register(id: u64, label: Option<&str>, url: &str, cancel: &Arc<AtomicBool>) -> Self {
+        let tracked = label.is_some_and(|label| {
+            let info = DownloadInfo {
+                id,
+                label: label.to_string(),
+                url: url.to_string(),
+                state: DownloadState::Queued,
+                received_bytes: 0,
+                total_bytes: None,
+                error: None,
+            };
+            let Ok(mut downloads) = DOWNLOADS.lock() else {
+                return false;
+            };
+            info!("[downloads] #{} queued: {}", id, url);
+            downloads.insert(
+                id,
+                Tracked {
+                    info,
+                    cancel: cancel.clone(),
+                },
+            );
+            true
+        });
+        let mut tracker = Self {
+            id,
+            tracked,
+            last_emit: None,
+        };
+        tracker.update(true, |_| {});
+        tracker
+    }
+
+    /// Modify the entry and emit it; progress-only updates are throttled.
+    fn update(&mut self, force: bool, f: impl FnOnce(&mut DownloadInfo)) {
+        if !self.tracked {
+            return;
+        }
+        let info = {
+            let Ok(mut downloads) = DOWNLOADS.lock() else {
+                return;
+            };
+            let Some(tracked) = downloads.get_mut(&self.id) else {
+                return;
+            };
+            f(&mut tracked.info);
+            tracked.info.clone()
+        };
+        if !force
+            && self
+                .last_emit
+                .is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.last_emit = Some(Instant::now());
+        if let Some(app) = APP_HANDLE.get() {
+            let _ = app.emit_app_event(&AppEvent::DownloadProgress(info));
+        }
+    }
+}
+
+impl Drop for Tracker {
+    fn drop(&mut self) {
+        if !self.tracked {
+            return;
+        }
+        if let Ok(mut downloads) = DOWNLOADS.lock() {
+            if let Some(tracked) = downloads.remove(&self.id) {
+                info!(
+                    "[downloads] #{} {:?} ({} bytes)",
+                    self.id, tracked.info.state, tracked.info.received_bytes
+                );
+            }
+        }
+    }
+}
+
+/// A concurrent transfer slot; released on drop.
+struct Slot;
+
+impl Slot {
+    fn acquire(id: u64, cancel: &AtomicBool) -> AppResult<Slot> {
+        let poisoned = || AppError::Network("Download queue poisoned".into());
+        let mut slots = SLOTS.lock().map_err(|_| poisoned())?;
+        slots.queue.push_back(id);
+        loop {
+            if cancel.load(Ordering::SeqCst) {
+                slots.queue.retain(|queued| *queued != id);
+                drop(slots);
+                SLOTS_CHANGED.notify_all();
+                return Err(AppError::Network("Download cancelled".into()));
+            }
+            if slots.queue.front() == Some(&id) && slots.active < slots.limit {
+                slots.queue.pop_front();
+                slots.active += 1;
+                drop(slots);
+                // The next queued download may fit as well
+                SLOTS_CHANGED.notify_all();
+                return Ok(Slot);
+            }
+            slots = SLOTS_CHANGED
+                .wait_timeout(slots, QUEUE_POLL)
+                .map_err(|_| poisoned())?
+                .0;
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if let Ok(mut slots) = SLOTS.lock() {
+            slots.active = slots.active.saturating_sub(1);
+        }
+        SLOTS_CHANGED.notify_all();
+    }
+}
+
+/// Block until `bytes` fit within the global bandwidth cap.
+fn throttle(bytes: usize) {
+    let rate = RATE.load(Ordering::Relaxed);
+    if rate == 0 {
+        return;
+    }
+    let wait = {
+        let Ok(mut bucket) = BUCKET.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let refill = bucket
+            .last
+            .map_or(0.0, |last| (now - last).as_secs_f64() * rate as f64);
+        // Allow at most one second of burst
+        bucket.tokens = (bucket.tokens + refill).min(rate as f64) - bytes as f64;
+        bucket.last = Some(now);
+        if bucket.tokens >= 0.0 {
+            return;
+        }
+        Duration::from_secs_f64(-bucket.tokens / rate as f64)
+    };
+    std::thread::sleep(wait);
+}
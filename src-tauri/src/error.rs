@@ -15,10 +15,38 @@ pub enum AppError {
     Media(String),
     #[error("Discord: {0}")]
     Discord(String),
+    #[error("Storage: {0}")]
+    Storage(String),
+    #[error("Profile: {0}")]
+    Profile(String),
+    #[error("Library: {0}")]
+    Library(String),
+    #[error("Video: {0}")]
+    Video(String),
+    #[error("Network: {0}")]
+    Network(String),
+    #[error("Permission: {0}")]
+    Permission(String),
+    #[error("Notification: {0}")]
+    Notification(String),
+    #[error("Audio: {0}")]
+    Audio(String),
+    #[error("Widget: {0}")]
+    Widget(String),
+    #[error("Location: {0}")]
+    Location(String),
+    #[error("Font: {0}")]
+    Font(String),
+    #[error("Clipboard: {0}")]
+    Clipboard(String),
+    #[error("Display: {0}")]
+    Display(String),
     #[error(transparent)]
     Tauri(#[from] tauri::Error),
     #[error("IO: {0}")]
     Io(#[from] std::io::Error),
+    #[error("JSON: {0}")]
+    Json(#[from] serde_json::Error),
 }
 
 // Serialize as string for backwards compatibility — frontend already handles string errors.
@@ -6,8 +6,117 @@ pub enum AppEvent {
     WallpaperVisibility { visible: bool },
     UpdateProgress { status: String },
     SystemDataUpdate(Box<crate::system_monitor::SystemData>),
+    /// Compositor frame statistics, once per second while subscribed
+    RenderStats(crate::render_stats::RenderStats),
+    /// Spectrum of a subscribed audio source, about 30 times per second
+    AudioSpectrum(crate::audio_spectrum::AudioSpectrum),
+    /// System volume or mute changed (any source, including the volume keys)
+    VolumeChanged(crate::system_monitor::AudioInfo),
+    /// Battery saver toggled or the power source changed
+    PowerModeChanged(crate::power_mode::PowerMode),
+    /// Reduced motion, high contrast or transparency was toggled in the OS
+    AccessibilityPrefsChanged(crate::accessibility::AccessibilityPrefs),
+    /// No keyboard or mouse input for the configured idle threshold
+    #[serde(rename_all = "camelCase")]
+    UserIdle { idle_seconds: u64 },
+    /// Input resumed after `UserIdle`
+    UserActive,
+    /// The connection became metered (prefer cached content) or unmetered
+    ConnectionMetered(crate::metered::ConnectionCost),
+    /// The clipboard content changed (formats only, never the content)
+    ClipboardChanged(crate::clipboard::ClipboardFormats),
+    /// Cursor moved, while subscribed with `subscribe_cursor_position`
+    CursorPosition(crate::cursor::CursorPosition),
+    /// Fonts were installed or removed; `list_system_fonts` is stale
+    FontsChanged,
+    /// The sun entered another phase of the day at the current location
+    SunPhaseChanged {
+        phase: crate::sun::SunPhase,
+        elevation: f64,
+    },
     DeepLink { url: String },
+    /// Routed deep link the frontend has to act on
+    DeepLinkAction {
+        route: crate::deep_link::DeepLinkRoute,
+    },
     ReloadApp,
+    ProfileChanged {
+        profile: crate::profiles::Profile,
+        automatic: bool,
+    },
+    LocalWallpaperApplied {
+        wallpaper: crate::library::LocalWallpaper,
+    },
+    /// Wallpapers were added to the local library from the watched folder
+    LibraryChanged {
+        added: Vec<crate::library::LocalWallpaper>,
+    },
+    WallpaperPaused { paused: bool },
+    /// Quiet hours started (`active`) or ended
+    QuietHours { active: bool },
+    OpenHub {
+        section: Option<String>,
+    },
+    HookRecovered { reason: String },
+    /// The renderer stopped pinging, was reloaded and is responsive again
+    #[serde(rename_all = "camelCase")]
+    RendererHangRecovered { missed_pings: u64 },
+    DesktopGesture {
+        gesture: crate::desktop_actions::DesktopGesture,
+    },
+    NextWallpaper,
+    PreviousWallpaper,
+    /// Load a remote wallpaper by URL (scripting: local API, CLI)
+    ApplyWallpaperUrl { url: String },
+    /// Win+D / "Show desktop" revealed (`active`) or left the desktop
+    ShowDesktop { active: bool },
+    /// Monitors were added/removed or changed resolution, refresh rate or HDR
+    MonitorsChanged {
+        monitors: Vec<crate::system_monitor::DisplayInfo>,
+    },
+    /// The layer was resized for a monitor, taskbar or work-area change
+    WorkAreaChanged {
+        areas: Vec<crate::window_layer::MonitorWorkArea>,
+    },
+    /// A notification (or one of its buttons) was clicked; `action` is the
+    /// button id, None for the notification body
+    #[serde(rename_all = "camelCase")]
+    NotificationAction {
+        notification_id: String,
+        wallpaper_id: Option<String>,
+        action: Option<String>,
+    },
+    /// Wallpaper audio was ducked/muted (`active`) for another app, or restored
+    AudioDucking { active: bool },
+    /// Wallpaper volume changed (tray or `set_wallpaper_volume`)
+    WallpaperVolumeChanged { level: f32 },
+    /// A webview navigation outside the allowlist was cancelled
+    NavigationBlocked { url: String },
+    /// A wallpaper used a capability it was never granted or denied
+    #[serde(rename_all = "camelCase")]
+    PermissionRequested {
+        wallpaper_id: String,
+        capability: crate::permissions::Capability,
+    },
+    /// A wallpaper's stored settings were replaced (null when deleted)
+    #[serde(rename_all = "camelCase")]
+    WallpaperSettingsChanged {
+        wallpaper_id: String,
+        settings: serde_json::Value,
+    },
+    /// A labelled download changed state, or made progress (throttled)
+    DownloadProgress(crate::downloads::DownloadInfo),
+    /// A frontend-defined tray item was clicked
+    TrayCustomAction { id: String },
+    /// The app was launched again; its arguments were forwarded here
+    SecondInstance(crate::cli::SecondInstance),
+    /// Desktop widgets can be dragged (`editing`) or are locked again
+    WidgetEditMode { editing: bool },
+    /// The user session was locked, unlocked, attached to a remote or the
+    /// console, or logged on/off
+    SessionState {
+        state: crate::window_layer::SessionState,
+    },
 }
 
 impl AppEvent {
@@ -16,8 +125,47 @@ impl AppEvent {
             Self::WallpaperVisibility { .. } => "wallpaper-visibility",
             Self::UpdateProgress { .. } => "update-progress",
             Self::SystemDataUpdate(_) => "system-data-update",
+            Self::RenderStats(_) => "render-stats",
+            Self::AudioSpectrum(_) => "audio-spectrum",
+            Self::VolumeChanged(_) => "volume-changed",
+            Self::PowerModeChanged(_) => "power-mode-changed",
             Self::DeepLink { .. } => "deep-link",
+            Self::DeepLinkAction { .. } => "deep-link-action",
             Self::ReloadApp => "reload-app",
+            Self::ProfileChanged { .. } => "profile-changed",
+            Self::LocalWallpaperApplied { .. } => "local-wallpaper-applied",
+            Self::LibraryChanged { .. } => "library-changed",
+            Self::WallpaperPaused { .. } => "wallpaper-paused",
+            Self::QuietHours { .. } => "quiet-hours",
+            Self::OpenHub { .. } => "open-hub",
+            Self::HookRecovered { .. } => "hook-recovered",
+            Self::RendererHangRecovered { .. } => "renderer-hang-recovered",
+            Self::DesktopGesture { .. } => "desktop-gesture",
+            Self::NextWallpaper => "next-wallpaper",
+            Self::PreviousWallpaper => "previous-wallpaper",
+            Self::ApplyWallpaperUrl { .. } => "apply-wallpaper-url",
+            Self::ShowDesktop { .. } => "show-desktop",
+            Self::MonitorsChanged { .. } => "monitors-changed",
+            Self::WorkAreaChanged { .. } => "work-area-changed",
+            Self::NotificationAction { .. } => "notification-action",
+            Self::AudioDucking { .. } => "audio-ducking",
+            Self::WallpaperVolumeChanged { .. } => "wallpaper-volume-changed",
+            Self::NavigationBlocked { .. } => "navigation-blocked",
+            Self::PermissionRequested { .. } => "permission-requested",
+            Self::WallpaperSettingsChanged { .. } => "wallpaper-settings-changed",
+            Self::DownloadProgress(_) => "download-progress",
+            Self::TrayCustomAction { .. } => "tray-custom-action",
+            Self::SecondInstance(_) => "second-instance",
+            Self::WidgetEditMode { .. } => "widget-edit-mode",
+            Self::SessionState { .. } => "session-state",
+            Self::AccessibilityPrefsChanged(_) => "accessibility-prefs-changed",
+            Self::UserIdle { .. } => "user-idle",
+            Self::UserActive => "user-active",
+            Self::ConnectionMetered(_) => "connection-metered",
+            Self::SunPhaseChanged { .. } => "sun-phase-changed",
+            Self::ClipboardChanged(_) => "clipboard-changed",
+            Self::CursorPosition(_) => "cursor-position",
+            Self::FontsChanged => "fonts-changed",
         }
     }
 }
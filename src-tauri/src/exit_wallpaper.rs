@@ -0,0 +1,248 @@
+//! Static OS wallpaper while the app is not running.
+//!
+//! With `staticWallpaperOnExit`, a capture of the layer is kept on disk
+//! (refreshed periodically and on exit) and set as the Windows wallpaper when
+//! the app exits or panics, so the desktop shows the last frame instead of a
+//! stale image. The user's own wallpaper (and its style) is recorded in
+//! `exit_wallpaper.json` and put back on the next launch.
+
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const SNAPSHOT_FILE: &str = "exit-wallpaper.png";
+const STATE_FILE: &str = "exit_wallpaper.json";
+/// Keeps a recent frame around in case the app dies without running shutdown
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The user's wallpaper, replaced by our snapshot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Replaced {
+    /// Empty for a solid color background
+    original: String,
+    style: String,
+    tile: String,
+    /// Empty when nothing was replaced
+    snapshot: String,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static REFRESHING: AtomicBool = AtomicBool::new(false);
+
+/// Put the user's wallpaper back if the last run replaced it. Call after
+/// storage is initialized.
+pub fn init() {
+    let replaced: Replaced = crate::storage::load_json(STATE_FILE);
+    if replaced.snapshot.is_empty() {
+        return;
+    }
+    // Only undo our own change: the user may have picked another wallpaper since
+    if os::current().is_some_and(|c| c.eq_ignore_ascii_case(&replaced.snapshot)) {
+        match os::set(&replaced.original, &replaced.style, &replaced.tile) {
+            Ok(()) => info!("[exit_wallpaper] Restored {}", replaced.original),
+            Err(e) => {
+                warn!("[exit_wallpaper] Cannot restore wallpaper: {}", e);
+                return;
+            }
+        }
+    }
+    if let Err(e) = crate::storage::save_json(STATE_FILE, &Replaced::default()) {
+        warn!("[exit_wallpaper] Cannot clear state: {}", e);
+    }
+}
+
+pub fn configure(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+    if enabled && !REFRESHING.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(|| {
+            while ENABLED.load(Ordering::SeqCst) {
+                std::thread::sleep(REFRESH_INTERVAL);
+                if ENABLED.load(Ordering::SeqCst) && !crate::desktop_actions::is_paused() {
+                    if let Err(e) = save_snapshot() {
+                        warn!("[exit_wallpaper] Snapshot failed: {}", e);
+                    }
+                }
+            }
+            REFRESHING.store(false, Ordering::SeqCst);
+        });
+    }
+}
+
+fn save_snapshot() -> AppResult<()> {
+    let frame = crate::screenshot::capture_frame()?;
+    let path = crate::storage::config_path(SNAPSHOT_FILE)?;
+    let tmp = path.with_extension("png.tmp");
+    frame
+        .save_with_format(&tmp, image::ImageFormat::Png)
+        .map_err(|e| AppError::WindowLayer(format!("Cannot encode snapshot: {}", e)))?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Set the saved snapshot as the OS wallpaper, recording the user's one first.
+fn apply_snapshot() -> AppResult<()> {
+    let snapshot = crate::storage::config_path(SNAPSHOT_FILE)?;
+    if !snapshot.is_file() {
+        return Err(AppError::Storage("No snapshot saved yet".into()));
+    }
+    let snapshot = snapshot.to_string_lossy().into_owned();
+    let current = os::current().unwrap_or_default();
+    // Already ours (a previous run was not restored): keep the recorded original
+    let recorded: Replaced = crate::storage::load_json(STATE_FILE);
+    if recorded.snapshot.is_empty() || !current.eq_ignore_ascii_case(&snapshot) {
+        let (style, tile) = os::style();
+        crate::storage::save_json(
+            STATE_FILE,
+            &Replaced {
+                original: current,
+                style,
+                tile,
+                snapshot: snapshot.clone(),
+            },
+        )?;
+    }
+    // The capture spans every monitor the layer covers
+    os::set(&snapshot, os::STYLE_SPAN, "0")
+}
+
+/// Shutdown step: capture the current frame and leave it as the OS wallpaper.
+/// Runs before the layer is detached.
+pub fn on_shutdown() {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+    if let Err(e) = save_snapshot() {
+        warn!(
+            "[exit_wallpaper] Capture on exit failed, using last snapshot: {}",
+            e
+        );
+    }
+    match apply_snapshot() {
+        Ok(()) => info!("[exit_wallpaper] Snapshot set as the OS wallpaper"),
+        Err(e) => warn!("[exit_wallpaper] {}", e),
+    }
+}
+
+/// Panic path: no capture (the renderer state is unknown), just the last snapshot.
+pub fn on_crash() {
+    if ENABLED.load(Ordering::SeqCst) {
+        let _ = apply_snapshot();
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod os {
+    use crate::error::{AppError, AppResult};
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::System::Registry::{
+        RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPIF_SENDCHANGE, SPIF_UPDATEINIFILE, SPI_GETDESKWALLPAPER,
+        SPI_SETDESKWALLPAPER, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    /// `WallpaperStyle` value spanning the image across monitors
+    pub const STYLE_SPAN: &str = "22";
+    const DESKTOP_KEY: PCWSTR = w!("Control Panel\\Desktop");
+
+    pub fn current() -> Option<String> {
+        let mut buf = [0u16; 1024];
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETDESKWALLPAPER,
+                buf.len() as u32,
+                Some(buf.as_mut_ptr() as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+            .ok()?;
+        }
+        let len = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+        Some(String::from_utf16_lossy(&buf[..len]))
+    }
+
+    fn read(value: PCWSTR) -> String {
+        let mut buf = [0u16; 16];
+        let mut size = std::mem::size_of_val(&buf) as u32;
+        let ok = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                DESKTOP_KEY,
+                value,
+                RRF_RT_REG_SZ,
+                None,
+                Some(buf.as_mut_ptr() as *mut _),
+                Some(&mut size),
+            )
+        }
+        .is_ok();
+        if !ok {
+            return String::new();
+        }
+        let len = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+        String::from_utf16_lossy(&buf[..len])
+    }
+
+    fn write(value: PCWSTR, data: &str) {
+        if data.is_empty() {
+            return;
+        }
+        let wide: Vec<u16> = data.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            let _ = RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                DESKTOP_KEY,
+                value,
+                REG_SZ.0,
+                Some(wide.as_ptr() as *const _),
+                (wide.len() * 2) as u32,
+            );
+        }
+    }
+
+    /// (`WallpaperStyle`, `TileWallpaper`)
+    pub fn style() -> (String, String) {
+        (read(w!("WallpaperStyle")), read(w!("TileWallpaper")))
+    }
+
+    /// Set the wallpaper file and style; an empty path clears the wallpaper.
+    pub fn set(path: &str, style: &str, tile: &str) -> AppResult<()> {
+        // Explorer reads the style when the wallpaper changes
+        write(w!("WallpaperStyle"), style);
+        write(w!("TileWallpaper"), tile);
+        let path = HSTRING::from(path);
+        unsafe {
+            SystemParametersInfoW(
+                SPI_SETDESKWALLPAPER,
+                0,
+                Some(path.as_ptr() as *mut _),
+                SPIF_UPDATEINIFILE | SPIF_SENDCHANGE,
+            )
+        }
+        .map_err(|e| AppError::WindowLayer(format!("Cannot set the OS wallpaper: {}", e)))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod os {
+    use crate::error::{AppError, AppResult};
+
+    pub const STYLE_SPAN: &str = "";
+
+    pub fn current() -> Option<String> {
+        None
+    }
+
+    pub fn style() -> (String, String) {
+        (String::new(), String::new())
+    }
+
+    pub fn set(_path: &str, _style: &str, _tile: &str) -> AppResult<()> {
+        Err(AppError::WindowLayer(
+            "Setting the OS wallpaper is only supported on Windows".into(),
+        ))
+    }
+}
@@ -0,0 +1,227 @@
+//! Installed font enumeration for the wallpaper editor's text layers.
+//!
+//! Families and faces come from the DirectWrite system collection, which
+//! includes per-user fonts. Enumerating takes a moment with large font
+//! libraries, so the result is cached until the font registry keys change;
+//! `WM_FONTCHANGE` (see `window_layer`) additionally emits `fonts-changed`.
+//! File paths are only handed to the app itself, never to wallpapers.
+
+use crate::error::{AppError, AppResult};
+use log::info;
+use serde::Serialize;
+use std::sync::Mutex;
+use typeshare::typeshare;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontFace {
+    /// Face name within the family (e.g., "Bold Italic")
+    pub name: String,
+    /// CSS weight (100-950)
+    pub weight: u16,
+    pub italic: bool,
+    /// Font file; None for wallpapers and fonts not backed by a local file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontFamily {
+    pub name: String,
+    pub faces: Vec<FontFace>,
+}
+
+/// Font registry signature the cached list was built for
+type Signature = Vec<(u32, u64)>;
+
+static CACHE: Mutex<Option<(Signature, Vec<FontFamily>)>> = Mutex::new(None);
+
+/// Installed font families sorted by name; `with_paths` includes font files.
+pub fn list_system_fonts(with_paths: bool) -> AppResult<Vec<FontFamily>> {
+    let signature = signature();
+    let mut cache = CACHE
+        .lock()
+        .map_err(|_| AppError::Font("Font cache poisoned".into()))?;
+    let families = match cache.as_ref() {
+        Some((cached, families)) if *cached == signature => families.clone(),
+        _ => {
+            let mut families = enumerate()?;
+            families.sort_by_key(|f| f.name.to_lowercase());
+            info!("[fonts] {} font families", families.len());
+            *cache = Some((signature, families.clone()));
+            families
+        }
+    };
+    drop(cache);
+    Ok(if with_paths {
+        families
+    } else {
+        families
+            .into_iter()
+            .map(|mut family| {
+                family.faces.iter_mut().for_each(|face| face.path = None);
+                family
+            })
+            .collect()
+    })
+}
+
+/// Drop the cache and tell the frontend (`WM_FONTCHANGE`).
+pub fn on_fonts_changed(app: &tauri::AppHandle) {
+    use crate::events::{AppEvent, EmitAppEvent};
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = None;
+    }
+    info!("[fonts] Installed fonts changed");
+    let _ = app.emit_app_event(&AppEvent::FontsChanged);
+}
+
+/// Value count and last write time of the machine and user font keys.
+#[cfg(target_os = "windows")]
+fn signature() -> Signature {
+    use windows::core::{w, PWSTR};
+    use windows::Win32::Foundation::FILETIME;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegOpenKeyExW, RegQueryInfoKeyW, HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE,
+        KEY_QUERY_VALUE,
+    };
+
+    let subkey = w!("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Fonts");
+    [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER]
+        .into_iter()
+        .map(|root| unsafe {
+            let mut key = HKEY::default();
+            if RegOpenKeyExW(root, subkey, 0, KEY_QUERY_VALUE, &mut key).is_err() {
+                return (0, 0);
+            }
+            let mut values = 0u32;
+            let mut written = FILETIME::default();
+            let _ = RegQueryInfoKeyW(
+                key,
+                PWSTR::null(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(&mut values),
+                None,
+                None,
+                None,
+                Some(&mut written),
+            );
+            let _ = RegCloseKey(key);
+            (
+                values,
+                ((written.dwHighDateTime as u64) << 32) | written.dwLowDateTime as u64,
+            )
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn enumerate() -> AppResult<Vec<FontFamily>> {
+    use windows::core::{w, Interface};
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::Graphics::DirectWrite::{
+        DWriteCreateFactory, IDWriteFactory, IDWriteFontCollection, IDWriteFontFile,
+        IDWriteLocalFontFileLoader, IDWriteLocalizedStrings, DWRITE_FACTORY_TYPE_SHARED,
+        DWRITE_FONT_SIMULATIONS_NONE, DWRITE_FONT_STYLE_NORMAL,
+    };
+
+    let err = |e: windows::core::Error| AppError::Font(format!("DirectWrite: {}", e));
+
+    /// English name when there is one, else the first
+    unsafe fn localized(names: &IDWriteLocalizedStrings) -> Option<String> {
+        let mut index = 0u32;
+        let mut exists = BOOL(0);
+        if names
+            .FindLocaleName(w!("en-us"), &mut index, &mut exists)
+            .is_err()
+            || !exists.as_bool()
+        {
+            index = 0;
+        }
+        let len = names.GetStringLength(index).ok()? as usize;
+        let mut buf = vec![0u16; len + 1];
+        names.GetString(index, &mut buf).ok()?;
+        Some(String::from_utf16_lossy(&buf[..len]))
+    }
+
+    unsafe fn local_path(file: &IDWriteFontFile) -> Option<String> {
+        let loader = file
+            .GetLoader()
+            .ok()?
+            .cast::<IDWriteLocalFontFileLoader>()
+            .ok()?;
+        let mut key = std::ptr::null_mut();
+        let mut key_size = 0u32;
+        file.GetReferenceKey(&mut key, &mut key_size).ok()?;
+        let len = loader.GetFilePathLengthFromKey(key, key_size).ok()? as usize;
+        let mut buf = vec![0u16; len + 1];
+        loader.GetFilePathFromKey(key, key_size, &mut buf).ok()?;
+        Some(String::from_utf16_lossy(&buf[..len]))
+    }
+
+    let mut families = Vec::new();
+    unsafe {
+        let factory: IDWriteFactory =
+            DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED).map_err(err)?;
+        let mut collection: Option<IDWriteFontCollection> = None;
+        factory
+            .GetSystemFontCollection(&mut collection, BOOL(1))
+            .map_err(err)?;
+        let collection = collection.ok_or_else(|| AppError::Font("No system fonts".into()))?;
+        for i in 0..collection.GetFontFamilyCount() {
+            let Ok(family) = collection.GetFontFamily(i) else {
+                continue;
+            };
+            let Some(name) = family.GetFamilyNames().ok().and_then(|n| localized(&n)) else {
+                continue;
+            };
+            let mut faces = Vec::new();
+            for j in 0..family.GetFontCount() {
+                let Ok(font) = family.GetFont(j) else {
+                    continue;
+                };
+                // Bold/oblique synthesized from another face, not a real file
+                if font.GetSimulations() != DWRITE_FONT_SIMULATIONS_NONE {
+                    continue;
+                }
+                let path = font.CreateFontFace().ok().and_then(|face| {
+                    let mut count = 1u32;
+                    let mut files: [Option<IDWriteFontFile>; 1] = [None];
+                    face.GetFiles(&mut count, Some(files.as_mut_ptr())).ok()?;
+                    files[0].as_ref().and_then(|f| local_path(f))
+                });
+                faces.push(FontFace {
+                    name: font
+                        .GetFaceNames()
+                        .ok()
+                        .and_then(|n| localized(&n))
+                        .unwrap_or_default(),
+                    weight: font.GetWeight().0 as u16,
+                    italic: font.GetStyle() != DWRITE_FONT_STYLE_NORMAL,
+                    path,
+                });
+            }
+            if !faces.is_empty() {
+                families.push(FontFamily { name, faces });
+            }
+        }
+    }
+    Ok(families)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn signature() -> Signature {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn enumerate() -> AppResult<Vec<FontFamily>> {
+    Ok(Vec::new())
+}
@@ -0,0 +1,155 @@
+//! Frontend error bridge — JS errors from the wallpaper page into the backend log
+//! and crash-report breadcrumbs, so renderer problems line up with backend events.
+//!
+//! `CAPTURE_SCRIPT` is evaluated on page load and reports `error`,
+//! `unhandledrejection` and `console.error` through `report_frontend_error`.
+
+use log::error;
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use typeshare::typeshare;
+
+const MAX_MESSAGE_CHARS: usize = 2000;
+const MAX_STACK_CHARS: usize = 8000;
+/// At most this many reports per window; the rest are counted and summarized.
+const RATE_LIMIT: u32 = 30;
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+pub const CAPTURE_SCRIPT: &str = r#"
+if (!window.__MW_ERROR_BRIDGE__) {
+    window.__MW_ERROR_BRIDGE__ = true;
+    let last = "";
+    const send = (payload) => {
+        const key = payload.kind + payload.message;
+        if (key === last) return;
+        last = key;
+        try {
+            window.__TAURI__?.core?.invoke("report_frontend_error", { payload }).catch(() => {});
+        } catch (_) {}
+    };
+    const text = (v) => {
+        if (v instanceof Error) return v.message;
+        if (typeof v === "string") return v;
+        try { return JSON.stringify(v); } catch (_) { return String(v); }
+    };
+    window.addEventListener("error", (e) => send({
+        kind: "error",
+        message: e.message || text(e.error),
+        source: e.filename || null,
+        line: e.lineno || null,
+        column: e.colno || null,
+        stack: e.error?.stack || null,
+        url: location.href,
+    }));
+    window.addEventListener("unhandledrejection", (e) => send({
+        kind: "unhandledRejection",
+        message: text(e.reason),
+        stack: e.reason?.stack || null,
+        url: location.href,
+    }));
+    const consoleError = console.error;
+    console.error = (...args) => {
+        consoleError.apply(console, args);
+        send({
+            kind: "console",
+            message: args.map(text).join(" "),
+            stack: args.find((a) => a instanceof Error)?.stack || null,
+            url: location.href,
+        });
+    };
+}
+"#;
+
+#[typeshare]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontendError {
+    /// "error", "unhandledRejection", "console" or any caller-defined kind
+    pub kind: String,
+    pub message: String,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub line: Option<u32>,
+    #[serde(default)]
+    pub column: Option<u32>,
+    #[serde(default)]
+    pub stack: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+struct RateState {
+    window_start: Instant,
+    count: u32,
+    dropped: u32,
+}
+
+static RATE: Mutex<Option<RateState>> = Mutex::new(None);
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}…", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+/// Returns (allowed, reports dropped in the previous window).
+fn admit() -> (bool, u32) {
+    let Ok(mut guard) = RATE.lock() else {
+        return (false, 0);
+    };
+    let now = Instant::now();
+    let state = guard.get_or_insert(RateState {
+        window_start: now,
+        count: 0,
+        dropped: 0,
+    });
+    let mut flushed = 0;
+    if now.duration_since(state.window_start) >= RATE_WINDOW {
+        flushed = state.dropped;
+        *state = RateState {
+            window_start: now,
+            count: 0,
+            dropped: 0,
+        };
+    }
+    if state.count >= RATE_LIMIT {
+        state.dropped += 1;
+        return (false, flushed);
+    }
+    state.count += 1;
+    (true, flushed)
+}
+
+pub fn report(payload: FrontendError) {
+    let (allowed, dropped) = admit();
+    if dropped > 0 {
+        error!(target: "frontend", "{} frontend errors dropped (rate limit)", dropped);
+    }
+    if !allowed {
+        return;
+    }
+
+    let kind = truncate(&payload.kind, 32);
+    let message = truncate(&payload.message, MAX_MESSAGE_CHARS);
+    let location = match (&payload.source, payload.line) {
+        (Some(src), Some(line)) => format!(
+            " at {}:{}:{}",
+            truncate(src, 256),
+            line,
+            payload.column.unwrap_or(0)
+        ),
+        (Some(src), None) => format!(" at {}", truncate(src, 256)),
+        _ => String::new(),
+    };
+    error!(target: "frontend", "[{}] {}{}", kind, message, location);
+    if let Some(stack) = &payload.stack {
+        error!(target: "frontend", "{}", truncate(stack, MAX_STACK_CHARS));
+    }
+    crate::crash::breadcrumb(
+        "frontend",
+        &truncate(&format!("[{}] {}", kind, message), 300),
+    );
+}
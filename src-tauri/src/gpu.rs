@@ -0,0 +1,142 @@
+//! GPU adapters and the adapter driving each monitor, for diagnostics
+//! bundles and the performance HUD.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+/// WebView2 renders through ANGLE on Direct3D 11 on every monitor
+#[cfg(target_os = "windows")]
+const BACKEND: &str = "d3d11";
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuAdapter {
+    /// Adapter name (e.g., "NVIDIA GeForce RTX 4090")
+    pub name: String,
+    /// "nvidia", "amd", "intel", "microsoft" or the PCI vendor id in hex
+    pub vendor: String,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    /// Dedicated VRAM in bytes
+    pub vram: u64,
+    /// System memory the adapter can share, in bytes
+    pub shared_memory: u64,
+    /// User-mode driver version (e.g., "31.0.15.5222")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver_version: Option<String>,
+    /// Software rasterizer (Microsoft Basic Render Driver / WARP)
+    pub software: bool,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorGpu {
+    /// Monitor index, same order as `DisplayInfo`
+    pub monitor: u32,
+    /// GDI device name (`\\.\DISPLAY1`)
+    pub name: String,
+    /// Index into `GpuReport::adapters`
+    pub adapter: u32,
+    /// Graphics API the wallpaper is rendered with
+    pub backend: String,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuReport {
+    pub adapters: Vec<GpuAdapter>,
+    pub monitors: Vec<MonitorGpu>,
+}
+
+#[cfg(target_os = "windows")]
+fn vendor_name(vendor_id: u32) -> String {
+    match vendor_id {
+        0x10DE => "nvidia".into(),
+        0x1002 | 0x1022 => "amd".into(),
+        0x8086 => "intel".into(),
+        0x1414 => "microsoft".into(),
+        0x5143 => "qualcomm".into(),
+        id => format!("{:#06x}", id),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_gpu_info() -> GpuReport {
+    use windows::core::Interface;
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIDevice, IDXGIFactory1, DXGI_ADAPTER_FLAG_SOFTWARE,
+    };
+
+    let mut info = GpuReport::default();
+    let displays = crate::system_monitor::collect_display_info().unwrap_or_default();
+    unsafe {
+        let Ok(factory) = CreateDXGIFactory1::<IDXGIFactory1>() else {
+            return info;
+        };
+        let mut a = 0;
+        while let Ok(adapter) = factory.EnumAdapters1(a) {
+            let Ok(desc) = adapter.GetDesc1() else {
+                a += 1;
+                continue;
+            };
+            // Packed as four 16-bit fields
+            let driver_version = adapter
+                .CheckInterfaceSupport(&IDXGIDevice::IID)
+                .ok()
+                .map(|v| {
+                    let v = v as u64;
+                    format!(
+                        "{}.{}.{}.{}",
+                        v >> 48,
+                        (v >> 32) & 0xFFFF,
+                        (v >> 16) & 0xFFFF,
+                        v & 0xFFFF
+                    )
+                });
+            let index = info.adapters.len() as u32;
+            info.adapters.push(GpuAdapter {
+                name: wide_to_string(&desc.Description),
+                vendor: vendor_name(desc.VendorId),
+                vendor_id: desc.VendorId,
+                device_id: desc.DeviceId,
+                vram: desc.DedicatedVideoMemory as u64,
+                shared_memory: desc.SharedSystemMemory as u64,
+                driver_version,
+                software: desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0,
+            });
+
+            let mut o = 0;
+            while let Ok(output) = adapter.EnumOutputs(o) {
+                if let Ok(out) = output.GetDesc() {
+                    let name = wide_to_string(&out.DeviceName);
+                    if let Some(monitor) = displays.iter().position(|d| d.name == name) {
+                        info.monitors.push(MonitorGpu {
+                            monitor: monitor as u32,
+                            name,
+                            adapter: index,
+                            backend: BACKEND.into(),
+                        });
+                    }
+                }
+                o += 1;
+            }
+            a += 1;
+        }
+    }
+    info.monitors.sort_by_key(|m| m.monitor);
+    info
+}
+
+#[cfg(target_os = "windows")]
+fn wide_to_string(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_gpu_info() -> GpuReport {
+    GpuReport::default()
+}
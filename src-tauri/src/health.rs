@@ -0,0 +1,213 @@
+//! Health check — one structured status per subsystem for the diagnostics panel.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+const UPDATER_ENDPOINT: &str =
+    "https://github.com/MyWallpapers/client/releases/latest/download/latest.json";
+const UPDATER_TIMEOUT_SECS: u64 = 5;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthState {
+    /// Not applicable on this platform
+    Unsupported,
+    Ok,
+    Degraded,
+    Failed,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemHealth {
+    pub name: String,
+    pub state: HealthState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Worst state among subsystems
+    pub overall: HealthState,
+    pub subsystems: Vec<SubsystemHealth>,
+}
+
+fn status(name: &str, state: HealthState, detail: Option<String>) -> SubsystemHealth {
+    SubsystemHealth {
+        name: name.to_string(),
+        state,
+        detail,
+    }
+}
+
+fn from_result(name: &str, result: Result<Option<String>, String>) -> SubsystemHealth {
+    match result {
+        Ok(detail) => status(name, HealthState::Ok, detail),
+        Err(e) => status(name, HealthState::Failed, Some(e)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn injection() -> SubsystemHealth {
+    let layout = crate::window_layer::last_detection().map(|d| format!("{} layout", d.layout));
+    from_result(
+        "injection",
+        crate::window_layer::check_injection().map(|_| layout),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn input() -> SubsystemHealth {
+    let d = crate::window_layer::get_input_diagnostics();
+    let detail = format!("{} backend, {} recoveries", d.backend, d.hook_recoveries);
+    if !d.hook_thread_alive {
+        status(
+            "input",
+            HealthState::Failed,
+            Some("Hook thread is not running".into()),
+        )
+    } else if d.backend == "mouseHook" && !d.hook_installed {
+        status(
+            "input",
+            HealthState::Failed,
+            Some("Mouse hook is not installed".into()),
+        )
+    } else if d.dispatch_dropped > 0 || d.hook_recoveries > 0 {
+        status("input", HealthState::Degraded, Some(detail))
+    } else {
+        status("input", HealthState::Ok, Some(detail))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn composition_controller() -> SubsystemHealth {
+    if crate::window_layer::mouse_hook::get_comp_controller_ptr() != 0 {
+        status("compositionController", HealthState::Ok, None)
+    } else {
+        status(
+            "compositionController",
+            HealthState::Failed,
+            Some("WebView2 composition controller not available; input cannot be forwarded".into()),
+        )
+    }
+}
+
+fn webview_runtime() -> SubsystemHealth {
+    from_result(
+        "webviewRuntime",
+        tauri::webview_version()
+            .map(Some)
+            .map_err(|e| e.to_string()),
+    )
+}
+
+fn updater() -> SubsystemHealth {
+    let result = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(UPDATER_TIMEOUT_SECS))
+        .build()
+        .and_then(|c| c.head(UPDATER_ENDPOINT).send());
+    match result {
+        Ok(r) if r.status().is_success() => status("updater", HealthState::Ok, None),
+        Ok(r) => status(
+            "updater",
+            HealthState::Degraded,
+            Some(format!("Update endpoint returned HTTP {}", r.status())),
+        ),
+        Err(e) => status(
+            "updater",
+            HealthState::Degraded,
+            Some(format!("Update endpoint unreachable: {}", e)),
+        ),
+    }
+}
+
+/// Every config file must parse; a probe write checks the directory is writable.
+fn settings_store() -> SubsystemHealth {
+    let result = (|| -> Result<Option<String>, String> {
+        let files = crate::storage::list_json_files().map_err(|e| e.to_string())?;
+        let corrupt: Vec<&String> = files
+            .iter()
+            .filter(|name| {
+                crate::storage::config_path(name)
+                    .ok()
+                    .and_then(|p| std::fs::read(p).ok())
+                    .is_none_or(|b| serde_json::from_slice::<serde_json::Value>(&b).is_err())
+            })
+            .collect();
+        if !corrupt.is_empty() {
+            return Err(format!("Corrupt config files: {:?}", corrupt));
+        }
+        let probe = crate::storage::config_path(".health-probe").map_err(|e| e.to_string())?;
+        std::fs::write(&probe, b"ok").map_err(|e| format!("Config dir not writable: {}", e))?;
+        let _ = std::fs::remove_file(probe);
+        Ok(Some(format!("{} config files", files.len())))
+    })();
+    from_result("settingsStore", result)
+}
+
+fn library() -> SubsystemHealth {
+    from_result(
+        "library",
+        crate::library::library_root()
+            .map_err(|e| e.to_string())
+            .and_then(|root| {
+                if root.is_dir() {
+                    Ok(None)
+                } else {
+                    Err(format!("{} is missing", root.display()))
+                }
+            }),
+    )
+}
+
+fn crash_reporter() -> SubsystemHealth {
+    match crate::crash::status() {
+        Ok(s) if s.last_run_crashed => status(
+            "crashReporter",
+            HealthState::Degraded,
+            Some("Previous session did not exit cleanly".into()),
+        ),
+        Ok(s) => status(
+            "crashReporter",
+            HealthState::Ok,
+            Some(format!("{} stored reports", s.reports.len())),
+        ),
+        Err(e) => status("crashReporter", HealthState::Failed, Some(e.to_string())),
+    }
+}
+
+/// Blocking (probes the update endpoint); run off the async runtime.
+pub fn health_check() -> HealthReport {
+    let mut subsystems = Vec::new();
+    #[cfg(target_os = "windows")]
+    {
+        subsystems.push(injection());
+        subsystems.push(input());
+        subsystems.push(composition_controller());
+    }
+    #[cfg(not(target_os = "windows"))]
+    for name in ["injection", "input", "compositionController"] {
+        subsystems.push(status(name, HealthState::Unsupported, None));
+    }
+    subsystems.push(webview_runtime());
+    subsystems.push(updater());
+    subsystems.push(settings_store());
+    subsystems.push(library());
+    subsystems.push(crash_reporter());
+
+    let overall = subsystems
+        .iter()
+        .map(|s| s.state)
+        .max()
+        .unwrap_or(HealthState::Ok)
+        .max(HealthState::Ok);
+    HealthReport {
+        overall,
+        subsystems,
+    }
+}
@@ -0,0 +1,98 @@
+//! Renderer heartbeat watchdog.
+//!
+//! A hung page (JS deadlock, lost WebGL context) freezes the wallpaper
+//! silently. The page script pings every few seconds; after `MAX_MISSED`
+//! missed pings the webview is reloaded and `renderer-hang-recovered` emitted.
+//! A lost WebGL context stops the pings unless the page handles the loss
+//! itself (`preventDefault()` on `webglcontextlost` to restore it).
+
+use crate::events::{AppEvent, EmitAppEvent};
+use log::{info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+const PING_INTERVAL_SECS: u64 = 5;
+const MAX_MISSED: u64 = 3;
+/// Grace period for the initial page load
+const GRACE_SECS: u64 = 30;
+const PING_EVENT: &str = "webview-heartbeat";
+
+/// Evaluated on every finished page load.
+pub static SCRIPT: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        r#"
+if (!window.__MW_HEARTBEAT__) {{
+    window.__MW_HEARTBEAT__ = true;
+    let contextLost = false;
+    window.addEventListener('webglcontextlost', (e) => {{
+        // Let the page's own handler run first: preventDefault means it restores
+        setTimeout(() => {{ if (!e.defaultPrevented) contextLost = true; }}, 0);
+    }}, true);
+    setInterval(() => {{
+        if (!contextLost && window.__TAURI__?.event) {{
+            window.__TAURI__.event.emit('{}');
+        }}
+    }}, {});
+}}
+"#,
+        PING_EVENT,
+        PING_INTERVAL_SECS * 1000
+    )
+});
+
+/// Missed pings of the last reload, reported once the page pings again
+static PENDING_RECOVERY: AtomicU64 = AtomicU64::new(0);
+
+// Monotonic clock anchor — immune to NTP syncs, DST adjustments, and manual clock changes.
+static START_TIME: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+fn monotonic_secs() -> u64 {
+    START_TIME.elapsed().as_secs()
+}
+
+/// Listen for pings and start the watchdog thread.
+pub fn start(handle: &tauri::AppHandle) {
+    use tauri::{Listener, Manager};
+
+    let last_ping = Arc::new(AtomicU64::new(monotonic_secs()));
+    let ping = last_ping.clone();
+    let app = handle.clone();
+    handle.listen(PING_EVENT, move |_| {
+        ping.store(monotonic_secs(), Ordering::Relaxed);
+        let missed_pings = PENDING_RECOVERY.swap(0, Ordering::Relaxed);
+        if missed_pings > 0 {
+            info!("[heartbeat] Renderer recovered after reload");
+            let _ = app.emit_app_event(&AppEvent::RendererHangRecovered { missed_pings });
+        }
+    });
+
+    let handle = handle.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(GRACE_SECS));
+        loop {
+            std::thread::sleep(Duration::from_secs(PING_INTERVAL_SECS));
+            let silent = monotonic_secs().saturating_sub(last_ping.load(Ordering::Relaxed));
+            let missed = silent / PING_INTERVAL_SECS;
+            if missed < MAX_MISSED {
+                continue;
+            }
+            warn!(
+                "[heartbeat] Renderer missed {} pings ({}s), reloading",
+                missed, silent
+            );
+            crate::crash::breadcrumb(
+                "webview",
+                &format!("unresponsive for {}s, reloading", silent),
+            );
+            if let Some(w) = handle.get_webview_window("main") {
+                // Reload through the browser process: a deadlocked page cannot run script
+                if w.reload().is_err() {
+                    let _ = w.eval("window.location.reload()");
+                }
+                last_ping.store(monotonic_secs(), Ordering::Relaxed);
+                PENDING_RECOVERY.store(missed, Ordering::Relaxed);
+            }
+        }
+    });
+}
@@ -0,0 +1,75 @@
+//! Hub (settings, library, account) in its own window.
+//!
+//! The wallpaper window stays purely presentational: in non-interactive mode
+//! it never receives clicks, so the hub cannot live there. The hub window is
+//! created on demand (tray, deep links, desktop actions), closes like any
+//! regular window and is rebuilt the next time it is opened.
+
+use crate::error::AppResult;
+use crate::events::{AppEvent, EmitAppEvent};
+use log::info;
+use tauri::Manager;
+
+pub const LABEL: &str = "hub";
+/// Frontend route, resolved against `build.frontendDist`
+const ROUTE: &str = "hub";
+const DEFAULT_SIZE: (f64, f64) = (1200.0, 800.0);
+const MIN_SIZE: (f64, f64) = (800.0, 560.0);
+
+/// Browser arguments of the wallpaper window. WebView2 refuses to create a
+/// second webview in the same profile with different arguments.
+pub fn browser_args(app: &tauri::AppHandle) -> Option<String> {
+    app.config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == "main")
+        .and_then(|w| w.additional_browser_args.clone())
+}
+
+/// Show the hub, creating its window if needed, optionally on a section
+/// (`wallpapers`, `settings`, `settings/<tab>`).
+pub fn open(app: &tauri::AppHandle, section: Option<&str>) -> AppResult<()> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        if window.is_minimized().unwrap_or(false) {
+            window.unminimize()?;
+        }
+        window.show()?;
+        let _ = window.set_focus();
+        app.emit_app_event(&AppEvent::OpenHub {
+            section: section.map(str::to_string),
+        })?;
+        return Ok(());
+    }
+
+    // The page is not loaded yet to receive `open-hub`: pass the section along
+    let route = match section {
+        Some(section) => format!(
+            "{}?section={}",
+            ROUTE,
+            url::form_urlencoded::byte_serialize(section.as_bytes()).collect::<String>()
+        ),
+        None => ROUTE.to_string(),
+    };
+    let mut builder =
+        tauri::WebviewWindowBuilder::new(app, LABEL, tauri::WebviewUrl::App(route.into()))
+            .title("MyWallpaper")
+            .inner_size(DEFAULT_SIZE.0, DEFAULT_SIZE.1)
+            .min_inner_size(MIN_SIZE.0, MIN_SIZE.1)
+            .resizable(true)
+            .center()
+            .focused(true);
+    if let Some(args) = browser_args(app) {
+        builder = builder.additional_browser_args(&args);
+    }
+    builder.build()?;
+    info!("[hub] Window opened");
+    Ok(())
+}
+
+pub fn close(app: &tauri::AppHandle) -> AppResult<()> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.close()?;
+    }
+    Ok(())
+}
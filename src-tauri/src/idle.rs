@@ -0,0 +1,79 @@
+//! User idle time (no keyboard or mouse input).
+//!
+//! Checked every second and emitted as `user-idle` once the user has been
+//! away for `idle_threshold_secs`, then `user-active` on the next input, so
+//! scenes can switch to an ambient mode. Unrelated to the screensaver
+//! subsystem; a threshold of 0 turns the events off.
+
+use log::{error, info};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+pub const DEFAULT_THRESHOLD_SECS: u32 = 5 * 60;
+/// A day; anything longer is a setting mistake
+pub const MAX_THRESHOLD_SECS: u32 = 24 * 60 * 60;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static THRESHOLD_SECS: AtomicU32 = AtomicU32::new(DEFAULT_THRESHOLD_SECS);
+
+pub fn configure(threshold_secs: u32) {
+    THRESHOLD_SECS.store(threshold_secs, Ordering::SeqCst);
+}
+
+/// Start the `user-idle` / `user-active` watcher.
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut idle = false;
+        while RUNNING.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            let threshold = THRESHOLD_SECS.load(Ordering::SeqCst);
+            let idle_seconds = get_idle_seconds();
+            let now_idle = threshold > 0 && idle_seconds >= threshold as u64;
+            if now_idle == idle {
+                continue;
+            }
+            idle = now_idle;
+            let event = if idle {
+                info!("[idle] User idle for {}s", idle_seconds);
+                AppEvent::UserIdle { idle_seconds }
+            } else {
+                info!("[idle] User active");
+                AppEvent::UserActive
+            };
+            if let Err(e) = app.emit_app_event(&event) {
+                error!("[idle] Failed to emit event: {}", e);
+            }
+        }
+    });
+}
+
+/// Seconds since the last keyboard or mouse input in this session.
+#[cfg(target_os = "windows")]
+pub fn get_idle_seconds() -> u64 {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    unsafe {
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return 0;
+        }
+        // Both tick counts wrap after 49.7 days
+        (GetTickCount().wrapping_sub(info.dwTime) / 1000) as u64
+    }
+}
+
+/// Left for the Linux/macOS port; reports an active user.
+#[cfg(not(target_os = "windows"))]
+pub fn get_idle_seconds() -> u64 {
+    0
+}
@@ -2,75 +2,114 @@
 //!
 //! Tauri backend for the MyWallpaper animated wallpaper application.
 
+mod accessibility;
+mod animated_image;
+mod asset_protocol;
+mod audio_ducking;
+mod audio_spectrum;
+mod backup;
+mod brightness;
+mod browser_data;
+mod cli;
+mod clipboard;
+mod color_picker;
 mod commands;
+mod context_menu;
+mod crash;
+mod crypto;
+mod cursor;
+mod deep_link;
+mod desktop_actions;
+mod diagnostics;
+mod dimming;
 mod discord;
+mod downloads;
 pub mod error;
 pub mod events;
+mod exit_wallpaper;
+mod fonts;
+mod frontend_errors;
+mod gpu;
+mod health;
+mod heartbeat;
+mod hub;
+mod idle;
+mod library;
+mod lively;
+mod local_api;
+mod locale;
+mod location;
+mod logs;
 mod media;
+mod metered;
+mod mwpack;
+mod native_video;
+mod navigation;
+mod notifications;
+mod oauth;
+mod offline;
+mod pack_signing;
+mod palette;
+mod pause_rules;
+mod permissions;
+mod power_mode;
+mod preview;
+mod profiles;
+mod quiet_hours;
+mod render_stats;
+mod screenshot;
+mod secure_store;
+mod settings;
+mod shortcuts;
+mod shutdown;
+mod storage;
+mod sun;
 mod system_monitor;
+mod system_volume;
+mod trace;
+mod transition;
 mod tray;
+mod wallpaper_engine;
+mod wallpaper_settings;
+mod wallpaper_volume;
+mod watched_folder;
+mod webview2_runtime;
+mod webview_recovery;
+mod widgets;
 mod window_layer;
 
-use log::{error, info, warn};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, LazyLock};
-use std::time::Instant;
+use log::{error, info};
+use std::sync::LazyLock;
 
-// Timing constants for the WebView heartbeat watchdog
-const HEARTBEAT_GRACE_SECS: u64 = 30;
-const HEARTBEAT_POLL_SECS: u64 = 5;
-const HEARTBEAT_TIMEOUT_SECS: u64 = 15;
 const MONITOR_INTERVAL_SECS: u64 = 3;
 
-// Monotonic clock anchor — immune to NTP syncs, DST adjustments, and manual clock changes.
-static START_TIME: LazyLock<Instant> = LazyLock::new(Instant::now);
-
 static MW_INIT_SCRIPT: LazyLock<String> = LazyLock::new(|| {
     format!(
-        r#"window.__MW_INIT__ = {{ isTauri: true, platform: "{}", arch: "{}", appVersion: "{}", tauriVersion: "{}", debug: {} }};"#,
+        r#"window.__MW_INIT__ = {{ isTauri: true, platform: "{}", arch: "{}", appVersion: "{}", tauriVersion: "{}", debug: {}, launch: {} }};"#,
         std::env::consts::OS,
         std::env::consts::ARCH,
         env!("CARGO_PKG_VERSION"),
         tauri::VERSION,
         cfg!(debug_assertions),
+        serde_json::to_string(cli::launch_options()).unwrap_or_else(|_| "{}".into()),
     )
 });
 
-fn monotonic_secs() -> u64 {
-    START_TIME.elapsed().as_secs()
-}
-
-/// Keep at most 5 log files, delete older ones.
-#[cfg(target_os = "windows")]
-fn rotate_logs() -> Option<()> {
-    let base = std::path::PathBuf::from(std::env::var_os("LOCALAPPDATA")?);
-    let log_dir = base.join("com.mywallpaper.desktop").join("logs");
-    let canonical = log_dir.canonicalize().ok()?;
-    if !canonical.starts_with(base.canonicalize().unwrap_or_default()) {
-        return None;
-    }
-    let mut logs: Vec<_> = std::fs::read_dir(&canonical)
-        .ok()?
-        .flatten()
-        .filter(|e| e.path().extension().is_some_and(|x| x == "log"))
-        .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
-        .collect();
-    logs.sort_by(|a, b| b.1.cmp(&a.1));
-    logs.into_iter().skip(5).for_each(|(p, _)| {
-        let _ = std::fs::remove_file(p);
-    });
-    Some(())
-}
-
 pub fn main() {
-    #[cfg(target_os = "windows")]
-    rotate_logs();
-
+    // `mywallpaper pause|next|…` talks to the running instance and exits;
+    // otherwise launch flags (`--monitor`, `--windowed`, …) are recorded
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    browser_data::prepare();
+    if !webview2_runtime::ensure() {
+        std::process::exit(1);
+    }
     start_with_tauri_webview();
 }
 
 fn start_with_tauri_webview() {
-    use events::{AppEvent, EmitAppEvent};
     use tauri::{webview::PageLoadEvent, Listener, Manager};
 
     let app = tauri::Builder::default()
@@ -91,6 +130,11 @@ fn start_with_tauri_webview() {
                 .target(tauri_plugin_log::Target::new(
                     tauri_plugin_log::TargetKind::Stdout,
                 ))
+                // Size-based rotation in the log dir (see logs.rs for the tail reader)
+                .max_file_size(logs::MAX_FILE_BYTES)
+                .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(
+                    logs::KEEP_FILES,
+                ))
                 .build(),
         )
         .plugin(tauri_plugin_process::init())
@@ -99,35 +143,55 @@ fn start_with_tauri_webview() {
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--minimized"]),
         ))
-        .plugin(tauri_plugin_updater::Builder::new().build())
+        // The Windows installer is launched and the process exits right away
+        .plugin(
+            tauri_plugin_updater::Builder::new()
+                .on_before_exit(|| shutdown::run("Installing update"))
+                .build(),
+        )
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_deep_link::init())
-        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
-            args.into_iter()
-                .filter_map(|a| commands::validate_deep_link(&a))
-                .for_each(|url| {
-                    let _ = app.emit_app_event(&AppEvent::DeepLink { url });
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(
+            tauri::plugin::Builder::new("navigation-guard")
+                .on_navigation(navigation::on_navigation)
+                .build(),
+        )
+        // File reads run on the blocking pool so large files don't stall the UI
+        .register_asynchronous_uri_scheme_protocol(
+            library::PROTOCOL_SCHEME,
+            |_ctx, request, responder| {
+                tauri::async_runtime::spawn_blocking(move || {
+                    responder.respond(library::handle_protocol_request(&request))
                 });
+            },
+        )
+        .register_asynchronous_uri_scheme_protocol(
+            asset_protocol::PROTOCOL_SCHEME,
+            |_ctx, request, responder| {
+                tauri::async_runtime::spawn_blocking(move || {
+                    responder.respond(asset_protocol::handle_protocol_request(&request))
+                });
+            },
+        )
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            cli::handle_second_instance(app, args, cwd);
         }))
         .on_page_load(|webview, payload| {
             match payload.event() {
                 PageLoadEvent::Started => {
                     let _ = webview.eval(&*MW_INIT_SCRIPT);
-                }
-                PageLoadEvent::Finished => {
-                    // Heartbeat: frontend pings every 5s so backend can detect unresponsive WebView
-                    let _ = webview.eval(
-                        r#"
-                    if (!window.__MW_HEARTBEAT__) {
-                        window.__MW_HEARTBEAT__ = true;
-                        setInterval(() => {
-                            if (window.__TAURI__?.event) {
-                                window.__TAURI__.event.emit('webview-heartbeat');
-                            }
-                        }, 5000);
+                    if webview.label() == preview::LABEL {
+                        let _ = webview.eval(preview::INIT_SCRIPT);
+                    }
+                    if let Some(script) = widgets::init_script(webview.label()) {
+                        let _ = webview.eval(&script);
                     }
-                    "#,
-                    );
+                    let _ = webview.eval(frontend_errors::CAPTURE_SCRIPT);
+                }
+                // Heartbeat: the page pings so the backend can detect a hung renderer
+                PageLoadEvent::Finished if webview.label() == "main" => {
+                    let _ = webview.eval(&*heartbeat::SCRIPT);
                 }
                 _ => {}
             }
@@ -135,6 +199,10 @@ fn start_with_tauri_webview() {
         .setup(|app| {
             let handle = app.handle().clone();
 
+            if let Err(e) = crash::init(&handle) {
+                error!("[setup] Failed to initialize crash reporter: {}", e);
+            }
+
             info!(
                 "[main] Starting MyWallpaper Desktop v{} ({}/{})",
                 env!("CARGO_PKG_VERSION"),
@@ -149,74 +217,230 @@ fn start_with_tauri_webview() {
             let deep_link_handle = handle.clone();
             app.listen("deep-link://new-url", move |event| {
                 if let Ok(urls) = serde_json::from_str::<Vec<String>>(event.payload()) {
-                    urls.into_iter()
-                        .filter_map(|u| commands::validate_deep_link(&u))
-                        .for_each(|url| {
-                            let _ = deep_link_handle.emit_app_event(&AppEvent::DeepLink { url });
-                        });
+                    deep_link::handle_links(&deep_link_handle, urls);
                 }
             });
 
+            // Before injection: desktop detection reads its cache from storage
+            if let Err(e) = storage::init(&handle) {
+                error!("[setup] Failed to initialize config storage: {}", e);
+            }
+
+            let launch = cli::launch_options();
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.set_background_color(Some(tauri::webview::Color(0, 0, 0, 255)));
-                window_layer::setup_desktop_window(&window);
+                if launch.windowed {
+                    // `--windowed`: a regular window for debugging, never injected
+                    let _ = window.set_decorations(true);
+                    let _ = window.set_resizable(true);
+                    let _ = window.set_maximizable(true);
+                    let _ = window.set_minimizable(true);
+                    let _ = window.set_skip_taskbar(false);
+                    let _ = window.set_size(tauri::LogicalSize::new(1280.0, 720.0));
+                    let _ = window.center();
+                } else {
+                    window_layer::set_layer_monitor(launch.monitor);
+                    window_layer::setup_desktop_window(&window);
+                }
                 let _ = window.show();
             }
 
+            exit_wallpaper::init();
+            if let Err(e) = library::init(&handle) {
+                error!("[setup] Failed to initialize local library: {}", e);
+            }
+            downloads::init(handle.clone());
+            offline::init();
+            asset_protocol::init();
+            shortcuts::init(handle.clone());
+            settings::init();
+            audio_ducking::init(handle.clone());
+            quiet_hours::init(handle.clone());
+            pause_rules::init();
+            dimming::init();
+            permissions::init(handle.clone());
+            wallpaper_settings::init();
+            desktop_actions::init(handle.clone());
+            if launch.paused {
+                let _ = desktop_actions::set_paused(true);
+            }
+            // Launched inside an RDP session: no connect notification will come
+            window_layer::update_remote_pause();
+            local_api::init(handle.clone());
+            context_menu::init(handle.clone());
+            shutdown::init(handle.clone());
+
             system_monitor::start_monitor(handle.clone(), MONITOR_INTERVAL_SECS);
+            render_stats::start(handle.clone());
+            clipboard::start(handle.clone());
+            cursor::start(handle.clone());
+            audio_spectrum::init(handle.clone());
+            system_volume::start(handle.clone());
+            wallpaper_volume::start(handle.clone());
+            power_mode::start(handle.clone());
+            accessibility::start(handle.clone());
+            idle::start(handle.clone());
+            metered::start(handle.clone());
+            watched_folder::start(handle.clone());
+            profiles::init(handle.clone());
+            if let Some(profile) = &launch.profile {
+                if let Err(e) = profiles::apply_by_id_or_name(&handle, profile) {
+                    error!("[setup] --profile {}: {}", profile, e);
+                }
+            }
             discord::init();
+            widgets::init(handle.clone());
 
-            // WebView heartbeat watchdog — auto-reload if frontend stops responding
-            let last_heartbeat = Arc::new(AtomicU64::new(monotonic_secs()));
-            let hb = last_heartbeat.clone();
-            handle.listen("webview-heartbeat", move |_| {
-                hb.store(monotonic_secs(), Ordering::Relaxed);
-            });
-
-            let hb_handle = handle.clone();
-            let hb_ref = last_heartbeat.clone();
-            std::thread::spawn(move || {
-                use std::time::Duration;
-                use tauri::Manager;
-                // Grace period for initial page load
-                std::thread::sleep(Duration::from_secs(HEARTBEAT_GRACE_SECS));
-                loop {
-                    std::thread::sleep(Duration::from_secs(HEARTBEAT_POLL_SECS));
-                    let elapsed = monotonic_secs() - hb_ref.load(Ordering::Relaxed);
-                    if elapsed > HEARTBEAT_TIMEOUT_SECS {
-                        warn!("[heartbeat] WebView unresponsive ({}s), reloading", elapsed);
-                        if let Some(w) = hb_handle.get_webview_window("main") {
-                            let _ = w.eval("window.location.reload()");
-                            hb_ref.store(monotonic_secs(), Ordering::Relaxed);
-                        }
-                    }
-                }
-            });
+            heartbeat::start(&handle);
+            webview_recovery::init(handle.clone());
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            commands::get_system_info,
-            commands::get_system_data,
-            commands::subscribe_system_data,
-            commands::check_for_updates,
-            commands::download_and_install_update,
-            commands::restart_app,
-            commands::open_oauth_in_browser,
-            commands::reload_window,
-            commands::get_media_info,
-            commands::media_play_pause,
-            commands::media_next,
-            commands::media_prev,
-            commands::update_discord_presence,
-            window_layer::set_desktop_icons_visible,
-        ])
+        .invoke_handler({
+            let handler = tauri::generate_handler![
+                commands::get_system_info,
+                commands::get_gpu_info,
+                commands::open_preview,
+                commands::close_preview,
+                commands::open_hub_window,
+                commands::close_hub_window,
+                commands::list_widgets,
+                commands::save_widget,
+                commands::delete_widget,
+                commands::set_widget_visible,
+                commands::set_widget_edit_mode,
+                commands::start_widget_drag,
+                commands::get_system_data,
+                commands::subscribe_system_data,
+                commands::subscribe_render_stats,
+                commands::subscribe_cursor_position,
+                commands::subscribe_audio_spectrum,
+                commands::set_dimming,
+                commands::set_wallpaper_volume,
+                commands::get_power_mode,
+                commands::get_idle_seconds,
+                commands::get_accessibility_prefs,
+                commands::get_connection_cost,
+                commands::get_locale_info,
+                commands::list_system_fonts,
+                commands::clipboard_read_text,
+                commands::clipboard_write_text,
+                commands::clipboard_read_image,
+                commands::clipboard_write_image,
+                commands::subscribe_clipboard,
+                commands::get_location,
+                commands::get_sun_info,
+                commands::grant_asset_dir,
+                commands::revoke_asset_dir,
+                commands::list_asset_dirs,
+                commands::get_browser_storage_usage,
+                commands::clear_browser_storage,
+                commands::get_system_volume,
+                commands::set_system_volume,
+                commands::check_for_updates,
+                commands::download_and_install_update,
+                commands::restart_app,
+                commands::open_oauth_in_browser,
+                commands::start_oauth_flow,
+                commands::cancel_oauth_flow,
+                commands::secure_store_set,
+                commands::secure_store_get,
+                commands::secure_store_delete,
+                commands::reload_window,
+                commands::get_media_info,
+                commands::media_play_pause,
+                commands::media_next,
+                commands::media_prev,
+                commands::update_discord_presence,
+                commands::list_profiles,
+                commands::save_profile,
+                commands::delete_profile,
+                commands::apply_profile,
+                commands::list_local_wallpapers,
+                commands::apply_local_wallpaper,
+                commands::apply_wallpaper,
+                commands::get_monitors,
+                commands::set_monitor_brightness,
+                commands::capture_wallpaper_screenshot,
+                commands::get_wallpaper_palette,
+                commands::pick_screen_color,
+                commands::show_notification,
+                commands::notify_wallpaper_ready,
+                commands::scan_workshop_items,
+                commands::import_wallpaper_engine_projects,
+                commands::import_lively_wallpapers,
+                commands::install_pack,
+                commands::uninstall_pack,
+                commands::validate_pack,
+                commands::list_downloads,
+                commands::cancel_download,
+                commands::set_tray_menu,
+                commands::play_native_video,
+                commands::pause_native_video,
+                commands::resume_native_video,
+                commands::set_native_video_loop,
+                commands::set_native_video_volume,
+                commands::stop_native_video,
+                commands::get_native_video_status,
+                commands::apply_animated_image,
+                commands::stop_animated_image,
+                commands::cache_remote_wallpaper,
+                commands::pin_offline,
+                commands::unpin_offline,
+                commands::list_offline_wallpapers,
+                commands::resolve_wallpaper_url,
+                commands::export_backup,
+                commands::import_backup,
+                commands::get_backend_settings,
+                commands::set_backend_settings,
+                commands::run_desktop_action,
+                commands::list_shortcuts,
+                commands::register_action_shortcut,
+                commands::unregister_action_shortcut,
+                commands::set_allowed_origins,
+                commands::get_recent_logs,
+                commands::get_crash_status,
+                commands::upload_crash_report,
+                commands::delete_crash_report,
+                commands::export_diagnostics,
+                commands::health_check,
+                commands::start_trace_capture,
+                commands::report_frontend_error,
+                commands::get_local_api_info,
+                commands::regenerate_local_api_key,
+                commands::list_wallpaper_permissions,
+                commands::set_wallpaper_permission,
+                commands::revoke_wallpaper_permissions,
+                commands::get_wallpaper_settings,
+                commands::set_wallpaper_settings,
+                commands::set_desktop_icons_visible,
+                window_layer::get_input_diagnostics,
+                window_layer::get_window_layer,
+                window_layer::get_work_areas,
+                window_layer::set_passthrough_regions,
+            ];
+            // Async commands are only timed until they are spawned
+            move |invoke| {
+                let _span = trace::span("command", invoke.message.command().to_string());
+                handler(invoke)
+            }
+        })
         .build(tauri::generate_context!())
         .expect("Error while building MyWallpaper Desktop");
 
     app.run(|_app_handle, event| {
+        // Closing the window of a dead webview while it is rebuilt is not an exit
+        if let tauri::RunEvent::ExitRequested { code: None, api, .. } = &event {
+            if webview_recovery::is_recreating() {
+                api.prevent_exit();
+                return;
+            }
+        }
         if let tauri::RunEvent::ExitRequested { .. } | tauri::RunEvent::Exit = event {
-            window_layer::restore_desktop_icons_and_unhook();
+            shutdown::run("App exiting");
+        }
+        if let tauri::RunEvent::Exit = event {
+            crash::mark_clean_exit();
         }
     });
 }
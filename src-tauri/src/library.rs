@@ -0,0 +1,749 @@
+//! Local wallpaper library — offline wallpaper packs served over `mwlocal://`.
+//!
+//! Each subdirectory of the library root is one pack. A pack either ships a
+//! `wallpaper.json` manifest or is inferred from its contents (`index.html`
+//! → web, first video file → video, first image → image).
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use crate::pack_signing::{self, PackVerification};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::http::{header, Request, Response, StatusCode};
+use typeshare::typeshare;
+
+pub const PROTOCOL_SCHEME: &str = "mwlocal";
+pub const MANIFEST_FILE: &str = "wallpaper.json";
+const PREVIEW_FILE: &str = "preview.png";
+const PREVIEW_MAX_SIZE: u32 = 480;
+
+/// Reject archives that would expand beyond this (zip bomb guard).
+pub const MAX_EXTRACTED_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Largest range served per request; players ask again for the rest
+const MAX_RANGE_CHUNK: u64 = 4 * 1024 * 1024;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm", "mkv", "mov"];
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp"];
+
+static LIBRARY_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WallpaperKind {
+    Web,
+    Video,
+    Image,
+}
+
+/// On-disk `wallpaper.json` manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackManifest {
+    pub name: String,
+    pub kind: WallpaperKind,
+    /// Main file, relative to the pack directory
+    pub entry: String,
+    #[serde(default)]
+    pub preview: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Signature check result, recorded by `install_pack`
+    #[serde(default)]
+    pub verification: PackVerification,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalWallpaper {
+    /// Pack directory name
+    pub id: String,
+    pub name: String,
+    pub kind: WallpaperKind,
+    /// URL of the entry file over the local protocol
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub verification: PackVerification,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStatus {
+    Imported,
+    AlreadyImported,
+    Unsupported,
+    Failed,
+}
+
+/// Per-item outcome of an importer run.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    /// Source directory or archive
+    pub source: String,
+    /// Library pack id (set when imported or already present)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub status: ImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ImportResult {
+    pub fn new(source: &Path, status: ImportStatus) -> Self {
+        Self {
+            source: source.display().to_string(),
+            id: None,
+            name: None,
+            status,
+            message: None,
+        }
+    }
+}
+
+// ============================================================================
+// Indexing
+// ============================================================================
+
+/// Resolve and create the library root. Must run once during setup.
+pub fn init(app: &tauri::AppHandle) -> AppResult<()> {
+    use tauri::Manager;
+    let root = app.path().app_data_dir()?.join("library");
+    std::fs::create_dir_all(&root)?;
+    info!("[library] Root: {}", root.display());
+    let _ = LIBRARY_ROOT.set(root);
+    Ok(())
+}
+
+pub fn library_root() -> AppResult<&'static Path> {
+    LIBRARY_ROOT
+        .get()
+        .map(PathBuf::as_path)
+        .ok_or_else(|| AppError::Library("Library not initialized".into()))
+}
+
+/// URL the webview uses to fetch `rel_path` inside pack `id`.
+/// WebView2 maps custom schemes to `http://<scheme>.localhost/`.
+pub fn protocol_url(id: &str, rel_path: &str) -> String {
+    let path = rel_path.replace('\\', "/");
+    if cfg!(target_os = "windows") {
+        format!("http://{}.localhost/{}/{}", PROTOCOL_SCHEME, id, path)
+    } else {
+        format!("{}://localhost/{}/{}", PROTOCOL_SCHEME, id, path)
+    }
+}
+
+fn has_extension(path: &Path, exts: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| exts.iter().any(|x| x.eq_ignore_ascii_case(e)))
+}
+
+/// Kind of a standalone video or image file, judged by extension.
+pub fn file_kind(path: &Path) -> Option<WallpaperKind> {
+    if has_extension(path, VIDEO_EXTENSIONS) {
+        Some(WallpaperKind::Video)
+    } else if has_extension(path, IMAGE_EXTENSIONS) {
+        Some(WallpaperKind::Image)
+    } else {
+        None
+    }
+}
+
+/// Guess a manifest for a pack directory without `wallpaper.json`.
+fn infer_manifest(dir: &Path, name: &str) -> Option<PackManifest> {
+    if dir.join("index.html").is_file() {
+        return Some(PackManifest {
+            name: name.to_string(),
+            kind: WallpaperKind::Web,
+            entry: "index.html".into(),
+            preview: None,
+            author: None,
+            description: None,
+            verification: PackVerification::Unsigned,
+        });
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    files.sort();
+    let pick = |exts: &[&str]| {
+        files
+            .iter()
+            .find(|p| has_extension(p, exts))
+            .and_then(|p| p.file_name()?.to_str().map(str::to_string))
+    };
+    let (kind, entry) = if let Some(v) = pick(VIDEO_EXTENSIONS) {
+        (WallpaperKind::Video, v)
+    } else {
+        (WallpaperKind::Image, pick(IMAGE_EXTENSIONS)?)
+    };
+    Some(PackManifest {
+        name: name.to_string(),
+        kind,
+        entry,
+        preview: None,
+        author: None,
+        description: None,
+        verification: PackVerification::Unsigned,
+    })
+}
+
+/// Read a pack's manifest (explicit or inferred).
+pub fn read_manifest(dir: &Path) -> Option<PackManifest> {
+    let id = dir.file_name()?.to_str()?;
+    match std::fs::read(dir.join(MANIFEST_FILE)) {
+        Ok(bytes) => match serde_json::from_slice::<PackManifest>(&bytes) {
+            Ok(m) => Some(m),
+            Err(e) => {
+                warn!("[library] Invalid manifest in '{}': {}", id, e);
+                None
+            }
+        },
+        Err(_) => infer_manifest(dir, id),
+    }
+}
+
+fn to_local_wallpaper(id: &str, m: PackManifest) -> LocalWallpaper {
+    LocalWallpaper {
+        id: id.to_string(),
+        name: m.name,
+        kind: m.kind,
+        url: protocol_url(id, &m.entry),
+        preview_url: m.preview.as_deref().map(|p| protocol_url(id, p)),
+        author: m.author,
+        description: m.description,
+        verification: m.verification,
+    }
+}
+
+/// Index every pack in the library root.
+pub fn list_local_wallpapers() -> AppResult<Vec<LocalWallpaper>> {
+    let root = library_root()?;
+    let mut items: Vec<LocalWallpaper> = std::fs::read_dir(root)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter_map(|dir| {
+            let id = dir.file_name()?.to_str()?.to_string();
+            // Hidden directories are in-progress installs
+            if id.starts_with('.') {
+                return None;
+            }
+            let manifest = read_manifest(&dir)?;
+            Some(to_local_wallpaper(&id, manifest))
+        })
+        .collect();
+    items.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    Ok(items)
+}
+
+pub fn get_local_wallpaper(id: &str) -> AppResult<LocalWallpaper> {
+    validate_pack_id(id)?;
+    let dir = library_root()?.join(id);
+    let manifest = read_manifest(&dir)
+        .ok_or_else(|| AppError::Library(format!("Unknown local wallpaper: {}", id)))?;
+    Ok(to_local_wallpaper(id, manifest))
+}
+
+/// Tell the frontend to load a local wallpaper.
+pub fn apply_local_wallpaper(app: &tauri::AppHandle, id: &str) -> AppResult<LocalWallpaper> {
+    let wallpaper = get_local_wallpaper(id)?;
+    info!("[library] Applying local wallpaper '{}'", wallpaper.name);
+    crate::crash::breadcrumb("wallpaper", &format!("applied local '{}'", id));
+    app.emit_app_event(&AppEvent::LocalWallpaperApplied {
+        wallpaper: wallpaper.clone(),
+    })?;
+    Ok(wallpaper)
+}
+
+// ============================================================================
+// Installation (used by importers)
+// ============================================================================
+
+pub fn pack_exists(id: &str) -> bool {
+    library_root().is_ok_and(|root| root.join(id).is_dir())
+}
+
+fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)?.flatten() {
+        let file_type = entry.file_type()?;
+        let target = dst.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &target)?;
+        }
+        // Symlinks are skipped — a pack must be self-contained
+    }
+    Ok(())
+}
+
+/// Copy `src_dir` to the hidden staging directory of pack `id`, verify it and
+/// write its manifest. The staging directory is removed on error.
+fn stage_pack(src_dir: &Path, id: &str, manifest: &PackManifest) -> AppResult<PathBuf> {
+    let staging = library_root()?.join(format!(".{}.partial", id));
+    let _ = std::fs::remove_dir_all(&staging);
+
+    let result = copy_dir_recursive(src_dir, &staging)
+        .map_err(AppError::from)
+        .and_then(|_| {
+            // Verified before the pack becomes reachable over the protocol
+            let manifest = PackManifest {
+                verification: pack_signing::ensure_not_tampered(&staging)?,
+                ..manifest.clone()
+            };
+            std::fs::write(
+                staging.join(MANIFEST_FILE),
+                serde_json::to_vec_pretty(&manifest)?,
+            )?;
+            Ok(())
+        });
+    match result {
+        Ok(()) => Ok(staging),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            Err(e)
+        }
+    }
+}
+
+/// Copy `src_dir` into the library as pack `id` and write its manifest.
+/// Staged in a hidden directory and renamed so a failed copy leaves no half pack.
+/// Packs with a signature that does not verify are refused.
+pub fn install_pack(src_dir: &Path, id: &str, manifest: &PackManifest) -> AppResult<()> {
+    validate_pack_id(id)?;
+    let dest = library_root()?.join(id);
+    if dest.exists() {
+        return Err(AppError::Library(format!("Pack already exists: {}", id)));
+    }
+    let staging = stage_pack(src_dir, id, manifest)?;
+    std::fs::rename(&staging, &dest).map_err(|e| {
+        let _ = std::fs::remove_dir_all(&staging);
+        AppError::from(e)
+    })
+}
+
+/// Like `install_pack`, but an installed pack `id` is replaced. The old pack
+/// is only removed once the new one is in place; any failure leaves it as it was.
+pub fn replace_pack(src_dir: &Path, id: &str, manifest: &PackManifest) -> AppResult<()> {
+    validate_pack_id(id)?;
+    let root = library_root()?;
+    let dest = root.join(id);
+    let staging = stage_pack(src_dir, id, manifest)?;
+    if !dest.exists() {
+        return std::fs::rename(&staging, &dest).map_err(|e| {
+            let _ = std::fs::remove_dir_all(&staging);
+            AppError::from(e)
+        });
+    }
+
+    let old = root.join(format!(".{}.old", id));
+    let _ = std::fs::remove_dir_all(&old);
+    if let Err(e) = std::fs::rename(&dest, &old) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e.into());
+    }
+    if let Err(e) = std::fs::rename(&staging, &dest) {
+        if let Err(restore) = std::fs::rename(&old, &dest) {
+            warn!("[library] Could not restore pack '{}': {}", id, restore);
+        }
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e.into());
+    }
+    if let Err(e) = std::fs::remove_dir_all(&old) {
+        warn!("[library] Could not remove the replaced '{}': {}", id, e);
+    }
+    Ok(())
+}
+
+/// Writer that fails once the bytes written through all of its instances
+/// (summed in `total`) pass `MAX_EXTRACTED_BYTES`.
+struct CappedWriter<'a, W> {
+    inner: W,
+    total: &'a mut u64,
+}
+
+impl<W: std::io::Write> std::io::Write for CappedWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        *self.total = self.total.saturating_add(buf.len() as u64);
+        if *self.total > MAX_EXTRACTED_BYTES {
+            return Err(std::io::Error::other("extraction limit reached"));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Extract a pack archive (`.zip`) to a scratch directory inside the library root.
+/// Sizes declared in the archive are not trusted: extraction stops as soon as
+/// the bytes actually written pass `MAX_EXTRACTED_BYTES`. The caller removes
+/// `dest` on error.
+pub fn extract_zip(archive_path: &Path, dest: &Path) -> AppResult<()> {
+    let file = std::fs::File::open(archive_path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| AppError::Library(format!("Invalid zip: {}", e)))?;
+
+    let mut total: u64 = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| AppError::Library(format!("Invalid zip entry: {}", e)))?;
+        let Some(rel) = entry.enclosed_name() else {
+            return Err(AppError::Library(format!(
+                "Archive entry escapes the pack: {}",
+                entry.name()
+            )));
+        };
+        if entry.is_symlink() {
+            return Err(AppError::Library(format!(
+                "Archive entry is a symlink: {}",
+                entry.name()
+            )));
+        }
+        let path = dest.join(rel);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = CappedWriter {
+            inner: std::fs::File::create(&path)?,
+            total: &mut total,
+        };
+        if let Err(e) = std::io::copy(&mut entry, &mut out) {
+            return Err(if total > MAX_EXTRACTED_BYTES {
+                AppError::Library("Archive is too large to import".into())
+            } else {
+                AppError::Library(format!("Extraction failed: {}", e))
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Delete a pack from the library. Missing packs are not an error.
+pub fn remove_pack(id: &str) -> AppResult<()> {
+    validate_pack_id(id)?;
+    let dir = library_root()?.join(id);
+    match std::fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Create `preview.png` for an installed pack that has none.
+/// Only image packs can be thumbnailed without a video decoder.
+/// Returns the preview path relative to the pack, if one exists afterwards.
+pub fn generate_preview(id: &str) -> AppResult<Option<String>> {
+    validate_pack_id(id)?;
+    let dir = library_root()?.join(id);
+    let Some(mut manifest) = read_manifest(&dir) else {
+        return Err(AppError::Library(format!("Unknown local wallpaper: {}", id)));
+    };
+    if manifest.preview.is_some() {
+        return Ok(manifest.preview);
+    }
+    if manifest.kind != WallpaperKind::Image {
+        return Ok(None);
+    }
+
+    let img = image::open(dir.join(&manifest.entry))
+        .map_err(|e| AppError::Library(format!("Cannot decode {}: {}", manifest.entry, e)))?;
+    img.thumbnail(PREVIEW_MAX_SIZE, PREVIEW_MAX_SIZE)
+        .save_with_format(dir.join(PREVIEW_FILE), image::ImageFormat::Png)
+        .map_err(|e| AppError::Library(format!("Cannot write preview: {}", e)))?;
+
+    manifest.preview = Some(PREVIEW_FILE.into());
+    std::fs::write(dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest)?)?;
+    Ok(manifest.preview)
+}
+
+/// PNG thumbnail of a pack's preview, at most `max_size` px on its longest side.
+/// Image packs without a preview get one generated first.
+pub fn thumbnail_png(id: &str, max_size: u32) -> AppResult<Vec<u8>> {
+    let no_preview = || AppError::Library(format!("No preview for local wallpaper: {}", id));
+    let preview = generate_preview(id)?.ok_or_else(no_preview)?;
+    let path =
+        resolve_in_root(library_root()?, &Path::new(id).join(&preview)).ok_or_else(no_preview)?;
+    let img = image::open(&path)
+        .map_err(|e| AppError::Library(format!("Cannot decode {}: {}", preview, e)))?;
+    let mut png = std::io::Cursor::new(Vec::new());
+    img.thumbnail(max_size, max_size)
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| AppError::Library(format!("Cannot encode thumbnail: {}", e)))?;
+    Ok(png.into_inner())
+}
+
+pub fn validate_pack_id(id: &str) -> AppResult<()> {
+    let ok = !id.is_empty()
+        && id != "."
+        && id != ".."
+        && !id.contains(['/', '\\', ':'])
+        && !id.starts_with('.');
+    if ok {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("Invalid wallpaper id: {}", id)))
+    }
+}
+
+// ============================================================================
+// Protocol Handler
+// ============================================================================
+
+fn mime_for(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "html" | "htm" => "text/html",
+        "js" | "mjs" => "text/javascript",
+        "css" => "text/css",
+        "json" => "application/json",
+        "wasm" => "application/wasm",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        "mov" => "video/quicktime",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Resolve a request path (`/<id>/<rel>`) to a file inside the library root.
+/// Rejects traversal and symlinks escaping the root.
+fn resolve_request_path(root: &Path, url_path: &str) -> Option<PathBuf> {
+    let decoded = percent_decode(url_path.trim_start_matches('/'))?;
+    resolve_in_root(root, Path::new(&decoded))
+}
+
+pub(crate) fn resolve_in_root(root: &Path, rel: &Path) -> Option<PathBuf> {
+    if rel
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    let canonical_root = root.canonicalize().ok()?;
+    let full = canonical_root.join(rel).canonicalize().ok()?;
+    (full.starts_with(&canonical_root) && full.is_file()).then_some(full)
+}
+
+pub(crate) fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            // Exactly two hex digits; from_str_radix alone would accept `%+5`
+            let hex = bytes.get(i + 1..i + 3)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            out.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Parse a single `bytes=start-end` range against a file of `len` bytes.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = if start.is_empty() {
+        // Suffix range: last N bytes
+        let n: u64 = end.parse().ok()?;
+        (len.saturating_sub(n), len.checked_sub(1)?)
+    } else {
+        let s: u64 = start.parse().ok()?;
+        let e = if end.is_empty() {
+            len.checked_sub(1)?
+        } else {
+            end.parse::<u64>().ok()?.min(len.checked_sub(1)?)
+        };
+        (s, e)
+    };
+    (start <= end && end < len).then_some((start, end))
+}
+
+pub(crate) fn status_response(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+/// Serve a file from the library (Range-aware so video seeking works).
+pub fn serve_file(root: &Path, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    match resolve_request_path(root, request.uri().path()) {
+        Some(path) => serve_path(&path, request),
+        None => status_response(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Serve an already resolved file, honouring a `Range` header. Ranges are
+/// cut to `MAX_RANGE_CHUNK` so open-ended requests (`bytes=0-`) of large
+/// videos don't load the whole file.
+pub(crate) fn serve_path(path: &Path, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return status_response(StatusCode::NOT_FOUND);
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mime = mime_for(path);
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok());
+
+    if let Some(range) = range {
+        let Some((start, end)) = parse_range(range, len) else {
+            return Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                .body(Vec::new())
+                .unwrap_or_default();
+        };
+        let end = end.min(start + MAX_RANGE_CHUNK - 1);
+        let mut buf = vec![0u8; (end - start + 1) as usize];
+        if file.seek(SeekFrom::Start(start)).is_err() || file.read_exact(&mut buf).is_err() {
+            return status_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        return Response::builder()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_TYPE, mime)
+            .header(header::ACCEPT_RANGES, "bytes")
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, len),
+            )
+            .body(buf)
+            .unwrap_or_default();
+    }
+
+    let mut buf = Vec::with_capacity(len as usize);
+    if file.read_to_end(&mut buf).is_err() {
+        return status_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, mime)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .body(buf)
+        .unwrap_or_default()
+}
+
+/// `mwlocal://` protocol entry point. Blocks on file I/O; call it off the
+/// main thread.
+pub fn handle_protocol_request(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    match library_root() {
+        Ok(root) => serve_file(root, request),
+        Err(_) => status_response(StatusCode::SERVICE_UNAVAILABLE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decodes_escapes() {
+        assert_eq!(percent_decode("a%20b%2Fc").as_deref(), Some("a b/c"));
+        assert_eq!(percent_decode("caf%C3%A9").as_deref(), Some("café"));
+        assert_eq!(percent_decode("plain").as_deref(), Some("plain"));
+    }
+
+    #[test]
+    fn percent_decode_rejects_malformed_escapes() {
+        for raw in ["%", "%2", "a%zz", "%+5", "%-1", "% 5", "%C3"] {
+            assert_eq!(percent_decode(raw), None, "accepted {}", raw);
+        }
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=500-", 1000), Some((500, 999)));
+        // End past the file is clamped
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some((900, 999)));
+        // Only the first of several ranges is served
+        assert_eq!(parse_range("bytes=0-9, 20-29", 1000), Some((0, 9)));
+    }
+
+    #[test]
+    fn parses_suffix_ranges() {
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-5000", 1000), Some((0, 999)));
+        assert_eq!(parse_range("bytes=-0", 1000), None);
+    }
+
+    #[test]
+    fn rejects_unsatisfiable_ranges() {
+        for raw in [
+            "bytes=1000-",
+            "bytes=1000-1100",
+            "bytes=50-10",
+            "bytes=-",
+            "bytes=",
+            "bytes=a-b",
+            "items=0-10",
+            "0-10",
+        ] {
+            assert_eq!(parse_range(raw, 1000), None, "accepted {}", raw);
+        }
+        // Any range of an empty file
+        assert_eq!(parse_range("bytes=0-", 0), None);
+        assert_eq!(parse_range("bytes=-10", 0), None);
+    }
+}
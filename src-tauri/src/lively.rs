@@ -0,0 +1,287 @@
+//! Lively Wallpaper importer — `.zip` exports and library folders with `LivelyInfo.json`.
+
+use crate::error::AppResult;
+use crate::library::{self, ImportResult, ImportStatus, PackManifest, WallpaperKind};
+use log::{info, warn};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const LIVELY_INFO_FILE: &str = "LivelyInfo.json";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Subset of Lively's `LivelyInfo.json` (PascalCase on disk).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LivelyInfo {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    desc: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
+    preview: Option<String>,
+    /// Lively `WallpaperType` enum (serialized as an integer)
+    #[serde(default, rename = "Type")]
+    kind: Option<i64>,
+    #[serde(default)]
+    file_name: Option<String>,
+    #[serde(default)]
+    is_absolute_path: bool,
+}
+
+// Lively WallpaperType values
+const LIVELY_WEB: i64 = 1;
+const LIVELY_WEB_AUDIO: i64 = 2;
+const LIVELY_URL: i64 = 3;
+const LIVELY_VIDEO: i64 = 7;
+const LIVELY_GIF: i64 = 8;
+const LIVELY_VIDEO_STREAM: i64 = 10;
+const LIVELY_PICTURE: i64 = 11;
+
+// ============================================================================
+// Discovery
+// ============================================================================
+
+/// Lively's own wallpaper library (installer and Microsoft Store builds).
+#[cfg(target_os = "windows")]
+pub fn find_library_dirs() -> Vec<PathBuf> {
+    let Some(local) = std::env::var_os("LOCALAPPDATA").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let lib_suffix = Path::new("Lively Wallpaper")
+        .join("Library")
+        .join("wallpapers");
+    let mut roots = vec![local.join(&lib_suffix)];
+    if let Ok(packages) = std::fs::read_dir(local.join("Packages")) {
+        roots.extend(
+            packages
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().contains("LivelyWallpaper"))
+                .map(|e| e.path().join("LocalCache").join("Local").join(&lib_suffix)),
+        );
+    }
+    roots
+        .into_iter()
+        .filter_map(|r| std::fs::read_dir(r).ok())
+        .flat_map(|rd| rd.flatten().map(|e| e.path()))
+        .filter(|p| p.join(LIVELY_INFO_FILE).is_file())
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn find_library_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+fn read_info(dir: &Path) -> AppResult<LivelyInfo> {
+    let bytes = std::fs::read(dir.join(LIVELY_INFO_FILE))?;
+    // Lively writes a UTF-8 BOM
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+    Ok(serde_json::from_slice(bytes)?)
+}
+
+/// Keep only relative paths that exist inside the pack.
+fn existing_relative(dir: &Path, rel: Option<&str>) -> Option<String> {
+    let rel = rel?.trim();
+    let p = Path::new(rel);
+    (!rel.is_empty() && p.is_relative() && dir.join(p).is_file()).then(|| rel.to_string())
+}
+
+fn convert(dir: &Path, info: &LivelyInfo) -> Result<PackManifest, String> {
+    let kind = match info.kind {
+        Some(LIVELY_WEB) | Some(LIVELY_WEB_AUDIO) => WallpaperKind::Web,
+        Some(LIVELY_VIDEO) => WallpaperKind::Video,
+        Some(LIVELY_GIF) | Some(LIVELY_PICTURE) => WallpaperKind::Image,
+        Some(LIVELY_URL) | Some(LIVELY_VIDEO_STREAM) => {
+            return Err("Remote URL/stream wallpapers have nothing to import".into())
+        }
+        Some(other) => return Err(format!("Unsupported Lively wallpaper type {}", other)),
+        None => return Err("LivelyInfo.json has no wallpaper type".into()),
+    };
+    if info.is_absolute_path {
+        return Err("Wallpaper references files outside its folder".into());
+    }
+    let entry = existing_relative(dir, info.file_name.as_deref())
+        .ok_or("Wallpaper file missing from package")?;
+
+    Ok(PackManifest {
+        name: info
+            .title
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| "Lively import".into()),
+        kind,
+        entry,
+        preview: existing_relative(dir, info.thumbnail.as_deref())
+            .or_else(|| existing_relative(dir, info.preview.as_deref())),
+        author: info.author.clone().filter(|a| !a.trim().is_empty()),
+        description: info.desc.clone().filter(|d| !d.trim().is_empty()),
+        // Filled in by install_pack
+        verification: Default::default(),
+    })
+}
+
+fn sanitized_stem(source: &Path) -> Option<String> {
+    let stem = source.file_stem()?.to_str()?;
+    Some(
+        stem.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect(),
+    )
+}
+
+/// `lively-<stem>-<hash>`: the hash of `LivelyInfo.json` keeps different
+/// wallpapers with the same folder or archive name apart, while importing the
+/// same wallpaper again still maps to the same pack.
+fn pack_id_for(source: &Path, content_dir: &Path) -> Option<String> {
+    use sha2::{Digest, Sha256};
+
+    let stem = sanitized_stem(source)?;
+    let info = std::fs::read(content_dir.join(LIVELY_INFO_FILE)).ok()?;
+    let digest: String = Sha256::digest(&info)[..4]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Some(format!("lively-{}-{}", stem, digest))
+}
+
+/// Import an unpacked Lively wallpaper directory.
+fn import_dir(source: &Path, content_dir: &Path) -> ImportResult {
+    let mut result = ImportResult::new(source, ImportStatus::Failed);
+
+    let info = match read_info(content_dir) {
+        Ok(i) => i,
+        Err(e) => {
+            result.message = Some(format!("Unreadable {}: {}", LIVELY_INFO_FILE, e));
+            return result;
+        }
+    };
+    result.name = info.title.clone();
+
+    let Some(id) = pack_id_for(source, content_dir) else {
+        result.message = Some("Could not derive a pack id".into());
+        return result;
+    };
+    result.id = Some(id.clone());
+
+    if library::pack_exists(&id) {
+        result.status = ImportStatus::AlreadyImported;
+        return result;
+    }
+
+    let manifest = match convert(content_dir, &info) {
+        Ok(m) => m,
+        Err(reason) => {
+            result.status = ImportStatus::Unsupported;
+            result.message = Some(reason);
+            return result;
+        }
+    };
+
+    match library::install_pack(content_dir, &id, &manifest) {
+        Ok(()) => {
+            result.status = ImportStatus::Imported;
+            if let Err(e) = library::generate_preview(&id) {
+                warn!("[lively] Preview generation failed for {}: {}", id, e);
+            }
+        }
+        Err(e) => result.message = Some(e.to_string()),
+    }
+    result
+}
+
+/// `LivelyInfo.json` is at the archive root or inside a single top-level folder.
+fn locate_content_dir(extracted: &Path) -> Option<PathBuf> {
+    if extracted.join(LIVELY_INFO_FILE).is_file() {
+        return Some(extracted.to_path_buf());
+    }
+    let subdirs: Vec<PathBuf> = std::fs::read_dir(extracted)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    match subdirs.as_slice() {
+        [only] if only.join(LIVELY_INFO_FILE).is_file() => Some(only.clone()),
+        _ => None,
+    }
+}
+
+fn import_zip(archive_path: &Path) -> ImportResult {
+    let mut result = ImportResult::new(archive_path, ImportStatus::Failed);
+    let root = match library::library_root() {
+        Ok(r) => r,
+        Err(e) => {
+            result.message = Some(e.to_string());
+            return result;
+        }
+    };
+    // The pack id needs LivelyInfo.json, so duplicates are detected after extraction
+    let Some(stem) = sanitized_stem(archive_path) else {
+        result.message = Some("Could not derive a pack id".into());
+        return result;
+    };
+
+    let scratch = root.join(format!(".lively-{}.extract", stem));
+    let _ = std::fs::remove_dir_all(&scratch);
+    let result = match library::extract_zip(archive_path, &scratch) {
+        Ok(()) => match locate_content_dir(&scratch) {
+            Some(content) => import_dir(archive_path, &content),
+            None => {
+                result.message = Some(format!("{} not found in archive", LIVELY_INFO_FILE));
+                result
+            }
+        },
+        Err(e) => {
+            result.message = Some(e.to_string());
+            result
+        }
+    };
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+/// Import the given `.zip` files / folders, or Lively's own library when `paths` is empty.
+pub fn import_wallpapers(paths: Vec<String>) -> AppResult<Vec<ImportResult>> {
+    library::library_root()?;
+    let sources: Vec<PathBuf> = if paths.is_empty() {
+        find_library_dirs()
+    } else {
+        paths.into_iter().map(PathBuf::from).collect()
+    };
+    info!("[lively] Importing {} source(s)", sources.len());
+
+    Ok(sources
+        .iter()
+        .map(|src| {
+            let is_zip = src
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+            if is_zip && src.is_file() {
+                import_zip(src)
+            } else if src.join(LIVELY_INFO_FILE).is_file() {
+                import_dir(src, src)
+            } else {
+                let mut r = ImportResult::new(src, ImportStatus::Unsupported);
+                r.message = Some("Not a Lively wallpaper (.zip or folder)".into());
+                r
+            }
+        })
+        .collect())
+}
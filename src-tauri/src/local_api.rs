@@ -0,0 +1,522 @@
+//! Local REST API — scripting/automation surface bound to 127.0.0.1.
+//!
+//! Every request must carry the API key (`Authorization: Bearer <key>` or
+//! `X-API-Key: <key>`). The key is generated on first use and kept in
+//! `local_api.json`.
+//!
+//! - `GET  /status`    — version, pause state, active profile, local wallpapers
+//! - `POST /wallpaper` — `{"id": "<local pack>"}` or `{"url": "https://…"}`
+//! - `POST /pause`     — `{"paused": bool}`, toggles when the body is empty
+//! - `POST /next`      — ask the frontend for the next wallpaper
+//!
+//! Controller endpoints (Stream Deck-style plugins). This set is stable: routes
+//! are only ever added under `/deck/v1`, never changed. Ids are percent-encoded
+//! path segments.
+//!
+//! - `GET  /deck/v1/wallpapers`                — `[{id, name, kind, thumbnail}]`
+//! - `GET  /deck/v1/wallpapers/<id>/thumbnail` — PNG, 144 px max (`image/png`)
+//! - `POST /deck/v1/wallpapers/<id>/apply`
+//! - `POST /deck/v1/pause/toggle`              — `{paused}`
+//! - `GET  /deck/v1/profiles`                  — `[{id, name, active}]`
+//! - `POST /deck/v1/profiles/<id>/apply`
+
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use typeshare::typeshare;
+
+const KEY_FILE: &str = "local_api.json";
+pub const DEFAULT_PORT: u16 = 39517;
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+const DECK_PREFIX: &str = "/deck/v1";
+/// Stream Deck XL key size; smaller devices downscale
+const DECK_THUMBNAIL_SIZE: u32 = 144;
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+struct RunningServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyFile {
+    #[serde(default)]
+    api_key: String,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiInfo {
+    pub running: bool,
+    pub port: u16,
+    pub api_key: String,
+}
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+fn app() -> AppResult<&'static tauri::AppHandle> {
+    APP_HANDLE
+        .get()
+        .ok_or_else(|| AppError::Validation("Local API not initialized".into()))
+}
+
+// ============================================================================
+// API key
+// ============================================================================
+
+/// Current API key, created on first use.
+pub fn api_key() -> AppResult<String> {
+    let stored: KeyFile = crate::storage::load_json(KEY_FILE);
+    if !stored.api_key.is_empty() {
+        return Ok(stored.api_key);
+    }
+    regenerate_key()
+}
+
+/// Replace the API key; clients using the old one are rejected from now on.
+pub fn regenerate_key() -> AppResult<String> {
+    let api_key = crate::crypto::random_hex(24)?;
+    crate::storage::save_json(
+        KEY_FILE,
+        &KeyFile {
+            api_key: api_key.clone(),
+        },
+    )?;
+    Ok(api_key)
+}
+
+// ============================================================================
+// Server lifecycle
+// ============================================================================
+
+pub fn info() -> AppResult<LocalApiInfo> {
+    let port = SERVER.lock().ok().and_then(|s| s.as_ref().map(|r| r.port));
+    Ok(LocalApiInfo {
+        running: port.is_some(),
+        port: port.unwrap_or_else(|| crate::settings::get().local_api_port),
+        api_key: api_key()?,
+    })
+}
+
+fn stop_server(server: RunningServer) {
+    server.stop.store(true, Ordering::SeqCst);
+    // Wake the blocking accept() so the thread sees the flag
+    let _ = TcpStream::connect_timeout(
+        &SocketAddr::from((Ipv4Addr::LOCALHOST, server.port)),
+        Duration::from_millis(200),
+    );
+    info!("[local_api] Stopped");
+}
+
+/// Start, restart or stop the server to match the settings.
+pub fn configure(enabled: bool, port: u16) {
+    let Ok(mut current) = SERVER.lock() else {
+        return;
+    };
+    if current.as_ref().is_some_and(|s| enabled && s.port == port) {
+        return;
+    }
+    if let Some(server) = current.take() {
+        stop_server(server);
+    }
+    if !enabled {
+        return;
+    }
+    if let Err(e) = api_key() {
+        warn!("[local_api] Not started: {}", e);
+        return;
+    }
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+        Ok(l) => l,
+        Err(e) => {
+            warn!("[local_api] Cannot bind 127.0.0.1:{}: {}", port, e);
+            return;
+        }
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if thread_stop.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(stream) = stream else {
+                continue;
+            };
+            std::thread::spawn(move || handle_connection(stream));
+        }
+    });
+    info!("[local_api] Listening on 127.0.0.1:{}", port);
+    *current = Some(RunningServer { port, stop });
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, (u16, &'static str)> {
+    let mut reader = BufReader::new(stream.take((MAX_HEADER_BYTES + MAX_BODY_BYTES) as u64));
+    let mut line = String::new();
+    let mut header_bytes = 0;
+    let mut read_line = |reader: &mut BufReader<std::io::Take<&TcpStream>>, line: &mut String| {
+        line.clear();
+        match reader.read_line(line) {
+            Ok(0) | Err(_) => Err((400, "Malformed request")),
+            Ok(n) => {
+                header_bytes += n;
+                if header_bytes > MAX_HEADER_BYTES {
+                    Err((431, "Headers too large"))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    };
+
+    read_line(&mut reader, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err((400, "Malformed request line"));
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or("/").to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        read_line(&mut reader, &mut line)?;
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = trimmed.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .map(|(_, v)| v.parse::<usize>().map_err(|_| (400, "Bad Content-Length")))
+        .transpose()?
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err((413, "Body too large"));
+    }
+    let mut body = vec![0u8; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| (400, "Truncated body"))?;
+
+    Ok(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn write_response(mut stream: &TcpStream, status: u16, content_type: &str, body: &[u8]) {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason,
+        content_type,
+        body.len()
+    );
+    let _ = stream.write_all(body);
+    let _ = stream.flush();
+}
+
+fn write_json(stream: &TcpStream, status: u16, body: &Value) {
+    write_response(
+        stream,
+        status,
+        "application/json",
+        body.to_string().as_bytes(),
+    );
+}
+
+fn error_body(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+fn authorized(req: &Request) -> bool {
+    let Ok(key) = api_key() else {
+        return false;
+    };
+    let provided = req
+        .header("x-api-key")
+        .or_else(|| req.header("authorization")?.strip_prefix("Bearer "))
+        .unwrap_or("");
+    crate::crypto::constant_time_eq(provided.trim().as_bytes(), key.as_bytes())
+}
+
+fn handle_connection(stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+    let req = match read_request(&stream) {
+        Ok(r) => r,
+        Err((status, message)) => {
+            write_json(&stream, status, &error_body(message));
+            return;
+        }
+    };
+    // Browsers never send our key, but reject any cross-site request outright
+    if req.header("origin").is_some() || !authorized(&req) {
+        write_json(&stream, 401, &error_body("Missing or invalid API key"));
+        return;
+    }
+    let result = match req.path.strip_prefix(DECK_PREFIX) {
+        Some(rest) => deck_route(&req.method, rest),
+        None => route(&req).map(Reply::Json),
+    };
+    let status = match result {
+        Ok(Reply::Json(body)) => {
+            write_json(&stream, 200, &body);
+            200
+        }
+        Ok(Reply::Png(bytes)) => {
+            write_response(&stream, 200, "image/png", &bytes);
+            200
+        }
+        Err((status, message)) => {
+            write_json(&stream, status, &error_body(&message));
+            status
+        }
+    };
+    info!("[local_api] {} {} -> {}", req.method, req.path, status);
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+enum Reply {
+    Json(Value),
+    Png(Vec<u8>),
+}
+
+#[derive(Debug, Deserialize)]
+struct WallpaperBody {
+    id: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PauseBody {
+    paused: Option<bool>,
+}
+
+fn parse_body<T: serde::de::DeserializeOwned + Default>(body: &[u8]) -> Result<T, (u16, String)> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body).map_err(|e| (400, format!("Invalid JSON body: {}", e)))
+}
+
+fn internal(e: AppError) -> (u16, String) {
+    (500, e.to_string())
+}
+
+/// Shared with the CLI forwarding path.
+pub fn status_json() -> Value {
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "paused": crate::desktop_actions::is_paused(),
+        "activeProfile": crate::profiles::list_profiles().active,
+        "localWallpapers": crate::library::list_local_wallpapers()
+            .map(|list| list.into_iter().map(|w| json!({ "id": w.id, "name": w.name })).collect())
+            .unwrap_or_else(|_| Vec::<Value>::new()),
+    })
+}
+
+/// Apply a local pack by id or a remote wallpaper by http(s) URL.
+pub fn apply_wallpaper(id: Option<&str>, url: Option<&str>) -> AppResult<Value> {
+    let app = app()?;
+    match (id, url) {
+        (Some(id), None) => {
+            let wallpaper = crate::library::apply_local_wallpaper(app, id)?;
+            Ok(json!({ "applied": wallpaper.id }))
+        }
+        (None, Some(url)) => {
+            let applied =
+                crate::transition::apply_url(app, url, &crate::transition::Transition::default())?;
+            Ok(json!({ "applied": applied }))
+        }
+        _ => Err(AppError::Validation(
+            "Provide exactly one of \"id\" or \"url\"".into(),
+        )),
+    }
+}
+
+fn route(req: &Request) -> Result<Value, (u16, String)> {
+    match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/status") => Ok(status_json()),
+        ("POST", "/wallpaper") => {
+            let body: WallpaperBody = serde_json::from_slice(&req.body)
+                .map_err(|e| (400, format!("Invalid JSON body: {}", e)))?;
+            apply_wallpaper(body.id.as_deref(), body.url.as_deref()).map_err(|e| match e {
+                AppError::Validation(_) | AppError::Library(_) => (400, e.to_string()),
+                e => internal(e),
+            })
+        }
+        ("POST", "/pause") => {
+            let body: PauseBody = parse_body(&req.body)?;
+            let paused = match body.paused {
+                Some(p) => crate::desktop_actions::set_paused(p).map(|_| p),
+                None => crate::desktop_actions::toggle_pause(),
+            }
+            .map_err(internal)?;
+            Ok(json!({ "paused": paused }))
+        }
+        ("POST", "/next") => {
+            crate::desktop_actions::run(&crate::desktop_actions::DesktopAction::NextWallpaper)
+                .map_err(internal)?;
+            Ok(json!({ "next": true }))
+        }
+        (_, "/status" | "/wallpaper" | "/pause" | "/next") => {
+            Err((405, "Method not allowed".to_string()))
+        }
+        _ => Err((404, "Not found".to_string())),
+    }
+}
+
+// ============================================================================
+// Controller routes (/deck/v1)
+// ============================================================================
+
+/// Percent-encode an id for use as a path segment.
+fn encode_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn deck_wallpapers() -> AppResult<Value> {
+    let wallpapers = crate::library::list_local_wallpapers()?
+        .into_iter()
+        .map(|w| {
+            let has_thumbnail =
+                w.preview_url.is_some() || w.kind == crate::library::WallpaperKind::Image;
+            json!({
+                "id": w.id,
+                "name": w.name,
+                "kind": w.kind,
+                "thumbnail": has_thumbnail.then(|| {
+                    format!("{}/wallpapers/{}/thumbnail", DECK_PREFIX, encode_segment(&w.id))
+                }),
+            })
+        })
+        .collect();
+    Ok(Value::Array(wallpapers))
+}
+
+fn deck_profiles() -> Value {
+    let list = crate::profiles::list_profiles();
+    list.profiles
+        .iter()
+        .map(|p| {
+            json!({
+                "id": p.id,
+                "name": p.name,
+                "active": list.active.as_deref() == Some(p.id.as_str()),
+            })
+        })
+        .collect()
+}
+
+fn not_found_or_bad_request(e: AppError) -> (u16, String) {
+    match e {
+        AppError::Library(_) | AppError::Profile(_) => (404, e.to_string()),
+        AppError::Validation(_) => (400, e.to_string()),
+        e => internal(e),
+    }
+}
+
+fn deck_route(method: &str, rest: &str) -> Result<Reply, (u16, String)> {
+    let segments: Vec<String> = rest
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(crate::library::percent_decode)
+        .collect::<Option<_>>()
+        .ok_or((400, "Invalid path encoding".to_string()))?;
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["wallpapers"]) => deck_wallpapers().map(Reply::Json).map_err(internal),
+        ("GET", ["wallpapers", id, "thumbnail"]) => {
+            crate::library::thumbnail_png(id, DECK_THUMBNAIL_SIZE)
+                .map(Reply::Png)
+                .map_err(not_found_or_bad_request)
+        }
+        ("POST", ["wallpapers", id, "apply"]) => apply_wallpaper(Some(*id), None)
+            .map(Reply::Json)
+            .map_err(not_found_or_bad_request),
+        ("POST", ["pause", "toggle"]) => {
+            let paused = crate::desktop_actions::toggle_pause().map_err(internal)?;
+            Ok(Reply::Json(json!({ "paused": paused })))
+        }
+        ("GET", ["profiles"]) => Ok(Reply::Json(deck_profiles())),
+        ("POST", ["profiles", id, "apply"]) => {
+            crate::profiles::apply_profile(app().map_err(internal)?, id, false)
+                .map_err(not_found_or_bad_request)?;
+            Ok(Reply::Json(json!({ "applied": id })))
+        }
+        (
+            _,
+            ["wallpapers"]
+            | ["wallpapers", _, "thumbnail" | "apply"]
+            | ["pause", "toggle"]
+            | ["profiles"]
+            | ["profiles", _, "apply"],
+        ) => Err((405, "Method not allowed".to_string())),
+        _ => Err((404, "Not found".to_string())),
+    }
+}
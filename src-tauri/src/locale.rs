@@ -0,0 +1,170 @@
+//! OS language, region and formatting preferences, so clock and weather
+//! widgets format values the way the user set them up instead of guessing
+//! from `navigator.language` (WebView2 reports the browser UI language).
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Weekday {
+    #[default]
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MeasurementSystem {
+    #[default]
+    Metric,
+    /// US customary units
+    Us,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    /// Display language as a BCP 47 tag (e.g., "fr-FR")
+    pub language: String,
+    /// Locale used for formats as a BCP 47 tag; can differ from `language`
+    pub locale: String,
+    /// ISO 3166 region code (e.g., "FR")
+    pub region: String,
+    pub first_day_of_week: Weekday,
+    /// The time format shows hours 0-23
+    pub hour24: bool,
+    pub measurement: MeasurementSystem,
+    pub temperature: TemperatureUnit,
+}
+
+/// Regions that report temperatures in Fahrenheit
+const FAHRENHEIT_REGIONS: &[&str] = &["US", "BS", "BZ", "KY", "PW", "LR", "FM", "MH"];
+
+fn temperature_for(region: &str) -> TemperatureUnit {
+    if FAHRENHEIT_REGIONS.contains(&region) {
+        TemperatureUnit::Fahrenheit
+    } else {
+        TemperatureUnit::Celsius
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_locale_info() -> LocaleInfo {
+    use windows::core::{PCWSTR, PWSTR};
+    use windows::Win32::Globalization::{
+        GetLocaleInfoEx, GetUserDefaultLocaleName, GetUserPreferredUILanguages,
+        LOCALE_IFIRSTDAYOFWEEK, LOCALE_IMEASURE, LOCALE_SISO3166CTRYNAME, LOCALE_STIMEFORMAT,
+        MUI_LANGUAGE_NAME,
+    };
+
+    fn wide(buf: &[u16]) -> String {
+        let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+        String::from_utf16_lossy(&buf[..len])
+    }
+    // Null locale name = the user default locale
+    let info = |lctype: u32| -> String {
+        let mut buf = [0u16; 128];
+        let n = unsafe { GetLocaleInfoEx(PCWSTR::null(), lctype, Some(&mut buf)) };
+        if n > 0 {
+            wide(&buf)
+        } else {
+            String::new()
+        }
+    };
+
+    let mut buf = [0u16; 85]; // LOCALE_NAME_MAX_LENGTH
+    let locale = if unsafe { GetUserDefaultLocaleName(&mut buf) } > 0 {
+        wide(&buf)
+    } else {
+        String::new()
+    };
+    // Multi-string, most preferred first
+    let mut count = 0u32;
+    let mut languages = [0u16; 256];
+    let mut len = languages.len() as u32;
+    let language = match unsafe {
+        GetUserPreferredUILanguages(
+            MUI_LANGUAGE_NAME,
+            &mut count,
+            PWSTR(languages.as_mut_ptr()),
+            &mut len,
+        )
+    } {
+        Ok(()) if count > 0 => wide(&languages),
+        _ => locale.clone(),
+    };
+    let region = info(LOCALE_SISO3166CTRYNAME);
+    // LOCALE_IFIRSTDAYOFWEEK: 0 = Monday ... 6 = Sunday
+    let first_day_of_week = match info(LOCALE_IFIRSTDAYOFWEEK).as_str() {
+        "1" => Weekday::Tuesday,
+        "2" => Weekday::Wednesday,
+        "3" => Weekday::Thursday,
+        "4" => Weekday::Friday,
+        "5" => Weekday::Saturday,
+        "6" => Weekday::Sunday,
+        _ => Weekday::Monday,
+    };
+    LocaleInfo {
+        language,
+        locale,
+        temperature: temperature_for(&region),
+        region,
+        first_day_of_week,
+        // "H" is 0-23, "h" 1-12
+        hour24: info(LOCALE_STIMEFORMAT).contains('H'),
+        measurement: if info(LOCALE_IMEASURE) == "1" {
+            MeasurementSystem::Us
+        } else {
+            MeasurementSystem::Metric
+        },
+    }
+}
+
+/// Language and region from `LANG` (e.g., "en_US.UTF-8"); formats default.
+#[cfg(not(target_os = "windows"))]
+pub fn get_locale_info() -> LocaleInfo {
+    let lang = std::env::var("LC_ALL")
+        .or_else(|_| std::env::var("LANG"))
+        .unwrap_or_default();
+    let tag = lang
+        .split(['.', '@'])
+        .next()
+        .unwrap_or("")
+        .replace('_', "-");
+    let region = tag.split('-').nth(1).unwrap_or("").to_string();
+    LocaleInfo {
+        language: tag.clone(),
+        locale: tag,
+        temperature: temperature_for(&region),
+        measurement: if region == "US" {
+            MeasurementSystem::Us
+        } else {
+            MeasurementSystem::Metric
+        },
+        first_day_of_week: if region == "US" {
+            Weekday::Sunday
+        } else {
+            Weekday::Monday
+        },
+        hour24: region != "US",
+        region,
+    }
+}
@@ -0,0 +1,140 @@
+//! Coarse device location for weather and sunrise/sunset scheduling.
+//!
+//! Asks the OS location service (Windows.Devices.Geolocation; Windows shows
+//! its own consent prompt the first time) and rounds the fix to about 1 km.
+//! When the service is off, denied or has no fix, the manual location from
+//! settings is used instead. Fixes are cached for a while so polling widgets
+//! do not keep the location service busy.
+
+use crate::error::{AppError, AppResult};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use typeshare::typeshare;
+
+const CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+/// Two decimals: ~1.1 km of latitude
+#[cfg(target_os = "windows")]
+const COARSE_FACTOR: f64 = 100.0;
+
+static CACHE: Mutex<Option<(Instant, Location)>> = Mutex::new(None);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LocationSource {
+    /// OS location service
+    Os,
+    /// `manual_location` setting
+    Manual,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Accuracy radius in meters reported by the OS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+    pub source: LocationSource,
+}
+
+pub fn validate(manual: &ManualLocation) -> AppResult<()> {
+    if !(-90.0..=90.0).contains(&manual.latitude) || !(-180.0..=180.0).contains(&manual.longitude) {
+        return Err(AppError::Validation(
+            "Manual location needs a latitude within ±90 and a longitude within ±180".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn coarse(value: f64) -> f64 {
+    (value * COARSE_FACTOR).round() / COARSE_FACTOR
+}
+
+/// Current coarse location. Blocks while the OS looks up a fix.
+pub fn get_location() -> AppResult<Location> {
+    if let Some((at, location)) = *CACHE.lock().map_err(|_| cache_error())? {
+        if at.elapsed() < CACHE_TTL {
+            return Ok(location);
+        }
+    }
+    let location = match query() {
+        Ok(location) => location,
+        Err(e) => {
+            let manual = crate::settings::get().manual_location.ok_or(e)?;
+            info!("[location] OS location unavailable, using the manual location");
+            Location {
+                latitude: manual.latitude,
+                longitude: manual.longitude,
+                accuracy: None,
+                source: LocationSource::Manual,
+            }
+        }
+    };
+    *CACHE.lock().map_err(|_| cache_error())? = Some((Instant::now(), location));
+    Ok(location)
+}
+
+/// Forget the cached fix (the manual location changed).
+pub fn invalidate() {
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = None;
+    }
+}
+
+fn cache_error() -> AppError {
+    AppError::Location("Location cache poisoned".into())
+}
+
+#[cfg(target_os = "windows")]
+fn query() -> AppResult<Location> {
+    use windows::Devices::Geolocation::{GeolocationAccessStatus, Geolocator, PositionAccuracy};
+
+    let err = |what: &str, e: windows::core::Error| AppError::Location(format!("{}: {}", what, e));
+    let access = Geolocator::RequestAccessAsync()
+        .and_then(|op| op.get())
+        .map_err(|e| err("Access request failed", e))?;
+    if access != GeolocationAccessStatus::Allowed {
+        return Err(AppError::Location(
+            "Location access is turned off or denied in Windows settings".into(),
+        ));
+    }
+    let locator = Geolocator::new().map_err(|e| err("Geolocator unavailable", e))?;
+    // Wi-Fi / IP based, no GPS warm-up
+    let _ = locator.SetDesiredAccuracy(PositionAccuracy::Default);
+    let coordinate = locator
+        .GetGeopositionAsync()
+        .and_then(|op| op.get())
+        .and_then(|p| p.Coordinate())
+        .map_err(|e| err("No position", e))?;
+    let position = coordinate
+        .Point()
+        .and_then(|p| p.Position())
+        .map_err(|e| err("No position", e))?;
+    Ok(Location {
+        latitude: coarse(position.Latitude),
+        longitude: coarse(position.Longitude),
+        accuracy: coordinate.Accuracy().ok(),
+        source: LocationSource::Os,
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn query() -> AppResult<Location> {
+    Err(AppError::Location(
+        "No OS location service on this platform; set a manual location".into(),
+    ))
+}
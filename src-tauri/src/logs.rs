@@ -0,0 +1,86 @@
+//! Log files — rotation limits for the log plugin and tail access for bug reports.
+//!
+//! The log plugin writes `[date][time][target][LEVEL] message` lines to the app
+//! log directory and rotates by size; rotated files keep the `.log` extension.
+
+use crate::error::{AppError, AppResult};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Rotate the active log file past this size.
+pub const MAX_FILE_BYTES: u128 = 5 * 1024 * 1024;
+/// Log files kept on disk, active one included.
+pub const KEEP_FILES: usize = 5;
+/// Upper bound for `get_recent_logs`, whatever the caller asks for.
+const MAX_LINES: usize = 5000;
+pub const DEFAULT_LINES: usize = 500;
+
+pub fn log_dir(app: &tauri::AppHandle) -> AppResult<PathBuf> {
+    use tauri::Manager;
+    Ok(app.path().app_log_dir()?)
+}
+
+/// `.log` files in `dir`, newest first.
+pub fn log_files(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "log"))
+        .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1));
+    Ok(files.into_iter().map(|(p, _)| p).collect())
+}
+
+/// Level of a formatted log line, from its first bracketed fields.
+fn line_level(line: &str) -> Option<log::Level> {
+    if !line.starts_with('[') {
+        return None;
+    }
+    line.split(']')
+        .take(5)
+        .filter_map(|field| field.strip_prefix('['))
+        .find_map(|field| log::Level::from_str(field).ok())
+}
+
+pub fn parse_level(level: &str) -> AppResult<log::Level> {
+    log::Level::from_str(level)
+        .map_err(|_| AppError::Validation(format!("Unknown log level: {}", level)))
+}
+
+/// Last `lines` log lines at `min_level` or more severe, oldest first.
+/// Continuation lines of multi-line messages inherit the level of their entry.
+pub fn recent_logs(
+    dir: &Path,
+    lines: usize,
+    min_level: Option<log::Level>,
+) -> AppResult<Vec<String>> {
+    let wanted = lines.clamp(1, MAX_LINES);
+    let mut result: Vec<String> = Vec::new();
+
+    for path in log_files(dir)? {
+        let bytes = std::fs::read(&path)?;
+        let text = String::from_utf8_lossy(&bytes);
+        let mut level = log::Level::Info;
+        let mut file_lines: Vec<String> = text
+            .lines()
+            .filter(|line| {
+                if let Some(l) = line_level(line) {
+                    level = l;
+                }
+                min_level.is_none_or(|min| level <= min)
+            })
+            .map(str::to_string)
+            .collect();
+
+        let missing = wanted - result.len();
+        if file_lines.len() > missing {
+            file_lines.drain(..file_lines.len() - missing);
+        }
+        file_lines.append(&mut result);
+        result = file_lines;
+        if result.len() >= wanted {
+            break;
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,108 @@
+//! Metered connection detection (Windows connection cost).
+//!
+//! Polled every few seconds and emitted as `connection-metered`. While the
+//! connection counts as metered, `offline::resolve_wallpaper_url` serves the
+//! cached copy of a remote wallpaper without touching the network. The
+//! `metered_connection` setting can force either answer.
+
+use crate::settings::MeteredPolicy;
+use log::{error, info};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::time::Duration;
+use typeshare::typeshare;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// `MeteredPolicy` as its index
+static POLICY: AtomicU8 = AtomicU8::new(0);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionCost {
+    /// Treated as metered, after the user override
+    pub metered: bool,
+    /// The OS reports the connection as metered, roaming or over its data limit
+    pub detected: bool,
+}
+
+pub fn configure(policy: MeteredPolicy) {
+    POLICY.store(policy as u8, Ordering::SeqCst);
+}
+
+fn policy() -> MeteredPolicy {
+    match POLICY.load(Ordering::SeqCst) {
+        1 => MeteredPolicy::Metered,
+        2 => MeteredPolicy::Unmetered,
+        _ => MeteredPolicy::Auto,
+    }
+}
+
+pub fn get() -> ConnectionCost {
+    let detected = query().unwrap_or(false);
+    let metered = match policy() {
+        MeteredPolicy::Auto => detected,
+        MeteredPolicy::Metered => true,
+        MeteredPolicy::Unmetered => false,
+    };
+    ConnectionCost { metered, detected }
+}
+
+pub fn is_metered() -> bool {
+    get().metered
+}
+
+/// Start the `connection-metered` watcher.
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut last = get();
+        while RUNNING.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = get();
+            if current.metered == last.metered {
+                last = current;
+                continue;
+            }
+            info!(
+                "[metered] Connection is {}",
+                if current.metered {
+                    "metered, preferring cached content"
+                } else {
+                    "unmetered"
+                }
+            );
+            if let Err(e) = app.emit_app_event(&AppEvent::ConnectionMetered(current)) {
+                error!("[metered] Failed to emit event: {}", e);
+            }
+            last = current;
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+fn query() -> Option<bool> {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+    // None when there is no internet connection at all
+    let profile = NetworkInformation::GetInternetConnectionProfile().ok()?;
+    let cost = profile.GetConnectionCost().ok()?;
+    let cost_type = cost.NetworkCostType().ok()?;
+    Some(
+        cost_type == NetworkCostType::Fixed
+            || cost_type == NetworkCostType::Variable
+            || cost.Roaming().unwrap_or(false)
+            || cost.OverDataLimit().unwrap_or(false),
+    )
+}
+
+#[cfg(not(target_os = "windows"))]
+fn query() -> Option<bool> {
+    None
+}
@@ -0,0 +1,767 @@
+//! `.mwpack` wallpaper packages.
+//!
+//! A `.mwpack` is a zip archive with `manifest.json` at its root, the entry
+//! file (`index.html` for web wallpapers, or the video/image itself), its
+//! assets and an optional preview image:
+//!
+//! ```json
+//! {
+//!   "formatVersion": 1,
+//!   "id": "aurora-waves",
+//!   "name": "Aurora Waves",
+//!   "kind": "web",
+//!   "entry": "index.html",
+//!   "preview": "preview.png",
+//!   "author": "…",
+//!   "description": "…"
+//! }
+//! ```
+//!
+//! Installing validates the manifest against the archive contents, extracts
+//! it into the library as pack `<id>` (replacing an older install of the same
+//! id) and serves it over `mwlocal://` like every other library pack.
+
+use crate::error::{AppError, AppResult};
+use crate::library::{self, LocalWallpaper, PackManifest, WallpaperKind};
+use crate::pack_signing::PackVerification;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use typeshare::typeshare;
+
+pub const PACK_EXTENSION: &str = "mwpack";
+const PACK_MANIFEST_FILE: &str = "manifest.json";
+const FORMAT_VERSION: u32 = 1;
+const MAX_MANIFEST_BYTES: u64 = 64 * 1024;
+const MAX_DOWNLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Keeps concurrent downloads apart
+static DOWNLOAD_SEQ: AtomicU32 = AtomicU32::new(0);
+
+/// `manifest.json` of a `.mwpack`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MwpackManifest {
+    format_version: u32,
+    id: String,
+    name: String,
+    kind: WallpaperKind,
+    entry: String,
+    #[serde(default)]
+    preview: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+fn invalid(reason: impl std::fmt::Display) -> AppError {
+    AppError::Library(format!("Invalid .mwpack: {}", reason))
+}
+
+/// Pack ids become library directory names (and URL segments).
+fn validate_id(id: &str) -> AppResult<()> {
+    library::validate_pack_id(id)?;
+    let ok = id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if ok {
+        Ok(())
+    } else {
+        Err(invalid(format!(
+            "id '{}' must be 1-64 of a-z, 0-9, '-', '_', '.'",
+            id
+        )))
+    }
+}
+
+/// A relative path inside the archive, without `..` or absolute parts.
+fn is_inner_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn read_manifest(archive: &mut zip::ZipArchive<std::fs::File>) -> AppResult<MwpackManifest> {
+    let entry = archive
+        .by_name(PACK_MANIFEST_FILE)
+        .map_err(|_| invalid(format!("{} is missing", PACK_MANIFEST_FILE)))?;
+    if entry.size() > MAX_MANIFEST_BYTES {
+        return Err(invalid(format!("{} is too large", PACK_MANIFEST_FILE)));
+    }
+    let mut bytes = Vec::new();
+    entry.take(MAX_MANIFEST_BYTES).read_to_end(&mut bytes)?;
+    let manifest: MwpackManifest = serde_json::from_slice(&bytes).map_err(invalid)?;
+
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(invalid(format!(
+            "unsupported format version {}",
+            manifest.format_version
+        )));
+    }
+    validate_id(&manifest.id)?;
+    if manifest.name.trim().is_empty() {
+        return Err(invalid("name is empty"));
+    }
+    for (field, path) in [
+        ("entry", Some(&manifest.entry)),
+        ("preview", manifest.preview.as_ref()),
+    ] {
+        let Some(path) = path else {
+            continue;
+        };
+        if !is_inner_path(path) || archive.by_name(path).is_err() {
+            return Err(invalid(format!(
+                "{} '{}' is not in the archive",
+                field, path
+            )));
+        }
+    }
+    Ok(manifest)
+}
+
+/// Download a pack over HTTPS into `dest`.
+fn download(url: &str, dest: &Path) -> AppResult<()> {
+    let parsed =
+        url::Url::parse(url).map_err(|_| AppError::Validation("Invalid pack URL".into()))?;
+    if parsed.scheme() != "https" {
+        return Err(AppError::Validation("Pack URLs must use HTTPS".into()));
+    }
+    let name = parsed
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("wallpaper pack");
+    crate::downloads::Download::new(url, dest)
+        .label(name)
+        .max_bytes(MAX_DOWNLOAD_BYTES)
+        .run()?;
+    Ok(())
+}
+
+/// Validate, extract and install a `.mwpack` file.
+fn install_file(path: &Path) -> AppResult<LocalWallpaper> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?).map_err(invalid)?;
+    let manifest = read_manifest(&mut archive)?;
+    drop(archive);
+
+    let root = library::library_root()?;
+    let scratch = root.join(format!(".{}.extract", manifest.id));
+    let _ = std::fs::remove_dir_all(&scratch);
+    let result = library::extract_zip(path, &scratch).and_then(|_| {
+        // The library writes its own manifest (`wallpaper.json`)
+        let _ = std::fs::remove_file(scratch.join(PACK_MANIFEST_FILE));
+        if library::pack_exists(&manifest.id) {
+            info!("[mwpack] Replacing installed pack '{}'", manifest.id);
+        }
+        library::replace_pack(
+            &scratch,
+            &manifest.id,
+            &PackManifest {
+                name: manifest.name.trim().to_string(),
+                kind: manifest.kind,
+                entry: manifest.entry.clone(),
+                preview: manifest.preview.clone(),
+                author: manifest.author.clone(),
+                description: manifest.description.clone(),
+                verification: PackVerification::Unsigned,
+            },
+        )
+    });
+    let _ = std::fs::remove_dir_all(&scratch);
+    result?;
+
+    if manifest.preview.is_none() {
+        if let Err(e) = library::generate_preview(&manifest.id) {
+            warn!(
+                "[mwpack] Preview generation failed for {}: {}",
+                manifest.id, e
+            );
+        }
+    }
+    info!("[mwpack] Installed '{}'", manifest.id);
+    library::get_local_wallpaper(&manifest.id)
+}
+
+/// Install a `.mwpack` from a local path or an HTTPS URL.
+pub fn install(path_or_url: &str) -> AppResult<LocalWallpaper> {
+    let source = path_or_url.trim();
+    if !source.starts_with("https://") && !source.starts_with("http://") {
+        return install_file(Path::new(source));
+    }
+    let root = library::library_root()?;
+    let download_path = root.join(format!(
+        ".download-{}.{}",
+        DOWNLOAD_SEQ.fetch_add(1, Ordering::Relaxed),
+        PACK_EXTENSION
+    ));
+    let result = download(source, &download_path).and_then(|_| install_file(&download_path));
+    let _ = std::fs::remove_file(&download_path);
+    result
+}
+
+/// Remove an installed pack from the library.
+pub fn uninstall(id: &str) -> AppResult<()> {
+    library::validate_pack_id(id)?;
+    if !library::pack_exists(id) {
+        return Err(AppError::Library(format!(
+            "Unknown local wallpaper: {}",
+            id
+        )));
+    }
+    library::remove_pack(id)?;
+    info!("[mwpack] Uninstalled '{}'", id);
+    Ok(())
+}
+
+// ============================================================================
+// Validation (creator tooling)
+// ============================================================================
+
+/// HTML and CSS files larger than this are not scanned for references
+const MAX_SCANNED_BYTES: u64 = 2 * 1024 * 1024;
+
+const MANIFEST_FIELDS: &[&str] = &[
+    "formatVersion",
+    "id",
+    "name",
+    "kind",
+    "entry",
+    "preview",
+    "author",
+    "description",
+];
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    /// The pack is refused on install or breaks once installed
+    Error,
+    Warning,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackIssue {
+    pub severity: IssueSeverity,
+    /// Manifest field or pack file the issue is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    pub message: String,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackReport {
+    /// No errors; warnings do not block installing
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub file_count: u32,
+    /// Uncompressed size of every file
+    pub total_bytes: u64,
+    pub issues: Vec<PackIssue>,
+}
+
+#[derive(Default)]
+struct Issues(Vec<PackIssue>);
+
+impl Issues {
+    fn push(&mut self, severity: IssueSeverity, location: Option<&str>, message: String) {
+        self.0.push(PackIssue {
+            severity,
+            location: location.map(str::to_string),
+            message,
+        });
+    }
+
+    fn error(&mut self, location: Option<&str>, message: impl Into<String>) {
+        self.push(IssueSeverity::Error, location, message.into());
+    }
+
+    fn warning(&mut self, location: Option<&str>, message: impl Into<String>) {
+        self.push(IssueSeverity::Warning, location, message.into());
+    }
+}
+
+/// A `.mwpack` archive or an unpacked project folder.
+enum PackSource {
+    Archive(zip::ZipArchive<std::fs::File>),
+    Folder(PathBuf),
+}
+
+impl PackSource {
+    /// Up to `limit` bytes of `path` (forward slashes, relative to the root).
+    fn read(&mut self, path: &str, limit: u64) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        match self {
+            Self::Archive(archive) => archive
+                .by_name(path)
+                .ok()?
+                .take(limit)
+                .read_to_end(&mut bytes)
+                .ok()?,
+            Self::Folder(dir) => std::fs::File::open(dir.join(path))
+                .ok()?
+                .take(limit)
+                .read_to_end(&mut bytes)
+                .ok()?,
+        };
+        Some(bytes)
+    }
+
+    /// Every file as relative path → uncompressed size.
+    fn list_files(&mut self, issues: &mut Issues) -> BTreeMap<String, u64> {
+        let mut files = BTreeMap::new();
+        match self {
+            Self::Archive(archive) => {
+                for i in 0..archive.len() {
+                    let entry = match archive.by_index(i) {
+                        Ok(e) => e,
+                        Err(e) => {
+                            issues.error(None, format!("Unreadable archive entry: {}", e));
+                            continue;
+                        }
+                    };
+                    if entry.is_dir() {
+                        continue;
+                    }
+                    let name = entry.name().to_string();
+                    if is_inner_path(&name) {
+                        files.insert(name, entry.size());
+                    } else {
+                        issues.error(Some(&name), "Path escapes the pack");
+                    }
+                }
+            }
+            Self::Folder(root) => {
+                let mut stack = vec![root.clone()];
+                while let Some(dir) = stack.pop() {
+                    let Ok(entries) = std::fs::read_dir(&dir) else {
+                        continue;
+                    };
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        let rel = path
+                            .strip_prefix(&*root)
+                            .unwrap_or(&path)
+                            .to_string_lossy()
+                            .replace('\\', "/");
+                        match entry.file_type() {
+                            Ok(t) if t.is_dir() => stack.push(path),
+                            Ok(t) if t.is_file() => {
+                                files.insert(rel, entry.metadata().map_or(0, |m| m.len()));
+                            }
+                            _ => issues.warning(
+                                Some(&rel),
+                                "Symbolic links are not packed; copy the file instead",
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+        files
+    }
+}
+
+/// Check that `path` names a file of the pack.
+fn check_file(files: &BTreeMap<String, u64>, field: &str, path: &str, issues: &mut Issues) {
+    if !is_inner_path(path) || path.contains('\\') {
+        issues.error(
+            Some(field),
+            format!("'{}' must be a relative path using '/'", path),
+        );
+    } else if !files.contains_key(path) {
+        let hint = if files.keys().any(|f| f.eq_ignore_ascii_case(path)) {
+            " (file names are case-sensitive)"
+        } else {
+            ""
+        };
+        issues.error(
+            Some(field),
+            format!("'{}' is not in the pack{}", path, hint),
+        );
+    }
+}
+
+/// Check `manifest.json` field by field.
+fn check_manifest(
+    manifest: &serde_json::Value,
+    files: &BTreeMap<String, u64>,
+    issues: &mut Issues,
+) {
+    let Some(fields) = manifest.as_object() else {
+        issues.error(Some(PACK_MANIFEST_FILE), "Must be a JSON object");
+        return;
+    };
+    for key in fields
+        .keys()
+        .filter(|k| !MANIFEST_FIELDS.contains(&k.as_str()))
+    {
+        issues.warning(Some(key), "Unknown field, ignored");
+    }
+    let string = |key: &str, issues: &mut Issues| match fields.get(key) {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(s)) => Some(s.clone()),
+        Some(_) => {
+            issues.error(Some(key), "Must be a string");
+            None
+        }
+    };
+
+    match fields.get("formatVersion").and_then(|v| v.as_u64()) {
+        Some(v) if v == FORMAT_VERSION as u64 => {}
+        Some(v) => issues.error(
+            Some("formatVersion"),
+            format!("Unsupported version {}; expected {}", v, FORMAT_VERSION),
+        ),
+        None => issues.error(Some("formatVersion"), "Required number is missing"),
+    }
+    match string("id", issues) {
+        Some(id) if validate_id(&id).is_ok() => {}
+        Some(_) => issues.error(
+            Some("id"),
+            "Must be 1-64 characters of a-z, 0-9, '-', '_' or '.'",
+        ),
+        None => issues.error(Some("id"), "Required string is missing"),
+    }
+    if string("name", issues).is_none_or(|n| n.trim().is_empty()) {
+        issues.error(Some("name"), "Required and must not be empty");
+    }
+    let kind = match fields.get("kind") {
+        None => {
+            issues.error(Some("kind"), "Required string is missing");
+            None
+        }
+        Some(v) => {
+            let kind = serde_json::from_value::<WallpaperKind>(v.clone()).ok();
+            if kind.is_none() {
+                issues.error(Some("kind"), "Must be \"web\", \"video\" or \"image\"");
+            }
+            kind
+        }
+    };
+    let entry = string("entry", issues);
+    match &entry {
+        Some(entry) => check_file(files, "entry", entry, issues),
+        None => issues.error(Some("entry"), "Required string is missing"),
+    }
+    match string("preview", issues) {
+        Some(preview) => check_file(files, "preview", &preview, issues),
+        None if kind != Some(WallpaperKind::Image) => issues.warning(
+            Some("preview"),
+            "No preview; the library shows a blank tile for this pack",
+        ),
+        None => {}
+    }
+    string("author", issues);
+    string("description", issues);
+
+    let (Some(kind), Some(entry)) = (kind, entry) else {
+        return;
+    };
+    let entry_path = Path::new(&entry);
+    let matches = match kind {
+        WallpaperKind::Web => entry_path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm")),
+        kind => library::file_kind(entry_path) == Some(kind),
+    };
+    if !matches {
+        issues.warning(
+            Some("entry"),
+            format!(
+                "'{}' does not look like a {} file",
+                entry,
+                format!("{:?}", kind).to_lowercase()
+            ),
+        );
+    }
+}
+
+/// Local references in `src`/`href` attributes and CSS `url(...)` values.
+fn asset_references(text: &str, html: bool) -> Vec<String> {
+    let lower = text.to_ascii_lowercase();
+    let mut refs = Vec::new();
+    let mut take_value = |start: usize, terminators: &[char]| {
+        let rest = text[start..].trim_start();
+        let (quote, rest) = match rest.chars().next() {
+            Some(q @ ('"' | '\'')) => (Some(q), &rest[1..]),
+            _ => (None, rest),
+        };
+        let end = rest
+            .find(|c: char| match quote {
+                Some(q) => c == q,
+                None => c.is_whitespace() || terminators.contains(&c),
+            })
+            .unwrap_or(rest.len());
+        refs.push(rest[..end].trim().to_string());
+    };
+    if html {
+        for attr in ["src=", "href="] {
+            for (i, _) in lower.match_indices(attr) {
+                // Not `data-src=` and the like
+                if i == 0 || lower.as_bytes()[i - 1].is_ascii_whitespace() {
+                    take_value(i + attr.len(), &['>']);
+                }
+            }
+        }
+    }
+    for (i, _) in lower.match_indices("url(") {
+        take_value(i + 4, &[')']);
+    }
+    refs.retain(|r| {
+        let lower = r.to_ascii_lowercase();
+        !r.is_empty()
+            && !r.starts_with(['#', '{', '$'])
+            && !r.starts_with("//")
+            && !r.contains("://")
+            && !r.contains("{{")
+            && !r.contains("${")
+            && !["data:", "blob:", "javascript:", "mailto:", "about:", "tel:"]
+                .iter()
+                .any(|scheme| lower.starts_with(scheme))
+    });
+    refs
+}
+
+/// Resolve `reference` against the folder of `from`; None when it leaves the pack.
+fn resolve_reference(from: &str, reference: &str) -> Option<String> {
+    let path = reference.split(['?', '#']).next().unwrap_or("");
+    let path = library::percent_decode(path).unwrap_or_else(|| path.to_string());
+    let mut segments: Vec<&str> = from.split('/').collect();
+    segments.pop();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            s => segments.push(s),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+fn check_references(source: &mut PackSource, files: &BTreeMap<String, u64>, issues: &mut Issues) {
+    let documents: Vec<(String, bool)> = files
+        .iter()
+        .filter(|(_, size)| **size <= MAX_SCANNED_BYTES)
+        .filter_map(|(path, _)| {
+            let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+            match ext.as_str() {
+                "html" | "htm" => Some((path.clone(), true)),
+                "css" => Some((path.clone(), false)),
+                _ => None,
+            }
+        })
+        .collect();
+    for (document, html) in documents {
+        let Some(bytes) = source.read(&document, MAX_SCANNED_BYTES) else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(&bytes);
+        for reference in asset_references(&text, html) {
+            if reference.starts_with('/') {
+                issues.error(
+                    Some(&document),
+                    format!(
+                        "'{}' is relative to the protocol root, not the pack; drop the leading '/'",
+                        reference
+                    ),
+                );
+                continue;
+            }
+            match resolve_reference(&document, &reference) {
+                None => issues.error(
+                    Some(&document),
+                    format!("'{}' points outside the pack", reference),
+                ),
+                Some(target) if !files.contains_key(&target) => issues.error(
+                    Some(&document),
+                    format!("'{}' is not in the pack", reference),
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Check a `.mwpack` or a project folder (with `manifest.json` at its root)
+/// before publishing. Problems are reported, not returned as errors.
+pub fn validate(path: &str) -> AppResult<PackReport> {
+    let path = Path::new(path.trim());
+    let mut issues = Issues::default();
+    let mut source = if path.is_dir() {
+        PackSource::Folder(path.to_path_buf())
+    } else if path.is_file() {
+        if !path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case(PACK_EXTENSION))
+        {
+            issues.warning(
+                None,
+                format!(
+                    "Packs are distributed with the .{} extension",
+                    PACK_EXTENSION
+                ),
+            );
+        }
+        PackSource::Archive(zip::ZipArchive::new(std::fs::File::open(path)?).map_err(invalid)?)
+    } else {
+        return Err(AppError::Validation(format!(
+            "{} does not exist",
+            path.display()
+        )));
+    };
+
+    let files = source.list_files(&mut issues);
+    let total_bytes = files.values().sum::<u64>();
+    if total_bytes > library::MAX_EXTRACTED_BYTES {
+        issues.error(
+            None,
+            format!(
+                "Pack expands to {} MiB; the limit is {} MiB",
+                total_bytes / (1024 * 1024),
+                library::MAX_EXTRACTED_BYTES / (1024 * 1024)
+            ),
+        );
+    }
+    if matches!(source, PackSource::Archive(_)) {
+        let size = std::fs::metadata(path)?.len();
+        if size > MAX_DOWNLOAD_BYTES {
+            issues.warning(
+                None,
+                format!(
+                    "Archive is larger than {} MiB and cannot be installed from a URL",
+                    MAX_DOWNLOAD_BYTES / (1024 * 1024)
+                ),
+            );
+        }
+    }
+
+    let manifest = match files.get(PACK_MANIFEST_FILE) {
+        None => {
+            issues.error(Some(PACK_MANIFEST_FILE), "Missing at the root of the pack");
+            None
+        }
+        Some(size) if *size > MAX_MANIFEST_BYTES => {
+            issues.error(
+                Some(PACK_MANIFEST_FILE),
+                format!("Larger than {} KiB", MAX_MANIFEST_BYTES / 1024),
+            );
+            None
+        }
+        Some(_) => source
+            .read(PACK_MANIFEST_FILE, MAX_MANIFEST_BYTES)
+            .and_then(
+                |bytes| match serde_json::from_slice::<serde_json::Value>(&bytes) {
+                    Ok(value) => Some(value),
+                    Err(e) => {
+                        issues.error(Some(PACK_MANIFEST_FILE), format!("Invalid JSON: {}", e));
+                        None
+                    }
+                },
+            ),
+    };
+    let id = manifest
+        .as_ref()
+        .and_then(|m| m.get("id")?.as_str().map(str::to_string));
+    if let Some(manifest) = &manifest {
+        check_manifest(manifest, &files, &mut issues);
+    }
+    check_references(&mut source, &files, &mut issues);
+
+    let issues = issues.0;
+    info!(
+        "[mwpack] Validated {}: {} issue(s)",
+        path.display(),
+        issues.len()
+    );
+    Ok(PackReport {
+        valid: !issues.iter().any(|i| i.severity == IssueSeverity::Error),
+        id,
+        file_count: files.len() as u32,
+        total_bytes,
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_quoted_and_unquoted_html_references() {
+        let html = r#"<img src="img/a.png"><script src='js/app.js'></script>
+<link href=css/style.css><video src=media/clip.mp4 autoplay>"#;
+        assert_eq!(
+            asset_references(html, true),
+            ["img/a.png", "js/app.js", "media/clip.mp4", "css/style.css"]
+        );
+    }
+
+    #[test]
+    fn finds_css_urls() {
+        let css = r#"a { background: url("bg.png") } b { background: url('fonts/x.woff2') }
+c { background: url(tiles/t.png) }"#;
+        assert_eq!(
+            asset_references(css, false),
+            ["bg.png", "fonts/x.woff2", "tiles/t.png"]
+        );
+        // Attributes only count in HTML
+        assert!(asset_references(r#"src="a.png""#, false).is_empty());
+    }
+
+    #[test]
+    fn skips_non_local_references() {
+        let html = r##"<img data-src="lazy.png"><a href="#top"></a>
+<img src="https://cdn.example/x.png"><img src="//cdn.example/y.png">
+<img src="data:image/png;base64,AAAA"><a href="mailto:a@b.c"></a>
+<img src="{{ image }}"><img src="${path}"><img src="">"##;
+        assert!(asset_references(html, true).is_empty());
+    }
+
+    #[test]
+    fn resolves_relative_references() {
+        assert_eq!(
+            resolve_reference("index.html", "img/a.png").as_deref(),
+            Some("img/a.png")
+        );
+        assert_eq!(
+            resolve_reference("css/style.css", "../fonts/x.woff2").as_deref(),
+            Some("fonts/x.woff2")
+        );
+        assert_eq!(
+            resolve_reference("index.html", "./media/my%20clip.mp4?t=1#start").as_deref(),
+            Some("media/my clip.mp4")
+        );
+    }
+
+    #[test]
+    fn rejects_references_leaving_the_pack() {
+        for (from, reference) in [
+            ("index.html", "../secret.txt"),
+            ("css/style.css", "../../secret.txt"),
+            ("index.html", "img/../../secret.txt"),
+            ("index.html", "%2e%2e/secret.txt"),
+        ] {
+            assert_eq!(
+                resolve_reference(from, reference),
+                None,
+                "{} -> {}",
+                from,
+                reference
+            );
+        }
+    }
+}
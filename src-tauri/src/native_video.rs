@@ -0,0 +1,485 @@
+//! Native video wallpaper — hardware-decoded playback via Media Foundation (Windows only).
+//!
+//! Each playing video owns a child window of the WorkerW layer, sized to one
+//! monitor (or the whole virtual desktop) and driven by an MFPlay player on a
+//! dedicated thread. Commands reach that thread through posted window messages,
+//! the same pattern as the mouse dispatch window.
+
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum VideoLayer {
+    /// Drawn on top of the WebView (the WebView is hidden while videos cover the whole layer)
+    #[default]
+    Replace,
+    /// Drawn behind the WebView (visible through a transparent page)
+    Behind,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoStatus {
+    /// Monitor index (enumeration order), or None when spanning all monitors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<u32>,
+    pub path: String,
+    pub playing: bool,
+    pub looping: bool,
+    /// Volume (0.0 - 1.0)
+    pub volume: f32,
+    pub layer: VideoLayer,
+}
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mkv", "webm", "mov", "avi", "wmv"];
+
+fn validate_video_path(path: &str) -> AppResult<std::path::PathBuf> {
+    let p = std::path::Path::new(path);
+    let ok_ext = p
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| VIDEO_EXTENSIONS.iter().any(|x| x.eq_ignore_ascii_case(e)));
+    if !ok_ext {
+        return Err(AppError::Validation("Unsupported video format".into()));
+    }
+    p.canonicalize()
+        .ok()
+        .filter(|c| c.is_file())
+        .ok_or_else(|| AppError::Video(format!("Video file not found: {}", path)))
+}
+
+// ============================================================================
+// Windows: Media Foundation host windows
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+mod host {
+    use super::{VideoLayer, VideoStatus};
+    use crate::error::{AppError, AppResult};
+    use std::cell::{Cell, RefCell};
+    use std::sync::Mutex;
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+    use windows::Win32::Media::MediaFoundation::*;
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    const WM_APP: u32 = 0x8000;
+    const WM_MWP_VIDEO: u32 = WM_APP + 50;
+    const LOOP_TIMER_ID: usize = 1;
+    const LOOP_TIMER_MS: u32 = 250;
+
+    pub const CMD_PLAY: usize = 0;
+    pub const CMD_PAUSE: usize = 1;
+    pub const CMD_VOLUME: usize = 2;
+    pub const CMD_LOOP: usize = 3;
+
+    pub struct VideoEntry {
+        pub hwnd: isize,
+        pub status: VideoStatus,
+    }
+
+    pub static PLAYERS: Mutex<Vec<VideoEntry>> = Mutex::new(Vec::new());
+
+    thread_local! {
+        static PLAYER: RefCell<Option<IMFPMediaPlayer>> = const { RefCell::new(None) };
+        static LOOPING: Cell<bool> = const { Cell::new(true) };
+    }
+
+    unsafe fn with_player(f: impl FnOnce(&IMFPMediaPlayer)) {
+        PLAYER.with(|p| {
+            if let Some(ref player) = *p.borrow() {
+                f(player);
+            }
+        });
+    }
+
+    unsafe extern "system" fn video_wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wp: WPARAM,
+        lp: LPARAM,
+    ) -> LRESULT {
+        match msg {
+            // Let clicks fall through to the icon layer / mouse hook
+            WM_NCHITTEST => LRESULT(HTTRANSPARENT as isize),
+            WM_MWP_VIDEO => {
+                with_player(|p| {
+                    let _ = match wp.0 {
+                        CMD_PLAY => p.Play(),
+                        CMD_PAUSE => p.Pause(),
+                        CMD_VOLUME => p.SetVolume(f32::from_bits(lp.0 as u32)),
+                        CMD_LOOP => {
+                            LOOPING.with(|l| l.set(lp.0 != 0));
+                            Ok(())
+                        }
+                        _ => Ok(()),
+                    };
+                });
+                LRESULT(0)
+            }
+            WM_TIMER if wp.0 == LOOP_TIMER_ID => {
+                // MFPlay stops at end of stream; restart from 0 when looping
+                if LOOPING.with(Cell::get) {
+                    with_player(|p| {
+                        if p.GetState()
+                            .is_ok_and(|s| s == MFP_MEDIAPLAYER_STATE_STOPPED)
+                        {
+                            let zero = windows::core::PROPVARIANT::from(0i64);
+                            let _ = p.SetPosition(&MFP_POSITIONTYPE_100NS, &zero);
+                            let _ = p.Play();
+                        }
+                    });
+                }
+                LRESULT(0)
+            }
+            WM_PAINT => {
+                use windows::Win32::Graphics::Gdi::{BeginPaint, EndPaint, PAINTSTRUCT};
+                let mut ps = PAINTSTRUCT::default();
+                let _ = BeginPaint(hwnd, &mut ps);
+                with_player(|p| {
+                    let _ = p.UpdateVideo();
+                });
+                let _ = EndPaint(hwnd, &ps);
+                LRESULT(0)
+            }
+            WM_SIZE => {
+                with_player(|p| {
+                    let _ = p.UpdateVideo();
+                });
+                LRESULT(0)
+            }
+            WM_CLOSE => {
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_DESTROY => {
+                PostQuitMessage(0);
+                LRESULT(0)
+            }
+            _ => DefWindowProcW(hwnd, msg, wp, lp),
+        }
+    }
+
+    /// Create the host window + player on a dedicated thread. Returns the host HWND.
+    pub fn spawn(
+        path: String,
+        rect: RECT,
+        layer: VideoLayer,
+        looping: bool,
+        volume: f32,
+    ) -> AppResult<isize> {
+        let parent = crate::window_layer::mouse_hook::get_target_parent_hwnd();
+        if parent == 0 {
+            return Err(AppError::Video("Desktop layer not ready".into()));
+        }
+        let (tx, rx) = std::sync::mpsc::channel::<AppResult<isize>>();
+
+        std::thread::spawn(move || unsafe {
+            use windows::Win32::System::Com::{
+                CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED,
+            };
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+            let cls = windows::core::w!("MWP_VideoHost");
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(video_wnd_proc),
+                lpszClassName: cls,
+                ..Default::default()
+            };
+            // Fails harmlessly when already registered by a previous video
+            let _ = RegisterClassW(&wc);
+
+            let hwnd = match CreateWindowExW(
+                WS_EX_NOACTIVATE,
+                cls,
+                windows::core::w!(""),
+                WS_CHILD | WS_VISIBLE | WS_CLIPSIBLINGS,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+                HWND(parent as *mut _),
+                None,
+                None,
+                None,
+            ) {
+                Ok(h) => h,
+                Err(e) => {
+                    let _ = tx.send(Err(AppError::Video(format!("CreateWindow failed: {}", e))));
+                    CoUninitialize();
+                    return;
+                }
+            };
+
+            let insert_after = match layer {
+                VideoLayer::Replace => HWND_TOP,
+                VideoLayer::Behind => HWND_BOTTOM,
+            };
+            let _ = SetWindowPos(
+                hwnd,
+                insert_after,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            );
+
+            let mut player: Option<IMFPMediaPlayer> = None;
+            let created = MFPCreateMediaPlayer(
+                &windows::core::HSTRING::from(path.as_str()),
+                true,
+                MFP_OPTION_NONE,
+                None::<&IMFPMediaPlayerCallback>,
+                hwnd,
+                Some(&mut player),
+            );
+            let player = match (created, player) {
+                (Ok(()), Some(p)) => p,
+                (Err(e), _) => {
+                    let _ = DestroyWindow(hwnd);
+                    let _ = tx.send(Err(AppError::Video(format!("MFPlay failed: {}", e))));
+                    CoUninitialize();
+                    return;
+                }
+                (Ok(()), None) => {
+                    let _ = DestroyWindow(hwnd);
+                    let _ = tx.send(Err(AppError::Video("MFPlay returned no player".into())));
+                    CoUninitialize();
+                    return;
+                }
+            };
+            let _ = player.SetVolume(volume);
+            PLAYER.with(|p| *p.borrow_mut() = Some(player));
+            LOOPING.with(|l| l.set(looping));
+            SetTimer(hwnd, LOOP_TIMER_ID, LOOP_TIMER_MS, None);
+
+            let _ = tx.send(Ok(hwnd.0 as isize));
+            log::info!("[native_video] Playing {}", path);
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).into() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+
+            if let Some(p) = PLAYER.with(|p| p.borrow_mut().take()) {
+                let _ = p.Shutdown();
+            }
+            CoUninitialize();
+        });
+
+        rx.recv()
+            .map_err(|_| AppError::Video("Video thread exited".into()))?
+    }
+
+    pub fn post(hwnd: isize, cmd: usize, value: isize) {
+        unsafe {
+            let _ = PostMessageW(
+                HWND(hwnd as *mut _),
+                WM_MWP_VIDEO,
+                WPARAM(cmd),
+                LPARAM(value),
+            );
+        }
+    }
+
+    pub fn close(hwnd: isize) {
+        unsafe {
+            let _ = PostMessageW(HWND(hwnd as *mut _), WM_CLOSE, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Apply `f` to every player matching `monitor` (None = all players).
+#[cfg(target_os = "windows")]
+fn for_targets(monitor: Option<u32>, mut f: impl FnMut(&mut host::VideoEntry)) -> AppResult<()> {
+    let mut players = host::PLAYERS
+        .lock()
+        .map_err(|_| AppError::Video("Player registry poisoned".into()))?;
+    let mut found = false;
+    for entry in players
+        .iter_mut()
+        .filter(|e| monitor.is_none() || e.status.monitor == monitor)
+    {
+        f(entry);
+        found = true;
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(AppError::Video("No video playing on that monitor".into()))
+    }
+}
+
+/// Play a video file on one monitor, or spanning all monitors when `monitor` is None.
+#[cfg(target_os = "windows")]
+pub fn play(
+    path: &str,
+    monitor: Option<u32>,
+    looping: bool,
+    volume: f32,
+    layer: VideoLayer,
+) -> AppResult<()> {
+    let file = validate_video_path(path)?;
+    let volume = volume.clamp(0.0, 1.0);
+    let rect = crate::window_layer::layer_rect(monitor)?;
+
+    // A spanning video replaces everything; a per-monitor video replaces that monitor and any span
+    let _ = stop_players(monitor);
+    if monitor.is_some() {
+        if let Ok(mut players) = host::PLAYERS.lock() {
+            players.retain(|e| {
+                let keep = e.status.monitor.is_some();
+                if !keep {
+                    host::close(e.hwnd);
+                }
+                keep
+            });
+        }
+    }
+
+    let path_str = file.to_string_lossy().into_owned();
+    let spawned = host::spawn(path_str.clone(), rect, layer, looping, volume).and_then(|hwnd| {
+        host::PLAYERS
+            .lock()
+            .map_err(|_| AppError::Video("Player registry poisoned".into()))?
+            .push(host::VideoEntry {
+                hwnd,
+                status: VideoStatus {
+                    monitor,
+                    path: path_str,
+                    playing: true,
+                    looping,
+                    volume,
+                    layer,
+                },
+            });
+        Ok(())
+    });
+    sync_webview_visibility();
+    spawned
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_playing(monitor: Option<u32>, playing: bool) -> AppResult<()> {
+    for_targets(monitor, |e| {
+        let cmd = if playing {
+            host::CMD_PLAY
+        } else {
+            host::CMD_PAUSE
+        };
+        host::post(e.hwnd, cmd, 0);
+        e.status.playing = playing;
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_looping(monitor: Option<u32>, looping: bool) -> AppResult<()> {
+    for_targets(monitor, |e| {
+        host::post(e.hwnd, host::CMD_LOOP, looping as isize);
+        e.status.looping = looping;
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_volume(monitor: Option<u32>, volume: f32) -> AppResult<()> {
+    let volume = volume.clamp(0.0, 1.0);
+    for_targets(monitor, |e| {
+        host::post(e.hwnd, host::CMD_VOLUME, volume.to_bits() as isize);
+        e.status.volume = volume;
+    })
+}
+
+/// Stop and destroy players matching `monitor` (None = all).
+#[cfg(target_os = "windows")]
+pub fn stop(monitor: Option<u32>) -> AppResult<()> {
+    let result = stop_players(monitor);
+    sync_webview_visibility();
+    result
+}
+
+#[cfg(target_os = "windows")]
+fn stop_players(monitor: Option<u32>) -> AppResult<()> {
+    let mut players = host::PLAYERS
+        .lock()
+        .map_err(|_| AppError::Video("Player registry poisoned".into()))?;
+    players.retain(|e| {
+        let matches = monitor.is_none() || e.status.monitor == monitor;
+        if matches {
+            host::close(e.hwnd);
+        }
+        !matches
+    });
+    Ok(())
+}
+
+/// Hide the WebView while `Replace` videos cover every monitor, show it otherwise.
+#[cfg(target_os = "windows")]
+fn sync_webview_visibility() {
+    let monitors = crate::window_layer::monitor_rects().len() as u32;
+    let covered = host::PLAYERS
+        .lock()
+        .map(|players| {
+            let replacing = |m: Option<u32>| {
+                players
+                    .iter()
+                    .any(|e| e.status.layer == VideoLayer::Replace && e.status.monitor == m)
+            };
+            replacing(None) || (monitors > 0 && (0..monitors).all(|i| replacing(Some(i))))
+        })
+        .unwrap_or(false);
+    crate::window_layer::mouse_hook::set_webview_visible(!covered);
+}
+
+#[cfg(target_os = "windows")]
+pub fn status() -> Vec<VideoStatus> {
+    host::PLAYERS
+        .lock()
+        .map(|p| p.iter().map(|e| e.status.clone()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn play(
+    path: &str,
+    _monitor: Option<u32>,
+    _looping: bool,
+    _volume: f32,
+    _layer: VideoLayer,
+) -> AppResult<()> {
+    validate_video_path(path)?;
+    Err(AppError::Video("Not supported on this platform".into()))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_playing(_monitor: Option<u32>, _playing: bool) -> AppResult<()> {
+    Err(AppError::Video("Not supported on this platform".into()))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_looping(_monitor: Option<u32>, _looping: bool) -> AppResult<()> {
+    Err(AppError::Video("Not supported on this platform".into()))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_volume(_monitor: Option<u32>, _volume: f32) -> AppResult<()> {
+    Err(AppError::Video("Not supported on this platform".into()))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn stop(_monitor: Option<u32>) -> AppResult<()> {
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn status() -> Vec<VideoStatus> {
+    Vec::new()
+}
@@ -0,0 +1,97 @@
+//! Top-level navigation allowlist for the webview.
+//!
+//! Wallpaper content runs inside the app page; a script doing
+//! `top.location = …` must not take the desktop over. Navigations to origins
+//! outside the built-in set and `allowedOrigins` (backend settings) are
+//! cancelled and reported as `navigation-blocked`.
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use log::warn;
+use std::sync::RwLock;
+use url::Url;
+
+/// App origins (see `build.frontendDist`)
+pub const APP_ORIGINS: &[&str] = &[
+    "https://dev.mywallpaper.online",
+    "https://app.mywallpaper.online",
+];
+/// Local library protocol
+const LIBRARY_ORIGINS: &[&str] = &["http://mwlocal.localhost", "mwlocal://localhost"];
+
+static EXTRA_ORIGINS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Serialized origin (`scheme://host[:port]`) of `url`, if it has one.
+pub fn origin_of(url: &Url) -> Option<String> {
+    match url.origin() {
+        url::Origin::Tuple(..) => Some(url.origin().ascii_serialization()),
+        // Custom schemes are opaque to the URL spec: compare scheme and host
+        url::Origin::Opaque(_) => Some(format!("{}://{}", url.scheme(), url.host_str()?)),
+    }
+}
+
+/// Normalize user-supplied origins; rejects anything but bare http(s) origins.
+pub fn normalize_origins(origins: &[String]) -> AppResult<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::with_capacity(origins.len());
+    for raw in origins {
+        let invalid = || AppError::Validation(format!("Invalid origin: {}", raw));
+        let url = Url::parse(raw.trim()).map_err(|_| invalid())?;
+        if !matches!(url.scheme(), "https" | "http")
+            || url.path() != "/"
+            || url.query().is_some()
+            || url.fragment().is_some()
+            || !url.username().is_empty()
+        {
+            return Err(invalid());
+        }
+        let origin = origin_of(&url).ok_or_else(invalid)?;
+        if !normalized.contains(&origin) {
+            normalized.push(origin);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Replace the user-configured origins (already normalized).
+pub fn set_extra_origins(origins: &[String]) {
+    if let Ok(mut extra) = EXTRA_ORIGINS.write() {
+        *extra = origins.to_vec();
+    }
+}
+
+pub fn is_allowed(url: &Url) -> bool {
+    if url.scheme() == "about" && url.path() == "blank" {
+        return true;
+    }
+    let Some(origin) = origin_of(url) else {
+        return false;
+    };
+    APP_ORIGINS.contains(&origin.as_str())
+        || LIBRARY_ORIGINS.contains(&origin.as_str())
+        || EXTRA_ORIGINS
+            .read()
+            .map(|extra| extra.contains(&origin))
+            .unwrap_or(false)
+}
+
+/// Navigation hook for every webview; false cancels the navigation.
+pub fn on_navigation(webview: &tauri::Webview, url: &Url) -> bool {
+    use tauri::Manager;
+
+    if is_allowed(url) {
+        return true;
+    }
+    // Origin only in the log: full URLs may carry tokens
+    let origin = origin_of(url).unwrap_or_else(|| url.scheme().to_string());
+    warn!(
+        "[navigation] Blocked navigation of '{}' to {}",
+        webview.label(),
+        origin
+    );
+    let _ = webview
+        .app_handle()
+        .emit_app_event(&AppEvent::NavigationBlocked {
+            url: url.to_string(),
+        });
+    false
+}
@@ -0,0 +1,199 @@
+//! Native notifications on behalf of wallpapers (Windows toasts).
+//!
+//! Toasts are shown under the app's AppUserModelID (the installer's Start menu
+//! shortcut carries it), so they work while the hub is closed. Clicking the
+//! toast or one of its buttons emits `notification-action`.
+
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use typeshare::typeshare;
+
+const MAX_TITLE_LEN: usize = 128;
+const MAX_BODY_LEN: usize = 1024;
+const MAX_ACTION_ID_LEN: usize = 64;
+/// Toast limit for buttons
+const MAX_ACTIONS: usize = 5;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationAction {
+    /// Reported back in `notification-action`
+    pub id: String,
+    pub label: String,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRequest {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    /// Absolute path of a local image shown as the app logo
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<NotificationAction>,
+}
+
+fn validate(req: &NotificationRequest) -> AppResult<()> {
+    let invalid = |msg: &str| Err(AppError::Validation(msg.to_string()));
+    if req.title.trim().is_empty() || req.title.chars().count() > MAX_TITLE_LEN {
+        return invalid("Notification title must be 1-128 characters");
+    }
+    if req.body.chars().count() > MAX_BODY_LEN {
+        return invalid("Notification body is too long");
+    }
+    if req.actions.len() > MAX_ACTIONS {
+        return invalid("At most 5 notification actions are supported");
+    }
+    for action in &req.actions {
+        if action.id.is_empty()
+            || action.id.len() > MAX_ACTION_ID_LEN
+            || action.label.trim().is_empty()
+        {
+            return invalid("Notification actions need an id and a label");
+        }
+    }
+    if let Some(icon) = &req.icon {
+        let path = std::path::Path::new(icon);
+        if !path.is_absolute() || !path.is_file() {
+            return invalid("Notification icon must be an existing absolute file path");
+        }
+    }
+    Ok(())
+}
+
+/// Show a notification; returns its id, as reported in `notification-action`.
+pub fn show(
+    app: &tauri::AppHandle,
+    wallpaper_id: Option<&str>,
+    req: &NotificationRequest,
+) -> AppResult<String> {
+    validate(req)?;
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed).to_string();
+    toast::show(app, &id, wallpaper_id, req)?;
+    log::info!(
+        "[notifications] Shown #{} for {}",
+        id,
+        wallpaper_id.unwrap_or("app")
+    );
+    Ok(id)
+}
+
+#[cfg(target_os = "windows")]
+mod toast {
+    use super::NotificationRequest;
+    use crate::error::{AppError, AppResult};
+    use crate::events::{AppEvent, EmitAppEvent};
+    use windows::core::{IInspectable, Interface, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{
+        ToastActivatedEventArgs, ToastNotification, ToastNotificationManager,
+    };
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+
+    fn toast_err(e: windows::core::Error) -> AppError {
+        AppError::Notification(e.to_string())
+    }
+
+    /// Activation arguments are `<action id>`; the body click passes "".
+    fn build_xml(req: &NotificationRequest) -> String {
+        let icon = req
+            .icon
+            .as_deref()
+            .map(|path| {
+                format!(
+                    r#"<image placement="appLogoOverride" src="file:///{}"/>"#,
+                    escape(&path.replace('\\', "/"))
+                )
+            })
+            .unwrap_or_default();
+        let actions: String = req
+            .actions
+            .iter()
+            .map(|a| {
+                format!(
+                    r#"<action content="{}" arguments="{}" activationType="foreground"/>"#,
+                    escape(&a.label),
+                    escape(&a.id)
+                )
+            })
+            .collect();
+        format!(
+            r#"<toast launch=""><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text>{}</binding></visual><actions>{}</actions></toast>"#,
+            escape(&req.title),
+            escape(&req.body),
+            icon,
+            actions
+        )
+    }
+
+    pub fn show(
+        app: &tauri::AppHandle,
+        id: &str,
+        wallpaper_id: Option<&str>,
+        req: &NotificationRequest,
+    ) -> AppResult<()> {
+        let doc = XmlDocument::new().map_err(toast_err)?;
+        doc.LoadXml(&HSTRING::from(build_xml(req)))
+            .map_err(toast_err)?;
+        let toast = ToastNotification::CreateToastNotification(&doc).map_err(toast_err)?;
+
+        let app_handle = app.clone();
+        let notification_id = id.to_string();
+        let wallpaper_id = wallpaper_id.map(str::to_string);
+        toast
+            .Activated(&TypedEventHandler::new(
+                move |_: &Option<ToastNotification>, args: &Option<IInspectable>| {
+                    let action = args
+                        .as_ref()
+                        .and_then(|a| a.cast::<ToastActivatedEventArgs>().ok())
+                        .and_then(|a| a.Arguments().ok())
+                        .map(|a| a.to_string())
+                        .filter(|a| !a.is_empty());
+                    let _ = app_handle.emit_app_event(&AppEvent::NotificationAction {
+                        notification_id: notification_id.clone(),
+                        wallpaper_id: wallpaper_id.clone(),
+                        action,
+                    });
+                    Ok(())
+                },
+            ))
+            .map_err(toast_err)?;
+
+        let aumid = HSTRING::from(app.config().identifier.as_str());
+        ToastNotificationManager::CreateToastNotifierWithId(&aumid)
+            .and_then(|notifier| notifier.Show(&toast))
+            .map_err(toast_err)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod toast {
+    use super::NotificationRequest;
+    use crate::error::{AppError, AppResult};
+
+    pub fn show(
+        _app: &tauri::AppHandle,
+        _id: &str,
+        _wallpaper_id: Option<&str>,
+        _req: &NotificationRequest,
+    ) -> AppResult<()> {
+        Err(AppError::Notification(
+            "Notifications are only supported on Windows".into(),
+        ))
+    }
+}
@@ -0,0 +1,260 @@
+//! OAuth authorization-code flow with PKCE over a loopback redirect (RFC 8252).
+//!
+//! Fallback for systems where the `mywallpaper://` scheme is not registered:
+//! a one-shot listener on 127.0.0.1 receives the redirect, the code is
+//! exchanged here and the token response is handed back to the frontend.
+
+use crate::crypto::{constant_time_eq, random_urlsafe};
+use crate::error::{AppError, AppResult};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use log::{info, warn};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use typeshare::typeshare;
+
+const CALLBACK_PATH: &str = "/callback";
+/// How long the user has to finish signing in.
+const FLOW_TIMEOUT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+const TOKEN_TIMEOUT: Duration = Duration::from_secs(20);
+
+const DONE_PAGE: &str = "<!doctype html><meta charset=\"utf-8\"><title>MyWallpaper</title>\
+<p style=\"font-family:sans-serif\">Signed in. You can close this window and return to MyWallpaper.</p>";
+const FAILED_PAGE: &str = "<!doctype html><meta charset=\"utf-8\"><title>MyWallpaper</title>\
+<p style=\"font-family:sans-serif\">Sign-in failed. Return to MyWallpaper and try again.</p>";
+
+static FLOW_ACTIVE: AtomicBool = AtomicBool::new(false);
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+#[typeshare]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthFlowRequest {
+    /// Authorization endpoint; PKCE, state and redirect parameters are appended
+    pub authorize_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+/// Clears the single-flow flag however the flow ends.
+struct FlowGuard;
+
+impl Drop for FlowGuard {
+    fn drop(&mut self) {
+        FLOW_ACTIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// (verifier, S256 challenge)
+fn pkce_pair() -> AppResult<(String, String)> {
+    // 32 bytes -> 43 characters, the RFC 7636 minimum
+    let verifier = random_urlsafe(32)?;
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    Ok((verifier, challenge))
+}
+
+fn respond(mut stream: &TcpStream, status: &str, html: &str) {
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        html.len(),
+        html
+    );
+    let _ = stream.flush();
+}
+
+/// Outcome of one connection to the loopback listener.
+enum Callback {
+    /// Not the redirect (favicon, probes); keep waiting
+    Ignored,
+    Code(String),
+    Failed(String),
+}
+
+fn read_callback(stream: &TcpStream, state: &str) -> Callback {
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+    let mut line = String::new();
+    if BufReader::new(stream).read_line(&mut line).is_err() {
+        return Callback::Ignored;
+    }
+    let mut parts = line.split_whitespace();
+    let (Some("GET"), Some(target)) = (parts.next(), parts.next()) else {
+        respond(stream, "405 Method Not Allowed", FAILED_PAGE);
+        return Callback::Ignored;
+    };
+    let Ok(url) = url::Url::parse(&format!("http://127.0.0.1{}", target)) else {
+        respond(stream, "400 Bad Request", FAILED_PAGE);
+        return Callback::Ignored;
+    };
+    if url.path() != CALLBACK_PATH {
+        respond(stream, "404 Not Found", FAILED_PAGE);
+        return Callback::Ignored;
+    }
+    let param = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+
+    let returned_state = param("state").unwrap_or_default();
+    if !constant_time_eq(returned_state.as_bytes(), state.as_bytes()) {
+        // Forged or stale redirect: not ours, keep waiting for the real one
+        warn!("[oauth] Ignored redirect with mismatched state");
+        respond(stream, "400 Bad Request", FAILED_PAGE);
+        return Callback::Ignored;
+    }
+    if let Some(error) = param("error") {
+        respond(stream, "200 OK", FAILED_PAGE);
+        let description = param("error_description").unwrap_or_default();
+        return Callback::Failed(format!("{} {}", error, description).trim().to_string());
+    }
+    match param("code").filter(|c| !c.is_empty()) {
+        Some(code) => {
+            respond(stream, "200 OK", DONE_PAGE);
+            Callback::Code(code)
+        }
+        None => {
+            respond(stream, "400 Bad Request", FAILED_PAGE);
+            Callback::Failed("Redirect carried no authorization code".into())
+        }
+    }
+}
+
+fn wait_for_code(listener: &TcpListener, state: &str) -> AppResult<String> {
+    listener.set_nonblocking(true)?;
+    let deadline = Instant::now() + FLOW_TIMEOUT;
+    loop {
+        if CANCELLED.load(Ordering::SeqCst) {
+            return Err(AppError::OAuth("Sign-in cancelled".into()));
+        }
+        if Instant::now() >= deadline {
+            return Err(AppError::OAuth("Sign-in timed out".into()));
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = stream.set_nonblocking(false);
+                match read_callback(&stream, state) {
+                    Callback::Ignored => {}
+                    Callback::Code(code) => return Ok(code),
+                    Callback::Failed(e) => return Err(AppError::OAuth(e)),
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(POLL_INTERVAL)
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn exchange_code(
+    request: &OAuthFlowRequest,
+    code: &str,
+    redirect_uri: &str,
+    verifier: &str,
+) -> AppResult<Value> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(TOKEN_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    let response = client
+        .post(&request.token_url)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", request.client_id.as_str()),
+            ("code_verifier", verifier),
+        ])
+        .send()
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .map_err(|e| AppError::OAuth(format!("Invalid token response: {}", e)))?;
+    if !status.is_success() {
+        let error = body
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown_error");
+        return Err(AppError::OAuth(format!(
+            "Token exchange failed ({}): {}",
+            status, error
+        )));
+    }
+    if body.get("access_token").and_then(Value::as_str).is_none() {
+        return Err(AppError::OAuth("Token response has no access_token".into()));
+    }
+    Ok(body)
+}
+
+/// Run the whole flow; blocks until the redirect arrives, the flow times out or
+/// is cancelled. Returns the token endpoint's JSON response.
+pub fn run_flow(app: &tauri::AppHandle, request: OAuthFlowRequest) -> AppResult<Value> {
+    use tauri_plugin_opener::OpenerExt;
+
+    crate::commands::validate_oauth_url(&request.authorize_url)?;
+    crate::commands::validate_oauth_url(&request.token_url)?;
+    if request.client_id.trim().is_empty() {
+        return Err(AppError::Validation("client_id must not be empty".into()));
+    }
+    if FLOW_ACTIVE.swap(true, Ordering::SeqCst) {
+        return Err(AppError::OAuth("A sign-in is already in progress".into()));
+    }
+    let _guard = FlowGuard;
+    CANCELLED.store(false, Ordering::SeqCst);
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let redirect_uri = format!(
+        "http://127.0.0.1:{}{}",
+        listener.local_addr()?.port(),
+        CALLBACK_PATH
+    );
+    let (verifier, challenge) = pkce_pair()?;
+    let state = random_urlsafe(16)?;
+
+    let mut authorize = url::Url::parse(&request.authorize_url)
+        .map_err(|_| AppError::Validation("Invalid authorize URL".into()))?;
+    {
+        let mut query = authorize.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &request.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", &state);
+        if let Some(scope) = request.scope.as_deref().filter(|s| !s.is_empty()) {
+            query.append_pair("scope", scope);
+        }
+    }
+
+    info!("[oauth] Loopback sign-in listening on {}", redirect_uri);
+    app.opener()
+        .open_url(authorize.as_str(), None::<&str>)
+        .map_err(|e| AppError::OAuth(format!("Failed to open browser: {}", e)))?;
+
+    let code = wait_for_code(&listener, &state)?;
+    drop(listener);
+    let tokens = exchange_code(&request, &code, &redirect_uri, &verifier)?;
+    info!("[oauth] Loopback sign-in completed");
+    Ok(tokens)
+}
+
+/// Abort the flow in progress, if any.
+pub fn cancel() {
+    if FLOW_ACTIVE.load(Ordering::SeqCst) {
+        CANCELLED.store(true, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,533 @@
+//! Offline cache — mirrors remote web wallpapers into the local library.
+//!
+//! A snapshot crawls the wallpaper's entry page and every same-origin asset it
+//! references from HTML/CSS (plus any extra URLs the frontend observed while the
+//! page ran), stores them as a library pack and rewrites absolute references to
+//! relative paths so the copy works over `mwlocal://`. Cross-origin assets (CDNs)
+//! are left untouched and still need the network.
+
+use crate::downloads::{Download, Downloaded};
+use crate::error::{AppError, AppResult};
+use crate::library::{self, PackManifest, WallpaperKind};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use typeshare::typeshare;
+use url::Url;
+
+const OFFLINE_FILE: &str = "offline.json";
+const PACK_PREFIX: &str = "offline-";
+const MAX_ASSETS: usize = 500;
+const MAX_ASSET_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_TOTAL_BYTES: u64 = 512 * 1024 * 1024;
+/// Reachability probe before falling back to the cached copy.
+const PROBE_TIMEOUT_SECS: u64 = 3;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineEntry {
+    /// Wallpaper identifier understood by the frontend
+    pub wallpaper_id: String,
+    /// Remote entry URL the snapshot was taken from
+    pub source_url: String,
+    /// Library pack holding the snapshot
+    pub pack_id: String,
+    /// Explicitly pinned by the user (vs. cached automatically on first load)
+    pub pinned: bool,
+    /// Unix timestamp (seconds) of the snapshot
+    pub cached_at: u64,
+    pub asset_count: u32,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct OfflineIndex {
+    entries: Vec<OfflineEntry>,
+}
+
+static INDEX: Mutex<OfflineIndex> = Mutex::new(OfflineIndex {
+    entries: Vec::new(),
+});
+
+fn persist(index: &OfflineIndex) {
+    if let Err(e) = crate::storage::save_json(OFFLINE_FILE, index) {
+        warn!("[offline] Failed to save cache index: {}", e);
+    }
+}
+
+fn lock_index() -> AppResult<std::sync::MutexGuard<'static, OfflineIndex>> {
+    INDEX
+        .lock()
+        .map_err(|_| AppError::Library("Offline index poisoned".into()))
+}
+
+pub fn init() {
+    if let Ok(mut index) = INDEX.lock() {
+        *index = crate::storage::load_json(OFFLINE_FILE);
+        // Drop entries whose pack was removed from the library by hand
+        index.entries.retain(|e| library::pack_exists(&e.pack_id));
+        info!("[offline] {} cached wallpaper(s)", index.entries.len());
+    }
+}
+
+// ============================================================================
+// URL → Local Path Mapping
+// ============================================================================
+
+fn pack_id_for(wallpaper_id: &str) -> String {
+    let sanitized: String = wallpaper_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}{}", PACK_PREFIX, sanitized)
+}
+
+fn same_origin(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// Pack-relative path for a same-origin URL (`/a/b/` → `a/b/index.html`).
+fn local_path_for(url: &Url) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for seg in url.path_segments()? {
+        let decoded = library::percent_decode(seg)?;
+        if decoded.is_empty() {
+            continue;
+        }
+        let p = Path::new(&decoded);
+        if p.components().count() != 1
+            || !matches!(p.components().next(), Some(Component::Normal(_)))
+        {
+            return None;
+        }
+        parts.push(decoded);
+    }
+    if url.path().ends_with('/') || parts.is_empty() {
+        parts.push("index.html".into());
+    }
+    Some(parts.join("/"))
+}
+
+/// Relative reference from the document at `from` to the file at `to` (both pack-relative).
+fn relative_ref(from: &str, to: &str) -> String {
+    let from_dir: Vec<&str> = from.split('/').collect::<Vec<_>>();
+    let from_dir = &from_dir[..from_dir.len() - 1];
+    let to_parts: Vec<&str> = to.split('/').collect();
+    let common = from_dir
+        .iter()
+        .zip(to_parts.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut out: Vec<&str> = vec![".."; from_dir.len() - common];
+    out.extend(&to_parts[common..]);
+    out.join("/")
+}
+
+// ============================================================================
+// Reference Extraction
+// ============================================================================
+
+/// A URL reference inside a text document, as a byte span.
+struct Reference {
+    start: usize,
+    end: usize,
+}
+
+fn is_fetchable(reference: &str) -> bool {
+    let r = reference.trim();
+    !(r.is_empty()
+        || r.starts_with('#')
+        || r.starts_with("data:")
+        || r.starts_with("blob:")
+        || r.starts_with("javascript:")
+        || r.starts_with("mailto:")
+        || r.starts_with("about:"))
+}
+
+/// Quoted values of `src=`, `href=` and `poster=` attributes.
+fn html_references(text: &str) -> Vec<Reference> {
+    let lower = text.to_ascii_lowercase();
+    let mut refs = Vec::new();
+    for attr in ["src=", "href=", "poster="] {
+        let mut from = 0;
+        while let Some(pos) = lower[from..].find(attr) {
+            let at = from + pos;
+            let after = at + attr.len();
+            from = after;
+            // Word boundary: skip `data-src=` and similar
+            let prev = lower[..at].chars().next_back();
+            if prev.is_some_and(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                continue;
+            }
+            let Some(quote) = text[after..].chars().next() else {
+                break;
+            };
+            if quote != '"' && quote != '\'' {
+                continue;
+            }
+            let start = after + 1;
+            if let Some(len) = text[start..].find(quote) {
+                refs.push(Reference {
+                    start,
+                    end: start + len,
+                });
+            }
+        }
+    }
+    refs
+}
+
+/// `url(...)` values and `@import "..."` targets.
+fn css_references(text: &str) -> Vec<Reference> {
+    let mut refs = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = text[from..].find("url(") {
+        let mut start = from + pos + 4;
+        from = start;
+        let Some(close) = text[start..].find(')') else {
+            break;
+        };
+        let mut end = start + close;
+        let inner = &text[start..end];
+        let trimmed = inner.trim();
+        if let Some(q) = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'') {
+            let offset = inner.find(q).unwrap_or(0);
+            start += offset + 1;
+            end = text[start..].find(q).map(|l| start + l).unwrap_or(end);
+        } else {
+            start += inner.len() - inner.trim_start().len();
+            end -= inner.len() - inner.trim_end().len();
+        }
+        refs.push(Reference { start, end });
+    }
+    from = 0;
+    while let Some(pos) = text[from..].find("@import") {
+        let after = from + pos + "@import".len();
+        from = after;
+        let rest = &text[after..];
+        let skip = rest.len() - rest.trim_start().len();
+        let start_quote = after + skip;
+        let Some(q) = text[start_quote..]
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+        else {
+            continue; // `@import url(...)` handled above
+        };
+        let start = start_quote + 1;
+        if let Some(len) = text[start..].find(q) {
+            refs.push(Reference {
+                start,
+                end: start + len,
+            });
+        }
+    }
+    refs
+}
+
+// ============================================================================
+// Snapshot
+// ============================================================================
+
+fn http_client(timeout_secs: u64) -> AppResult<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .user_agent(concat!("MyWallpaper-Desktop/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| AppError::Network(e.to_string()))
+}
+
+/// Download one asset straight to its place in the snapshot.
+fn fetch(url: &Url, path: &Path) -> AppResult<Downloaded> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Download::new(url.as_str(), path)
+        .max_bytes(MAX_ASSET_BYTES)
+        .run()
+}
+
+/// Queue same-origin references and rewrite them relative to the document.
+fn process_document(
+    text: &str,
+    refs: Vec<Reference>,
+    doc_url: &Url,
+    doc_path: &str,
+    queue: &mut VecDeque<Url>,
+) -> String {
+    let mut refs = refs;
+    refs.sort_by_key(|r| r.start);
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for r in refs {
+        if r.start < cursor {
+            continue;
+        }
+        let raw = &text[r.start..r.end];
+        if !is_fetchable(raw) {
+            continue;
+        }
+        let Ok(mut target) = doc_url.join(raw.trim()) else {
+            continue;
+        };
+        if !same_origin(&target, doc_url) {
+            continue;
+        }
+        target.set_fragment(None);
+        queue.push_back(target.clone());
+
+        // Relative references already resolve correctly inside the pack
+        let is_absolute = raw.starts_with('/') || raw.contains("://");
+        if let (true, Some(local)) = (is_absolute, local_path_for(&target)) {
+            out.push_str(&text[cursor..r.start]);
+            out.push_str(&relative_ref(doc_path, &local));
+            cursor = r.end;
+        }
+    }
+    out.push_str(&text[cursor..]);
+    out
+}
+
+struct Snapshot {
+    entry: String,
+    asset_count: u32,
+    total_bytes: u64,
+}
+
+/// Crawl `entry_url` (plus `extra` asset URLs) into `dest`.
+fn crawl(entry_url: &Url, extra: &[String], dest: &Path) -> AppResult<Snapshot> {
+    let entry = local_path_for(entry_url)
+        .ok_or_else(|| AppError::Validation(format!("Unsupported wallpaper URL: {}", entry_url)))?;
+
+    let mut queue: VecDeque<Url> = VecDeque::from([entry_url.clone()]);
+    queue.extend(
+        extra
+            .iter()
+            .filter_map(|u| entry_url.join(u).ok())
+            .filter(|u| same_origin(u, entry_url)),
+    );
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut snap = Snapshot {
+        entry,
+        asset_count: 0,
+        total_bytes: 0,
+    };
+
+    while let Some(mut url) = queue.pop_front() {
+        url.set_fragment(None);
+        let Some(local) = local_path_for(&url) else {
+            continue;
+        };
+        if !seen.insert(local.clone()) {
+            continue;
+        }
+        if snap.asset_count as usize >= MAX_ASSETS {
+            warn!("[offline] Asset limit reached, snapshot may be incomplete");
+            break;
+        }
+
+        let path: PathBuf = dest.join(&local);
+        let fetched = match fetch(&url, &path) {
+            Ok(r) => r,
+            // The entry page is mandatory; missing secondary assets are tolerated
+            Err(e) if local == snap.entry => return Err(e),
+            Err(e) => {
+                warn!("[offline] Skipping {}", e);
+                continue;
+            }
+        };
+        snap.total_bytes += fetched.bytes;
+        if snap.total_bytes > MAX_TOTAL_BYTES {
+            return Err(AppError::Library(
+                "Wallpaper is too large to cache offline".into(),
+            ));
+        }
+
+        let lower = local.to_ascii_lowercase();
+        let is_html = fetched.content_type.starts_with("text/html")
+            || lower.ends_with(".html")
+            || lower.ends_with(".htm");
+        let is_css = fetched.content_type.starts_with("text/css") || lower.ends_with(".css");
+        if is_html || is_css {
+            // Non-UTF-8 documents are kept as downloaded
+            if let Ok(text) = std::fs::read_to_string(&path) {
+                let mut refs = css_references(&text);
+                if is_html {
+                    refs.extend(html_references(&text));
+                }
+                std::fs::write(
+                    &path,
+                    process_document(&text, refs, &url, &local, &mut queue),
+                )?;
+            }
+        }
+        snap.asset_count += 1;
+    }
+    Ok(snap)
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Take (or retake) a snapshot of a remote wallpaper and record it in the index.
+fn snapshot(
+    wallpaper_id: &str,
+    url: &str,
+    extra: &[String],
+    pinned: bool,
+) -> AppResult<OfflineEntry> {
+    if wallpaper_id.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Wallpaper id must not be empty".into(),
+        ));
+    }
+    let entry_url = Url::parse(url)
+        .map_err(|e| AppError::Validation(format!("Invalid wallpaper URL: {}", e)))?;
+    if !matches!(entry_url.scheme(), "http" | "https") {
+        return Err(AppError::Validation(
+            "Only http(s) wallpapers can be cached".into(),
+        ));
+    }
+
+    let pack_id = pack_id_for(wallpaper_id);
+    let root = library::library_root()?;
+    let scratch = root.join(format!(".{}.snapshot", pack_id));
+    let _ = std::fs::remove_dir_all(&scratch);
+
+    let result = crawl(&entry_url, extra, &scratch).and_then(|snap| {
+        let manifest = PackManifest {
+            name: wallpaper_id.to_string(),
+            kind: WallpaperKind::Web,
+            entry: snap.entry.clone(),
+            preview: None,
+            author: None,
+            description: Some(format!("Offline copy of {}", entry_url)),
+            // Filled in by install_pack
+            verification: Default::default(),
+        };
+        // Replace the previous snapshot only once the new one is complete
+        library::replace_pack(&scratch, &pack_id, &manifest)?;
+        Ok(snap)
+    });
+    let _ = std::fs::remove_dir_all(&scratch);
+    let snap = result?;
+
+    let entry = OfflineEntry {
+        wallpaper_id: wallpaper_id.to_string(),
+        source_url: entry_url.to_string(),
+        pack_id,
+        pinned,
+        cached_at: now_secs(),
+        asset_count: snap.asset_count,
+        total_bytes: snap.total_bytes,
+    };
+    let mut index = lock_index()?;
+    index.entries.retain(|e| e.wallpaper_id != wallpaper_id);
+    index.entries.push(entry.clone());
+    persist(&index);
+    info!(
+        "[offline] Cached '{}' ({} assets, {} bytes)",
+        wallpaper_id, entry.asset_count, entry.total_bytes
+    );
+    Ok(entry)
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+pub fn list_offline() -> Vec<OfflineEntry> {
+    INDEX.lock().map(|i| i.entries.clone()).unwrap_or_default()
+}
+
+fn find(wallpaper_id: &str) -> Option<OfflineEntry> {
+    list_offline()
+        .into_iter()
+        .find(|e| e.wallpaper_id == wallpaper_id)
+}
+
+/// Snapshot a wallpaper after its first successful load. No-op if already cached.
+/// `assets` are extra same-origin URLs the page loaded dynamically.
+pub fn cache_remote_wallpaper(
+    wallpaper_id: &str,
+    url: &str,
+    assets: &[String],
+) -> AppResult<OfflineEntry> {
+    match find(wallpaper_id) {
+        Some(existing) => Ok(existing),
+        None => snapshot(wallpaper_id, url, assets, false),
+    }
+}
+
+/// Take a fresh snapshot and keep it available offline.
+pub fn pin_offline(wallpaper_id: &str, url: &str, assets: &[String]) -> AppResult<OfflineEntry> {
+    snapshot(wallpaper_id, url, assets, true)
+}
+
+/// Forget a cached wallpaper and delete its snapshot.
+pub fn unpin_offline(wallpaper_id: &str) -> AppResult<()> {
+    let mut index = lock_index()?;
+    let Some(pos) = index
+        .entries
+        .iter()
+        .position(|e| e.wallpaper_id == wallpaper_id)
+    else {
+        return Err(AppError::Library(format!(
+            "Wallpaper is not cached: {}",
+            wallpaper_id
+        )));
+    };
+    let entry = index.entries.remove(pos);
+    library::remove_pack(&entry.pack_id)?;
+    persist(&index);
+    Ok(())
+}
+
+/// URL the frontend should load: the remote URL while it is reachable,
+/// otherwise the cached copy over `mwlocal://` when one exists. On a metered
+/// connection the cached copy is preferred without probing the remote.
+pub fn resolve_wallpaper_url(wallpaper_id: &str, url: &str) -> String {
+    let Some(entry) = find(wallpaper_id) else {
+        return url.to_string();
+    };
+    let metered = crate::metered::is_metered();
+    let reachable = !metered
+        && http_client(PROBE_TIMEOUT_SECS)
+            .ok()
+            .and_then(|c| c.head(url).send().ok())
+            .is_some_and(|r| !r.status().is_server_error());
+    if reachable {
+        return url.to_string();
+    }
+    match library::get_local_wallpaper(&entry.pack_id) {
+        Ok(local) => {
+            info!(
+                "[offline] Remote {}, serving cached '{}'",
+                if metered { "metered" } else { "unreachable" },
+                wallpaper_id
+            );
+            local.url
+        }
+        Err(_) => url.to_string(),
+    }
+}
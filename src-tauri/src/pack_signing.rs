@@ -0,0 +1,144 @@
+//! Ed25519 signatures for wallpaper packs.
+//!
+//! A signed pack ships `signature.json`:
+//! `{"publisher": "<key id>", "signature": "<base64>"}`. The signature covers
+//! the pack listing — one `<sha256 hex>  <relative/path>\n` line per file,
+//! sorted by path, after the `DOMAIN` prefix. `wallpaper.json`, `preview.png`
+//! and the signature itself are excluded: the library rewrites the first two.
+//!
+//! Packs are checked on install, before the library serves them; the result is
+//! recorded in the manifest and shown in the library listing.
+
+use crate::error::{AppError, AppResult};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use typeshare::typeshare;
+
+pub const SIGNATURE_FILE: &str = "signature.json";
+const DOMAIN: &str = "mywallpaper-pack-v1\n";
+/// Files the library writes itself, never covered by the signature.
+const UNSIGNED_FILES: &[&str] = &[crate::library::MANIFEST_FILE, "preview.png", SIGNATURE_FILE];
+
+/// Official publisher key (base64, 32 bytes), injected by release builds.
+const OFFICIAL_KEY: Option<&str> = option_env!("MYWALLPAPER_PACK_PUBKEY");
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum PackVerification {
+    #[default]
+    Unsigned,
+    Verified {
+        publisher: String,
+    },
+    /// Signature from a known publisher that does not match the contents
+    Invalid {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct SignatureFile {
+    publisher: String,
+    signature: String,
+}
+
+fn publisher_key(publisher: &str) -> Option<VerifyingKey> {
+    let encoded = match publisher {
+        "mywallpaper" => OFFICIAL_KEY?,
+        _ => return None,
+    };
+    let bytes: [u8; 32] = STANDARD.decode(encoded).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if file_type.is_file() {
+            let Ok(rel) = path.strip_prefix(root) else {
+                continue;
+            };
+            let rel: Vec<_> = rel.iter().map(|c| c.to_string_lossy()).collect();
+            out.push(rel.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// The signed message for the pack in `dir`.
+fn pack_listing(dir: &Path) -> AppResult<Vec<u8>> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.retain(|f| !UNSIGNED_FILES.contains(&f.as_str()));
+    files.sort();
+
+    let mut listing = DOMAIN.to_string();
+    for rel in files {
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut std::fs::File::open(dir.join(&rel))?, &mut hasher)?;
+        listing.push_str(&format!("{:x}  {}\n", hasher.finalize(), rel));
+    }
+    Ok(listing.into_bytes())
+}
+
+fn check(dir: &Path, key: &VerifyingKey, file: &SignatureFile) -> Result<(), String> {
+    let signature = STANDARD
+        .decode(file.signature.trim())
+        .ok()
+        .and_then(|b| Signature::from_slice(&b).ok())
+        .ok_or("Malformed signature")?;
+    let listing = pack_listing(dir).map_err(|e| e.to_string())?;
+    key.verify_strict(&listing, &signature)
+        .map_err(|_| "Signature does not match the pack contents".to_string())
+}
+
+/// Verify the pack in `dir`. I/O errors reading the signature are reported as
+/// invalid rather than unsigned, so a broken signed pack is never trusted.
+pub fn verify_pack(dir: &Path) -> PackVerification {
+    let bytes = match std::fs::read(dir.join(SIGNATURE_FILE)) {
+        Ok(b) => b,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return PackVerification::Unsigned,
+        Err(e) => {
+            return PackVerification::Invalid {
+                reason: e.to_string(),
+            }
+        }
+    };
+    let file: SignatureFile = match serde_json::from_slice(&bytes) {
+        Ok(f) => f,
+        Err(e) => {
+            return PackVerification::Invalid {
+                reason: format!("Malformed {}: {}", SIGNATURE_FILE, e),
+            }
+        }
+    };
+    // Publishers this build has no key for are treated like unsigned packs
+    let Some(key) = publisher_key(&file.publisher) else {
+        return PackVerification::Unsigned;
+    };
+    match check(dir, &key, &file) {
+        Ok(()) => PackVerification::Verified {
+            publisher: file.publisher,
+        },
+        Err(reason) => PackVerification::Invalid { reason },
+    }
+}
+
+/// Refuse packs whose signature is present but does not verify.
+pub fn ensure_not_tampered(dir: &Path) -> AppResult<PackVerification> {
+    match verify_pack(dir) {
+        PackVerification::Invalid { reason } => Err(AppError::Library(format!(
+            "Pack signature rejected: {}",
+            reason
+        ))),
+        verification => Ok(verification),
+    }
+}
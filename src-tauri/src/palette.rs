@@ -0,0 +1,213 @@
+//! Wallpaper palette extraction and accent-color sync.
+//!
+//! Colors are sampled from the current frame (see `screenshot`) or from a
+//! local pack's preview. With `syncAccentColor` (backend settings) the accent
+//! picked from the frame is written to the Windows personalization settings
+//! each time the frontend reports new content ready.
+
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use serde::Serialize;
+use typeshare::typeshare;
+
+/// Sample grid: large enough for stable shares, cheap to bucket.
+const SAMPLE_SIZE: u32 = 64;
+const MAX_COLORS: usize = 5;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteColor {
+    /// `#rrggbb`
+    pub color: String,
+    /// Fraction of the sampled pixels (0-1)
+    pub share: f32,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WallpaperPalette {
+    /// `#rrggbb` mean of all pixels
+    pub average: String,
+    /// Most common colors, most frequent first
+    pub dominant: Vec<PaletteColor>,
+    /// Most vivid dominant color, suitable as a UI accent
+    pub accent: String,
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Chroma (0-1): HSL saturation damped towards black and white, so neither
+/// greys nor washed-out/near-black colors win.
+fn vividness([r, g, b]: [u8; 3]) -> f32 {
+    (r.max(g).max(b) - r.min(g).min(b)) as f32 / 255.0
+}
+
+fn extract(img: &image::DynamicImage) -> WallpaperPalette {
+    let sample = img.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_rgb8();
+    // 4 bits per channel; each bucket keeps its pixel count and channel sums
+    let mut buckets = vec![(0u32, [0u64; 3]); 1 << 12];
+    let mut total = [0u64; 3];
+    for px in sample.pixels() {
+        let [r, g, b] = px.0;
+        let idx = ((r as usize >> 4) << 8) | ((g as usize >> 4) << 4) | (b as usize >> 4);
+        let bucket = &mut buckets[idx];
+        bucket.0 += 1;
+        for ((sum, total), value) in bucket.1.iter_mut().zip(&mut total).zip(px.0) {
+            *sum += value as u64;
+            *total += value as u64;
+        }
+    }
+    let count = (sample.width() * sample.height()).max(1) as u64;
+    let mean = |sums: [u64; 3], n: u64| sums.map(|s| (s / n.max(1)) as u8);
+
+    buckets.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+    let dominant: Vec<[u8; 3]> = buckets
+        .iter()
+        .take_while(|(n, _)| *n > 0)
+        .take(MAX_COLORS)
+        .map(|(n, sums)| mean(*sums, *n as u64))
+        .collect();
+    let average = mean(total, count);
+    let accent = dominant
+        .iter()
+        .copied()
+        .max_by(|a, b| vividness(*a).total_cmp(&vividness(*b)))
+        .filter(|c| vividness(*c) > 0.05)
+        .unwrap_or(average);
+
+    WallpaperPalette {
+        average: hex(average),
+        dominant: buckets
+            .iter()
+            .zip(&dominant)
+            .map(|((n, _), color)| PaletteColor {
+                color: hex(*color),
+                share: *n as f32 / count as f32,
+            })
+            .collect(),
+        accent: hex(accent),
+    }
+}
+
+/// Palette of the current frame, or of local pack `source_id`'s preview.
+pub fn palette(source_id: Option<&str>) -> AppResult<WallpaperPalette> {
+    let img = match source_id {
+        Some(id) => {
+            let png = crate::library::thumbnail_png(id, SAMPLE_SIZE * 4)?;
+            image::load_from_memory(&png)
+                .map_err(|e| AppError::Library(format!("Cannot decode preview: {}", e)))?
+        }
+        None => image::DynamicImage::ImageRgba8(crate::screenshot::capture_frame()?),
+    };
+    Ok(extract(&img))
+}
+
+/// New content is on screen: follow it with the OS accent color if enabled.
+pub fn on_content_ready() {
+    if !crate::settings::get().sync_accent_color {
+        return;
+    }
+    std::thread::spawn(|| {
+        // Let the first frames of the new content settle before sampling
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        let result = palette(None).and_then(|p| set_os_accent_color(&p.accent));
+        if let Err(e) = result {
+            warn!("[palette] Accent sync failed: {}", e);
+        }
+    });
+}
+
+fn parse_hex(color: &str) -> AppResult<[u8; 3]> {
+    let invalid = || AppError::Validation(format!("Invalid color: {}", color));
+    let digits = color.strip_prefix('#').ok_or_else(invalid)?;
+    if digits.len() != 6 {
+        return Err(invalid());
+    }
+    let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+    Ok([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+/// Write `color` (`#rrggbb`) as the Windows accent color.
+#[cfg(target_os = "windows")]
+fn set_os_accent_color(color: &str) -> AppResult<()> {
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER, KEY_SET_VALUE,
+        REG_DWORD, REG_OPTION_NON_VOLATILE,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+    };
+
+    let [r, g, b] = parse_hex(color)?;
+    // AccentColor/AccentColorMenu are ABGR, ColorizationColor is ARGB
+    let abgr = 0xFF00_0000 | ((b as u32) << 16) | ((g as u32) << 8) | r as u32;
+    let argb = 0xC400_0000 | ((r as u32) << 16) | ((g as u32) << 8) | b as u32;
+
+    unsafe fn set_dwords(subkey: PCWSTR, values: &[(PCWSTR, u32)]) -> AppResult<()> {
+        let mut key = HKEY::default();
+        let status = RegCreateKeyExW(
+            HKEY_CURRENT_USER,
+            subkey,
+            0,
+            PCWSTR::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_SET_VALUE,
+            None,
+            &mut key,
+            None,
+        );
+        if status.is_err() {
+            return Err(AppError::Io(std::io::Error::from_raw_os_error(
+                status.0 as i32,
+            )));
+        }
+        let mut result = Ok(());
+        for (name, value) in values {
+            let status = RegSetValueExW(key, *name, 0, REG_DWORD, Some(&value.to_le_bytes()));
+            if status.is_err() {
+                result = Err(AppError::Io(std::io::Error::from_raw_os_error(
+                    status.0 as i32,
+                )));
+            }
+        }
+        let _ = RegCloseKey(key);
+        result
+    }
+
+    unsafe {
+        set_dwords(
+            w!("Software\\Microsoft\\Windows\\DWM"),
+            &[(w!("AccentColor"), abgr), (w!("ColorizationColor"), argb)],
+        )?;
+        set_dwords(
+            w!("Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Accent"),
+            &[(w!("AccentColorMenu"), abgr), (w!("StartColorMenu"), abgr)],
+        )?;
+        // Tell the shell and running apps to reload the accent
+        let _ = SendMessageTimeoutW(
+            HWND_BROADCAST,
+            WM_SETTINGCHANGE,
+            WPARAM(0),
+            LPARAM(w!("ImmersiveColorSet").as_ptr() as isize),
+            SMTO_ABORTIFHUNG,
+            1000,
+            None,
+        );
+    }
+    info!("[palette] OS accent color set to {}", color);
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_os_accent_color(color: &str) -> AppResult<()> {
+    parse_hex(color)?;
+    Err(AppError::Validation(
+        "Accent color sync is only supported on Windows".into(),
+    ))
+}
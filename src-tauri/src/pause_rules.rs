@@ -0,0 +1,139 @@
+//! Process-based pause rules — pause the wallpaper while listed executables
+//! (games, DAWs, video editors) are running or focused, regardless of occlusion.
+//!
+//! A watcher samples the process list and the foreground window every couple
+//! of seconds while rules exist and holds an automatic pause (see
+//! `desktop_actions::auto_pause`) while any rule matches.
+
+use crate::error::{AppError, AppResult};
+use crate::settings::{PauseRule, PauseTrigger};
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const PAUSE_SOURCE: &str = "pause rules";
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Lowercase the executable names and add `.exe` where missing.
+pub fn normalize(rules: &[PauseRule]) -> AppResult<Vec<PauseRule>> {
+    rules
+        .iter()
+        .map(|rule| {
+            let name = rule.executable.trim().to_ascii_lowercase();
+            if name.is_empty() || name.contains(['/', '\\', ':']) {
+                return Err(AppError::Validation(format!(
+                    "Pause rule needs an executable file name, got '{}'",
+                    rule.executable
+                )));
+            }
+            Ok(PauseRule {
+                executable: if name.ends_with(".exe") {
+                    name
+                } else {
+                    format!("{}.exe", name)
+                },
+                trigger: rule.trigger,
+            })
+        })
+        .collect()
+}
+
+/// Start the watcher (idle while no rules are configured).
+pub fn init() {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let mut matched: Option<String> = None;
+        loop {
+            let rules = crate::settings::get().pause_rules;
+            let current = if rules.is_empty() {
+                None
+            } else {
+                processes::matching_rule(&rules)
+            };
+            if current != matched {
+                match &current {
+                    Some(exe) => info!("[pause_rules] {} matched, pausing", exe),
+                    None => info!("[pause_rules] No rule matches, resuming"),
+                }
+                if let Err(e) = crate::desktop_actions::auto_pause(PAUSE_SOURCE, current.is_some())
+                {
+                    warn!("[pause_rules] Cannot update pause: {}", e);
+                }
+                matched = current;
+            }
+            std::thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+mod processes {
+    use super::*;
+    use std::collections::HashMap;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    /// Lowercase executable name per process id.
+    unsafe fn running() -> HashMap<u32, String> {
+        let mut names = HashMap::new();
+        let Ok(snap) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return names;
+        };
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        if Process32FirstW(snap, &mut entry).is_ok() {
+            loop {
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|c| *c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                names.insert(
+                    entry.th32ProcessID,
+                    String::from_utf16_lossy(&entry.szExeFile[..len]).to_ascii_lowercase(),
+                );
+                if Process32NextW(snap, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+        let _ = CloseHandle(snap);
+        names
+    }
+
+    /// Executable of the first rule that currently matches.
+    pub fn matching_rule(rules: &[PauseRule]) -> Option<String> {
+        unsafe {
+            let names = running();
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut pid));
+            let focused = names.get(&pid);
+            rules
+                .iter()
+                .find(|rule| match rule.trigger {
+                    PauseTrigger::Running => names.values().any(|n| *n == rule.executable),
+                    PauseTrigger::Focused => focused == Some(&rule.executable),
+                })
+                .map(|rule| rule.executable.clone())
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod processes {
+    use super::*;
+
+    pub fn matching_rule(_rules: &[PauseRule]) -> Option<String> {
+        None
+    }
+}
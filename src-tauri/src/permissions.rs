@@ -0,0 +1,247 @@
+//! Per-wallpaper capabilities for sensitive commands.
+//!
+//! Wallpaper content runs in sandboxed frames without IPC; the app page relays
+//! its requests and passes the wallpaper id along. Relayed calls need the
+//! matching capability, persisted per wallpaper id in `permissions.json`.
+//! Unknown requests are refused and announced with `permission-requested`,
+//! sent only to the trusted windows (`main`, `hub`) so the user is prompted
+//! from the app's own settings UI. Calls without a wallpaper id are trusted
+//! only from those windows; preview and widget windows always name one.
+
+use crate::error::{AppError, AppResult};
+use crate::events::AppEvent;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use typeshare::typeshare;
+
+const PERMISSIONS_FILE: &str = "permissions.json";
+/// Windows running the app's own pages
+const TRUSTED_LABELS: [&str; 2] = ["main", crate::hub::LABEL];
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Capability {
+    /// CPU/GPU/RAM/network statistics
+    SystemStats,
+    /// Show/hide desktop icons (switches interaction mode)
+    DesktopIcons,
+    /// Now-playing information and media keys
+    MediaControl,
+    /// Capture the rendered wallpaper
+    Screenshots,
+    /// Show OS notifications
+    Notifications,
+    /// Microphone input (audio spectrum)
+    Microphone,
+    /// Change the system volume
+    SystemVolume,
+    /// Coarse device location
+    Location,
+    /// Read and write the clipboard, and hear about its changes
+    Clipboard,
+    /// Change the real display brightness
+    DisplayBrightness,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WallpaperPermissions {
+    #[serde(default)]
+    pub granted: Vec<Capability>,
+    #[serde(default)]
+    pub denied: Vec<Capability>,
+}
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static STORE: Mutex<BTreeMap<String, WallpaperPermissions>> = Mutex::new(BTreeMap::new());
+/// Prompts already emitted and not yet answered, to avoid repeating them on
+/// every polled call.
+static PENDING: Mutex<Option<HashSet<(String, Capability)>>> = Mutex::new(None);
+
+fn persist(store: &BTreeMap<String, WallpaperPermissions>) {
+    if let Err(e) = crate::storage::save_json(PERMISSIONS_FILE, store) {
+        error!("[permissions] Failed to save permissions: {}", e);
+    }
+}
+
+fn store_error() -> AppError {
+    AppError::Permission("Permission store poisoned".into())
+}
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+    if let Ok(mut store) = STORE.lock() {
+        *store = crate::storage::load_json(PERMISSIONS_FILE);
+        info!(
+            "[permissions] Loaded grants for {} wallpaper(s)",
+            store.len()
+        );
+    }
+}
+
+pub fn is_trusted(label: &str) -> bool {
+    TRUSTED_LABELS.contains(&label)
+}
+
+/// Fail unless `caller` runs one of the app's own pages.
+pub fn require_trusted(caller: &tauri::Webview) -> AppResult<()> {
+    if is_trusted(caller.label()) {
+        return Ok(());
+    }
+    Err(AppError::Permission(format!(
+        "Window '{}' cannot call this command",
+        caller.label()
+    )))
+}
+
+/// Fail unless `wallpaper_id` holds `capability`; without one, unless
+/// `caller` is trusted.
+pub fn require(
+    caller: &tauri::Webview,
+    wallpaper_id: Option<&str>,
+    capability: Capability,
+) -> AppResult<()> {
+    let Some(id) = wallpaper_id else {
+        return require_trusted(caller);
+    };
+    let decision = {
+        let store = STORE.lock().map_err(|_| store_error())?;
+        store.get(id).map(|p| {
+            if p.granted.contains(&capability) {
+                Some(true)
+            } else if p.denied.contains(&capability) {
+                Some(false)
+            } else {
+                None
+            }
+        })
+    };
+    match decision.flatten() {
+        Some(true) => Ok(()),
+        Some(false) => Err(AppError::Permission(format!(
+            "Wallpaper '{}' was denied {:?}",
+            id, capability
+        ))),
+        None => {
+            request_prompt(id, capability);
+            Err(AppError::Permission(format!(
+                "Wallpaper '{}' needs {:?} (pending user approval)",
+                id, capability
+            )))
+        }
+    }
+}
+
+/// Fail unless the caller is the app or `target` itself: wallpapers only
+/// reach their own data.
+pub fn require_self(
+    caller: &tauri::Webview,
+    wallpaper_id: Option<&str>,
+    target: &str,
+) -> AppResult<()> {
+    match wallpaper_id {
+        Some(id) if id != target => Err(AppError::Permission(format!(
+            "Wallpaper '{}' cannot access data of '{}'",
+            id, target
+        ))),
+        Some(_) => Ok(()),
+        None => require_trusted(caller),
+    }
+}
+
+/// Whether `wallpaper_id` holds `capability`, without prompting.
+pub fn is_granted(wallpaper_id: &str, capability: Capability) -> bool {
+    STORE
+        .lock()
+        .ok()
+        .and_then(|store| {
+            store
+                .get(wallpaper_id)
+                .map(|p| p.granted.contains(&capability))
+        })
+        .unwrap_or(false)
+}
+
+fn request_prompt(id: &str, capability: Capability) {
+    let first = PENDING
+        .lock()
+        .map(|mut p| {
+            p.get_or_insert_with(HashSet::new)
+                .insert((id.to_string(), capability))
+        })
+        .unwrap_or(false);
+    if !first {
+        return;
+    }
+    info!("[permissions] '{}' requests {:?}", id, capability);
+    if let Some(app) = APP_HANDLE.get() {
+        let event = AppEvent::PermissionRequested {
+            wallpaper_id: id.to_string(),
+            capability,
+        };
+        if let Err(e) = emit_to_trusted(app, &event) {
+            error!("[permissions] Failed to emit event: {}", e);
+        }
+    }
+}
+
+/// Emit `event` to the trusted windows only; `emit_app_event` reaches every
+/// webview, previews and widgets included.
+fn emit_to_trusted(app: &tauri::AppHandle, event: &AppEvent) -> tauri::Result<()> {
+    use tauri::{Emitter, EventTarget};
+    app.emit_filter(event.event_name(), event, |target| match target {
+        EventTarget::Window { label }
+        | EventTarget::Webview { label }
+        | EventTarget::WebviewWindow { label } => is_trusted(label),
+        _ => false,
+    })
+}
+
+pub fn list() -> BTreeMap<String, WallpaperPermissions> {
+    STORE.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Record the user's decision (`None` forgets it, so the next use prompts again).
+pub fn set(wallpaper_id: &str, capability: Capability, granted: Option<bool>) -> AppResult<()> {
+    if wallpaper_id.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Wallpaper id must not be empty".into(),
+        ));
+    }
+    let mut store = STORE.lock().map_err(|_| store_error())?;
+    let entry = store.entry(wallpaper_id.to_string()).or_default();
+    entry.granted.retain(|c| *c != capability);
+    entry.denied.retain(|c| *c != capability);
+    match granted {
+        Some(true) => entry.granted.push(capability),
+        Some(false) => entry.denied.push(capability),
+        None => {}
+    }
+    if entry.granted.is_empty() && entry.denied.is_empty() {
+        store.remove(wallpaper_id);
+    }
+    persist(&store);
+    if let Ok(mut pending) = PENDING.lock() {
+        if let Some(p) = pending.as_mut() {
+            p.remove(&(wallpaper_id.to_string(), capability));
+        }
+    }
+    info!(
+        "[permissions] {:?} for '{}': {:?}",
+        capability, wallpaper_id, granted
+    );
+    Ok(())
+}
+
+/// Forget every decision for a wallpaper (e.g. when it is removed).
+pub fn revoke_all(wallpaper_id: &str) -> AppResult<()> {
+    let mut store = STORE.lock().map_err(|_| store_error())?;
+    if store.remove(wallpaper_id).is_some() {
+        persist(&store);
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! OS power-saving state (Windows battery/energy saver) and the selected
+//! power plan / power mode.
+//!
+//! Polled every few seconds and emitted as `power-mode-changed`, so the
+//! frontend can lighten the wallpaper; profiles can also activate on it
+//! (`ActivationRule::PowerSaver`, `ActivationRule::PowerProfile`).
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use typeshare::typeshare;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerMode {
+    /// Battery saver / energy saver is on
+    pub power_saver: bool,
+    /// Running on battery power
+    pub on_battery: bool,
+    /// Power plan, or the Windows 11 power mode where it overrides the plan
+    pub profile: PowerProfile,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PowerProfile {
+    /// "Power saver" plan / "Best power efficiency" mode
+    PowerSaver,
+    #[default]
+    Balanced,
+    /// "High/Ultimate performance" plan / "Best performance" mode
+    Performance,
+}
+
+/// Current power mode; all false where it cannot be determined.
+pub fn get() -> PowerMode {
+    query().unwrap_or_default()
+}
+
+/// Start the `power-mode-changed` watcher.
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut last = get();
+        while RUNNING.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = get();
+            if current == last {
+                continue;
+            }
+            info!(
+                "[power_mode] Power saver {}, {}, {:?} profile",
+                if current.power_saver { "on" } else { "off" },
+                if current.on_battery {
+                    "on battery"
+                } else {
+                    "on AC"
+                },
+                current.profile
+            );
+            if let Err(e) = app.emit_app_event(&AppEvent::PowerModeChanged(current)) {
+                error!("[power_mode] Failed to emit event: {}", e);
+            }
+            last = current;
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+fn query() -> Option<PowerMode> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+    Some(PowerMode {
+        power_saver: status.SystemStatusFlag == 1,
+        on_battery: status.ACLineStatus == 0,
+        profile: power_profile(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "powrprof")]
+extern "system" {
+    /// Power mode slider overlay (Windows 10 1709+); zero GUID when balanced
+    fn PowerGetEffectiveOverlayScheme(overlay: *mut windows::core::GUID) -> u32;
+}
+
+#[cfg(target_os = "windows")]
+fn power_profile() -> PowerProfile {
+    use windows::core::GUID;
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::System::Power::PowerGetActiveScheme;
+
+    const OVERLAY_BEST_EFFICIENCY: GUID = GUID::from_u128(0x961cc777_2547_4f9d_8174_7d86181b8a7a);
+    const OVERLAY_BEST_PERFORMANCE: GUID = GUID::from_u128(0xded574b5_45a0_4f42_8737_46345c09c238);
+    const SCHEME_POWER_SAVER: GUID = GUID::from_u128(0xa1841308_3541_4fab_bc81_f71556f20b4a);
+    const SCHEME_HIGH_PERFORMANCE: GUID = GUID::from_u128(0x8c5e7fda_e8bf_4a96_9a85_a6e23a8c635c);
+    const SCHEME_ULTIMATE_PERFORMANCE: GUID =
+        GUID::from_u128(0xe9a42b02_d5df_448d_aa00_03f14749eb61);
+
+    unsafe {
+        let mut overlay = GUID::zeroed();
+        if PowerGetEffectiveOverlayScheme(&mut overlay) == 0 {
+            if overlay == OVERLAY_BEST_EFFICIENCY {
+                return PowerProfile::PowerSaver;
+            }
+            if overlay == OVERLAY_BEST_PERFORMANCE {
+                return PowerProfile::Performance;
+            }
+        }
+        let mut scheme: *mut GUID = std::ptr::null_mut();
+        if PowerGetActiveScheme(None, &mut scheme).is_err() || scheme.is_null() {
+            return PowerProfile::Balanced;
+        }
+        let active = *scheme;
+        let _ = LocalFree(HLOCAL(scheme as *mut _));
+        match active {
+            SCHEME_POWER_SAVER => PowerProfile::PowerSaver,
+            SCHEME_HIGH_PERFORMANCE | SCHEME_ULTIMATE_PERFORMANCE => PowerProfile::Performance,
+            _ => PowerProfile::Balanced,
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn query() -> Option<PowerMode> {
+    None
+}
@@ -0,0 +1,90 @@
+//! Windowed preview: a wallpaper in a regular resizable window, never
+//! injected into the desktop, so content can be tried before it is applied.
+//!
+//! The preview gets the same init script (plus `__MW_INIT__.preview = true`)
+//! and command surface as the wallpaper window. It runs InPrivate so its
+//! cookies and storage never leak into the desktop wallpaper, and it is not
+//! watched by the heartbeat.
+
+use crate::error::{AppError, AppResult};
+use log::info;
+use tauri::Manager;
+
+pub const LABEL: &str = "preview";
+/// Evaluated after `__MW_INIT__` in the preview window
+pub const INIT_SCRIPT: &str = "window.__MW_INIT__ && (window.__MW_INIT__.preview = true);";
+
+const DEFAULT_SIZE: (f64, f64) = (1280.0, 720.0);
+/// Share of the target monitor the window covers
+const MONITOR_FRACTION: f64 = 0.7;
+
+/// Open (or retarget) the preview window on `monitor` (index in `DisplayInfo`
+/// order; the primary monitor when `None`).
+pub fn open(app: &tauri::AppHandle, url: &str, monitor: Option<u32>) -> AppResult<()> {
+    let url = url::Url::parse(url)
+        .map_err(|e| AppError::Validation(format!("Invalid preview URL: {}", e)))?;
+    // The navigation guard would cancel anything else anyway
+    if !crate::navigation::is_allowed(&url) {
+        return Err(AppError::Validation(format!(
+            "Origin not allowed for preview: {}",
+            url
+        )));
+    }
+    let target = match monitor {
+        Some(i) => Some(
+            app.available_monitors()?
+                .into_iter()
+                .nth(i as usize)
+                .ok_or_else(|| AppError::Validation(format!("No monitor {}", i)))?,
+        ),
+        None => app.primary_monitor()?,
+    };
+
+    let window = match app.get_webview_window(LABEL) {
+        Some(window) => {
+            window.navigate(url.clone())?;
+            window
+        }
+        None => {
+            let mut builder = tauri::WebviewWindowBuilder::new(
+                app,
+                LABEL,
+                tauri::WebviewUrl::External(url.clone()),
+            )
+            .title("MyWallpaper Preview")
+            .inner_size(DEFAULT_SIZE.0, DEFAULT_SIZE.1)
+            .resizable(true)
+            .incognito(true)
+            .visible(false);
+            if let Some(args) = crate::hub::browser_args(app) {
+                builder = builder.additional_browser_args(&args);
+            }
+            builder.build()?
+        }
+    };
+
+    match target {
+        Some(m) => {
+            let (pos, size) = (m.position(), m.size());
+            let w = (size.width as f64 * MONITOR_FRACTION) as u32;
+            let h = (size.height as f64 * MONITOR_FRACTION) as u32;
+            window.set_size(tauri::PhysicalSize::new(w, h))?;
+            window.set_position(tauri::PhysicalPosition::new(
+                pos.x + (size.width - w) as i32 / 2,
+                pos.y + (size.height - h) as i32 / 2,
+            ))?;
+        }
+        None => window.center()?,
+    }
+    window.show()?;
+    let _ = window.set_focus();
+    info!("[preview] Opened {}", url);
+    Ok(())
+}
+
+pub fn close(app: &tauri::AppHandle) -> AppResult<()> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.close()?;
+    }
+    Ok(())
+}
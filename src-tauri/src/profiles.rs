@@ -0,0 +1,354 @@
+//! Profiles — named bundles of wallpaper settings with automatic activation rules.
+//!
+//! A background thread evaluates each profile's rules (power source, power
+//! saver, running processes, monitor layout) and activates the first profile
+//! whose rules all match. A manual `apply_profile` stays in effect until the matching profile
+//! changes.
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use typeshare::typeshare;
+
+const PROFILES_FILE: &str = "profiles.json";
+const RULE_POLL_SECS: u64 = 10;
+const MAX_NAME_LEN: usize = 64;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LayerMode {
+    /// Icons shown, clicks pass through to the desktop
+    Wallpaper,
+    /// Icons hidden, wallpaper UI is interactive
+    Interface,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum ActivationRule {
+    /// Running on battery power
+    OnBattery,
+    /// Running on AC power (always true on desktops without a battery)
+    OnAc,
+    /// OS battery/energy saver is on
+    PowerSaver,
+    /// The OS power plan / power mode is `profile`
+    PowerProfile {
+        profile: crate::power_mode::PowerProfile,
+    },
+    /// A process with this executable name is running (case-insensitive, `.exe` optional)
+    ProcessRunning { name: String },
+    /// Number of connected monitors, optionally with the primary monitor resolution
+    #[serde(rename_all = "camelCase")]
+    MonitorLayout {
+        count: u32,
+        #[serde(default)]
+        primary_width: Option<u32>,
+        #[serde(default)]
+        primary_height: Option<u32>,
+    },
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    /// Wallpaper identifier understood by the frontend
+    #[serde(default)]
+    pub wallpaper: Option<String>,
+    #[serde(default)]
+    pub fps_cap: Option<u32>,
+    /// Wallpaper audio volume (0.0 - 1.0)
+    #[serde(default)]
+    pub volume: Option<f32>,
+    #[serde(default)]
+    pub muted: Option<bool>,
+    #[serde(default)]
+    pub layer_mode: Option<LayerMode>,
+    /// All rules must match for automatic activation. Empty = manual only.
+    #[serde(default)]
+    pub rules: Vec<ActivationRule>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub profiles: Vec<Profile>,
+    /// Id of the currently active profile
+    pub active: Option<String>,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+static STORE: Mutex<ProfileList> = Mutex::new(ProfileList {
+    profiles: Vec::new(),
+    active: None,
+});
+static RULES_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn persist(store: &ProfileList) {
+    if let Err(e) = crate::storage::save_json(PROFILES_FILE, store) {
+        error!("[profiles] Failed to save profiles: {}", e);
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+pub fn list_profiles() -> ProfileList {
+    STORE.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Insert or replace a profile (matched by id).
+pub fn save_profile(profile: Profile) -> AppResult<()> {
+    if profile.id.trim().is_empty() {
+        return Err(AppError::Validation("Profile id must not be empty".into()));
+    }
+    if profile.name.trim().is_empty() || profile.name.len() > MAX_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "Profile name must be 1-{} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    if profile.volume.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+        return Err(AppError::Validation(
+            "Profile volume must be between 0 and 1".into(),
+        ));
+    }
+    let mut store = STORE
+        .lock()
+        .map_err(|_| AppError::Profile("Profile store poisoned".into()))?;
+    match store.profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) => *existing = profile,
+        None => store.profiles.push(profile),
+    }
+    persist(&store);
+    Ok(())
+}
+
+pub fn delete_profile(id: &str) -> AppResult<()> {
+    let mut store = STORE
+        .lock()
+        .map_err(|_| AppError::Profile("Profile store poisoned".into()))?;
+    let before = store.profiles.len();
+    store.profiles.retain(|p| p.id != id);
+    if store.profiles.len() == before {
+        return Err(AppError::Profile(format!("Unknown profile: {}", id)));
+    }
+    if store.active.as_deref() == Some(id) {
+        store.active = None;
+    }
+    persist(&store);
+    Ok(())
+}
+
+/// Activate a profile: apply backend-side settings and notify the frontend.
+pub fn apply_profile(app: &tauri::AppHandle, id: &str, automatic: bool) -> AppResult<()> {
+    let profile = {
+        let mut store = STORE
+            .lock()
+            .map_err(|_| AppError::Profile("Profile store poisoned".into()))?;
+        let profile = store
+            .profiles
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| AppError::Profile(format!("Unknown profile: {}", id)))?;
+        store.active = Some(profile.id.clone());
+        persist(&store);
+        profile
+    };
+
+    if let Some(mode) = profile.layer_mode {
+        crate::window_layer::set_desktop_icons_visible(mode == LayerMode::Wallpaper)?;
+    }
+    if let Some(cap) = profile.fps_cap {
+        crate::animated_image::set_fps_cap(cap);
+    }
+    // Muting wins over the level; unmuting alone keeps the current level
+    let level = if profile.muted == Some(true) {
+        Some(0.0)
+    } else {
+        profile.volume
+    };
+    if let Some(level) = level {
+        crate::wallpaper_volume::set(app, level)?;
+    }
+
+    info!(
+        "[profiles] Activated '{}' ({})",
+        profile.name,
+        if automatic { "rule" } else { "manual" }
+    );
+    crate::crash::breadcrumb("profile", &format!("activated '{}'", profile.id));
+    app.emit_app_event(&AppEvent::ProfileChanged { profile, automatic })?;
+    Ok(())
+}
+
+// ============================================================================
+// Rule Evaluation
+// ============================================================================
+
+/// Snapshot of the environment the rules are matched against.
+struct RuleContext {
+    on_battery: bool,
+    power_saver: bool,
+    power_profile: crate::power_mode::PowerProfile,
+    /// Lowercased process names without `.exe`
+    processes: HashSet<String>,
+    monitors: Vec<crate::system_monitor::DisplayInfo>,
+}
+
+fn normalize_process_name(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    lower.strip_suffix(".exe").map(str::to_string).unwrap_or(lower)
+}
+
+fn build_context(sys: &mut sysinfo::System, profiles: &[Profile]) -> RuleContext {
+    let rules = || profiles.iter().flat_map(|p| p.rules.iter());
+
+    // AC line status: a battery that is full or not charging on AC is not battery power
+    let on_battery = rules()
+        .any(|r| matches!(r, ActivationRule::OnBattery | ActivationRule::OnAc))
+        && crate::power_mode::get().on_battery;
+
+    let power_mode = if rules().any(|r| {
+        matches!(
+            r,
+            ActivationRule::PowerSaver | ActivationRule::PowerProfile { .. }
+        )
+    }) {
+        crate::power_mode::get()
+    } else {
+        Default::default()
+    };
+
+    let processes = if rules().any(|r| matches!(r, ActivationRule::ProcessRunning { .. })) {
+        sys.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            true,
+            sysinfo::ProcessRefreshKind::nothing(),
+        );
+        sys.processes()
+            .values()
+            .map(|p| normalize_process_name(&p.name().to_string_lossy()))
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let monitors = if rules().any(|r| matches!(r, ActivationRule::MonitorLayout { .. })) {
+        crate::system_monitor::collect_display_info().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    RuleContext {
+        on_battery,
+        power_saver: power_mode.power_saver,
+        power_profile: power_mode.profile,
+        processes,
+        monitors,
+    }
+}
+
+fn rule_matches(rule: &ActivationRule, ctx: &RuleContext) -> bool {
+    match rule {
+        ActivationRule::OnBattery => ctx.on_battery,
+        ActivationRule::OnAc => !ctx.on_battery,
+        ActivationRule::PowerSaver => ctx.power_saver,
+        ActivationRule::PowerProfile { profile } => ctx.power_profile == *profile,
+        ActivationRule::ProcessRunning { name } => {
+            ctx.processes.contains(&normalize_process_name(name))
+        }
+        ActivationRule::MonitorLayout {
+            count,
+            primary_width,
+            primary_height,
+        } => {
+            if ctx.monitors.len() as u32 != *count {
+                return false;
+            }
+            let primary = ctx.monitors.iter().find(|m| m.primary);
+            primary_width.is_none_or(|w| primary.is_some_and(|m| m.width == w))
+                && primary_height.is_none_or(|h| primary.is_some_and(|m| m.height == h))
+        }
+    }
+}
+
+/// First profile with at least one rule where every rule matches.
+fn matching_profile(profiles: &[Profile], ctx: &RuleContext) -> Option<String> {
+    profiles
+        .iter()
+        .find(|p| !p.rules.is_empty() && p.rules.iter().all(|r| rule_matches(r, ctx)))
+        .map(|p| p.id.clone())
+}
+
+/// Activate a profile given by id or (case-insensitive) name, e.g. from `--profile`.
+pub fn apply_by_id_or_name(app: &tauri::AppHandle, key: &str) -> AppResult<()> {
+    let profiles = list_profiles().profiles;
+    let id = profiles
+        .iter()
+        .find(|p| p.id == key)
+        .or_else(|| profiles.iter().find(|p| p.name.eq_ignore_ascii_case(key)))
+        .map(|p| p.id.clone())
+        .ok_or_else(|| AppError::Profile(format!("Unknown profile: {}", key)))?;
+    apply_profile(app, &id, false)
+}
+
+/// (Re)load persisted profiles from disk.
+pub fn reload() {
+    if let Ok(mut store) = STORE.lock() {
+        *store = crate::storage::load_json(PROFILES_FILE);
+        info!("[profiles] Loaded {} profile(s)", store.profiles.len());
+    }
+}
+
+/// Load persisted profiles and start the rule evaluation thread.
+pub fn init(app_handle: tauri::AppHandle) {
+    reload();
+
+    if RULES_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let mut sys = sysinfo::System::new();
+        let mut last_match: Option<String> = None;
+
+        while RULES_RUNNING.load(Ordering::SeqCst) {
+            let profiles = list_profiles().profiles;
+            if profiles.iter().any(|p| !p.rules.is_empty()) {
+                let ctx = build_context(&mut sys, &profiles);
+                let matched = matching_profile(&profiles, &ctx);
+                // Only act on transitions so a manual apply is not immediately overridden
+                if matched != last_match {
+                    if let Some(ref id) = matched {
+                        if let Err(e) = apply_profile(&app_handle, id, true) {
+                            error!("[profiles] Auto-activation failed: {}", e);
+                        }
+                    }
+                    last_match = matched;
+                }
+            }
+            std::thread::sleep(Duration::from_secs(RULE_POLL_SECS));
+        }
+    });
+}
@@ -0,0 +1,118 @@
+//! Quiet hours — a nightly window in which the wallpaper is paused, or
+//! replaced by a static image, to save power.
+//!
+//! The schedule is evaluated against local time every few seconds; only the
+//! transitions act, so resuming by hand during quiet hours sticks until the
+//! next window. The tray tooltip shows while quiet hours are active.
+
+use crate::settings::{QuietHours, QuietHoursAction};
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+const PAUSE_SOURCE: &str = "quiet hours";
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static IMAGE_SHOWN: AtomicBool = AtomicBool::new(false);
+
+/// Whether `minute` (after local midnight) falls in `[start, end)`, wrapping
+/// past midnight when `start > end`.
+pub(crate) fn in_window(minute: u16, start: u16, end: u16) -> bool {
+    if start <= end {
+        (start..end).contains(&minute)
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+fn format_minute(minute: u16) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+pub fn init(app: tauri::AppHandle) {
+    if APP_HANDLE.set(app).is_err() {
+        return;
+    }
+    std::thread::spawn(|| loop {
+        let quiet = crate::settings::get().quiet_hours;
+        let should = quiet.enabled
+            && local_minute().is_some_and(|m| in_window(m, quiet.start_minute, quiet.end_minute));
+        if should != is_active() {
+            if should {
+                begin(&quiet);
+            } else {
+                end();
+            }
+        }
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+fn begin(quiet: &QuietHours) {
+    ACTIVE.store(true, Ordering::SeqCst);
+    info!(
+        "[quiet_hours] Started ({:?} until {})",
+        quiet.action,
+        format_minute(quiet.end_minute)
+    );
+    if let Err(e) = crate::desktop_actions::auto_pause(PAUSE_SOURCE, true) {
+        warn!("[quiet_hours] Cannot pause: {}", e);
+    }
+    if quiet.action == QuietHoursAction::StaticImage {
+        if let Some(path) = &quiet.static_image {
+            let fit = crate::animated_image::ImageFit::Cover;
+            match crate::animated_image::apply(path, fit, None, None) {
+                Ok(()) => IMAGE_SHOWN.store(true, Ordering::SeqCst),
+                Err(e) => warn!("[quiet_hours] Cannot show static image: {}", e),
+            }
+        }
+    }
+    notify(
+        true,
+        Some(&format!(
+            "Quiet hours until {}",
+            format_minute(quiet.end_minute)
+        )),
+    );
+}
+
+fn end() {
+    ACTIVE.store(false, Ordering::SeqCst);
+    info!("[quiet_hours] Ended");
+    if IMAGE_SHOWN.swap(false, Ordering::SeqCst) {
+        let _ = crate::animated_image::stop(None);
+    }
+    if let Err(e) = crate::desktop_actions::auto_pause(PAUSE_SOURCE, false) {
+        warn!("[quiet_hours] Cannot resume: {}", e);
+    }
+    notify(false, None);
+}
+
+fn notify(active: bool, status: Option<&str>) {
+    use crate::events::{AppEvent, EmitAppEvent};
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    crate::tray::set_status(app, status);
+    let _ = app.emit_app_event(&AppEvent::QuietHours { active });
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn local_minute() -> Option<u16> {
+    let now = unsafe { windows::Win32::System::SystemInformation::GetLocalTime() };
+    Some(now.wHour * 60 + now.wMinute)
+}
+
+/// Local time needs the OS time zone database; quiet hours stay inactive.
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn local_minute() -> Option<u16> {
+    None
+}
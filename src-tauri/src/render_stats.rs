@@ -0,0 +1,141 @@
+//! Presented frame rate, measured at the compositor.
+//!
+//! Scenes only see their own requestAnimationFrame rate, which keeps ticking
+//! while the compositor stalls. While subscribed, DWM composition timing is
+//! sampled every second and emitted as `render-stats`. DWM reports for the
+//! whole desktop (per-window statistics are gone since Windows 8), which is
+//! what the wallpaper, composed by DWM, actually gets on screen.
+
+use log::{error, info};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use typeshare::typeshare;
+
+const INTERVAL: Duration = Duration::from_secs(1);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static SUBSCRIBED: AtomicBool = AtomicBool::new(false);
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderStats {
+    /// Frames composed and presented per second
+    pub fps: f32,
+    /// Display refresh rate in Hz
+    pub refresh_rate: f32,
+    /// Frames dropped since the previous sample
+    pub dropped_frames: u64,
+    /// Refreshes that missed their frame since the previous sample
+    pub missed_frames: u64,
+}
+
+/// Start the sampling thread (idle until `set_subscribed(true)`).
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut previous: Option<sampler::Sample> = None;
+        while RUNNING.load(Ordering::SeqCst) {
+            std::thread::sleep(INTERVAL);
+            if !SUBSCRIBED.load(Ordering::Relaxed) {
+                previous = None;
+                continue;
+            }
+            let Some(sample) = sampler::sample() else {
+                continue;
+            };
+            if let Some(prev) = previous.replace(sample) {
+                let stats = sampler::diff(&prev, &sample);
+                if let Err(e) = app.emit_app_event(&AppEvent::RenderStats(stats)) {
+                    error!("[render_stats] Failed to emit event: {}", e);
+                }
+            }
+        }
+    });
+}
+
+pub fn set_subscribed(subscribed: bool) {
+    if SUBSCRIBED.swap(subscribed, Ordering::SeqCst) != subscribed {
+        info!(
+            "[render_stats] {}",
+            if subscribed {
+                "Subscribed"
+            } else {
+                "Unsubscribed"
+            }
+        );
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod sampler {
+    use super::RenderStats;
+    use std::time::Instant;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Dwm::{DwmGetCompositionTimingInfo, DWM_TIMING_INFO};
+
+    #[derive(Clone, Copy)]
+    pub struct Sample {
+        at: Instant,
+        frames: u64,
+        dropped: u64,
+        missed: u64,
+        refresh_rate: f32,
+    }
+
+    pub fn sample() -> Option<Sample> {
+        let mut info = DWM_TIMING_INFO {
+            cbSize: std::mem::size_of::<DWM_TIMING_INFO>() as u32,
+            ..Default::default()
+        };
+        // A null window selects the desktop-wide statistics
+        unsafe { DwmGetCompositionTimingInfo(HWND::default(), &mut info) }.ok()?;
+        let rate = info.rateRefresh;
+        Some(Sample {
+            at: Instant::now(),
+            frames: info.cFrame,
+            dropped: info.cFramesDropped,
+            missed: info.cFramesMissed,
+            refresh_rate: if rate.uiDenominator == 0 {
+                0.0
+            } else {
+                rate.uiNumerator as f32 / rate.uiDenominator as f32
+            },
+        })
+    }
+
+    pub fn diff(prev: &Sample, cur: &Sample) -> RenderStats {
+        let secs = cur
+            .at
+            .duration_since(prev.at)
+            .as_secs_f32()
+            .max(f32::EPSILON);
+        RenderStats {
+            fps: cur.frames.saturating_sub(prev.frames) as f32 / secs,
+            refresh_rate: cur.refresh_rate,
+            dropped_frames: cur.dropped.saturating_sub(prev.dropped),
+            missed_frames: cur.missed.saturating_sub(prev.missed),
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod sampler {
+    use super::RenderStats;
+
+    #[derive(Clone, Copy)]
+    pub struct Sample;
+
+    pub fn sample() -> Option<Sample> {
+        None
+    }
+
+    pub fn diff(_prev: &Sample, _cur: &Sample) -> RenderStats {
+        unreachable!("no samples without DWM")
+    }
+}
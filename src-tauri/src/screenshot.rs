@@ -0,0 +1,157 @@
+//! Captures of the composited wallpaper frame.
+//!
+//! The layer window is captured with `PrintWindow(PW_RENDERFULLCONTENT)`, which
+//! includes WebView2's DirectComposition content but not the desktop icons
+//! stacked above it. Used by the screenshot command, transitions and palettes.
+
+use crate::error::{AppError, AppResult};
+use std::path::Path;
+
+/// Current wallpaper frame as PNG, downscaled to fit `max_size` if given.
+pub fn capture_png(max_size: Option<u32>) -> AppResult<Vec<u8>> {
+    let mut img = image::DynamicImage::ImageRgba8(capture_frame()?);
+    if let Some(max) = max_size.filter(|m| *m > 0) {
+        if img.width() > max || img.height() > max {
+            img = img.thumbnail(max, max);
+        }
+    }
+    let mut png = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| AppError::WindowLayer(format!("Cannot encode screenshot: {}", e)))?;
+    Ok(png.into_inner())
+}
+
+/// Write a capture to `path` (absolute, `.png`).
+pub fn save_png(path: &Path, png: &[u8]) -> AppResult<()> {
+    let is_png = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("png"));
+    if !path.is_absolute() || !is_png {
+        return Err(AppError::Validation(
+            "Screenshot path must be an absolute .png path".into(),
+        ));
+    }
+    std::fs::write(path, png)?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn capture_frame() -> AppResult<image::RgbaImage> {
+    unsafe { LayerSnapshot::take() }.map(|s| s.to_image())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn capture_frame() -> AppResult<image::RgbaImage> {
+    Err(AppError::WindowLayer(
+        "Wallpaper capture is only supported on Windows".into(),
+    ))
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) use snapshot::LayerSnapshot;
+
+#[cfg(target_os = "windows")]
+mod snapshot {
+    use crate::error::{AppError, AppResult};
+    use crate::window_layer::mouse_hook;
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleDC, CreateDIBSection, DeleteDC, DeleteObject, GetDC, ReleaseDC,
+        SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP, HDC, HGDIOBJ,
+    };
+    use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+    /// PW_RENDERFULLCONTENT: capture DirectComposition content (WebView2)
+    const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
+    /// Top-down 32bpp BGRX copy of the layer window, selected into `mem_dc`.
+    pub(crate) struct LayerSnapshot {
+        pub mem_dc: HDC,
+        bitmap: HBITMAP,
+        old: HGDIOBJ,
+        bits: *const u8,
+        /// Screen rectangle of the layer window at capture time
+        pub rect: RECT,
+    }
+
+    impl LayerSnapshot {
+        pub unsafe fn take() -> AppResult<Self> {
+            let layer = HWND(mouse_hook::get_webview_hwnd() as *mut _);
+            if layer.is_invalid() {
+                return Err(AppError::WindowLayer("Desktop layer not ready".into()));
+            }
+            let mut rect = RECT::default();
+            GetWindowRect(layer, &mut rect).map_err(|e| AppError::WindowLayer(e.to_string()))?;
+            let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+            if width <= 0 || height <= 0 {
+                return Err(AppError::WindowLayer("Desktop layer has no size".into()));
+            }
+
+            let screen_dc = GetDC(None);
+            let mem_dc = CreateCompatibleDC(screen_dc);
+            let _ = ReleaseDC(None, screen_dc);
+            let info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    biHeight: -height, // top-down
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut bits = std::ptr::null_mut();
+            let bitmap = match CreateDIBSection(mem_dc, &info, DIB_RGB_COLORS, &mut bits, None, 0) {
+                Ok(b) => b,
+                Err(e) => {
+                    let _ = DeleteDC(mem_dc);
+                    return Err(AppError::WindowLayer(e.to_string()));
+                }
+            };
+            let snapshot = LayerSnapshot {
+                mem_dc,
+                bitmap,
+                old: SelectObject(mem_dc, bitmap),
+                bits: bits as *const u8,
+                rect,
+            };
+            if !PrintWindow(layer, mem_dc, PW_RENDERFULLCONTENT).as_bool() {
+                return Err(AppError::WindowLayer("PrintWindow failed".into()));
+            }
+            Ok(snapshot)
+        }
+
+        pub fn width(&self) -> i32 {
+            self.rect.right - self.rect.left
+        }
+
+        pub fn height(&self) -> i32 {
+            self.rect.bottom - self.rect.top
+        }
+
+        /// RGBA copy; the alpha channel is forced opaque (PrintWindow leaves it unset).
+        pub fn to_image(&self) -> image::RgbaImage {
+            let (w, h) = (self.width() as u32, self.height() as u32);
+            let len = (w * h * 4) as usize;
+            let bgra = unsafe { std::slice::from_raw_parts(self.bits, len) };
+            let mut rgba = Vec::with_capacity(len);
+            for px in bgra.chunks_exact(4) {
+                rgba.extend_from_slice(&[px[2], px[1], px[0], 255]);
+            }
+            image::RgbaImage::from_raw(w, h, rgba).unwrap_or_default()
+        }
+    }
+
+    impl Drop for LayerSnapshot {
+        fn drop(&mut self) {
+            unsafe {
+                SelectObject(self.mem_dc, self.old);
+                let _ = DeleteObject(self.bitmap);
+                let _ = DeleteDC(self.mem_dc);
+            }
+        }
+    }
+}
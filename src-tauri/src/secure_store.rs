@@ -0,0 +1,59 @@
+//! Secrets in the OS credential store — Windows Credential Manager, macOS
+//! Keychain, Secret Service on Linux — instead of WebView localStorage.
+//!
+//! Entries live under one service name, keyed by a short identifier chosen by
+//! the frontend (e.g. `hub.refreshToken`).
+
+use crate::error::{AppError, AppResult};
+use keyring::Entry;
+
+const SERVICE: &str = "com.mywallpaper.desktop";
+const MAX_KEY_LEN: usize = 128;
+/// Windows stores at most 2560 bytes of UTF-16; stay well below on every OS.
+const MAX_VALUE_LEN: usize = 1200;
+
+fn entry(key: &str) -> AppResult<Entry> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid secure store key: {}",
+            key
+        )));
+    }
+    Entry::new(SERVICE, key).map_err(store_error)
+}
+
+fn store_error(e: keyring::Error) -> AppError {
+    AppError::Storage(format!("Credential store: {}", e))
+}
+
+pub fn set(key: &str, value: &str) -> AppResult<()> {
+    if value.len() > MAX_VALUE_LEN {
+        return Err(AppError::Validation(format!(
+            "Secure store values are limited to {} bytes",
+            MAX_VALUE_LEN
+        )));
+    }
+    entry(key)?.set_password(value).map_err(store_error)
+}
+
+/// `None` when no secret is stored under `key`.
+pub fn get(key: &str) -> AppResult<Option<String>> {
+    match entry(key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(store_error(e)),
+    }
+}
+
+/// Deleting a missing entry is not an error.
+pub fn delete(key: &str) -> AppResult<()> {
+    match entry(key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(store_error(e)),
+    }
+}
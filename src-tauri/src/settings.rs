@@ -0,0 +1,493 @@
+//! Backend settings — options that take effect outside the WebView (input hooks,
+//! desktop actions). Persisted in `settings.json`; frontend-only preferences stay
+//! in the frontend.
+
+use crate::desktop_actions::{DesktopAction, GestureBinding};
+use crate::dimming::Dimming;
+use crate::error::{AppError, AppResult};
+use crate::shortcuts::ShortcutBinding;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use typeshare::typeshare;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Whether the connection counts as metered (see `metered`).
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum MeteredPolicy {
+    /// Follow the OS connection cost
+    #[default]
+    Auto,
+    Metered,
+    Unmetered,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum InputBackend {
+    /// WH_MOUSE_LL hook — full icon interaction emulation
+    #[default]
+    MouseHook,
+    /// Raw Input on the dispatch window — lower latency, cannot be dropped by
+    /// hook timeouts; desktop icons are handled natively by Explorer
+    RawInput,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioDuckingMode {
+    #[default]
+    Off,
+    /// Lower wallpaper audio to `duckVolume`
+    Duck,
+    Mute,
+}
+
+/// What happens to wallpaper audio while other applications play sound
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioDuckingPolicy {
+    #[serde(default)]
+    pub mode: AudioDuckingMode,
+    /// Fraction of the wallpaper's volume kept while ducked (0-1)
+    #[serde(default = "default_duck_volume")]
+    pub duck_volume: f32,
+    /// Silence from other applications before the volume is restored
+    #[serde(default = "default_restore_after_ms")]
+    pub restore_after_ms: u32,
+}
+
+fn default_duck_volume() -> f32 {
+    AUDIO_DUCKING_DEFAULTS.duck_volume
+}
+
+fn default_restore_after_ms() -> u32 {
+    AUDIO_DUCKING_DEFAULTS.restore_after_ms
+}
+
+const AUDIO_DUCKING_DEFAULTS: AudioDuckingPolicy = AudioDuckingPolicy {
+    mode: AudioDuckingMode::Off,
+    duck_volume: 0.2,
+    restore_after_ms: 2000,
+};
+
+impl Default for AudioDuckingPolicy {
+    fn default() -> Self {
+        AUDIO_DUCKING_DEFAULTS
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum QuietHoursAction {
+    #[default]
+    Pause,
+    /// Pause and show `staticImage` natively over the wallpaper
+    StaticImage,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes after local midnight; the window wraps past midnight
+    #[serde(default = "default_quiet_start")]
+    pub start_minute: u16,
+    #[serde(default = "default_quiet_end")]
+    pub end_minute: u16,
+    #[serde(default)]
+    pub action: QuietHoursAction,
+    /// Absolute path of a PNG, GIF or WebP image
+    #[serde(default)]
+    pub static_image: Option<String>,
+}
+
+fn default_quiet_start() -> u16 {
+    QUIET_HOURS_DEFAULTS.start_minute
+}
+
+fn default_quiet_end() -> u16 {
+    QUIET_HOURS_DEFAULTS.end_minute
+}
+
+/// 22:00-07:00, off
+const QUIET_HOURS_DEFAULTS: QuietHours = QuietHours {
+    enabled: false,
+    start_minute: 22 * 60,
+    end_minute: 7 * 60,
+    action: QuietHoursAction::Pause,
+    static_image: None,
+};
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        QUIET_HOURS_DEFAULTS
+    }
+}
+
+fn validate_quiet_hours(quiet: &QuietHours) -> AppResult<()> {
+    use crate::quiet_hours::MINUTES_PER_DAY;
+    if quiet.start_minute >= MINUTES_PER_DAY
+        || quiet.end_minute >= MINUTES_PER_DAY
+        || quiet.start_minute == quiet.end_minute
+    {
+        return Err(AppError::Validation(
+            "Quiet hours need distinct start and end times within a day".into(),
+        ));
+    }
+    if quiet.action == QuietHoursAction::StaticImage {
+        let path = quiet.static_image.as_deref().map(std::path::Path::new);
+        let valid = path.is_some_and(|p| {
+            p.is_absolute()
+                && p.is_file()
+                && p.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+                    ["png", "gif", "webp"].contains(&e.to_ascii_lowercase().as_str())
+                })
+        });
+        if !valid {
+            return Err(AppError::Validation(
+                "Quiet hours image must be an existing PNG, GIF or WebP file".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Dimming applied during a nightly window (local time)
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DimmingSchedule {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes after local midnight; the window wraps past midnight
+    #[serde(default = "default_dimming_start")]
+    pub start_minute: u16,
+    #[serde(default = "default_dimming_end")]
+    pub end_minute: u16,
+    #[serde(default = "default_scheduled_dimming")]
+    pub dimming: Dimming,
+}
+
+fn default_dimming_start() -> u16 {
+    DIMMING_SCHEDULE_DEFAULTS.start_minute
+}
+
+fn default_dimming_end() -> u16 {
+    DIMMING_SCHEDULE_DEFAULTS.end_minute
+}
+
+fn default_scheduled_dimming() -> Dimming {
+    DIMMING_SCHEDULE_DEFAULTS.dimming
+}
+
+/// 21:00-07:00 at 30% and 3400 K, off
+const DIMMING_SCHEDULE_DEFAULTS: DimmingSchedule = DimmingSchedule {
+    enabled: false,
+    start_minute: 21 * 60,
+    end_minute: 7 * 60,
+    dimming: Dimming {
+        level: 0.3,
+        temperature: 3400,
+    },
+};
+
+impl Default for DimmingSchedule {
+    fn default() -> Self {
+        DIMMING_SCHEDULE_DEFAULTS
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PauseTrigger {
+    /// Whenever the process is running
+    #[default]
+    Running,
+    /// Only while one of its windows is in the foreground
+    Focused,
+}
+
+/// Pause the wallpaper while an executable runs or is focused
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseRule {
+    /// File name, e.g. `ableton live 12 suite.exe` (case-insensitive)
+    pub executable: String,
+    #[serde(default)]
+    pub trigger: PauseTrigger,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendSettings {
+    /// Action run on a double-click on empty desktop space
+    #[serde(default)]
+    pub double_click_action: DesktopAction,
+    /// Replace Explorer's empty-desktop right-click menu with ours
+    #[serde(default)]
+    pub custom_context_menu: bool,
+    /// How desktop mouse input reaches the wallpaper
+    #[serde(default)]
+    pub input_backend: InputBackend,
+    /// Recognize mouse gestures on empty desktop (emits `desktop-gesture`)
+    #[serde(default)]
+    pub desktop_gestures: bool,
+    /// Actions bound to gestures; unbound gestures are only reported to the frontend
+    #[serde(default)]
+    pub gesture_bindings: Vec<GestureBinding>,
+    /// Serve the local REST API on 127.0.0.1
+    #[serde(default)]
+    pub local_api_enabled: bool,
+    #[serde(default = "default_local_api_port")]
+    pub local_api_port: u16,
+    /// Global keyboard shortcuts bound to actions
+    #[serde(default)]
+    pub shortcuts: Vec<ShortcutBinding>,
+    /// Origins the webview may navigate to besides the app's own
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Keep the wallpaper out from under the taskbar (monitor work area)
+    #[serde(default)]
+    pub fit_to_work_area: bool,
+    /// Follow the wallpaper with the Windows accent color
+    #[serde(default)]
+    pub sync_accent_color: bool,
+    /// Duck or mute wallpaper audio while other applications play sound
+    #[serde(default)]
+    pub audio_ducking: AudioDuckingPolicy,
+    /// Wallpaper audio level (0-1), relative to its mixer volume
+    #[serde(default = "default_wallpaper_volume")]
+    pub wallpaper_volume: f32,
+    /// Nightly pause (or static image) window
+    #[serde(default)]
+    pub quiet_hours: QuietHours,
+    /// Executables that pause the wallpaper
+    #[serde(default)]
+    pub pause_rules: Vec<PauseRule>,
+    /// Scheduled dimming / warm tint over the wallpaper
+    #[serde(default)]
+    pub dimming_schedule: DimmingSchedule,
+    /// Absolute folder for the webview's user data (cookies, caches, site
+    /// storage); None keeps the default. Applied on the next launch.
+    #[serde(default)]
+    pub browser_data_dir: Option<String>,
+    /// Combined download rate cap in KiB/s; 0 = unlimited
+    #[serde(default)]
+    pub download_bandwidth_limit_kbps: u32,
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: u8,
+    /// Leave the last frame as the OS wallpaper when the app exits
+    #[serde(default)]
+    pub static_wallpaper_on_exit: bool,
+    /// Hold a static frame while the session is shown over Remote Desktop
+    #[serde(default = "default_pause_on_remote_session")]
+    pub pause_on_remote_session: bool,
+    /// Pause animation while the OS asks for reduced motion
+    #[serde(default)]
+    pub pause_on_reduce_motion: bool,
+    /// Override metered connection detection
+    #[serde(default)]
+    pub metered_connection: MeteredPolicy,
+    /// Used when the OS location service is off or has no fix
+    #[serde(default)]
+    pub manual_location: Option<crate::location::ManualLocation>,
+    /// Seconds without input before `user-idle` is emitted; 0 = never
+    #[serde(default = "default_idle_threshold_secs")]
+    pub idle_threshold_secs: u32,
+    /// Absolute folder whose new images, videos and packs are imported into
+    /// the library; None turns the watcher off
+    #[serde(default)]
+    pub watched_folder: Option<String>,
+    /// Apply wallpapers as soon as they are imported from the watched folder
+    #[serde(default)]
+    pub watched_folder_auto_apply: bool,
+}
+
+fn default_local_api_port() -> u16 {
+    crate::local_api::DEFAULT_PORT
+}
+
+fn default_max_concurrent_downloads() -> u8 {
+    crate::downloads::DEFAULT_MAX_CONCURRENT
+}
+
+fn default_idle_threshold_secs() -> u32 {
+    crate::idle::DEFAULT_THRESHOLD_SECS
+}
+
+fn default_wallpaper_volume() -> f32 {
+    DEFAULTS.wallpaper_volume
+}
+
+fn default_pause_on_remote_session() -> bool {
+    DEFAULTS.pause_on_remote_session
+}
+
+const DEFAULTS: BackendSettings = BackendSettings {
+    double_click_action: DesktopAction::None,
+    custom_context_menu: false,
+    input_backend: InputBackend::MouseHook,
+    desktop_gestures: false,
+    gesture_bindings: Vec::new(),
+    local_api_enabled: false,
+    local_api_port: crate::local_api::DEFAULT_PORT,
+    shortcuts: Vec::new(),
+    allowed_origins: Vec::new(),
+    fit_to_work_area: false,
+    sync_accent_color: false,
+    audio_ducking: AUDIO_DUCKING_DEFAULTS,
+    wallpaper_volume: 1.0,
+    quiet_hours: QUIET_HOURS_DEFAULTS,
+    pause_rules: Vec::new(),
+    dimming_schedule: DIMMING_SCHEDULE_DEFAULTS,
+    browser_data_dir: None,
+    download_bandwidth_limit_kbps: 0,
+    max_concurrent_downloads: crate::downloads::DEFAULT_MAX_CONCURRENT,
+    static_wallpaper_on_exit: false,
+    pause_on_remote_session: true,
+    pause_on_reduce_motion: false,
+    metered_connection: MeteredPolicy::Auto,
+    manual_location: None,
+    idle_threshold_secs: crate::idle::DEFAULT_THRESHOLD_SECS,
+    watched_folder: None,
+    watched_folder_auto_apply: false,
+};
+
+impl Default for BackendSettings {
+    fn default() -> Self {
+        DEFAULTS
+    }
+}
+
+static SETTINGS: Mutex<BackendSettings> = Mutex::new(DEFAULTS);
+
+/// Push settings into the subsystems that cache them.
+fn apply(settings: &BackendSettings) {
+    #[cfg(target_os = "windows")]
+    {
+        use crate::window_layer::mouse_hook;
+        mouse_hook::set_desktop_double_click_enabled(
+            settings.double_click_action != DesktopAction::None,
+        );
+        mouse_hook::set_custom_context_menu_enabled(settings.custom_context_menu);
+        mouse_hook::set_raw_input_backend(settings.input_backend == InputBackend::RawInput);
+        mouse_hook::set_desktop_gestures_enabled(settings.desktop_gestures);
+    }
+    crate::local_api::configure(settings.local_api_enabled, settings.local_api_port);
+    crate::shortcuts::apply(&settings.shortcuts);
+    crate::navigation::set_extra_origins(&settings.allowed_origins);
+    crate::window_layer::set_fit_to_work_area(settings.fit_to_work_area);
+    crate::window_layer::set_pause_on_remote_session(settings.pause_on_remote_session);
+    crate::accessibility::configure(settings.pause_on_reduce_motion);
+    crate::metered::configure(settings.metered_connection);
+    crate::location::invalidate();
+    crate::idle::configure(settings.idle_threshold_secs);
+    crate::watched_folder::configure(
+        settings.watched_folder.as_deref(),
+        settings.watched_folder_auto_apply,
+    );
+    crate::audio_ducking::configure(settings.audio_ducking);
+    crate::wallpaper_volume::configure(settings.wallpaper_volume);
+    crate::downloads::configure(
+        settings.download_bandwidth_limit_kbps,
+        settings.max_concurrent_downloads,
+    );
+    crate::exit_wallpaper::configure(settings.static_wallpaper_on_exit);
+}
+
+pub fn get() -> BackendSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+pub fn update(mut settings: BackendSettings) -> AppResult<()> {
+    crate::desktop_actions::validate(&settings.double_click_action)?;
+    if settings.local_api_port < 1024 {
+        return Err(AppError::Validation(
+            "Local API port must be 1024 or higher".into(),
+        ));
+    }
+    for binding in &settings.gesture_bindings {
+        crate::desktop_actions::validate(&binding.action)?;
+    }
+    crate::shortcuts::validate(&settings.shortcuts)?;
+    validate_quiet_hours(&settings.quiet_hours)?;
+    if let Some(manual) = &settings.manual_location {
+        crate::location::validate(manual)?;
+    }
+    if settings.idle_threshold_secs > crate::idle::MAX_THRESHOLD_SECS {
+        return Err(AppError::Validation(
+            "Idle threshold must be at most 24 hours".into(),
+        ));
+    }
+    settings.pause_rules = crate::pause_rules::normalize(&settings.pause_rules)?;
+    let schedule = &settings.dimming_schedule;
+    if schedule.start_minute >= crate::quiet_hours::MINUTES_PER_DAY
+        || schedule.end_minute >= crate::quiet_hours::MINUTES_PER_DAY
+        || schedule.start_minute == schedule.end_minute
+    {
+        return Err(AppError::Validation(
+            "Dimming schedule needs distinct start and end times within a day".into(),
+        ));
+    }
+    crate::dimming::validate(&schedule.dimming)?;
+    let ducking = &settings.audio_ducking;
+    if !(0.0..=1.0).contains(&ducking.duck_volume) || ducking.restore_after_ms > 60_000 {
+        return Err(AppError::Validation(
+            "Audio ducking volume must be 0-1 and the restore delay at most 60s".into(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&settings.wallpaper_volume) {
+        return Err(AppError::Validation(
+            "Wallpaper volume must be between 0 and 1".into(),
+        ));
+    }
+    settings.allowed_origins = crate::navigation::normalize_origins(&settings.allowed_origins)?;
+    settings.browser_data_dir =
+        crate::browser_data::normalize_dir(settings.browser_data_dir.as_deref())?;
+    settings.watched_folder =
+        crate::watched_folder::normalize_dir(settings.watched_folder.as_deref())?;
+    if !(1..=crate::downloads::MAX_CONCURRENT_LIMIT).contains(&settings.max_concurrent_downloads) {
+        return Err(AppError::Validation(format!(
+            "Concurrent downloads must be 1-{}",
+            crate::downloads::MAX_CONCURRENT_LIMIT
+        )));
+    }
+    let mut current = SETTINGS
+        .lock()
+        .map_err(|_| AppError::Storage("Settings store poisoned".into()))?;
+    crate::storage::save_json(SETTINGS_FILE, &settings)?;
+    apply(&settings);
+    *current = settings;
+    drop(current);
+    // Reads the schedule back through `get()`
+    if let Err(e) = crate::dimming::refresh() {
+        log::warn!("[settings] Dimming not applied: {}", e);
+    }
+    Ok(())
+}
+
+/// (Re)load settings from disk and apply them.
+pub fn init() {
+    let loaded: BackendSettings = crate::storage::load_json(SETTINGS_FILE);
+    apply(&loaded);
+    match SETTINGS.lock() {
+        Ok(mut s) => {
+            *s = loaded;
+            info!("[settings] Loaded backend settings");
+        }
+        Err(_) => error!("[settings] Settings store poisoned"),
+    }
+}
@@ -0,0 +1,187 @@
+//! User-configurable global shortcuts bound to desktop actions.
+//!
+//! Bindings live in backend settings (`shortcuts`); `apply` re-registers them
+//! with the global-shortcut plugin whenever settings change.
+
+use crate::desktop_actions::DesktopAction;
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use typeshare::typeshare;
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+/// Shortcuts currently registered by us, to unregister on the next apply.
+static REGISTERED: Mutex<Vec<Shortcut>> = Mutex::new(Vec::new());
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    /// Accelerator such as `Ctrl+Alt+P`
+    pub accelerator: String,
+    pub action: DesktopAction,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutInfo {
+    pub accelerator: String,
+    pub action: DesktopAction,
+    /// False when the OS refused the shortcut (held by another application)
+    pub registered: bool,
+}
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+pub fn parse(accelerator: &str) -> AppResult<Shortcut> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| AppError::Validation(format!("Invalid shortcut '{}': {}", accelerator, e)))
+}
+
+/// Reject unparsable accelerators, unsupported actions and duplicates.
+pub fn validate(bindings: &[ShortcutBinding]) -> AppResult<()> {
+    let mut seen: Vec<(Shortcut, &str)> = Vec::with_capacity(bindings.len());
+    for binding in bindings {
+        if binding.action == DesktopAction::None {
+            return Err(AppError::Validation(format!(
+                "Shortcut '{}' has no action",
+                binding.accelerator
+            )));
+        }
+        crate::desktop_actions::validate(&binding.action)?;
+        let shortcut = parse(&binding.accelerator)?;
+        if let Some((_, other)) = seen.iter().find(|(s, _)| *s == shortcut) {
+            return Err(AppError::Validation(format!(
+                "Shortcut '{}' conflicts with '{}'",
+                binding.accelerator, other
+            )));
+        }
+        seen.push((shortcut, &binding.accelerator));
+    }
+    Ok(())
+}
+
+fn on_shortcut(shortcut: Shortcut) {
+    // Off the event loop: settings updates block on the main thread while
+    // holding the settings lock, and actions may block on window calls
+    std::thread::spawn(move || {
+        let Some(action) = crate::settings::get()
+            .shortcuts
+            .into_iter()
+            .find(|b| parse(&b.accelerator).is_ok_and(|s| s == shortcut))
+            .map(|b| b.action)
+        else {
+            return;
+        };
+        info!("[shortcuts] {} -> {:?}", shortcut.into_string(), action);
+        if let Err(e) = crate::desktop_actions::run(&action) {
+            warn!("[shortcuts] Shortcut action failed: {}", e);
+        }
+    });
+}
+
+/// Replace our registered shortcuts with `bindings` (already validated).
+pub fn apply(bindings: &[ShortcutBinding]) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let Ok(mut registered) = REGISTERED.lock() else {
+        return;
+    };
+    let global = app.global_shortcut();
+    for shortcut in registered.drain(..) {
+        let _ = global.unregister(shortcut);
+    }
+    for binding in bindings {
+        let Ok(shortcut) = parse(&binding.accelerator) else {
+            continue;
+        };
+        let result = global.on_shortcut(shortcut, |_app, shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                on_shortcut(*shortcut);
+            }
+        });
+        match result {
+            Ok(()) => registered.push(shortcut),
+            Err(e) => warn!(
+                "[shortcuts] Cannot register '{}': {}",
+                binding.accelerator, e
+            ),
+        }
+    }
+    info!("[shortcuts] {} shortcut(s) registered", registered.len());
+}
+
+fn is_registered(shortcut: &Shortcut) -> bool {
+    REGISTERED
+        .lock()
+        .map(|r| r.contains(shortcut))
+        .unwrap_or(false)
+}
+
+pub fn list() -> Vec<ShortcutInfo> {
+    crate::settings::get()
+        .shortcuts
+        .into_iter()
+        .map(|b| ShortcutInfo {
+            registered: parse(&b.accelerator).is_ok_and(|s| is_registered(&s)),
+            accelerator: b.accelerator,
+            action: b.action,
+        })
+        .collect()
+}
+
+/// Bind `accelerator` to `action`, replacing the action's previous shortcut.
+/// Fails when the accelerator is bound to another action or held by another
+/// application; settings are left unchanged then.
+pub fn register(accelerator: &str, action: DesktopAction) -> AppResult<ShortcutInfo> {
+    let shortcut = parse(accelerator)?;
+    let mut settings = crate::settings::get();
+    if let Some(other) = settings
+        .shortcuts
+        .iter()
+        .find(|b| b.action != action && parse(&b.accelerator).is_ok_and(|s| s == shortcut))
+    {
+        return Err(AppError::Validation(format!(
+            "Shortcut '{}' is already bound to {:?}",
+            accelerator, other.action
+        )));
+    }
+    let previous = settings.clone();
+    settings.shortcuts.retain(|b| b.action != action);
+    settings.shortcuts.push(ShortcutBinding {
+        accelerator: shortcut.into_string(),
+        action: action.clone(),
+    });
+    crate::settings::update(settings)?;
+
+    // The OS refuses shortcuts held by other applications: keep the old binding
+    if !is_registered(&shortcut) {
+        crate::settings::update(previous)?;
+        return Err(AppError::Validation(format!(
+            "Shortcut '{}' is already in use by another application",
+            accelerator
+        )));
+    }
+    Ok(ShortcutInfo {
+        accelerator: shortcut.into_string(),
+        action,
+        registered: true,
+    })
+}
+
+/// Remove the binding for `accelerator`, if any.
+pub fn unregister(accelerator: &str) -> AppResult<()> {
+    let shortcut = parse(accelerator)?;
+    let mut settings = crate::settings::get();
+    settings
+        .shortcuts
+        .retain(|b| !parse(&b.accelerator).is_ok_and(|s| s == shortcut));
+    crate::settings::update(settings)
+}
@@ -0,0 +1,154 @@
+//! Shutdown sequence shared by every exit path: tray Quit, `ExitRequested` /
+//! `Exit`, Windows logoff/shutdown (`WM_ENDSESSION`), `taskkill` (`WM_CLOSE`)
+//! and Ctrl+C when started from a console.
+//!
+//! `run` executes once: it leaves a snapshot as the OS wallpaper (when
+//! enabled), restores the desktop icons and removes the hooks, detaches the
+//! layer window from WorkerW, stops native players and restores ducked audio,
+//! then runs the hooks registered with `on_exit` in order.
+
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+type Hook = Box<dyn FnOnce() + Send>;
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+static DONE: AtomicBool = AtomicBool::new(false);
+static HOOKS: Mutex<Vec<(&'static str, Hook)>> = Mutex::new(Vec::new());
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+    #[cfg(target_os = "windows")]
+    session::start();
+}
+
+/// Register work to do on exit, after the built-in steps.
+pub fn on_exit(name: &'static str, hook: impl FnOnce() + Send + 'static) {
+    if let Ok(mut hooks) = HOOKS.lock() {
+        hooks.push((name, Box::new(hook)));
+    }
+}
+
+/// Run the shutdown sequence; later calls return immediately.
+pub fn run(reason: &str) {
+    if DONE.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("[shutdown] {}", reason);
+    // Needs the layer still attached to capture it
+    crate::exit_wallpaper::on_shutdown();
+    crate::window_layer::restore_desktop_icons_and_unhook();
+    crate::window_layer::detach_layer();
+    let _ = crate::native_video::stop(None);
+    let _ = crate::animated_image::stop(None);
+    crate::audio_ducking::shutdown();
+
+    let hooks = HOOKS
+        .lock()
+        .map(|mut h| std::mem::take(&mut *h))
+        .unwrap_or_default();
+    for (name, hook) in hooks {
+        info!("[shutdown] {}", name);
+        hook();
+    }
+}
+
+/// Run the shutdown sequence and exit the app.
+pub fn exit(app: &tauri::AppHandle, code: i32) {
+    run("Exit requested");
+    app.exit(code);
+}
+
+/// Exit through the app handle when there is one (event-loop driven paths).
+fn request_exit(reason: &str) {
+    run(reason);
+    match APP_HANDLE.get() {
+        Some(app) => app.exit(0),
+        None => std::process::exit(0),
+    }
+}
+
+// ============================================================================
+// Windows: session end and console signals
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+mod session {
+    use log::error;
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::Console::{SetConsoleCtrlHandler, CTRL_C_EVENT};
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    /// Hidden top-level window: message-only windows miss session broadcasts.
+    pub fn start() {
+        unsafe {
+            let _ = SetConsoleCtrlHandler(Some(console_handler), true);
+        }
+        std::thread::spawn(|| unsafe {
+            let cls = windows::core::w!("MWP_ShutdownWatch");
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                lpszClassName: cls,
+                ..Default::default()
+            };
+            let _ = RegisterClassW(&wc);
+            if CreateWindowExW(
+                WS_EX_TOOLWINDOW,
+                cls,
+                windows::core::w!(""),
+                WINDOW_STYLE(0),
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                None,
+                None,
+            )
+            .is_err()
+            {
+                error!("[shutdown] Failed to create session watch window");
+                return;
+            }
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).into() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+    }
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        match msg {
+            WM_QUERYENDSESSION => LRESULT(1),
+            // The process may be killed as soon as this returns
+            WM_ENDSESSION if wparam.0 != 0 => {
+                super::run("Session ending");
+                crate::crash::mark_clean_exit();
+                LRESULT(0)
+            }
+            WM_CLOSE => {
+                super::request_exit("Close requested");
+                LRESULT(0)
+            }
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+
+    unsafe extern "system" fn console_handler(ctrl_type: u32) -> BOOL {
+        let reason = if ctrl_type == CTRL_C_EVENT {
+            "Ctrl+C"
+        } else {
+            "Console closed"
+        };
+        super::request_exit(reason);
+        BOOL(1)
+    }
+}
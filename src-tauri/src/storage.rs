@@ -0,0 +1,67 @@
+//! JSON persistence in the app config directory.
+//!
+//! Each subsystem owns one file (e.g. `profiles.json`). Writes go through a
+//! temp file + rename so a crash mid-write never leaves a truncated file.
+
+use crate::error::{AppError, AppResult};
+use log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Resolve and create the app config directory. Must run once during setup.
+pub fn init(app: &tauri::AppHandle) -> AppResult<()> {
+    use tauri::Manager;
+    let dir = app.path().app_config_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let _ = CONFIG_DIR.set(dir);
+    Ok(())
+}
+
+/// Absolute path of a file inside the config directory.
+pub fn config_path(file_name: &str) -> AppResult<PathBuf> {
+    CONFIG_DIR
+        .get()
+        .map(|d| d.join(file_name))
+        .ok_or_else(|| AppError::Storage("Config directory not initialized".into()))
+}
+
+/// Load a JSON file, falling back to `T::default()` when missing or corrupt.
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    let Ok(path) = config_path(file_name) else {
+        return T::default();
+    };
+    match std::fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            warn!("[storage] Ignoring corrupt {}: {}", file_name, e);
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+/// Atomically write a value as pretty JSON.
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> AppResult<()> {
+    let path = config_path(file_name)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(value)?)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Names of every JSON file in the config directory.
+pub fn list_json_files() -> AppResult<Vec<String>> {
+    let dir = CONFIG_DIR
+        .get()
+        .ok_or_else(|| AppError::Storage("Config directory not initialized".into()))?;
+    let mut names: Vec<String> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|n| n.ends_with(".json"))
+        .collect();
+    names.sort();
+    Ok(names)
+}
@@ -0,0 +1,335 @@
+//! Sun position, day phases and moon phase for day/night scenes.
+//!
+//! Computed from the coarse location (`location::get_location`) with the
+//! sunrise equation and low-precision solar/lunar formulas, good to about a
+//! minute, which is plenty for switching scenes. Dates are in local mean
+//! solar time at that location, so no time zone database is needed; all
+//! times are Unix timestamps (seconds).
+//!
+//! After the first `get_sun_info` a watcher re-evaluates the sun every
+//! minute and emits `sun-phase-changed` on transitions.
+
+use crate::error::{AppError, AppResult};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use typeshare::typeshare;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Retry delay while no location is available
+const LOCATION_RETRY: Duration = Duration::from_secs(5 * 60);
+const SECONDS_PER_DAY: f64 = 86_400.0;
+const J2000: f64 = 2_451_545.0;
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+/// Days from 1970-01-01 to 2000-01-01
+const J2000_UNIX_DAY: i64 = 10_957;
+const OBLIQUITY: f64 = 23.4397;
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+/// A new moon, as a Julian date
+const NEW_MOON_JD: f64 = 2_451_550.1;
+/// Sun altitudes (degrees) of the day's events; sunrise includes refraction
+/// and the solar disc
+const SUNRISE_ALTITUDE: f64 = -0.833;
+const CIVIL_TWILIGHT_ALTITUDE: f64 = -6.0;
+const GOLDEN_HOUR_ALTITUDE: f64 = 6.0;
+
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SunPhase {
+    /// Sun more than 6° below the horizon
+    Night,
+    /// Morning civil twilight
+    Dawn,
+    /// Sun less than 6° above the horizon, morning or evening
+    GoldenHour,
+    Day,
+    /// Evening civil twilight
+    Dusk,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MoonPhase {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoonInfo {
+    pub phase: MoonPhase,
+    /// Position in the lunar cycle: 0 = new, 0.5 = full
+    pub cycle: f64,
+    /// Illuminated fraction of the disc (0-1)
+    pub illumination: f64,
+}
+
+/// Events of one day. Events the sun never reaches that day (polar day or
+/// night, high-latitude summer twilight) are None.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SunInfo {
+    /// "YYYY-MM-DD", local mean solar date at the location
+    pub date: String,
+    /// Start of morning civil twilight
+    pub dawn: Option<u64>,
+    pub sunrise: Option<u64>,
+    /// End of the morning golden hour
+    pub golden_hour_end: Option<u64>,
+    pub solar_noon: u64,
+    /// Start of the evening golden hour
+    pub golden_hour_start: Option<u64>,
+    pub sunset: Option<u64>,
+    /// End of evening civil twilight
+    pub dusk: Option<u64>,
+    /// Sun elevation at solar noon in degrees; negative all day in polar night
+    pub noon_elevation: f64,
+    /// Current sun elevation in degrees (today only)
+    pub elevation: Option<f64>,
+    /// Current phase (today only)
+    pub phase: Option<SunPhase>,
+    /// Moon at solar noon
+    pub moon: MoonInfo,
+}
+
+// ============================================================================
+// Calendar
+// ============================================================================
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = (if z >= 0 { z } else { z - 146_096 }) / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn parse_date(date: &str) -> AppResult<i64> {
+    let invalid = || AppError::Validation(format!("Expected a YYYY-MM-DD date, got '{}'", date));
+    let mut parts = date.trim().splitn(3, '-');
+    let mut next = || parts.next().and_then(|p| p.parse::<i64>().ok());
+    let (Some(year), Some(month), Some(day)) = (next(), next(), next()) else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    let days = days_from_civil(year, month as u32, day as u32);
+    // Rejects 02-30 and friends
+    if civil_from_days(days) != (year, month as u32, day as u32) {
+        return Err(invalid());
+    }
+    Ok(days)
+}
+
+fn unix_now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+fn jd_to_unix(jd: f64) -> u64 {
+    ((jd - UNIX_EPOCH_JD) * SECONDS_PER_DAY).round().max(0.0) as u64
+}
+
+// ============================================================================
+// Astronomy
+// ============================================================================
+
+/// Mean anomaly and ecliptic longitude (radians) `d` days after J2000.
+fn solar_longitude(d: f64) -> (f64, f64) {
+    let m = (357.5291 + 0.985_600_28 * d).rem_euclid(360.0).to_radians();
+    let center = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let lambda = (m.to_degrees() + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    (m, lambda)
+}
+
+fn declination(lambda: f64) -> f64 {
+    (lambda.sin() * OBLIQUITY.to_radians().sin()).asin()
+}
+
+/// Solar transit (Julian date) and declination (radians) of a day.
+fn solar_day(day: i64, longitude: f64) -> (f64, f64) {
+    let j_star = (day - J2000_UNIX_DAY) as f64 - longitude / 360.0;
+    let (m, lambda) = solar_longitude(j_star);
+    let transit = J2000 + j_star + 0.0053 * m.sin() - 0.0069 * (2.0 * lambda).sin();
+    (transit, declination(lambda))
+}
+
+/// Julian dates at which the sun passes `altitude` before and after transit.
+fn crossings(transit: f64, decl: f64, latitude: f64, altitude: f64) -> Option<(f64, f64)> {
+    let lat = latitude.to_radians();
+    let cos_w = (altitude.to_radians().sin() - lat.sin() * decl.sin()) / (lat.cos() * decl.cos());
+    if !(-1.0..=1.0).contains(&cos_w) {
+        return None;
+    }
+    let w = cos_w.acos().to_degrees() / 360.0;
+    Some((transit - w, transit + w))
+}
+
+/// Sun elevation and hour angle (degrees; negative before noon) at `unix`.
+fn sun_position(unix: f64, latitude: f64, longitude: f64) -> (f64, f64) {
+    let d = unix / SECONDS_PER_DAY + UNIX_EPOCH_JD - J2000;
+    let (_, lambda) = solar_longitude(d);
+    let decl = declination(lambda);
+    let eps = OBLIQUITY.to_radians();
+    let ra = (eps.cos() * lambda.sin()).atan2(lambda.cos()).to_degrees();
+    let sidereal = 280.460_618_37 + 360.985_647_366_29 * d;
+    let hour_angle = (sidereal + longitude - ra + 180.0).rem_euclid(360.0) - 180.0;
+    let lat = latitude.to_radians();
+    let elevation = (lat.sin() * decl.sin()
+        + lat.cos() * decl.cos() * hour_angle.to_radians().cos())
+    .asin()
+    .to_degrees();
+    (elevation, hour_angle)
+}
+
+fn phase_of(elevation: f64, hour_angle: f64) -> SunPhase {
+    if elevation >= GOLDEN_HOUR_ALTITUDE {
+        SunPhase::Day
+    } else if elevation >= SUNRISE_ALTITUDE {
+        SunPhase::GoldenHour
+    } else if elevation >= CIVIL_TWILIGHT_ALTITUDE {
+        if hour_angle < 0.0 {
+            SunPhase::Dawn
+        } else {
+            SunPhase::Dusk
+        }
+    } else {
+        SunPhase::Night
+    }
+}
+
+fn moon_at(jd: f64) -> MoonInfo {
+    use MoonPhase::*;
+    const PHASES: [MoonPhase; 8] = [
+        New,
+        WaxingCrescent,
+        FirstQuarter,
+        WaxingGibbous,
+        Full,
+        WaningGibbous,
+        LastQuarter,
+        WaningCrescent,
+    ];
+    let cycle = ((jd - NEW_MOON_JD) / SYNODIC_MONTH).rem_euclid(1.0);
+    MoonInfo {
+        phase: PHASES[(cycle * 8.0).round() as usize % 8],
+        cycle,
+        illumination: (1.0 - (std::f64::consts::TAU * cycle).cos()) / 2.0,
+    }
+}
+
+/// Local mean solar day at `longitude` containing `unix`.
+fn local_day(unix: f64, longitude: f64) -> i64 {
+    ((unix + longitude / 360.0 * SECONDS_PER_DAY) / SECONDS_PER_DAY).floor() as i64
+}
+
+/// Sun and moon for `date` ("YYYY-MM-DD"; today when None) at a location.
+pub fn compute(date: Option<&str>, latitude: f64, longitude: f64) -> AppResult<SunInfo> {
+    let now = unix_now();
+    let today = local_day(now, longitude);
+    let day = match date {
+        Some(date) => parse_date(date)?,
+        None => today,
+    };
+    let (transit, decl) = solar_day(day, longitude);
+    let at = |altitude| crossings(transit, decl, latitude, altitude);
+    let (dawn, dusk) = at(CIVIL_TWILIGHT_ALTITUDE).unzip();
+    let (sunrise, sunset) = at(SUNRISE_ALTITUDE).unzip();
+    let (golden_hour_end, golden_hour_start) = at(GOLDEN_HOUR_ALTITUDE).unzip();
+    let current = (day == today).then(|| sun_position(now, latitude, longitude));
+    let (year, month, dom) = civil_from_days(day);
+    Ok(SunInfo {
+        date: format!("{:04}-{:02}-{:02}", year, month, dom),
+        dawn: dawn.map(jd_to_unix),
+        sunrise: sunrise.map(jd_to_unix),
+        golden_hour_end: golden_hour_end.map(jd_to_unix),
+        solar_noon: jd_to_unix(transit),
+        golden_hour_start: golden_hour_start.map(jd_to_unix),
+        sunset: sunset.map(jd_to_unix),
+        dusk: dusk.map(jd_to_unix),
+        noon_elevation: 90.0 - (latitude - decl.to_degrees()).abs(),
+        elevation: current.map(|(elevation, _)| elevation),
+        phase: current.map(|(elevation, hour_angle)| phase_of(elevation, hour_angle)),
+        moon: moon_at(transit),
+    })
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Sun and moon for `date` at the current location. Starts the
+/// `sun-phase-changed` watcher.
+pub fn get_sun_info(app: &tauri::AppHandle, date: Option<&str>) -> AppResult<SunInfo> {
+    let location = crate::location::get_location()?;
+    let info = compute(date, location.latitude, location.longitude)?;
+    start(app.clone());
+    Ok(info)
+}
+
+fn start(app: tauri::AppHandle) {
+    if WATCHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut last: Option<SunPhase> = None;
+        loop {
+            let location = match crate::location::get_location() {
+                Ok(location) => location,
+                Err(e) => {
+                    warn!("[sun] No location, retrying later: {}", e);
+                    std::thread::sleep(LOCATION_RETRY);
+                    continue;
+                }
+            };
+            let (elevation, hour_angle) =
+                sun_position(unix_now(), location.latitude, location.longitude);
+            let phase = phase_of(elevation, hour_angle);
+            if last.is_some_and(|last| last != phase) {
+                info!("[sun] Phase changed to {:?}", phase);
+                if let Err(e) = app.emit_app_event(&AppEvent::SunPhaseChanged { phase, elevation })
+                {
+                    error!("[sun] Failed to emit event: {}", e);
+                }
+            }
+            last = Some(phase);
+            std::thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
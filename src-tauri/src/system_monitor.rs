@@ -116,7 +116,7 @@ pub struct GpuInfo {
 }
 
 #[typeshare]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DisplayInfo {
     /// Display name/model
@@ -133,10 +133,16 @@ pub struct DisplayInfo {
     pub scale_factor: Option<f32>,
     /// Whether this is the primary display
     pub primary: bool,
+    /// Windows HDR ("Use HDR") is on for this display
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hdr: Option<bool>,
+    /// Brightness of SDR white in nits (HDR displays; 80 = reference white)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sdr_white_level: Option<f32>,
 }
 
 #[typeshare]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioInfo {
     /// System volume level (0.0 - 1.0)
@@ -204,7 +210,7 @@ pub fn collect_system_data(mask: u32) -> SystemData {
 }
 
 /// Collect battery info. Returns None on desktops without a battery.
-fn collect_battery_info() -> Option<BatteryInfo> {
+pub fn collect_battery_info() -> Option<BatteryInfo> {
     let manager = battery::Manager::new().ok()?;
     let mut batteries = manager.batteries().ok()?;
     let batt = batteries.next()?.ok()?;
@@ -269,7 +275,7 @@ fn collect_gpu_info() -> Option<GpuInfo> {
 // ============================================================================
 
 #[cfg(target_os = "windows")]
-fn collect_display_info() -> Option<Vec<DisplayInfo>> {
+pub fn collect_display_info() -> Option<Vec<DisplayInfo>> {
     use std::mem::{size_of, zeroed};
     use windows::core::PCWSTR;
     use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
@@ -340,6 +346,8 @@ fn collect_display_info() -> Option<Vec<DisplayInfo>> {
             refresh_rate,
             scale_factor: Some(dpi_x as f32 / 96.0),
             primary,
+            hdr: None,
+            sdr_white_level: None,
         });
 
         BOOL(1)
@@ -354,24 +362,157 @@ fn collect_display_info() -> Option<Vec<DisplayInfo>> {
             LPARAM(&mut acc as *mut _ as isize),
         );
         if acc.0.is_empty() {
-            None
-        } else {
-            Some(acc.0)
+            return None;
+        }
+        let hdr = collect_hdr_outputs();
+        let white_levels = collect_sdr_white_levels();
+        for display in &mut acc.0 {
+            display.hdr = hdr
+                .iter()
+                .find(|(name, _)| *name == display.name)
+                .map(|(_, on)| *on);
+            display.sdr_white_level = white_levels
+                .iter()
+                .find(|(name, _)| *name == display.name)
+                .map(|(_, nits)| *nits)
+                .filter(|_| display.hdr == Some(true));
+        }
+        Some(acc.0)
+    }
+}
+
+/// Trims a fixed-size UTF-16 buffer at its NUL terminator.
+#[cfg(target_os = "windows")]
+fn wide_to_string(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
+/// HDR state per DXGI output, keyed by GDI device name (`\\.\DISPLAY1`).
+#[cfg(target_os = "windows")]
+fn collect_hdr_outputs() -> Vec<(String, bool)> {
+    use windows::core::Interface;
+    use windows::Win32::Graphics::Dxgi::Common::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020;
+    use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1, IDXGIOutput6};
+
+    let mut outputs = Vec::new();
+    unsafe {
+        let Ok(factory) = CreateDXGIFactory1::<IDXGIFactory1>() else {
+            return outputs;
+        };
+        let mut a = 0;
+        while let Ok(adapter) = factory.EnumAdapters1(a) {
+            let mut o = 0;
+            while let Ok(output) = adapter.EnumOutputs(o) {
+                // IDXGIOutput6 needs Windows 10 1703+
+                if let Ok(desc) = output.cast::<IDXGIOutput6>().and_then(|o6| o6.GetDesc1()) {
+                    outputs.push((
+                        wide_to_string(&desc.DeviceName),
+                        desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020,
+                    ));
+                }
+                o += 1;
+            }
+            a += 1;
+        }
+    }
+    outputs
+}
+
+/// SDR white level (nits) per active display path, keyed by GDI device name.
+#[cfg(target_os = "windows")]
+fn collect_sdr_white_levels() -> Vec<(String, f32)> {
+    use std::mem::size_of;
+    use windows::Win32::Devices::Display::{
+        DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+        DISPLAYCONFIG_DEVICE_INFO_GET_SDR_WHITE_LEVEL, DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+        DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO, DISPLAYCONFIG_SDR_WHITE_LEVEL,
+        DISPLAYCONFIG_SOURCE_DEVICE_NAME, QDC_ONLY_ACTIVE_PATHS,
+    };
+
+    let mut levels = Vec::new();
+    unsafe {
+        let (mut path_count, mut mode_count) = (0u32, 0u32);
+        if GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count).0
+            != 0
+        {
+            return levels;
+        }
+        let mut paths = vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
+        let mut modes = vec![DISPLAYCONFIG_MODE_INFO::default(); mode_count as usize];
+        if QueryDisplayConfig(
+            QDC_ONLY_ACTIVE_PATHS,
+            &mut path_count,
+            paths.as_mut_ptr(),
+            &mut mode_count,
+            modes.as_mut_ptr(),
+            None,
+        )
+        .0 != 0
+        {
+            return levels;
+        }
+        paths.truncate(path_count as usize);
+
+        for path in &paths {
+            let mut source = DISPLAYCONFIG_SOURCE_DEVICE_NAME::default();
+            source.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+            source.header.size = size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32;
+            source.header.adapterId = path.sourceInfo.adapterId;
+            source.header.id = path.sourceInfo.id;
+            if DisplayConfigGetDeviceInfo(&mut source.header) != 0 {
+                continue;
+            }
+
+            let mut white = DISPLAYCONFIG_SDR_WHITE_LEVEL::default();
+            white.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SDR_WHITE_LEVEL;
+            white.header.size = size_of::<DISPLAYCONFIG_SDR_WHITE_LEVEL>() as u32;
+            white.header.adapterId = path.targetInfo.adapterId;
+            white.header.id = path.targetInfo.id;
+            if DisplayConfigGetDeviceInfo(&mut white.header) != 0 {
+                continue;
+            }
+            // Reported in thousandths of the 80-nit reference white
+            levels.push((
+                wide_to_string(&source.viewGdiDeviceName),
+                white.SDRWhiteLevel as f32 / 1000.0 * 80.0,
+            ));
         }
     }
+    levels
 }
 
 #[cfg(not(target_os = "windows"))]
-fn collect_display_info() -> Option<Vec<DisplayInfo>> {
+pub fn collect_display_info() -> Option<Vec<DisplayInfo>> {
     None
 }
 
+/// Refresh rate of one monitor (None = the primary display).
+pub fn refresh_rate_for(monitor: Option<u32>) -> Option<u32> {
+    let displays = collect_display_info()?;
+    match monitor {
+        Some(i) => displays.get(i as usize)?.refresh_rate,
+        None => displays.iter().find(|d| d.primary)?.refresh_rate,
+    }
+}
+
+/// Snap an FPS cap down to an even divisor of the refresh rate
+/// (144 Hz: 144, 72, 48, 36…), so every frame is shown for the same number
+/// of refreshes instead of judder from an uneven cadence.
+pub fn snap_fps_cap(cap: u32, refresh_hz: u32) -> u32 {
+    if cap == 0 || refresh_hz == 0 {
+        return cap;
+    }
+    let divisor = refresh_hz.div_ceil(cap).max(1);
+    (refresh_hz / divisor).max(1)
+}
+
 // ============================================================================
 // Audio — WASAPI (Windows)
 // ============================================================================
 
 #[cfg(target_os = "windows")]
-fn collect_audio_info() -> Option<AudioInfo> {
+pub(crate) fn collect_audio_info() -> Option<AudioInfo> {
     use windows::Win32::Media::Audio::{
         eMultimedia, eRender, IMMDeviceEnumerator, MMDeviceEnumerator,
     };
@@ -403,7 +544,7 @@ fn collect_audio_info() -> Option<AudioInfo> {
 }
 
 #[cfg(not(target_os = "windows"))]
-fn collect_audio_info() -> Option<AudioInfo> {
+pub(crate) fn collect_audio_info() -> Option<AudioInfo> {
     None
 }
 
@@ -0,0 +1,104 @@
+//! System (default output device) volume for volume-knob widgets.
+//!
+//! Changes from any source — volume keys, the mixer, other apps — are emitted
+//! as `volume-changed`. The endpoint is polled rather than observed through
+//! `IAudioEndpointVolumeCallback` so that switching the default output device
+//! is picked up without re-registering.
+
+use crate::error::{AppError, AppResult};
+use crate::system_monitor::{collect_audio_info, AudioInfo};
+use log::{error, info};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Start the `volume-changed` watcher.
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        use crate::events::{AppEvent, EmitAppEvent};
+
+        let mut last = collect_audio_info();
+        while RUNNING.load(Ordering::SeqCst) {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = collect_audio_info();
+            if current == last {
+                continue;
+            }
+            if let Some(audio) = &current {
+                if let Err(e) = app.emit_app_event(&AppEvent::VolumeChanged(audio.clone())) {
+                    error!("[system_volume] Failed to emit event: {}", e);
+                }
+            }
+            last = current;
+        }
+    });
+}
+
+pub fn get() -> AppResult<AudioInfo> {
+    collect_audio_info().ok_or_else(|| AppError::Audio("No audio output device".into()))
+}
+
+/// Set the master volume (0-1) and optionally the mute state.
+pub fn set(level: f32, muted: Option<bool>) -> AppResult<()> {
+    if !(0.0..=1.0).contains(&level) {
+        return Err(AppError::Validation(
+            "Volume must be between 0 and 1".into(),
+        ));
+    }
+    endpoint::set(level, muted)?;
+    info!("[system_volume] Volume set to {:.2}", level);
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod endpoint {
+    use crate::error::{AppError, AppResult};
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+    use windows::Win32::Media::Audio::{
+        eMultimedia, eRender, IMMDeviceEnumerator, MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    pub fn set(level: f32, muted: Option<bool>) -> AppResult<()> {
+        let audio_err = |e: windows::core::Error| AppError::Audio(e.to_string());
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(audio_err)?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eMultimedia)
+                .map_err(audio_err)?;
+            let volume: IAudioEndpointVolume =
+                device.Activate(CLSCTX_ALL, None).map_err(audio_err)?;
+            volume
+                .SetMasterVolumeLevelScalar(level, std::ptr::null())
+                .map_err(audio_err)?;
+            if let Some(muted) = muted {
+                volume
+                    .SetMute(BOOL::from(muted), std::ptr::null())
+                    .map_err(audio_err)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod endpoint {
+    use crate::error::{AppError, AppResult};
+
+    pub fn set(_level: f32, _muted: Option<bool>) -> AppResult<()> {
+        Err(AppError::Audio(
+            "System volume control is only supported on Windows".into(),
+        ))
+    }
+}
@@ -0,0 +1,182 @@
+//! On-demand performance traces in Chrome trace format.
+//!
+//! `span(category, name)` guards are placed around injection, desktop detection, hook
+//! dispatch and command handling. They cost one atomic load until
+//! `start_trace_capture` runs; during a capture each finished span is recorded
+//! as a complete (`"ph": "X"`) event. The result is written to the log
+//! directory as `trace-<unix time>.json`, loadable in chrome://tracing,
+//! Perfetto or speedscope (flamegraph view).
+
+use crate::error::{AppError, AppResult};
+use log::info;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+pub const MAX_DURATION_SECS: u64 = 120;
+/// Hook dispatch alone can produce ~1000 events/s while the mouse moves
+const MAX_EVENTS: usize = 500_000;
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+/// A capture is running or being written out
+static BUSY: AtomicBool = AtomicBool::new(false);
+static EPOCH: LazyLock<Instant> = LazyLock::new(Instant::now);
+static EVENTS: Mutex<Vec<Event>> = Mutex::new(Vec::new());
+static THREADS: Mutex<Vec<(u64, String)>> = Mutex::new(Vec::new());
+static NEXT_TID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static TID: u64 = {
+        let tid = NEXT_TID.fetch_add(1, Ordering::Relaxed);
+        let name = std::thread::current()
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("thread-{}", tid));
+        if let Ok(mut threads) = THREADS.lock() {
+            threads.push((tid, name));
+        }
+        tid
+    };
+}
+
+struct Event {
+    name: Cow<'static, str>,
+    cat: &'static str,
+    tid: u64,
+    start_us: u64,
+    dur_us: u64,
+}
+
+/// Records its lifetime as one event when dropped during a capture.
+pub struct Span {
+    name: Cow<'static, str>,
+    cat: &'static str,
+    start: Instant,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !CAPTURING.load(Ordering::Relaxed) {
+            return;
+        }
+        let event = Event {
+            name: std::mem::take(&mut self.name),
+            cat: self.cat,
+            tid: TID.with(|t| *t),
+            start_us: self.start.saturating_duration_since(*EPOCH).as_micros() as u64,
+            dur_us: self.start.elapsed().as_micros() as u64,
+        };
+        if let Ok(mut events) = EVENTS.lock() {
+            if events.len() < MAX_EVENTS {
+                events.push(event);
+            }
+        }
+    }
+}
+
+/// Start a span in category `cat`; `None` (free) when no capture is running.
+pub fn span(cat: &'static str, name: impl Into<Cow<'static, str>>) -> Option<Span> {
+    if !CAPTURING.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(Span {
+        name: name.into(),
+        cat,
+        start: Instant::now(),
+    })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFile {
+    trace_events: Vec<serde_json::Value>,
+    display_time_unit: &'static str,
+}
+
+/// Record spans for `duration`, then write the trace file and return its path.
+/// Blocks for the whole capture.
+pub fn capture(app: &tauri::AppHandle, duration: Duration) -> AppResult<PathBuf> {
+    if duration.is_zero() || duration > Duration::from_secs(MAX_DURATION_SECS) {
+        return Err(AppError::Validation(format!(
+            "Trace duration must be between 1 and {} seconds",
+            MAX_DURATION_SECS
+        )));
+    }
+    if BUSY.swap(true, Ordering::SeqCst) {
+        return Err(AppError::Validation(
+            "A trace capture is already running".into(),
+        ));
+    }
+    let result = record(app, duration);
+    BUSY.store(false, Ordering::SeqCst);
+    result
+}
+
+fn record(app: &tauri::AppHandle, duration: Duration) -> AppResult<PathBuf> {
+    LazyLock::force(&EPOCH);
+    if let Ok(mut events) = EVENTS.lock() {
+        events.clear();
+        // Avoid reallocating on the hook thread mid-capture
+        events.reserve(MAX_EVENTS / 10);
+    }
+    CAPTURING.store(true, Ordering::SeqCst);
+    info!("[trace] Capturing for {:?}", duration);
+    std::thread::sleep(duration);
+    CAPTURING.store(false, Ordering::SeqCst);
+
+    let events = EVENTS
+        .lock()
+        .map(|mut e| std::mem::take(&mut *e))
+        .unwrap_or_default();
+    let pid = std::process::id();
+    let mut trace_events: Vec<serde_json::Value> = THREADS
+        .lock()
+        .map(|threads| {
+            threads
+                .iter()
+                .map(|(tid, name)| {
+                    serde_json::json!({
+                        "name": "thread_name",
+                        "ph": "M",
+                        "pid": pid,
+                        "tid": tid,
+                        "args": { "name": name },
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    trace_events.extend(events.iter().map(|e| {
+        serde_json::json!({
+            "name": e.name,
+            "cat": e.cat,
+            "ph": "X",
+            "pid": pid,
+            "tid": e.tid,
+            "ts": e.start_us,
+            "dur": e.dur_us,
+        })
+    }));
+
+    let dir = crate::logs::log_dir(app)?;
+    std::fs::create_dir_all(&dir)?;
+    let unix = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|t| t.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!("trace-{}.json", unix));
+    let file = TraceFile {
+        trace_events,
+        display_time_unit: "ms",
+    };
+    std::fs::write(&path, serde_json::to_vec(&file)?)?;
+    info!(
+        "[trace] {} events written to {}",
+        events.len(),
+        path.display()
+    );
+    Ok(path)
+}
@@ -0,0 +1,302 @@
+//! Transitions between wallpapers.
+//!
+//! Loading new content hard-cuts inside the WebView. To hide the cut, the
+//! current frame (see `screenshot`) is shown in a layered child window above the
+//! layer, the new content is loaded underneath, and the snapshot is faded or
+//! slid away once the frontend reports the new content ready (or after
+//! `READY_TIMEOUT`).
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use typeshare::typeshare;
+
+const MAX_DURATION_MS: u32 = 5000;
+#[cfg(target_os = "windows")]
+const READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+#[cfg(target_os = "windows")]
+const FRAME: std::time::Duration = std::time::Duration::from_millis(16);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum TransitionKind {
+    /// Hard cut
+    None,
+    #[default]
+    Crossfade,
+    /// The old wallpaper slides out towards the given side
+    SlideLeft,
+    SlideRight,
+    SlideUp,
+    SlideDown,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Transition {
+    #[serde(default)]
+    pub kind: TransitionKind,
+    #[serde(default = "default_duration_ms")]
+    pub duration_ms: u32,
+}
+
+fn default_duration_ms() -> u32 {
+    600
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self {
+            kind: TransitionKind::default(),
+            duration_ms: default_duration_ms(),
+        }
+    }
+}
+
+/// A snapshot overlay is on screen.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// The frontend finished loading the new content.
+static READY: AtomicBool = AtomicBool::new(false);
+
+/// Load a remote wallpaper (http/https) behind a transition.
+pub fn apply_url(app: &tauri::AppHandle, url: &str, transition: &Transition) -> AppResult<String> {
+    let parsed =
+        url::Url::parse(url).map_err(|_| AppError::Validation("Invalid wallpaper URL".into()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(AppError::Validation(
+            "Wallpaper URL must use http(s)".into(),
+        ));
+    }
+    if transition.duration_ms > MAX_DURATION_MS {
+        return Err(AppError::Validation(format!(
+            "Transition duration must be at most {} ms",
+            MAX_DURATION_MS
+        )));
+    }
+    if transition.kind != TransitionKind::None && transition.duration_ms > 0 {
+        // A failed snapshot only costs the effect, never the switch itself
+        if let Err(e) = begin(transition) {
+            log::warn!("[transition] Snapshot failed, hard cut: {}", e);
+        }
+    }
+    app.emit_app_event(&AppEvent::ApplyWallpaperUrl {
+        url: parsed.to_string(),
+    })?;
+    Ok(parsed.to_string())
+}
+
+/// The new content is rendered; start animating the snapshot away.
+pub fn content_ready() {
+    READY.store(true, Ordering::SeqCst);
+    crate::palette::on_content_ready();
+}
+
+/// Cover the layer with a snapshot of its current frame. Returns once the
+/// snapshot is on screen; the animation runs on its own thread.
+#[cfg(target_os = "windows")]
+fn begin(transition: &Transition) -> AppResult<()> {
+    if ACTIVE.swap(true, Ordering::SeqCst) {
+        // The running overlay already hides the switch
+        return Ok(());
+    }
+    READY.store(false, Ordering::SeqCst);
+    let (tx, rx) = std::sync::mpsc::channel();
+    let transition = *transition;
+    std::thread::spawn(move || {
+        // The overlay must be created and destroyed on this thread
+        match unsafe { overlay::Overlay::snapshot() } {
+            Ok(overlay) => {
+                let _ = tx.send(Ok(()));
+                overlay.run(&transition);
+            }
+            Err(e) => {
+                let _ = tx.send(Err(e));
+            }
+        }
+        ACTIVE.store(false, Ordering::SeqCst);
+    });
+    rx.recv_timeout(std::time::Duration::from_secs(1))
+        .map_err(|_| AppError::WindowLayer("Snapshot timed out".into()))?
+}
+
+#[cfg(not(target_os = "windows"))]
+fn begin(_transition: &Transition) -> AppResult<()> {
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod overlay {
+    use super::{Transition, TransitionKind, FRAME, READY, READY_TIMEOUT};
+    use crate::error::{AppError, AppResult};
+    use crate::screenshot::LayerSnapshot;
+    use crate::window_layer::mouse_hook;
+    use std::sync::atomic::Ordering;
+    use std::time::Instant;
+    use windows::Win32::Foundation::{HWND, POINT, SIZE};
+    use windows::Win32::Graphics::Gdi::{ScreenToClient, AC_SRC_OVER, BLENDFUNCTION};
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    fn win_err(e: windows::core::Error) -> AppError {
+        AppError::WindowLayer(e.to_string())
+    }
+
+    pub struct Overlay {
+        hwnd: HWND,
+        frame: LayerSnapshot,
+        /// Position in the layer parent's client coordinates
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    }
+
+    impl Overlay {
+        pub unsafe fn snapshot() -> AppResult<Self> {
+            let parent = HWND(mouse_hook::get_target_parent_hwnd() as *mut _);
+            if parent.is_invalid() {
+                return Err(AppError::WindowLayer("Desktop layer not ready".into()));
+            }
+            let frame = LayerSnapshot::take()?;
+            let mut origin = POINT {
+                x: frame.rect.left,
+                y: frame.rect.top,
+            };
+            let _ = ScreenToClient(parent, &mut origin);
+            let mut overlay = Overlay {
+                hwnd: HWND::default(),
+                x: origin.x,
+                y: origin.y,
+                width: frame.width(),
+                height: frame.height(),
+                frame,
+            };
+
+            let cls = windows::core::w!("MWP_TransitionOverlay");
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(DefWindowProcW),
+                lpszClassName: cls,
+                ..Default::default()
+            };
+            let _ = RegisterClassW(&wc);
+            overlay.hwnd = CreateWindowExW(
+                WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_NOACTIVATE,
+                cls,
+                windows::core::w!(""),
+                WS_CHILD,
+                overlay.x,
+                overlay.y,
+                overlay.width,
+                overlay.height,
+                parent,
+                None,
+                None,
+                None,
+            )
+            .map_err(win_err)?;
+            overlay.update(255)?;
+            // Above the layer window, still below the icons (other parent)
+            let _ = SetWindowPos(
+                overlay.hwnd,
+                HWND_TOP,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_SHOWWINDOW,
+            );
+            Ok(overlay)
+        }
+
+        unsafe fn update(&self, alpha: u8) -> AppResult<()> {
+            let blend = BLENDFUNCTION {
+                BlendOp: AC_SRC_OVER as u8,
+                BlendFlags: 0,
+                SourceConstantAlpha: alpha,
+                // Ignore the bitmap's alpha channel: PrintWindow leaves it unset
+                AlphaFormat: 0,
+            };
+            UpdateLayeredWindow(
+                self.hwnd,
+                None,
+                None,
+                Some(&SIZE {
+                    cx: self.width,
+                    cy: self.height,
+                }),
+                self.frame.mem_dc,
+                Some(&POINT { x: 0, y: 0 }),
+                windows::Win32::Foundation::COLORREF(0),
+                Some(&blend),
+                ULW_ALPHA,
+            )
+            .map_err(win_err)
+        }
+
+        /// Hold the snapshot until the new content is ready, then animate it away.
+        pub fn run(self, transition: &Transition) {
+            let held = Instant::now();
+            while !READY.load(Ordering::SeqCst) && held.elapsed() < READY_TIMEOUT {
+                self.pump_and_sleep();
+            }
+
+            let duration = std::time::Duration::from_millis(transition.duration_ms as u64);
+            let start = Instant::now();
+            loop {
+                let t = (start.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.0);
+                let eased = t * t * (3.0 - 2.0 * t);
+                unsafe { self.frame(transition.kind, eased) };
+                if t >= 1.0 {
+                    break;
+                }
+                self.pump_and_sleep();
+            }
+        }
+
+        unsafe fn frame(&self, kind: TransitionKind, progress: f32) {
+            let (dx, dy) = match kind {
+                TransitionKind::SlideLeft => (-(self.width as f32 * progress) as i32, 0),
+                TransitionKind::SlideRight => ((self.width as f32 * progress) as i32, 0),
+                TransitionKind::SlideUp => (0, -(self.height as f32 * progress) as i32),
+                TransitionKind::SlideDown => (0, (self.height as f32 * progress) as i32),
+                TransitionKind::Crossfade | TransitionKind::None => {
+                    let _ = self.update((255.0 * (1.0 - progress)) as u8);
+                    return;
+                }
+            };
+            let _ = SetWindowPos(
+                self.hwnd,
+                HWND::default(),
+                self.x + dx,
+                self.y + dy,
+                0,
+                0,
+                SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+        }
+
+        /// Keep the overlay's thread responsive between frames.
+        fn pump_and_sleep(&self) {
+            unsafe {
+                let mut msg = MSG::default();
+                while PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_REMOVE).as_bool() {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+            }
+            std::thread::sleep(FRAME);
+        }
+    }
+
+    impl Drop for Overlay {
+        fn drop(&mut self) {
+            unsafe {
+                if !self.hwnd.is_invalid() {
+                    let _ = DestroyWindow(self.hwnd);
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,134 @@
-//! System tray — quit.
+//! System tray — wallpaper actions + quit. The same actions back the desktop context menu.
+//! A left click on the icon opens the hub window; wallpaper volume and desktop
+//! widgets get a submenu.
+//!
+//! The frontend can contribute its own section (`set_tray_menu`), shown between
+//! the wallpaper actions and Quit; clicks on it are emitted as
+//! `tray-custom-action` with the frontend's item id.
 
-use log::{error, info};
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::sync::Mutex;
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
-    tray::TrayIconBuilder,
+    menu::{
+        CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, MenuItemKind, SubmenuBuilder,
+    },
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager,
 };
+use typeshare::typeshare;
+
+// Menu item ids shared by the tray and the desktop context menu
+pub const MENU_CHANGE_WALLPAPER: &str = "wallpaper-change";
+pub const MENU_TOGGLE_PAUSE: &str = "wallpaper-toggle-pause";
+pub const MENU_TOGGLE_ICONS: &str = "desktop-toggle-icons";
+pub const MENU_SETTINGS: &str = "open-settings";
+const MENU_QUIT: &str = "quit";
+const TRAY_ID: &str = "main";
+const TOOLTIP: &str = "MyWallpaper Desktop";
+/// Keeps frontend ids apart from the built-in ones
+const CUSTOM_PREFIX: &str = "custom:";
+const MAX_CUSTOM_ITEMS: usize = 32;
+const MAX_SUBMENU_DEPTH: usize = 3;
+const MAX_LABEL_LEN: usize = 64;
+const MAX_ITEM_ID_LEN: usize = 64;
+
+#[typeshare]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayMenuItem {
+    /// Reported back in `tray-custom-action`; ignored for submenus
+    pub id: String,
+    pub label: String,
+    /// Some = check item
+    #[serde(default)]
+    pub checked: Option<bool>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Non-empty = submenu
+    #[serde(default)]
+    pub items: Vec<TrayMenuItem>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+static CUSTOM_ITEMS: Mutex<Vec<TrayMenuItem>> = Mutex::new(Vec::new());
+
+/// Append the MyWallpaper actions (change, pause/resume, icons, settings).
+pub fn with_wallpaper_actions<'m>(
+    app: &AppHandle,
+    builder: MenuBuilder<'m, tauri::Wry, AppHandle>,
+) -> tauri::Result<MenuBuilder<'m, tauri::Wry, AppHandle>> {
+    let change = MenuItemBuilder::with_id(MENU_CHANGE_WALLPAPER, "Change wallpaper…").build(app)?;
+    let pause = MenuItemBuilder::with_id(MENU_TOGGLE_PAUSE, "Pause/resume wallpaper").build(app)?;
+    let icons =
+        MenuItemBuilder::with_id(MENU_TOGGLE_ICONS, "Show/hide desktop icons").build(app)?;
+    let settings = MenuItemBuilder::with_id(MENU_SETTINGS, "MyWallpaper settings").build(app)?;
+    Ok(builder
+        .item(&change)
+        .item(&pause)
+        .item(&icons)
+        .item(&settings))
+}
+
+/// Run a shared wallpaper action. Returns false for ids this module doesn't own.
+pub fn handle_wallpaper_action(id: &str) -> bool {
+    let result = match id {
+        MENU_CHANGE_WALLPAPER => crate::desktop_actions::open_hub(Some("wallpapers")),
+        MENU_TOGGLE_PAUSE => crate::desktop_actions::toggle_pause().map(|_| ()),
+        MENU_TOGGLE_ICONS => crate::window_layer::toggle_desktop_icons().map(|_| ()),
+        MENU_SETTINGS => crate::desktop_actions::open_hub(Some("settings")),
+        _ => return false,
+    };
+    if let Err(e) = result {
+        warn!("[tray] Menu action '{}' failed: {}", id, e);
+    }
+    true
+}
+
+fn custom_item(app: &AppHandle, item: &TrayMenuItem) -> tauri::Result<MenuItemKind<tauri::Wry>> {
+    let id = format!("{}{}", CUSTOM_PREFIX, item.id);
+    if !item.items.is_empty() {
+        let mut submenu = SubmenuBuilder::with_id(app, id, &item.label).enabled(item.enabled);
+        for child in &item.items {
+            submenu = submenu.item(&custom_item(app, child)?);
+        }
+        return Ok(MenuItemKind::Submenu(submenu.build()?));
+    }
+    Ok(match item.checked {
+        Some(checked) => MenuItemKind::Check(
+            CheckMenuItemBuilder::with_id(id, &item.label)
+                .checked(checked)
+                .enabled(item.enabled)
+                .build(app)?,
+        ),
+        None => MenuItemKind::MenuItem(
+            MenuItemBuilder::with_id(id, &item.label)
+                .enabled(item.enabled)
+                .build(app)?,
+        ),
+    })
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let custom = CUSTOM_ITEMS.lock().map(|c| c.clone()).unwrap_or_default();
+    let mut builder = with_wallpaper_actions(app, MenuBuilder::new(app))?
+        .item(&crate::wallpaper_volume::tray_submenu(app)?)
+        .item(&crate::widgets::tray_submenu(app)?);
+    if !custom.is_empty() {
+        builder = builder.separator();
+        for item in &custom {
+            builder = builder.item(&custom_item(app, item)?);
+        }
+    }
+    let quit_item = MenuItemBuilder::with_id(MENU_QUIT, "Quit").build(app)?;
+    builder.separator().item(&quit_item).build()
+}
 
 pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let icon = Image::from_bytes(include_bytes!("../icons/32x32.png")).unwrap_or_else(|_| {
@@ -14,17 +136,39 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
         Image::new_owned(vec![255u8; 32 * 32 * 4], 32, 32)
     });
 
-    let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
-    let menu = MenuBuilder::new(app).item(&quit_item).build()?;
+    let menu = build_menu(app)?;
 
-    let _tray = TrayIconBuilder::new()
+    // Menu event handlers are app-wide: this one also receives the desktop context menu
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
-        .tooltip("MyWallpaper Desktop")
+        .tooltip(TOOLTIP)
         .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_tray_icon_event(|_tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                if let Err(e) = crate::desktop_actions::open_hub(None) {
+                    warn!("[tray] Cannot open the hub: {}", e);
+                }
+            }
+        })
         .on_menu_event(move |app, event| {
-            if event.id().as_ref() == "quit" {
-                crate::window_layer::restore_desktop_icons_and_unhook();
-                app.exit(0);
+            let id = event.id().as_ref();
+            if id == MENU_QUIT {
+                crate::shutdown::exit(app, 0);
+            } else if let Some(custom) = id.strip_prefix(CUSTOM_PREFIX) {
+                let _ = app.emit_app_event(&AppEvent::TrayCustomAction {
+                    id: custom.to_string(),
+                });
+            } else if !handle_wallpaper_action(id)
+                && !crate::wallpaper_volume::handle_menu_event(app, id)
+                && !crate::widgets::handle_menu_event(app, id)
+            {
+                crate::context_menu::handle_menu_event(app, id);
             }
         })
         .build(app)?;
@@ -32,3 +176,68 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     info!("[tray] System tray ready.");
     Ok(())
 }
+
+fn validate_items(items: &[TrayMenuItem], depth: usize, count: &mut usize) -> AppResult<()> {
+    if depth > MAX_SUBMENU_DEPTH {
+        return Err(AppError::Validation(format!(
+            "Tray submenus nest at most {} levels",
+            MAX_SUBMENU_DEPTH
+        )));
+    }
+    for item in items {
+        *count += 1;
+        if *count > MAX_CUSTOM_ITEMS {
+            return Err(AppError::Validation(format!(
+                "At most {} custom tray items",
+                MAX_CUSTOM_ITEMS
+            )));
+        }
+        if item.label.trim().is_empty() || item.label.chars().count() > MAX_LABEL_LEN {
+            return Err(AppError::Validation("Invalid tray item label".into()));
+        }
+        if item.id.is_empty() || item.id.len() > MAX_ITEM_ID_LEN {
+            return Err(AppError::Validation(format!(
+                "Invalid tray item id for '{}'",
+                item.label
+            )));
+        }
+        validate_items(&item.items, depth + 1, count)?;
+    }
+    Ok(())
+}
+
+/// Replace the frontend-defined tray section and rebuild the menu (empty removes it).
+pub fn set_custom_items(app: &AppHandle, items: Vec<TrayMenuItem>) -> AppResult<()> {
+    validate_items(&items, 1, &mut 0)?;
+    *CUSTOM_ITEMS
+        .lock()
+        .map_err(|_| AppError::Validation("Tray menu state poisoned".into()))? = items;
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_menu(app)?))?;
+    }
+    Ok(())
+}
+
+/// Rebuild the menu after a change in state it shows (widgets, volume).
+pub fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Err(e) = build_menu(app).and_then(|menu| tray.set_menu(Some(menu))) {
+        warn!("[tray] Cannot rebuild the menu: {}", e);
+    }
+}
+
+/// Show a status line under the app name in the tray tooltip (None clears it).
+pub fn set_status(app: &AppHandle, status: Option<&str>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let tooltip = match status {
+        Some(status) => format!("{}\n{}", TOOLTIP, status),
+        None => TOOLTIP.to_string(),
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        warn!("[tray] Failed to update tooltip: {}", e);
+    }
+}
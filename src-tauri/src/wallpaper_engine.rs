@@ -0,0 +1,424 @@
+//! Wallpaper Engine importer — converts Workshop / local WE projects into library packs.
+//!
+//! Video and web projects map directly to library packs. Scene projects ship a
+//! proprietary `scene.pkg`; they are imported only when the project also contains
+//! a playable video fallback. Application projects are never imported.
+//!
+//! `scan_workshop` lists subscribed Workshop items without importing them, so
+//! the user can pick which ones `import_projects` should bring in.
+
+use crate::error::AppResult;
+use crate::library::{self, ImportResult, ImportStatus, PackManifest, WallpaperKind};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use typeshare::typeshare;
+
+/// Steam app id of Wallpaper Engine.
+pub const WE_APP_ID: &str = "431960";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Subset of Wallpaper Engine's `project.json`.
+#[derive(Debug, Deserialize)]
+struct WeProject {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    file: Option<String>,
+    #[serde(default)]
+    preview: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default, rename = "workshopid")]
+    workshop_id: Option<serde_json::Value>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WeProjectKind {
+    Video,
+    Web,
+    Scene,
+    Application,
+    Unknown,
+}
+
+/// A subscribed Workshop item found on disk.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkshopItem {
+    pub workshop_id: String,
+    pub title: String,
+    pub kind: WeProjectKind,
+    /// Project directory; pass it to `import_projects` to import this item
+    pub path: String,
+    /// Absolute path of the preview image or GIF
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_path: Option<String>,
+    /// Library pack id the item imports as
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_id: Option<String>,
+    pub imported: bool,
+    /// Why the item cannot be imported; None when it can
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unsupported_reason: Option<String>,
+}
+
+// ============================================================================
+// Steam Discovery
+// ============================================================================
+
+/// Steam install directory recorded by the Steam client.
+#[cfg(target_os = "windows")]
+fn registered_steam_root() -> Option<PathBuf> {
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let mut buf = [0u16; 512];
+    let mut size = std::mem::size_of_val(&buf) as u32;
+    unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Valve\\Steam"),
+            w!("SteamPath"),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buf.as_mut_ptr() as *mut _),
+            Some(&mut size),
+        )
+        .ok()
+        .ok()?;
+    }
+    let len = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+    // Written with forward slashes ("c:/program files (x86)/steam")
+    let path = String::from_utf16_lossy(&buf[..len]).replace('/', "\\");
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Steam install directories for this platform (registered, then defaults).
+fn steam_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    #[cfg(target_os = "windows")]
+    {
+        roots.extend(registered_steam_root());
+        for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+            if let Some(base) = std::env::var_os(var) {
+                roots.push(PathBuf::from(base).join("Steam"));
+            }
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(home) = std::env::var_os("HOME") {
+            let home = PathBuf::from(home);
+            roots.push(home.join(".steam/steam"));
+            roots.push(home.join(".local/share/Steam"));
+            roots.push(home.join(".var/app/com.valvesoftware.Steam/data/Steam"));
+        }
+    }
+    roots.into_iter().filter(|p| p.is_dir()).collect()
+}
+
+/// Parse `"path"` entries from `steamapps/libraryfolders.vdf`.
+fn parse_library_folders(vdf: &str) -> Vec<PathBuf> {
+    vdf.lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('"').collect();
+            // Line shape: <ws>"path"<ws>"D:\\SteamLibrary"
+            (parts.len() >= 4 && parts[1] == "path")
+                .then(|| PathBuf::from(parts[3].replace("\\\\", "\\")))
+        })
+        .collect()
+}
+
+/// Every Steam library folder (default install + extra libraries), deduplicated.
+pub fn steam_library_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for root in steam_roots() {
+        let vdf = root.join("steamapps").join("libraryfolders.vdf");
+        let extra = std::fs::read_to_string(vdf)
+            .map(|s| parse_library_folders(&s))
+            .unwrap_or_default();
+        for dir in std::iter::once(root).chain(extra) {
+            let key = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+            if dir.is_dir() && !dirs.contains(&key) {
+                dirs.push(key);
+            }
+        }
+    }
+    dirs
+}
+
+fn project_dirs_in(containers: Vec<PathBuf>) -> Vec<PathBuf> {
+    containers
+        .into_iter()
+        .filter_map(|c| std::fs::read_dir(c).ok())
+        .flat_map(|rd| rd.flatten().map(|e| e.path()))
+        .filter(|p| p.join("project.json").is_file())
+        .collect()
+}
+
+/// Project directories of subscribed Workshop items.
+fn workshop_dirs() -> Vec<PathBuf> {
+    project_dirs_in(
+        steam_library_dirs()
+            .into_iter()
+            .map(|lib| {
+                lib.join("steamapps")
+                    .join("workshop")
+                    .join("content")
+                    .join(WE_APP_ID)
+            })
+            .collect(),
+    )
+}
+
+/// Candidate project directories: Workshop subscriptions + local WE projects.
+pub fn find_project_dirs() -> Vec<PathBuf> {
+    let mut containers = Vec::new();
+    for lib in steam_library_dirs() {
+        let projects = lib
+            .join("steamapps")
+            .join("common")
+            .join("wallpaper_engine")
+            .join("projects");
+        containers.push(projects.join("myprojects"));
+        containers.push(projects.join("defaultprojects"));
+    }
+    let mut dirs = workshop_dirs();
+    dirs.extend(project_dirs_in(containers));
+    dirs
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+fn read_project(dir: &Path) -> AppResult<WeProject> {
+    let bytes = std::fs::read(dir.join("project.json"))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+fn workshop_id_string(v: &serde_json::Value) -> Option<String> {
+    match v {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Stable library id so re-running the import skips existing packs.
+fn pack_id_for(dir: &Path, project: &WeProject) -> Option<String> {
+    let raw = project
+        .workshop_id
+        .as_ref()
+        .and_then(workshop_id_string)
+        .or_else(|| dir.file_name()?.to_str().map(str::to_string))?;
+    let sanitized: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Some(format!("we-{}", sanitized))
+}
+
+/// Pick the library representation of a WE project, or explain why it can't be converted.
+fn convert(dir: &Path, project: &WeProject) -> Result<PackManifest, String> {
+    let kind = project.kind.as_deref().unwrap_or("").to_ascii_lowercase();
+    let name = project
+        .title
+        .clone()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| "Wallpaper Engine import".into());
+    let file = project.file.clone().filter(|f| dir.join(f).is_file());
+
+    let (kind, entry) = match kind.as_str() {
+        "video" => (
+            WallpaperKind::Video,
+            file.ok_or("Video file missing from project")?,
+        ),
+        "web" => (
+            WallpaperKind::Web,
+            file.ok_or("Web entry file missing from project")?,
+        ),
+        "scene" => {
+            // Scenes need WE's renderer; accept only an exported video alongside
+            let fallback = std::fs::read_dir(dir)
+                .map_err(|e| e.to_string())?
+                .flatten()
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .find(|f| {
+                    let lower = f.to_ascii_lowercase();
+                    lower.ends_with(".mp4") || lower.ends_with(".webm")
+                })
+                .ok_or("Scene wallpapers require Wallpaper Engine's renderer")?;
+            (WallpaperKind::Video, fallback)
+        }
+        "application" => return Err("Application wallpapers cannot be imported".into()),
+        other => return Err(format!("Unknown project type '{}'", other)),
+    };
+
+    Ok(PackManifest {
+        name,
+        kind,
+        entry,
+        preview: project.preview.clone().filter(|p| dir.join(p).is_file()),
+        author: None,
+        description: project.description.clone(),
+        // Filled in by install_pack
+        verification: Default::default(),
+    })
+}
+
+fn import_project(dir: &Path) -> ImportResult {
+    let mut result = ImportResult::new(dir, ImportStatus::Failed);
+
+    let project = match read_project(dir) {
+        Ok(p) => p,
+        Err(e) => {
+            result.message = Some(format!("Unreadable project.json: {}", e));
+            return result;
+        }
+    };
+    result.name = project.title.clone();
+
+    let Some(id) = pack_id_for(dir, &project) else {
+        result.message = Some("Could not derive a pack id".into());
+        return result;
+    };
+    result.id = Some(id.clone());
+
+    if library::pack_exists(&id) {
+        result.status = ImportStatus::AlreadyImported;
+        return result;
+    }
+
+    let manifest = match convert(dir, &project) {
+        Ok(m) => m,
+        Err(reason) => {
+            result.status = ImportStatus::Unsupported;
+            result.message = Some(reason);
+            return result;
+        }
+    };
+
+    match library::install_pack(dir, &id, &manifest) {
+        Ok(()) => {
+            result.status = ImportStatus::Imported;
+            let _ = library::generate_preview(&id);
+        }
+        Err(e) => {
+            warn!("[wallpaper_engine] Import of {} failed: {}", dir.display(), e);
+            result.message = Some(e.to_string());
+        }
+    }
+    result
+}
+
+fn scan_item(dir: &Path) -> Option<WorkshopItem> {
+    let project = match read_project(dir) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!(
+                "[wallpaper_engine] Skipping {}: unreadable project.json: {}",
+                dir.display(),
+                e
+            );
+            return None;
+        }
+    };
+    let folder = dir.file_name()?.to_str()?.to_string();
+    let kind = match project
+        .kind
+        .as_deref()
+        .unwrap_or("")
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "video" => WeProjectKind::Video,
+        "web" => WeProjectKind::Web,
+        "scene" => WeProjectKind::Scene,
+        "application" => WeProjectKind::Application,
+        _ => WeProjectKind::Unknown,
+    };
+    let pack_id = pack_id_for(dir, &project);
+    Some(WorkshopItem {
+        workshop_id: project
+            .workshop_id
+            .as_ref()
+            .and_then(workshop_id_string)
+            .unwrap_or_else(|| folder.clone()),
+        title: project
+            .title
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(folder),
+        kind,
+        path: dir.display().to_string(),
+        preview_path: project
+            .preview
+            .as_ref()
+            .map(|p| dir.join(p))
+            .filter(|p| p.is_file())
+            .map(|p| p.display().to_string()),
+        imported: pack_id.as_deref().is_some_and(library::pack_exists),
+        pack_id,
+        unsupported_reason: convert(dir, &project).err(),
+    })
+}
+
+/// Subscribed Workshop items, sorted by title. Nothing is imported.
+pub fn scan_workshop() -> Vec<WorkshopItem> {
+    let mut items: Vec<WorkshopItem> = workshop_dirs()
+        .iter()
+        .filter_map(|d| scan_item(d))
+        .collect();
+    items.sort_by_key(|i| i.title.to_lowercase());
+    info!("[wallpaper_engine] Found {} Workshop item(s)", items.len());
+    items
+}
+
+/// Import the given project directories, or every discoverable project when
+/// `paths` is empty.
+pub fn import_projects(paths: Vec<String>) -> AppResult<Vec<ImportResult>> {
+    library::library_root()?;
+    let dirs: Vec<PathBuf> = if paths.is_empty() {
+        find_project_dirs()
+    } else {
+        paths.into_iter().map(PathBuf::from).collect()
+    };
+    info!("[wallpaper_engine] Found {} project(s)", dirs.len());
+    let results: Vec<ImportResult> = dirs
+        .iter()
+        .map(|d| {
+            if d.join("project.json").is_file() {
+                import_project(d)
+            } else {
+                let mut r = ImportResult::new(d, ImportStatus::Unsupported);
+                r.message = Some("Not a Wallpaper Engine project folder".into());
+                r
+            }
+        })
+        .collect();
+    info!(
+        "[wallpaper_engine] Imported {} of {} project(s)",
+        results
+            .iter()
+            .filter(|r| r.status == ImportStatus::Imported)
+            .count(),
+        results.len()
+    );
+    Ok(results)
+}
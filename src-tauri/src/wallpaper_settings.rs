@@ -0,0 +1,74 @@
+//! Per-wallpaper settings — scene options such as speed or colors, stored as
+//! free-form JSON per wallpaper id in `wallpaper_settings.json` so they
+//! survive clearing the webview's site data.
+//!
+//! Relayed calls only reach the settings of the calling wallpaper (see
+//! `permissions::require_self`). Every write emits `wallpaper-settings-changed`
+//! so other windows showing the same wallpaper stay in sync.
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use log::{error, info};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+const SETTINGS_FILE: &str = "wallpaper_settings.json";
+/// Serialized size limit per wallpaper
+const MAX_SETTINGS_BYTES: usize = 256 * 1024;
+
+static STORE: Mutex<BTreeMap<String, Value>> = Mutex::new(BTreeMap::new());
+
+fn store_error() -> AppError {
+    AppError::Storage("Wallpaper settings store poisoned".into())
+}
+
+pub fn init() {
+    if let Ok(mut store) = STORE.lock() {
+        *store = crate::storage::load_json(SETTINGS_FILE);
+        info!(
+            "[wallpaper_settings] Loaded settings for {} wallpaper(s)",
+            store.len()
+        );
+    }
+}
+
+/// Stored settings of `wallpaper_id`, `Value::Null` when none were saved.
+pub fn get(wallpaper_id: &str) -> AppResult<Value> {
+    let store = STORE.lock().map_err(|_| store_error())?;
+    Ok(store.get(wallpaper_id).cloned().unwrap_or(Value::Null))
+}
+
+/// Replace the settings of `wallpaper_id`; `Value::Null` deletes them.
+pub fn set(app: &tauri::AppHandle, wallpaper_id: &str, settings: Value) -> AppResult<()> {
+    if wallpaper_id.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Wallpaper id must not be empty".into(),
+        ));
+    }
+    if serde_json::to_vec(&settings)?.len() > MAX_SETTINGS_BYTES {
+        return Err(AppError::Validation(format!(
+            "Wallpaper settings are limited to {} KiB",
+            MAX_SETTINGS_BYTES / 1024
+        )));
+    }
+    {
+        let mut store = STORE.lock().map_err(|_| store_error())?;
+        let previous = if settings.is_null() {
+            store.remove(wallpaper_id)
+        } else {
+            store.insert(wallpaper_id.to_string(), settings.clone())
+        };
+        if previous.as_ref().unwrap_or(&Value::Null) == &settings {
+            return Ok(());
+        }
+        crate::storage::save_json(SETTINGS_FILE, &*store)?;
+    }
+    if let Err(e) = app.emit_app_event(&AppEvent::WallpaperSettingsChanged {
+        wallpaper_id: wallpaper_id.to_string(),
+        settings,
+    }) {
+        error!("[wallpaper_settings] Failed to emit event: {}", e);
+    }
+    Ok(())
+}
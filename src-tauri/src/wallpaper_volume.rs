@@ -0,0 +1,234 @@
+//! Wallpaper audio volume, set by the user on top of the scene's own mixing.
+//!
+//! WebView2 can only mute a page, so the level scales the wallpaper's audio
+//! sessions (this process and the WebView2 renderers, as in `audio_ducking`)
+//! through `ISimpleAudioVolume`, relative to the user's mixer setting. A page
+//! only gets a session once it plays sound, so sessions are re-checked every
+//! second. While audio ducking holds them down the level waits for it to
+//! restore them. Windows remembers per-app session volumes across launches,
+//! so the mixer volumes are put back on exit.
+
+use crate::error::{AppError, AppResult};
+use log::{error, info, warn};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Duration;
+use tauri::menu::{CheckMenuItemBuilder, Submenu, SubmenuBuilder};
+use tauri::AppHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MENU_LEVEL_PREFIX: &str = "wallpaper-volume:";
+/// Tray presets in percent; 0 is "Mute"
+const TRAY_LEVELS: [u32; 5] = [0, 25, 50, 75, 100];
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static STOPPING: AtomicBool = AtomicBool::new(false);
+/// `f32` bits of the level, 1.0 until settings are applied
+static LEVEL: AtomicU32 = AtomicU32::new(0x3F80_0000);
+
+pub fn configure(level: f32) {
+    LEVEL.store(level.to_bits(), Ordering::SeqCst);
+}
+
+fn level() -> f32 {
+    f32::from_bits(LEVEL.load(Ordering::SeqCst))
+}
+
+/// Persist `level` (0-1) in settings and apply it.
+pub fn set(app: &AppHandle, level: f32) -> AppResult<()> {
+    use crate::events::{AppEvent, EmitAppEvent};
+
+    if !(0.0..=1.0).contains(&level) {
+        return Err(AppError::Validation(
+            "Wallpaper volume must be between 0 and 1".into(),
+        ));
+    }
+    let mut settings = crate::settings::get();
+    settings.wallpaper_volume = level;
+    crate::settings::update(settings)?;
+    info!("[wallpaper_volume] Volume set to {:.2}", level);
+    crate::tray::refresh_menu(app);
+    if let Err(e) = app.emit_app_event(&AppEvent::WallpaperVolumeChanged { level }) {
+        error!("[wallpaper_volume] Failed to emit event: {}", e);
+    }
+    Ok(())
+}
+
+/// Start applying the level to wallpaper audio sessions.
+pub fn start(app: AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    // The tray was built before settings were loaded
+    crate::tray::refresh_menu(&app);
+    crate::shutdown::on_exit("Restoring wallpaper audio volume", stop);
+    std::thread::spawn(sessions::run);
+}
+
+/// Put the mixer volumes back and stop.
+fn stop() {
+    STOPPING.store(true, Ordering::SeqCst);
+    let deadline = std::time::Instant::now() + POLL_INTERVAL * 3;
+    while RUNNING.load(Ordering::SeqCst) && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+pub fn tray_submenu(app: &AppHandle) -> tauri::Result<Submenu<tauri::Wry>> {
+    let current = (level() * 100.0).round() as u32;
+    let mut submenu = SubmenuBuilder::new(app, "Wallpaper volume");
+    for percent in TRAY_LEVELS {
+        let label = match percent {
+            0 => "Mute".to_string(),
+            _ => format!("{}%", percent),
+        };
+        submenu = submenu.item(
+            &CheckMenuItemBuilder::with_id(format!("{}{}", MENU_LEVEL_PREFIX, percent), label)
+                .checked(percent == current)
+                .build(app)?,
+        );
+    }
+    submenu.build()
+}
+
+/// Handle a tray click. Returns false for ids this module doesn't own.
+pub fn handle_menu_event(app: &AppHandle, id: &str) -> bool {
+    let Some(percent) = id
+        .strip_prefix(MENU_LEVEL_PREFIX)
+        .and_then(|p| p.parse::<u32>().ok())
+    else {
+        return false;
+    };
+    if let Err(e) = set(app, percent as f32 / 100.0) {
+        warn!("[wallpaper_volume] Menu action '{}' failed: {}", id, e);
+        // Undo the check the click toggled
+        crate::tray::refresh_menu(app);
+    }
+    true
+}
+
+#[cfg(target_os = "windows")]
+mod sessions {
+    use super::*;
+    use std::collections::HashMap;
+    use windows::core::Interface;
+    use windows::Win32::Media::Audio::{
+        eMultimedia, eRender, IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator,
+        ISimpleAudioVolume, MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    /// Mixer volume of a session before it was scaled, and the level applied
+    type Scaled = (f32, f32);
+
+    /// Audio sessions of the wallpaper on the default output device.
+    unsafe fn wallpaper_sessions(
+        enumerator: &IMMDeviceEnumerator,
+    ) -> Vec<(String, ISimpleAudioVolume)> {
+        let mut out = Vec::new();
+        let Ok(device) = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia) else {
+            return out;
+        };
+        let Ok(manager) = device.Activate::<IAudioSessionManager2>(CLSCTX_ALL, None) else {
+            return out;
+        };
+        let Ok(sessions) = manager.GetSessionEnumerator() else {
+            return out;
+        };
+        let own = crate::audio_ducking::own_process_tree();
+        for i in 0..sessions.GetCount().unwrap_or(0) {
+            let Ok(control) = sessions
+                .GetSession(i)
+                .and_then(|c| c.cast::<IAudioSessionControl2>())
+            else {
+                continue;
+            };
+            if !control.GetProcessId().is_ok_and(|pid| own.contains(&pid)) {
+                continue;
+            }
+            let Ok(id) = control.GetSessionInstanceIdentifier() else {
+                continue;
+            };
+            let id_string = id.to_string().unwrap_or_default();
+            CoTaskMemFree(Some(id.0 as *const _));
+            if let Ok(volume) = control.cast::<ISimpleAudioVolume>() {
+                out.push((id_string, volume));
+            }
+        }
+        out
+    }
+
+    unsafe fn apply(
+        sessions: &[(String, ISimpleAudioVolume)],
+        scaled: &mut HashMap<String, Scaled>,
+        level: f32,
+    ) {
+        for (id, volume) in sessions {
+            let original = match scaled.get(id) {
+                Some((_, applied)) if *applied == level => continue,
+                Some((original, _)) => *original,
+                // Sessions are left untouched until the level is lowered
+                None if level >= 1.0 => continue,
+                None => match volume.GetMasterVolume() {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                },
+            };
+            if volume
+                .SetMasterVolume(original * level, std::ptr::null())
+                .is_ok()
+            {
+                scaled.insert(id.clone(), (original, level));
+            }
+        }
+    }
+
+    unsafe fn restore(
+        sessions: &[(String, ISimpleAudioVolume)],
+        scaled: &mut HashMap<String, Scaled>,
+    ) {
+        for (id, volume) in sessions {
+            if let Some((original, _)) = scaled.get(id) {
+                let _ = volume.SetMasterVolume(*original, std::ptr::null());
+            }
+        }
+        scaled.clear();
+    }
+
+    pub fn run() {
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let enumerator: Option<IMMDeviceEnumerator> =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).ok();
+            let Some(enumerator) = enumerator else {
+                warn!("[wallpaper_volume] No audio device enumerator");
+                RUNNING.store(false, Ordering::SeqCst);
+                return;
+            };
+
+            let mut scaled: HashMap<String, Scaled> = HashMap::new();
+            while !STOPPING.load(Ordering::SeqCst) {
+                // Ducking saved the volumes it restores to; don't fight it
+                if !crate::audio_ducking::is_ducking() {
+                    let sessions = wallpaper_sessions(&enumerator);
+                    apply(&sessions, &mut scaled, level());
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            restore(&wallpaper_sessions(&enumerator), &mut scaled);
+            RUNNING.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Left for the Linux/macOS port; the setting is kept but not applied.
+#[cfg(not(target_os = "windows"))]
+mod sessions {
+    use super::*;
+
+    pub fn run() {
+        warn!("[wallpaper_volume] Wallpaper volume is only supported on Windows");
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,276 @@
+//! Watched folder — images, videos and packs dropped into a user folder are
+//! imported into the local library automatically.
+//!
+//! The folder is polled, and an item is only imported once its size and
+//! modification time held still between two polls, so files that are still
+//! being copied or downloaded are left alone. Imported sources are remembered
+//! in `watched_folder.json`: replacing a file imports it again, deleting it
+//! keeps the library copy. Each batch is announced with `library-changed`,
+//! and the newest item is applied when `watched_folder_auto_apply` is set.
+
+use crate::error::{AppError, AppResult};
+use crate::library::{self, LocalWallpaper, PackManifest};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+
+const STATE_FILE: &str = "watched_folder.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// Folder being watched and whether new items are applied
+static CONFIG: Mutex<Option<(PathBuf, bool)>> = Mutex::new(None);
+
+/// Size and modification time of a source, compared between polls
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct Stamp {
+    len: u64,
+    modified_ms: u64,
+}
+
+pub fn configure(folder: Option<&str>, auto_apply: bool) {
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = folder.map(|f| (PathBuf::from(f), auto_apply));
+    }
+}
+
+fn config() -> Option<(PathBuf, bool)> {
+    CONFIG.lock().ok()?.clone()
+}
+
+/// Trim the configured folder; None turns the feature off.
+pub fn normalize_dir(dir: Option<&str>) -> AppResult<Option<String>> {
+    let Some(dir) = dir.map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+    let path = Path::new(dir);
+    if !path.is_absolute() {
+        return Err(AppError::Validation(
+            "Watched folder must be an absolute path".into(),
+        ));
+    }
+    if path.is_file() {
+        return Err(AppError::Validation(format!("{} is a file", dir)));
+    }
+    // Watching the library (or a parent of it) would import packs into itself
+    if library::library_root().is_ok_and(|root| path.starts_with(root) || root.starts_with(path)) {
+        return Err(AppError::Validation(
+            "Watched folder cannot contain or be inside the wallpaper library".into(),
+        ));
+    }
+    Ok(Some(dir.to_string()))
+}
+
+/// Start polling the watched folder (idles while none is configured).
+pub fn start(app: tauri::AppHandle) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let mut imported: HashMap<String, Stamp> = crate::storage::load_json(STATE_FILE);
+        // Readings of the previous poll for items not imported yet
+        let mut pending: HashMap<PathBuf, Stamp> = HashMap::new();
+        while RUNNING.load(Ordering::SeqCst) {
+            match config() {
+                Some((folder, auto_apply)) => {
+                    let added = poll(&folder, &mut imported, &mut pending);
+                    if !added.is_empty() {
+                        announce(&app, added, auto_apply);
+                    }
+                }
+                None => pending.clear(),
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Import every stable, new or changed item in `folder`.
+fn poll(
+    folder: &Path,
+    imported: &mut HashMap<String, Stamp>,
+    pending: &mut HashMap<PathBuf, Stamp>,
+) -> Vec<LocalWallpaper> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        pending.clear();
+        return Vec::new();
+    };
+    let mut unstable = HashMap::new();
+    let mut added = Vec::new();
+    let mut dirty = false;
+    for path in entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| is_candidate(p))
+    {
+        let Some(stamp) = stamp(&path) else {
+            continue;
+        };
+        let key = path.display().to_string();
+        if imported.get(&key) == Some(&stamp) {
+            continue;
+        }
+        // Still being written until two readings agree
+        if pending.get(&path) != Some(&stamp) {
+            unstable.insert(path, stamp);
+            continue;
+        }
+        match import(&path) {
+            Ok(wallpaper) => {
+                info!("[watched_folder] Imported '{}' as '{}'", key, wallpaper.id);
+                added.push(wallpaper);
+            }
+            Err(e) => warn!("[watched_folder] Could not import '{}': {}", key, e),
+        }
+        // Failures are not retried until the item changes
+        imported.insert(key, stamp);
+        dirty = true;
+    }
+    *pending = unstable;
+    if dirty {
+        if let Err(e) = crate::storage::save_json(STATE_FILE, imported) {
+            warn!("[watched_folder] Failed to save {}: {}", STATE_FILE, e);
+        }
+    }
+    added
+}
+
+fn announce(app: &tauri::AppHandle, added: Vec<LocalWallpaper>, auto_apply: bool) {
+    use crate::events::{AppEvent, EmitAppEvent};
+
+    let newest = added.last().map(|w| w.id.clone());
+    if let Err(e) = app.emit_app_event(&AppEvent::LibraryChanged { added }) {
+        error!("[watched_folder] Failed to emit event: {}", e);
+    }
+    if let Some(id) = newest.filter(|_| auto_apply) {
+        if let Err(e) = library::apply_local_wallpaper(app, &id) {
+            warn!("[watched_folder] Could not apply '{}': {}", id, e);
+        }
+    }
+}
+
+fn is_mwpack(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(crate::mwpack::PACK_EXTENSION))
+}
+
+/// Pack folders, `.mwpack` files, videos and images; hidden items are skipped.
+fn is_candidate(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_none_or(|n| n.starts_with('.'));
+    !hidden && (path.is_dir() || is_mwpack(path) || library::file_kind(path).is_some())
+}
+
+fn modified_ms(meta: &std::fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let meta = std::fs::metadata(path).ok()?;
+    let mut stamp = Stamp {
+        len: meta.len(),
+        modified_ms: modified_ms(&meta),
+    };
+    if meta.is_dir() {
+        // Top-level files keep changing while a folder is copied in
+        stamp.len = 0;
+        for meta in std::fs::read_dir(path)
+            .ok()?
+            .flatten()
+            .flat_map(|e| e.metadata())
+        {
+            stamp.len += meta.len();
+            stamp.modified_ms = stamp.modified_ms.max(modified_ms(&meta));
+        }
+    }
+    Some(stamp)
+}
+
+/// `watched-<file name>`, so `sunset.jpg` and `sunset.mp4` stay apart.
+fn pack_id_for(source: &Path) -> Option<String> {
+    let name = source.file_name()?.to_str()?;
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Some(format!("watched-{}", sanitized))
+}
+
+fn import(source: &Path) -> AppResult<LocalWallpaper> {
+    // Packs carry their own id and replace older installs themselves
+    if is_mwpack(source) {
+        return crate::mwpack::install(&source.display().to_string());
+    }
+    let id = pack_id_for(source)
+        .ok_or_else(|| AppError::Library("Could not derive a pack id".into()))?;
+    // A source replaced since it was imported replaces its pack, which is
+    // kept when the new version fails to install
+    if source.is_dir() {
+        let manifest = library::read_manifest(source)
+            .ok_or_else(|| AppError::Library("No wallpaper found in folder".into()))?;
+        library::replace_pack(source, &id, &manifest)?;
+    } else {
+        install_file(source, &id)?;
+    }
+    if let Err(e) = library::generate_preview(&id) {
+        warn!(
+            "[watched_folder] Preview generation failed for {}: {}",
+            id, e
+        );
+    }
+    library::get_local_wallpaper(&id)
+}
+
+/// Wrap a single video or image into a pack.
+fn install_file(source: &Path, id: &str) -> AppResult<()> {
+    let unsupported = || AppError::Library("Unsupported file".into());
+    let kind = library::file_kind(source).ok_or_else(unsupported)?;
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(unsupported)?;
+    let name = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_name);
+
+    let scratch = library::library_root()?.join(format!(".{}.watch", id));
+    let _ = std::fs::remove_dir_all(&scratch);
+    let result = std::fs::create_dir_all(&scratch)
+        .and_then(|_| std::fs::copy(source, scratch.join(file_name)))
+        .map_err(AppError::from)
+        .and_then(|_| {
+            library::replace_pack(
+                &scratch,
+                id,
+                &PackManifest {
+                    name: name.to_string(),
+                    kind,
+                    entry: file_name.to_string(),
+                    preview: None,
+                    author: None,
+                    description: None,
+                    // Filled in by replace_pack
+                    verification: Default::default(),
+                },
+            )
+        });
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
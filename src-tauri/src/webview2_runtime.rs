@@ -0,0 +1,329 @@
+//! WebView2 runtime bootstrap, run before Tauri starts.
+//!
+//! Without the Evergreen WebView2 runtime the main window cannot be created
+//! and the app exits on launch. A fixed-version runtime extracted next to the
+//! executable (`WebView2Runtime\msedgewebview2.exe`) is used when present;
+//! otherwise, if no Evergreen runtime is installed, the user is offered to
+//! download and run Microsoft's bootstrapper behind a small native progress
+//! window (there is no webview to show UI in yet).
+
+/// Make sure a WebView2 runtime is available; false when the app cannot start.
+pub fn ensure() -> bool {
+    runtime::ensure()
+}
+
+#[cfg(target_os = "windows")]
+mod runtime {
+    use log::{error, info, warn};
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+    use std::sync::Mutex;
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    /// Evergreen bootstrapper (small; it downloads the runtime itself)
+    const BOOTSTRAPPER_URL: &str = "https://go.microsoft.com/fwlink/p/?LinkId=2124703";
+    const DOWNLOAD_PAGE: &str = "https://developer.microsoft.com/microsoft-edge/webview2/";
+    const MAX_BOOTSTRAPPER_BYTES: u64 = 16 * 1024 * 1024;
+    /// Folder next to the executable holding a fixed-version runtime
+    const FIXED_RUNTIME_DIR: &str = "WebView2Runtime";
+    /// EdgeUpdate client key of the Evergreen runtime, on 64-bit then 32-bit Windows
+    const CLIENT_KEYS: [PCWSTR; 2] = [
+        w!("SOFTWARE\\WOW6432Node\\Microsoft\\EdgeUpdate\\Clients\\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}"),
+        w!("SOFTWARE\\Microsoft\\EdgeUpdate\\Clients\\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}"),
+    ];
+
+    const WM_MWP_BOOT_PROGRESS: u32 = WM_APP + 70;
+    const WM_MWP_BOOT_INSTALLING: u32 = WM_APP + 71;
+    const WM_MWP_BOOT_DONE: u32 = WM_APP + 72;
+    const PROGRESS_WIDTH: i32 = 420;
+    const PROGRESS_HEIGHT: i32 = 130;
+
+    static LABEL_HWND: AtomicIsize = AtomicIsize::new(0);
+    static BAR_HWND: AtomicIsize = AtomicIsize::new(0);
+    static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+    pub fn ensure() -> bool {
+        if let Some(dir) = fixed_runtime_dir() {
+            info!(
+                "[webview2] Using fixed-version runtime in {}",
+                dir.display()
+            );
+            std::env::set_var("WEBVIEW2_BROWSER_EXECUTABLE_FOLDER", &dir);
+            return true;
+        }
+        if let Some(version) = installed_version() {
+            info!("[webview2] Evergreen runtime {}", version);
+            return true;
+        }
+        warn!("[webview2] No WebView2 runtime found");
+        if !confirm() {
+            return false;
+        }
+        match install() {
+            Ok(()) if installed_version().is_some() => {
+                info!("[webview2] Runtime installed");
+                true
+            }
+            Ok(()) if CANCELLED.load(Ordering::SeqCst) => false,
+            Ok(()) => {
+                report_failure("The installer finished without installing the runtime.");
+                false
+            }
+            Err(e) => {
+                error!("[webview2] Bootstrap failed: {}", e);
+                report_failure(&e);
+                false
+            }
+        }
+    }
+
+    fn fixed_runtime_dir() -> Option<PathBuf> {
+        // An explicit override is honoured by WebView2 itself
+        if std::env::var_os("WEBVIEW2_BROWSER_EXECUTABLE_FOLDER").is_some() {
+            return None;
+        }
+        let dir = std::env::current_exe()
+            .ok()?
+            .parent()?
+            .join(FIXED_RUNTIME_DIR);
+        dir.join("msedgewebview2.exe").is_file().then_some(dir)
+    }
+
+    /// Installed Evergreen runtime version, per machine or per user.
+    fn installed_version() -> Option<String> {
+        use windows::Win32::System::Registry::{
+            RegGetValueW, HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ,
+        };
+
+        unsafe fn read(root: HKEY, subkey: PCWSTR) -> Option<String> {
+            let mut buf = [0u16; 64];
+            let mut size = std::mem::size_of_val(&buf) as u32;
+            RegGetValueW(
+                root,
+                subkey,
+                w!("pv"),
+                RRF_RT_REG_SZ,
+                None,
+                Some(buf.as_mut_ptr() as *mut _),
+                Some(&mut size),
+            )
+            .ok()
+            .ok()?;
+            let len = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+            let version = String::from_utf16_lossy(&buf[..len]);
+            // Uninstalls leave "0.0.0.0" behind
+            (!version.is_empty() && version != "0.0.0.0").then_some(version)
+        }
+
+        unsafe {
+            CLIENT_KEYS
+                .iter()
+                .find_map(|key| read(HKEY_LOCAL_MACHINE, *key))
+                .or_else(|| read(HKEY_CURRENT_USER, CLIENT_KEYS[1]))
+        }
+    }
+
+    fn confirm() -> bool {
+        unsafe {
+            MessageBoxW(
+                None,
+                w!("MyWallpaper needs the Microsoft Edge WebView2 Runtime, which is not installed.\n\nDownload and install it now?"),
+                w!("MyWallpaper"),
+                MB_OKCANCEL | MB_ICONINFORMATION,
+            ) == IDOK
+        }
+    }
+
+    fn report_failure(reason: &str) {
+        let text = HSTRING::from(format!(
+            "The WebView2 Runtime could not be installed: {}\n\nInstall it from {} and start MyWallpaper again.",
+            reason, DOWNLOAD_PAGE
+        ));
+        unsafe {
+            MessageBoxW(None, &text, w!("MyWallpaper"), MB_OK | MB_ICONERROR);
+        }
+    }
+
+    /// Download and run the bootstrapper while the progress window is shown.
+    fn install() -> Result<(), String> {
+        let hwnd = progress_window().map_err(|e| e.to_string())?;
+        let hwnd_raw = hwnd.0 as isize;
+        let outcome = Mutex::new(Ok(()));
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let hwnd = HWND(hwnd_raw as *mut _);
+                let result = download_and_run(hwnd);
+                *outcome.lock().unwrap_or_else(|e| e.into_inner()) = result;
+                unsafe {
+                    let _ = PostMessageW(hwnd, WM_MWP_BOOT_DONE, WPARAM(0), LPARAM(0));
+                }
+            });
+            unsafe {
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+            }
+        });
+        outcome.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn download_and_run(hwnd: HWND) -> Result<(), String> {
+        let path = std::env::temp_dir().join("MicrosoftEdgeWebview2Setup.exe");
+        let on_progress = |received: u64, total: Option<u64>| {
+            if let Some(total) = total.filter(|t| *t > 0) {
+                let percent = (received * 100 / total).min(100) as usize;
+                unsafe {
+                    let _ = PostMessageW(hwnd, WM_MWP_BOOT_PROGRESS, WPARAM(percent), LPARAM(0));
+                }
+            }
+            !CANCELLED.load(Ordering::SeqCst)
+        };
+        let result = crate::downloads::Download::new(BOOTSTRAPPER_URL, &path)
+            .max_bytes(MAX_BOOTSTRAPPER_BYTES)
+            .on_progress(&on_progress)
+            .run();
+        if CANCELLED.load(Ordering::SeqCst) {
+            let _ = std::fs::remove_file(&path);
+            return Ok(());
+        }
+        let received = result.map_err(|e| e.to_string())?.bytes;
+
+        unsafe {
+            let _ = PostMessageW(hwnd, WM_MWP_BOOT_INSTALLING, WPARAM(0), LPARAM(0));
+        }
+        info!("[webview2] Running bootstrapper ({} bytes)", received);
+        // Installs per user without elevation, per machine when elevated
+        let status = std::process::Command::new(&path)
+            .args(["/silent", "/install"])
+            .status()
+            .map_err(|e| e.to_string());
+        let _ = std::fs::remove_file(&path);
+        match status? {
+            s if s.success() => Ok(()),
+            s => Err(format!("Installer exited with {}", s)),
+        }
+    }
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        use windows::Win32::UI::Controls::{PBM_SETMARQUEE, PBM_SETPOS, PBS_MARQUEE};
+        let label = HWND(LABEL_HWND.load(Ordering::SeqCst) as *mut _);
+        let bar = HWND(BAR_HWND.load(Ordering::SeqCst) as *mut _);
+        match msg {
+            WM_MWP_BOOT_PROGRESS => {
+                let text = HSTRING::from(format!(
+                    "Downloading the WebView2 Runtime installer... {}%",
+                    wparam.0
+                ));
+                let _ = SetWindowTextW(label, &text);
+                SendMessageW(bar, PBM_SETPOS, wparam, LPARAM(0));
+                LRESULT(0)
+            }
+            WM_MWP_BOOT_INSTALLING => {
+                let _ = SetWindowTextW(label, w!("Installing the WebView2 Runtime..."));
+                let style = GetWindowLongW(bar, GWL_STYLE);
+                SetWindowLongW(bar, GWL_STYLE, style | PBS_MARQUEE as i32);
+                SendMessageW(bar, PBM_SETMARQUEE, WPARAM(1), LPARAM(0));
+                LRESULT(0)
+            }
+            WM_MWP_BOOT_DONE => {
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_CLOSE => {
+                // Cancels the download; a running installer is left to finish
+                CANCELLED.store(true, Ordering::SeqCst);
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_DESTROY => {
+                PostQuitMessage(0);
+                LRESULT(0)
+            }
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+
+    fn progress_window() -> windows::core::Result<HWND> {
+        use windows::Win32::Graphics::Gdi::{GetSysColorBrush, COLOR_BTNFACE};
+        use windows::Win32::UI::Controls::{
+            InitCommonControlsEx, ICC_PROGRESS_CLASS, INITCOMMONCONTROLSEX, PROGRESS_CLASSW,
+        };
+
+        unsafe {
+            let _ = InitCommonControlsEx(&INITCOMMONCONTROLSEX {
+                dwSize: std::mem::size_of::<INITCOMMONCONTROLSEX>() as u32,
+                dwICC: ICC_PROGRESS_CLASS,
+            });
+            let cls = w!("MWP_WebView2Bootstrap");
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                lpszClassName: cls,
+                hCursor: LoadCursorW(None, IDC_ARROW)?,
+                hbrBackground: GetSysColorBrush(COLOR_BTNFACE),
+                ..Default::default()
+            };
+            let _ = RegisterClassW(&wc);
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                cls,
+                w!("MyWallpaper"),
+                WS_CAPTION | WS_SYSMENU | WS_VISIBLE,
+                (GetSystemMetrics(SM_CXSCREEN) - PROGRESS_WIDTH) / 2,
+                (GetSystemMetrics(SM_CYSCREEN) - PROGRESS_HEIGHT) / 2,
+                PROGRESS_WIDTH,
+                PROGRESS_HEIGHT,
+                None,
+                None,
+                None,
+                None,
+            )?;
+            let label = CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                w!("STATIC"),
+                w!("Downloading the WebView2 Runtime installer..."),
+                WS_CHILD | WS_VISIBLE,
+                16,
+                14,
+                PROGRESS_WIDTH - 48,
+                20,
+                hwnd,
+                None,
+                None,
+                None,
+            )?;
+            let bar = CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                PROGRESS_CLASSW,
+                PCWSTR::null(),
+                WS_CHILD | WS_VISIBLE,
+                16,
+                44,
+                PROGRESS_WIDTH - 48,
+                18,
+                hwnd,
+                None,
+                None,
+                None,
+            )?;
+            LABEL_HWND.store(label.0 as isize, Ordering::SeqCst);
+            BAR_HWND.store(bar.0 as isize, Ordering::SeqCst);
+            Ok(hwnd)
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod runtime {
+    pub fn ensure() -> bool {
+        true
+    }
+}
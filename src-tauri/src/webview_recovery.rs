@@ -0,0 +1,135 @@
+//! WebView2 process-failure recovery.
+//!
+//! WebView2 renders in separate browser, renderer and GPU processes. When one
+//! of them dies the layer keeps a dead child HWND in the desktop hierarchy:
+//! black, frozen, or no longer receiving input. A failed renderer is reloaded
+//! in place; a failed browser process takes the whole webview with it, so the
+//! main window is rebuilt from its config. Either way the webview is attached
+//! to the desktop layer again and the page restores its wallpaper on load.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+/// The main window is being rebuilt: closing the old one must not exit the app
+static RECREATING: AtomicBool = AtomicBool::new(false);
+
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app);
+    #[cfg(target_os = "windows")]
+    wry::set_process_failed_handler(failure::on_process_failed);
+}
+
+/// True while the main window is being rebuilt after a browser process exit.
+pub fn is_recreating() -> bool {
+    RECREATING.load(Ordering::SeqCst)
+}
+
+#[cfg(target_os = "windows")]
+mod failure {
+    use super::*;
+    use log::{error, info, warn};
+    use std::time::Duration;
+
+    const MAIN_LABEL: &str = "main";
+    /// Let WebView2 settle (GPU relaunch, process teardown) before recovering
+    const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+    // COREWEBVIEW2_PROCESS_FAILED_KIND values
+    const BROWSER_PROCESS_EXITED: i32 = 0;
+    const RENDER_PROCESS_EXITED: i32 = 1;
+    const RENDER_PROCESS_UNRESPONSIVE: i32 = 2;
+    const GPU_PROCESS_EXITED: i32 = 6;
+
+    static RECOVERING: AtomicBool = AtomicBool::new(false);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Recovery {
+        /// Re-attach the existing webview (GPU process relaunched)
+        Reattach,
+        /// Reload the page, then re-attach
+        Reload,
+        /// Rebuild the main window, then attach it
+        Recreate,
+    }
+
+    /// Runs on the WebView2 UI thread.
+    pub fn on_process_failed(kind: i32) {
+        let recovery = match kind {
+            BROWSER_PROCESS_EXITED => Recovery::Recreate,
+            RENDER_PROCESS_EXITED | RENDER_PROCESS_UNRESPONSIVE => Recovery::Reload,
+            GPU_PROCESS_EXITED => Recovery::Reattach,
+            // Frame, utility and helper processes are restarted by WebView2
+            _ => {
+                info!("[webview_recovery] WebView2 process failed (kind {})", kind);
+                return;
+            }
+        };
+        warn!(
+            "[webview_recovery] WebView2 process failed (kind {}), recovering: {:?}",
+            kind, recovery
+        );
+        crate::crash::breadcrumb("webview", &format!("process failed (kind {})", kind));
+        if recovery == Recovery::Recreate {
+            // The composition controller died with the browser process
+            crate::window_layer::mouse_hook::reset_webview_handles();
+        }
+        if RECOVERING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // Off the UI thread: building a window waits on it
+        std::thread::spawn(move || {
+            std::thread::sleep(SETTLE_DELAY);
+            if let Err(e) = recover(recovery) {
+                error!("[webview_recovery] Recovery failed: {}", e);
+            }
+            RECOVERING.store(false, Ordering::SeqCst);
+        });
+    }
+
+    fn recover(recovery: Recovery) -> tauri::Result<()> {
+        use tauri::Manager;
+
+        let Some(app) = APP_HANDLE.get() else {
+            return Ok(());
+        };
+        let window = match app.get_webview_window(MAIN_LABEL) {
+            Some(window) if recovery == Recovery::Reattach => window,
+            Some(window) if recovery == Recovery::Reload => {
+                window.reload()?;
+                window
+            }
+            old => {
+                RECREATING.store(true, Ordering::SeqCst);
+                let rebuilt = rebuild(app, old);
+                RECREATING.store(false, Ordering::SeqCst);
+                rebuilt?
+            }
+        };
+        crate::window_layer::setup_desktop_window(&window);
+        window.show()?;
+        info!("[webview_recovery] Webview recovered");
+        Ok(())
+    }
+
+    /// Replace the main window with a fresh one built from its config.
+    fn rebuild(
+        app: &tauri::AppHandle,
+        old: Option<tauri::WebviewWindow>,
+    ) -> tauri::Result<tauri::WebviewWindow> {
+        let config = app
+            .config()
+            .app
+            .windows
+            .iter()
+            .find(|w| w.label == MAIN_LABEL)
+            .cloned()
+            .ok_or(tauri::Error::WindowNotFound)?;
+        if let Some(old) = old {
+            old.destroy()?;
+        }
+        let window = tauri::WebviewWindowBuilder::from_config(app, &config)?.build()?;
+        let _ = window.set_background_color(Some(tauri::webview::Color(0, 0, 0, 255)));
+        Ok(window)
+    }
+}
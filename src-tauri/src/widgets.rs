@@ -0,0 +1,482 @@
+//! Desktop widgets — small frameless webview windows pinned to the desktop.
+//!
+//! Each widget (clock, notes, system monitors…) loads its own URL in a
+//! borderless window kept directly above the desktop icons, below every
+//! regular window, like Rainmeter skins. Widgets are persisted in
+//! `widgets.json`. In edit mode the widget page calls `start_widget_drag` to
+//! move its window; positions are saved when edit mode ends and on exit. The
+//! tray lists the widgets (show/hide) and toggles edit mode.
+
+use crate::error::{AppError, AppResult};
+use crate::events::{AppEvent, EmitAppEvent};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::menu::{CheckMenuItemBuilder, Submenu, SubmenuBuilder};
+use tauri::{AppHandle, Manager};
+use typeshare::typeshare;
+
+const WIDGETS_FILE: &str = "widgets.json";
+/// Window label prefix, followed by the widget id
+pub const LABEL_PREFIX: &str = "widget-";
+const MAX_WIDGETS: usize = 32;
+const MAX_ID_LEN: usize = 64;
+const MAX_NAME_LEN: usize = 64;
+const MIN_SIZE: u32 = 48;
+const MENU_EDIT: &str = "widgets:edit";
+const MENU_TOGGLE_PREFIX: &str = "widgets:toggle:";
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopWidget {
+    /// Chosen by the frontend; letters, digits, `-` and `_`
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// Screen position in physical pixels
+    pub x: i32,
+    pub y: i32,
+    /// Size in physical pixels
+    pub width: u32,
+    pub height: u32,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetList {
+    pub widgets: Vec<DesktopWidget>,
+    /// Widgets can be dragged
+    #[serde(default, skip_deserializing)]
+    pub editing: bool,
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+static STORE: Mutex<Vec<DesktopWidget>> = Mutex::new(Vec::new());
+static EDITING: AtomicBool = AtomicBool::new(false);
+
+fn label(id: &str) -> String {
+    format!("{}{}", LABEL_PREFIX, id)
+}
+
+fn store() -> AppResult<std::sync::MutexGuard<'static, Vec<DesktopWidget>>> {
+    STORE
+        .lock()
+        .map_err(|_| AppError::Widget("Widget store poisoned".into()))
+}
+
+fn persist(widgets: &[DesktopWidget]) {
+    let list = WidgetList {
+        widgets: widgets.to_vec(),
+        editing: false,
+    };
+    if let Err(e) = crate::storage::save_json(WIDGETS_FILE, &list) {
+        error!("[widgets] Failed to save widgets: {}", e);
+    }
+}
+
+fn validate(widget: &DesktopWidget) -> AppResult<()> {
+    if widget.id.is_empty()
+        || widget.id.len() > MAX_ID_LEN
+        || !widget
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::Validation(format!(
+            "Invalid widget id: {}",
+            widget.id
+        )));
+    }
+    if widget.name.trim().is_empty() || widget.name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::Validation(format!(
+            "Widget name must be 1-{} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    if widget.width < MIN_SIZE || widget.height < MIN_SIZE {
+        return Err(AppError::Validation(format!(
+            "Widgets must be at least {}x{} pixels",
+            MIN_SIZE, MIN_SIZE
+        )));
+    }
+    let url = url::Url::parse(&widget.url)
+        .map_err(|e| AppError::Validation(format!("Invalid widget URL: {}", e)))?;
+    // Same allowlist as the wallpaper window; the navigation guard enforces it anyway
+    if !crate::navigation::is_allowed(&url) {
+        return Err(AppError::Validation(format!(
+            "Origin not allowed for widgets: {}",
+            widget.url
+        )));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Windows
+// ============================================================================
+
+fn create_window(app: &AppHandle, widget: &DesktopWidget) -> AppResult<()> {
+    let url = url::Url::parse(&widget.url)
+        .map_err(|e| AppError::Validation(format!("Invalid widget URL: {}", e)))?;
+    let mut builder =
+        tauri::WebviewWindowBuilder::new(app, label(&widget.id), tauri::WebviewUrl::External(url))
+            .title(&widget.name)
+            .decorations(false)
+            .transparent(true)
+            .shadow(false)
+            .resizable(false)
+            .skip_taskbar(true)
+            .focused(false)
+            .visible(false);
+    if let Some(args) = crate::hub::browser_args(app) {
+        builder = builder.additional_browser_args(&args);
+    }
+    let window = builder.build()?;
+    window.set_size(tauri::PhysicalSize::new(widget.width, widget.height))?;
+    window.set_position(tauri::PhysicalPosition::new(widget.x, widget.y))?;
+
+    // Track drags; saved when edit mode ends
+    let id = widget.id.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Moved(pos) = event {
+            if let Ok(mut widgets) = STORE.lock() {
+                if let Some(w) = widgets.iter_mut().find(|w| w.id == id) {
+                    w.x = pos.x;
+                    w.y = pos.y;
+                }
+            }
+        }
+    });
+
+    #[cfg(target_os = "windows")]
+    os::pin_to_desktop(&window);
+    window.show()?;
+    Ok(())
+}
+
+/// Bring the widget's window in line with its settings.
+fn sync_window(app: &AppHandle, widget: &DesktopWidget) -> AppResult<()> {
+    let existing = app.get_webview_window(&label(&widget.id));
+    match (existing, widget.visible) {
+        (Some(window), false) => window.close()?,
+        (None, true) => create_window(app, widget)?,
+        (Some(window), true) => {
+            if window.url()?.as_str() != widget.url {
+                window
+                    .navigate(url::Url::parse(&widget.url).map_err(|e| {
+                        AppError::Validation(format!("Invalid widget URL: {}", e))
+                    })?)?;
+            }
+            window.set_title(&widget.name)?;
+            window.set_size(tauri::PhysicalSize::new(widget.width, widget.height))?;
+            window.set_position(tauri::PhysicalPosition::new(widget.x, widget.y))?;
+        }
+        (None, false) => {}
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+pub fn list() -> WidgetList {
+    WidgetList {
+        widgets: STORE.lock().map(|w| w.clone()).unwrap_or_default(),
+        editing: is_editing(),
+    }
+}
+
+pub fn is_editing() -> bool {
+    EDITING.load(Ordering::SeqCst)
+}
+
+/// Insert or replace a widget (matched by id) and update its window.
+pub fn save(app: &AppHandle, widget: DesktopWidget) -> AppResult<()> {
+    validate(&widget)?;
+    {
+        let mut widgets = store()?;
+        match widgets.iter_mut().find(|w| w.id == widget.id) {
+            Some(existing) => *existing = widget.clone(),
+            None if widgets.len() >= MAX_WIDGETS => {
+                return Err(AppError::Widget(format!("At most {} widgets", MAX_WIDGETS)));
+            }
+            None => widgets.push(widget.clone()),
+        }
+        persist(&widgets);
+    }
+    sync_window(app, &widget)?;
+    crate::tray::refresh_menu(app);
+    Ok(())
+}
+
+pub fn delete(app: &AppHandle, id: &str) -> AppResult<()> {
+    {
+        let mut widgets = store()?;
+        let before = widgets.len();
+        widgets.retain(|w| w.id != id);
+        if widgets.len() == before {
+            return Err(AppError::Widget(format!("Unknown widget: {}", id)));
+        }
+        persist(&widgets);
+    }
+    if let Some(window) = app.get_webview_window(&label(id)) {
+        window.close()?;
+    }
+    crate::tray::refresh_menu(app);
+    Ok(())
+}
+
+pub fn set_visible(app: &AppHandle, id: &str, visible: bool) -> AppResult<()> {
+    let widget = {
+        let mut widgets = store()?;
+        let widget = widgets
+            .iter_mut()
+            .find(|w| w.id == id)
+            .ok_or_else(|| AppError::Widget(format!("Unknown widget: {}", id)))?;
+        widget.visible = visible;
+        let widget = widget.clone();
+        persist(&widgets);
+        widget
+    };
+    sync_window(app, &widget)?;
+    crate::tray::refresh_menu(app);
+    Ok(())
+}
+
+/// Enter or leave edit mode; leaving it saves the dragged positions.
+pub fn set_edit_mode(app: &AppHandle, editing: bool) -> AppResult<()> {
+    if EDITING.swap(editing, Ordering::SeqCst) == editing {
+        return Ok(());
+    }
+    if !editing {
+        persist(&store()?);
+    }
+    info!("[widgets] Edit mode {}", if editing { "on" } else { "off" });
+    app.emit_app_event(&AppEvent::WidgetEditMode { editing })?;
+    crate::tray::refresh_menu(app);
+    Ok(())
+}
+
+/// Start dragging the calling widget's window (edit mode only).
+pub fn start_drag(window: &tauri::WebviewWindow) -> AppResult<()> {
+    if !window.label().starts_with(LABEL_PREFIX) {
+        return Err(AppError::Widget("Not a widget window".into()));
+    }
+    if !is_editing() {
+        return Err(AppError::Widget(
+            "Widgets can only be moved in edit mode".into(),
+        ));
+    }
+    window.start_dragging()?;
+    Ok(())
+}
+
+/// Init script addition for widget windows (`__MW_INIT__.widget = id`).
+pub fn init_script(label: &str) -> Option<String> {
+    let id = label.strip_prefix(LABEL_PREFIX)?;
+    Some(format!(
+        "window.__MW_INIT__ && (window.__MW_INIT__.widget = {});",
+        serde_json::to_string(id).ok()?
+    ))
+}
+
+/// Load persisted widgets and open the visible ones.
+pub fn init(app: AppHandle) {
+    let list: WidgetList = crate::storage::load_json(WIDGETS_FILE);
+    info!("[widgets] Loaded {} widget(s)", list.widgets.len());
+    for widget in list.widgets.iter().filter(|w| w.visible) {
+        if let Err(e) = create_window(&app, widget) {
+            warn!("[widgets] Cannot open '{}': {}", widget.id, e);
+        }
+    }
+    if let Ok(mut widgets) = STORE.lock() {
+        *widgets = list.widgets;
+    }
+    crate::shutdown::on_exit("Saving widget layout", || {
+        if let Ok(widgets) = STORE.lock() {
+            persist(&widgets);
+        }
+    });
+    // The tray was built before the widgets were loaded
+    crate::tray::refresh_menu(&app);
+}
+
+/// Replace the widgets with those in `widgets.json` (after a backup restore),
+/// closing the windows of widgets that are gone. Invalid entries are dropped.
+pub fn reload(app: &AppHandle) -> AppResult<()> {
+    let list: WidgetList = crate::storage::load_json(WIDGETS_FILE);
+    let loaded: Vec<DesktopWidget> = list
+        .widgets
+        .into_iter()
+        .filter(|w| match validate(w) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("[widgets] Dropping restored widget '{}': {}", w.id, e);
+                false
+            }
+        })
+        .take(MAX_WIDGETS)
+        .collect();
+    let removed: Vec<String> = {
+        let mut widgets = store()?;
+        let removed = widgets
+            .iter()
+            .filter(|w| !loaded.iter().any(|l| l.id == w.id))
+            .map(|w| w.id.clone())
+            .collect();
+        *widgets = loaded.clone();
+        persist(&widgets);
+        removed
+    };
+    for id in removed {
+        if let Some(window) = app.get_webview_window(&label(&id)) {
+            window.close()?;
+        }
+    }
+    for widget in &loaded {
+        if let Err(e) = sync_window(app, widget) {
+            warn!("[widgets] Cannot open '{}': {}", widget.id, e);
+        }
+    }
+    info!("[widgets] Reloaded {} widget(s)", loaded.len());
+    crate::tray::refresh_menu(app);
+    Ok(())
+}
+
+// ============================================================================
+// Tray
+// ============================================================================
+
+/// "Widgets" tray submenu: one check item per widget plus edit mode.
+pub fn tray_submenu(app: &AppHandle) -> tauri::Result<Submenu<tauri::Wry>> {
+    let widgets = STORE.lock().map(|w| w.clone()).unwrap_or_default();
+    let mut submenu = SubmenuBuilder::new(app, "Widgets");
+    for widget in &widgets {
+        submenu = submenu.item(
+            &CheckMenuItemBuilder::with_id(
+                format!("{}{}", MENU_TOGGLE_PREFIX, widget.id),
+                &widget.name,
+            )
+            .checked(widget.visible)
+            .build(app)?,
+        );
+    }
+    if !widgets.is_empty() {
+        submenu = submenu.separator();
+    }
+    submenu
+        .item(
+            &CheckMenuItemBuilder::with_id(MENU_EDIT, "Edit widget layout")
+                .checked(is_editing())
+                .enabled(!widgets.is_empty())
+                .build(app)?,
+        )
+        .build()
+}
+
+/// Handle a tray click. Returns false for ids this module doesn't own.
+pub fn handle_menu_event(app: &AppHandle, id: &str) -> bool {
+    let result = if id == MENU_EDIT {
+        set_edit_mode(app, !is_editing())
+    } else if let Some(widget_id) = id.strip_prefix(MENU_TOGGLE_PREFIX) {
+        let visible = STORE
+            .lock()
+            .ok()
+            .and_then(|w| w.iter().find(|w| w.id == widget_id).map(|w| w.visible))
+            .unwrap_or(false);
+        set_visible(app, widget_id, !visible)
+    } else {
+        return false;
+    };
+    if let Err(e) = result {
+        warn!("[widgets] Menu action '{}' failed: {}", id, e);
+    }
+    true
+}
+
+// ============================================================================
+// Windows: desktop pinning
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+mod os {
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass};
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    const PIN_SUBCLASS_ID: usize = 0x5749_4447;
+
+    /// Keep the window directly above the desktop icons whenever something
+    /// (activation, a click) tries to raise it.
+    pub fn pin_to_desktop(window: &tauri::WebviewWindow) {
+        let Ok(hwnd) = window.hwnd() else {
+            return;
+        };
+        let hwnd = HWND(hwnd.0 as *mut _);
+        unsafe {
+            // No Alt+Tab entry
+            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 | WS_EX_TOOLWINDOW.0;
+            let _ = SetWindowLongW(hwnd, GWL_EXSTYLE, ex_style as i32);
+            let _ = SetWindowSubclass(hwnd, Some(pin_subclass_proc), PIN_SUBCLASS_ID, 0);
+            // Goes through the subclass, which redirects it above the desktop
+            let _ = SetWindowPos(
+                hwnd,
+                HWND_TOP,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            );
+        }
+    }
+
+    /// Top-level window holding the desktop icons.
+    unsafe fn desktop_host() -> HWND {
+        let host = crate::window_layer::desktop_icons_host();
+        if host != 0 {
+            return HWND(host as *mut _);
+        }
+        FindWindowW(windows::core::w!("Progman"), None).unwrap_or_default()
+    }
+
+    unsafe extern "system" fn pin_subclass_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        uid_subclass: usize,
+        _ref_data: usize,
+    ) -> LRESULT {
+        match msg {
+            WM_WINDOWPOSCHANGING if lparam.0 != 0 => {
+                let pos = &mut *(lparam.0 as *mut WINDOWPOS);
+                if !pos.flags.contains(SWP_NOZORDER) {
+                    match GetWindow(desktop_host(), GW_HWNDPREV) {
+                        Ok(prev) if prev != hwnd => pos.hwndInsertAfter = prev,
+                        // Already right above the desktop (or no desktop yet)
+                        _ => pos.flags |= SWP_NOZORDER,
+                    }
+                }
+            }
+            WM_NCDESTROY => {
+                let _ = RemoveWindowSubclass(hwnd, Some(pin_subclass_proc), uid_subclass);
+            }
+            _ => {}
+        }
+        DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+}
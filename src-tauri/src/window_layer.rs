@@ -17,11 +17,343 @@ static IS_SESSION_ACTIVE: AtomicBool = AtomicBool::new(true);
 static WATCHDOG_PARENT: AtomicIsize = AtomicIsize::new(0);
 #[cfg(target_os = "windows")]
 static INTERFACE_MODE: AtomicBool = AtomicBool::new(false);
+/// Window our layer parent must stay behind (see `DesktopDetection::zorder_anchor`)
+#[cfg(target_os = "windows")]
+static ZORDER_ANCHOR: AtomicIsize = AtomicIsize::new(0);
+#[cfg(target_os = "windows")]
+static SHOW_DESKTOP_ACTIVE: AtomicBool = AtomicBool::new(false);
+#[cfg(target_os = "windows")]
+static SHOW_DESKTOP_WATCHING: AtomicBool = AtomicBool::new(false);
+#[cfg(target_os = "windows")]
+static FIT_TO_WORK_AREA: AtomicBool = AtomicBool::new(false);
+/// Monitor the layer is restricted to (`--monitor`), -1 = all monitors
+#[cfg(target_os = "windows")]
+static LAYER_MONITOR: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(-1);
+#[cfg(target_os = "windows")]
+static WORK_AREA_WATCHING: AtomicBool = AtomicBool::new(false);
+#[cfg(target_os = "windows")]
+static PAUSE_ON_REMOTE_SESSION: AtomicBool = AtomicBool::new(true);
+/// Hook threads and watchdogs started; a re-attached webview reuses them
+#[cfg(target_os = "windows")]
+static LAYER_ATTACHED: AtomicBool = AtomicBool::new(false);
+/// Monitors as of the last `monitors-changed` report
+#[cfg(target_os = "windows")]
+static KNOWN_MONITORS: std::sync::Mutex<Vec<crate::system_monitor::DisplayInfo>> =
+    std::sync::Mutex::new(Vec::new());
+#[cfg(target_os = "windows")]
+static APP_HANDLE: std::sync::OnceLock<tauri::AppHandle> = std::sync::OnceLock::new();
+// Page rectangles whose input is never delivered to the wallpaper, in any mode
+static PASSTHROUGH_REGIONS: std::sync::RwLock<Vec<LayerRect>> = std::sync::RwLock::new(Vec::new());
+const MAX_PASSTHROUGH_REGIONS: usize = 64;
+
+/// Snapshot of the desktop input pipeline, for remote debugging of hover/scroll issues.
+#[typeshare::typeshare]
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputDiagnostics {
+    /// "mouseHook" or "rawInput"
+    pub backend: String,
+    pub hook_installed: bool,
+    pub hook_thread_alive: bool,
+    pub session_active: bool,
+    pub interface_mode: bool,
+    /// Mouse events seen by the hook or the Raw Input handler
+    pub events_seen: u64,
+    /// Events swallowed (not passed to the next hook / Explorer)
+    pub suppressed: u64,
+    /// Routing state transitions: into IDLE (pass-through), NATIVE (icons), WEB (webview)
+    pub transitions_to_idle: u64,
+    pub transitions_to_native: u64,
+    pub transitions_to_web: u64,
+    /// Current routing state: "idle", "native" or "web"
+    pub route: String,
+    /// Mouse messages posted to the dispatch window but not yet handled
+    pub dispatch_queue_depth: u64,
+    pub dispatch_dropped: u64,
+    /// Hook re-installs / thread restarts done by the watchdog
+    pub hook_recoveries: u64,
+    /// Milliseconds since the hook last saw an event
+    pub last_hook_event_ms: Option<u32>,
+    /// Most recent input errors, oldest first
+    pub last_errors: Vec<String>,
+}
+
+/// Result of the last desktop detection, for diagnostics bundles.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopDetectionReport {
+    /// "24h2" (SHELLDLL_DefView under Progman), "legacy" (inside a WorkerW)
+    /// or "progman" (no WorkerW found, injected into Progman)
+    pub layout: String,
+    pub progman: String,
+    pub target_parent: String,
+    /// Window class of `target_parent` (WorkerW or Progman)
+    pub target_parent_class: String,
+    pub syslistview: String,
+    pub zorder_anchor: String,
+    pub explorer_pid: u32,
+    pub virtual_width: i32,
+    pub virtual_height: i32,
+    /// Unix timestamp (seconds) of the detection
+    pub detected_at: u64,
+}
+
+/// One window of the shell desktop hierarchy.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopWindowNode {
+    pub hwnd: String,
+    pub parent: String,
+    pub class_name: String,
+    pub visible: bool,
+    pub rect: [i32; 4],
+}
+
+/// Rectangle in page pixels: physical pixels relative to the wallpaper
+/// window's top-left corner (divide by `devicePixelRatio` for CSS pixels).
+#[typeshare::typeshare]
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[typeshare::typeshare]
+#[derive(Debug, Clone, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorWorkArea {
+    /// Index in `DisplayInfo` order
+    pub monitor: u32,
+    pub primary: bool,
+    pub bounds: LayerRect,
+    /// Bounds minus the taskbar and other app bars
+    pub work_area: LayerRect,
+}
+
+/// User session transitions, reported as `session-state`.
+#[typeshare::typeshare]
+#[derive(Debug, Clone, Copy, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionState {
+    Locked,
+    Unlocked,
+    /// The session was attached to a Remote Desktop client
+    Remote,
+    /// The session was attached back to the physical console
+    Console,
+    Logon,
+    Logoff,
+}
+
+/// Layer state for the settings UI and debugging.
+#[typeshare::typeshare]
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLayerState {
+    /// "desktop" (injected), "windowed" (`--windowed`) or "detached"
+    pub mode: String,
+    /// Interface mode: the wallpaper receives input instead of the desktop
+    pub interactive: bool,
+    pub icons_hidden: bool,
+    /// Detection layout ("24h2", "legacy", "progman"); None before injection
+    pub layout: Option<String>,
+    /// Window class of the injection parent
+    pub parent_class: Option<String>,
+    /// Why the injection check fails; None when healthy
+    pub injection_error: Option<String>,
+    pub fit_to_work_area: bool,
+    pub monitors: Vec<MonitorLayerStatus>,
+    /// Unix timestamp (seconds) of the last re-injection
+    pub last_recovery_at: Option<u64>,
+    /// The session is displayed over Remote Desktop
+    pub remote_session: bool,
+}
+
+#[typeshare::typeshare]
+#[derive(Debug, Clone, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorLayerStatus {
+    /// Index in `DisplayInfo` order
+    pub monitor: u32,
+    pub primary: bool,
+    /// Covered by the wallpaper (every monitor unless `--monitor` is set)
+    pub covered: bool,
+    /// Screen coordinates
+    pub bounds: LayerRect,
+}
+
+#[cfg(target_os = "windows")]
+static LAST_DETECTION: std::sync::Mutex<Option<DesktopDetectionReport>> =
+    std::sync::Mutex::new(None);
+/// Unix timestamp (seconds) of the last re-injection, 0 = never
+#[cfg(target_os = "windows")]
+static LAST_RECOVERY: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
 
 // ==============================================================================
 // Public API
 // ==============================================================================
 
+#[tauri::command]
+pub fn get_input_diagnostics() -> InputDiagnostics {
+    #[cfg(target_os = "windows")]
+    {
+        mouse_hook::diagnostics()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        InputDiagnostics::default()
+    }
+}
+
+#[tauri::command]
+pub fn get_window_layer() -> WindowLayerState {
+    let windowed = crate::cli::launch_options().windowed;
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+
+        let detection = last_detection();
+        let injection_error = (!windowed).then(check_injection).and_then(Result::err);
+        let mode = if windowed {
+            "windowed"
+        } else if LAYER_ATTACHED.load(Ordering::SeqCst)
+            && WATCHDOG_PARENT.load(Ordering::SeqCst) != 0
+        {
+            "desktop"
+        } else {
+            "detached"
+        };
+        let slv = mouse_hook::get_syslistview_hwnd();
+        let icons_hidden = slv != 0 && unsafe { !IsWindowVisible(HWND(slv as *mut _)).as_bool() };
+        let only = usize::try_from(LAYER_MONITOR.load(Ordering::SeqCst)).ok();
+        let monitors = monitor_work_rects()
+            .iter()
+            .enumerate()
+            .map(|(i, (b, _, primary))| MonitorLayerStatus {
+                monitor: i as u32,
+                primary: *primary,
+                covered: !windowed && only.is_none_or(|o| o == i),
+                bounds: LayerRect {
+                    x: b.left,
+                    y: b.top,
+                    width: b.right - b.left,
+                    height: b.bottom - b.top,
+                },
+            })
+            .collect();
+        let last_recovery = LAST_RECOVERY.load(Ordering::SeqCst);
+        WindowLayerState {
+            mode: mode.to_string(),
+            interactive: is_interface_mode(),
+            icons_hidden,
+            layout: detection.as_ref().map(|d| d.layout.clone()),
+            parent_class: detection.map(|d| d.target_parent_class),
+            injection_error,
+            fit_to_work_area: FIT_TO_WORK_AREA.load(Ordering::SeqCst),
+            monitors,
+            last_recovery_at: (last_recovery != 0).then_some(last_recovery),
+            remote_session: is_remote_session(),
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    WindowLayerState {
+        mode: if windowed { "windowed" } else { "detached" }.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Check that the WebView is still parented into the detected desktop layer.
+#[cfg(target_os = "windows")]
+pub fn check_injection() -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{GetParent, IsWindow};
+    let wv = mouse_hook::get_webview_hwnd();
+    let parent = mouse_hook::get_target_parent_hwnd();
+    if wv == 0 || parent == 0 {
+        return Err("Desktop injection has not run".into());
+    }
+    unsafe {
+        if !IsWindow(HWND(parent as *mut _)).as_bool() {
+            return Err("Desktop layer window is gone (Explorer restarted?)".into());
+        }
+        let actual = GetParent(HWND(wv as *mut _)).unwrap_or_default();
+        if actual.0 as isize != parent {
+            return Err(format!(
+                "WebView parent is {} instead of the desktop layer",
+                hwnd_hex(actual)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Last desktop detection result (None before injection or off Windows).
+pub fn last_detection() -> Option<DesktopDetectionReport> {
+    #[cfg(target_os = "windows")]
+    {
+        LAST_DETECTION.lock().ok().and_then(|d| d.clone())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        None
+    }
+}
+
+/// Progman, the WorkerW windows and all their descendants (ours included).
+pub fn desktop_window_tree() -> Vec<DesktopWindowNode> {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            EnumChildWindows, EnumWindows, GetClassNameW, GetParent, GetWindowRect, IsWindowVisible,
+        };
+        const MAX_NODES: usize = 256;
+
+        unsafe fn node(hwnd: HWND) -> DesktopWindowNode {
+            let mut buf = [0u16; 128];
+            let len = GetClassNameW(hwnd, &mut buf).max(0) as usize;
+            let mut r = RECT::default();
+            let _ = GetWindowRect(hwnd, &mut r);
+            DesktopWindowNode {
+                hwnd: hwnd_hex(hwnd),
+                parent: hwnd_hex(GetParent(hwnd).unwrap_or_default()),
+                class_name: String::from_utf16_lossy(&buf[..len]),
+                visible: IsWindowVisible(hwnd).as_bool(),
+                rect: [r.left, r.top, r.right, r.bottom],
+            }
+        }
+        unsafe extern "system" fn child_cb(hwnd: HWND, lp: LPARAM) -> BOOL {
+            let nodes = &mut *(lp.0 as *mut Vec<DesktopWindowNode>);
+            if nodes.len() >= MAX_NODES {
+                return BOOL(0);
+            }
+            nodes.push(node(hwnd));
+            BOOL(1)
+        }
+        unsafe extern "system" fn top_cb(hwnd: HWND, lp: LPARAM) -> BOOL {
+            if is_class_name(hwnd, "Progman") || is_class_name(hwnd, "WorkerW") {
+                let nodes = &mut *(lp.0 as *mut Vec<DesktopWindowNode>);
+                nodes.push(node(hwnd));
+                // Recurses into all descendants
+                let _ = EnumChildWindows(hwnd, Some(child_cb), lp);
+            }
+            BOOL(1)
+        }
+        let mut nodes: Vec<DesktopWindowNode> = Vec::new();
+        let _ = EnumWindows(Some(top_cb), LPARAM(&mut nodes as *mut _ as isize));
+        nodes.truncate(MAX_NODES);
+        nodes
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Vec::new()
+    }
+}
+
 #[allow(unused_variables)]
 pub fn setup_desktop_window(window: &tauri::WebviewWindow) {
     #[cfg(target_os = "windows")]
@@ -38,7 +370,6 @@ pub fn setup_desktop_window(window: &tauri::WebviewWindow) {
     }
 }
 
-#[tauri::command]
 #[allow(unused_variables)]
 pub fn set_desktop_icons_visible(visible: bool) -> crate::error::AppResult<()> {
     #[cfg(target_os = "windows")]
@@ -95,6 +426,191 @@ pub fn set_desktop_icons_visible(visible: bool) -> crate::error::AppResult<()> {
     Ok(())
 }
 
+/// Per-monitor bounds and work areas in page coordinates.
+#[tauri::command]
+pub fn get_work_areas() -> Vec<MonitorWorkArea> {
+    #[cfg(target_os = "windows")]
+    {
+        let monitors = monitor_work_rects();
+        let target = layer_target(&monitors);
+        to_work_areas(&monitors, &target)
+    }
+    #[cfg(not(target_os = "windows"))]
+    Vec::new()
+}
+
+/// Declare page rectangles the wallpaper never receives mouse input for, even
+/// in interface mode; input there goes to whatever is below. Replaces the
+/// previous set (an empty list clears it).
+#[tauri::command]
+pub fn set_passthrough_regions(regions: Vec<LayerRect>) -> crate::error::AppResult<()> {
+    use crate::error::AppError;
+    if regions.len() > MAX_PASSTHROUGH_REGIONS {
+        return Err(AppError::Validation(format!(
+            "At most {} passthrough regions are supported",
+            MAX_PASSTHROUGH_REGIONS
+        )));
+    }
+    if regions.iter().any(|r| r.width <= 0 || r.height <= 0) {
+        return Err(AppError::Validation(
+            "Passthrough regions need a positive size".into(),
+        ));
+    }
+    let count = regions.len();
+    *PASSTHROUGH_REGIONS
+        .write()
+        .map_err(|_| AppError::WindowLayer("Passthrough regions poisoned".into()))? = regions;
+    log::info!("[window_layer] {} passthrough region(s) set", count);
+    Ok(())
+}
+
+/// Restrict the layer to one monitor (None = the whole virtual desktop).
+/// Takes effect when the layer is next fitted.
+#[allow(unused_variables)]
+pub fn set_layer_monitor(monitor: Option<u32>) {
+    #[cfg(target_os = "windows")]
+    LAYER_MONITOR.store(
+        monitor.and_then(|m| i32::try_from(m).ok()).unwrap_or(-1),
+        Ordering::SeqCst,
+    );
+}
+
+/// Cover the work area (`true`) or the full monitor bounds with the wallpaper.
+#[allow(unused_variables)]
+pub fn set_fit_to_work_area(enabled: bool) {
+    #[cfg(target_os = "windows")]
+    if FIT_TO_WORK_AREA.swap(enabled, Ordering::SeqCst) != enabled {
+        fit_layer();
+    }
+}
+
+/// Hold a static frame while the session is remote (`true`), or animate anyway.
+#[allow(unused_variables)]
+pub fn set_pause_on_remote_session(enabled: bool) {
+    #[cfg(target_os = "windows")]
+    if PAUSE_ON_REMOTE_SESSION.swap(enabled, Ordering::SeqCst) != enabled {
+        update_remote_pause();
+    }
+}
+
+/// The session is displayed through a Remote Desktop client, not the console.
+#[cfg(target_os = "windows")]
+fn is_remote_session() -> bool {
+    use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_REMOTESESSION};
+    unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+}
+
+/// Animating over RDP costs bandwidth and renders badly: hold an automatic
+/// pause while the session is remote, release it once it is local again.
+/// Called at startup and on every remote/console connect.
+pub fn update_remote_pause() {
+    #[cfg(target_os = "windows")]
+    {
+        const PAUSE_SOURCE: &str = "remote session";
+        let hold = PAUSE_ON_REMOTE_SESSION.load(Ordering::SeqCst) && is_remote_session();
+        if let Err(e) = crate::desktop_actions::auto_pause(PAUSE_SOURCE, hold) {
+            log::warn!("[session] Cannot update remote-session pause: {}", e);
+        }
+    }
+}
+
+pub fn is_interface_mode() -> bool {
+    INTERFACE_MODE.load(Ordering::Relaxed)
+}
+
+/// False while the session is locked or switched away.
+#[cfg(target_os = "windows")]
+pub fn is_session_active() -> bool {
+    IS_SESSION_ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Switch between interface (overlay) mode and wallpaper mode.
+/// Returns true when interface mode was entered.
+pub fn toggle_interface_mode() -> crate::error::AppResult<bool> {
+    let entering = !is_interface_mode();
+    set_desktop_icons_visible(!entering)?;
+    Ok(entering)
+}
+
+/// Show/hide the desktop icons without changing the interaction mode.
+/// Returns the new visibility.
+pub fn toggle_desktop_icons() -> crate::error::AppResult<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            IsWindowVisible, ShowWindow, SW_HIDE, SW_SHOW,
+        };
+        let slv = mouse_hook::get_syslistview_hwnd();
+        if slv == 0 {
+            return Err(crate::error::AppError::WindowLayer(
+                "Desktop icons not found".into(),
+            ));
+        }
+        unsafe {
+            let h = HWND(slv as *mut _);
+            let visible = !IsWindowVisible(h).as_bool();
+            let _ = ShowWindow(h, if visible { SW_SHOW } else { SW_HIDE });
+            info!(
+                "[window_layer] Desktop icons {}",
+                if visible { "shown" } else { "hidden" }
+            );
+            Ok(visible)
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    Ok(true)
+}
+
+/// Top-level window holding the desktop icons (Progman on Win11 24H2+, the
+/// icon WorkerW before); 0 until the layer is injected.
+#[cfg(target_os = "windows")]
+pub fn desktop_icons_host() -> isize {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{GetAncestor, GA_ROOT};
+    let anchor = ZORDER_ANCHOR.load(Ordering::SeqCst);
+    if anchor == 0 {
+        return 0;
+    }
+    unsafe { GetAncestor(HWND(anchor as *mut _), GA_ROOT).0 as isize }
+}
+
+/// Refresh the desktop (same as F5 on the desktop).
+pub fn refresh_desktop() {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_KEYDOWN, WM_KEYUP};
+        const VK_F5: usize = 0x74;
+        let slv = mouse_hook::get_syslistview_hwnd();
+        if slv != 0 {
+            unsafe {
+                let h = HWND(slv as *mut _);
+                let _ = PostMessageW(h, WM_KEYDOWN, WPARAM(VK_F5), LPARAM(0));
+                let _ = PostMessageW(h, WM_KEYUP, WPARAM(VK_F5), LPARAM(0xC000_0001));
+            }
+        }
+    }
+}
+
+/// Open Explorer's own desktop menu at screen position `(x, y)`.
+#[allow(unused_variables)]
+pub fn show_native_desktop_menu(x: i32, y: i32) {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_CONTEXTMENU};
+        let slv = mouse_hook::get_syslistview_hwnd();
+        if slv != 0 {
+            let lp = ((x as i16 as u16 as u32) | ((y as i16 as u16 as u32) << 16)) as isize;
+            unsafe {
+                let h = HWND(slv as *mut _);
+                let _ = PostMessageW(h, WM_CONTEXTMENU, WPARAM(slv as usize), LPARAM(lp));
+            }
+        }
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn unhook_global(handle: &AtomicIsize, name: &str) {
     use windows::Win32::UI::WindowsAndMessaging::{UnhookWindowsHookEx, HHOOK};
@@ -133,26 +649,560 @@ pub fn restore_desktop_icons_and_unhook() {
     }
 }
 
+/// Hide the layer window and take it out of WorkerW/Progman so Explorer
+/// repaints its own wallpaper instead of the last rendered frame.
+pub fn detach_layer() {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::{SetParent, ShowWindow, SW_HIDE};
+
+        // Stop the zombie watchdog from re-injecting
+        WATCHDOG_PARENT.store(0, Ordering::SeqCst);
+        let wv = mouse_hook::get_webview_hwnd();
+        if wv == 0 {
+            return;
+        }
+        unsafe {
+            let hwnd = HWND(wv as *mut _);
+            let _ = ShowWindow(hwnd, SW_HIDE);
+            let _ = SetParent(hwnd, HWND::default());
+        }
+        info!("[window_layer] Layer window detached from the desktop");
+    }
+}
+
 // ==============================================================================
 // Windows: Helper Functions
 // ==============================================================================
 
-/// Zero-allocation UTF-16 class name comparison.
-/// CRITICAL for mouse hook performance — avoids heap allocations on the
-/// global Windows input thread where String::from_utf16_lossy would cause
-/// system-wide micro-stutters.
 #[cfg(target_os = "windows")]
-unsafe fn is_class_name(hwnd: windows::Win32::Foundation::HWND, expected: &str) -> bool {
-    use windows::Win32::UI::WindowsAndMessaging::GetClassNameW;
-    let mut buf = [0u16; 128];
-    let len = GetClassNameW(hwnd, &mut buf) as usize;
-    if len != expected.len() {
-        return false;
+fn hwnd_hex(hwnd: windows::Win32::Foundation::HWND) -> String {
+    format!("0x{:X}", hwnd.0 as usize)
+}
+
+#[cfg(target_os = "windows")]
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|t| t.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(target_os = "windows")]
+fn record_detection(d: &DesktopDetection) {
+    let layout = layout_name(d);
+    let report = DesktopDetectionReport {
+        layout: layout.to_string(),
+        progman: hwnd_hex(d.progman),
+        target_parent: hwnd_hex(d.target_parent),
+        target_parent_class: class_name(d.target_parent),
+        syslistview: hwnd_hex(d.syslistview),
+        zorder_anchor: hwnd_hex(d.zorder_anchor),
+        explorer_pid: d.explorer_pid,
+        virtual_width: d.v_width,
+        virtual_height: d.v_height,
+        detected_at: unix_now(),
+    };
+    info!("[window_layer] Desktop layout: {}", layout);
+    if let Ok(mut last) = LAST_DETECTION.lock() {
+        *last = Some(report);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn layout_name(d: &DesktopDetection) -> &'static str {
+    if d.shell_in_progman {
+        "24h2"
+    } else if d.target_parent == d.progman {
+        "progman"
+    } else {
+        "legacy"
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn class_name(hwnd: windows::Win32::Foundation::HWND) -> String {
+    use windows::Win32::UI::WindowsAndMessaging::GetClassNameW;
+    let mut class = [0u16; 64];
+    let len = unsafe { GetClassNameW(hwnd, &mut class) }.max(0) as usize;
+    String::from_utf16_lossy(&class[..len])
+}
+
+/// Zero-allocation UTF-16 class name comparison.
+/// CRITICAL for mouse hook performance — avoids heap allocations on the
+/// global Windows input thread where String::from_utf16_lossy would cause
+/// system-wide micro-stutters.
+#[cfg(target_os = "windows")]
+unsafe fn is_class_name(hwnd: windows::Win32::Foundation::HWND, expected: &str) -> bool {
+    use windows::Win32::UI::WindowsAndMessaging::GetClassNameW;
+    let mut buf = [0u16; 128];
+    let len = GetClassNameW(hwnd, &mut buf) as usize;
+    if len != expected.len() {
+        return false;
+    }
+    expected
+        .encode_utf16()
+        .zip(buf[..len].iter())
+        .all(|(a, b)| a == *b)
+}
+
+/// Monitor rectangles (screen coords) in enumeration order — same order as `DisplayInfo`.
+#[cfg(target_os = "windows")]
+pub fn monitor_rects() -> Vec<windows::Win32::Foundation::RECT> {
+    use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
+    unsafe extern "system" fn cb(_hm: HMONITOR, _hdc: HDC, rect: *mut RECT, lp: LPARAM) -> BOOL {
+        if lp.0 != 0 && !rect.is_null() {
+            (*(lp.0 as *mut Vec<RECT>)).push(rect.read());
+        }
+        BOOL(1)
+    }
+    let mut rects: Vec<RECT> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            HDC::default(),
+            None,
+            Some(cb),
+            LPARAM(&mut rects as *mut _ as isize),
+        );
+    }
+    rects
+}
+
+/// Rectangle of one monitor (or the whole virtual desktop when None) in
+/// WorkerW client coordinates, i.e. relative to the virtual desktop top-left.
+/// Used to place native render windows alongside the WebView.
+#[cfg(target_os = "windows")]
+pub fn layer_rect(
+    monitor: Option<u32>,
+) -> crate::error::AppResult<windows::Win32::Foundation::RECT> {
+    use crate::error::AppError;
+    use windows::Win32::Foundation::RECT;
+
+    let rects = monitor_rects();
+    if rects.is_empty() {
+        return Err(AppError::WindowLayer("No monitors found".into()));
+    }
+    let origin_x = rects.iter().map(|r| r.left).min().unwrap_or(0);
+    let origin_y = rects.iter().map(|r| r.top).min().unwrap_or(0);
+    let r = match monitor {
+        Some(i) => *rects
+            .get(i as usize)
+            .ok_or_else(|| AppError::Validation(format!("Unknown monitor index {}", i)))?,
+        None => RECT {
+            left: origin_x,
+            top: origin_y,
+            right: rects.iter().map(|r| r.right).max().unwrap_or(0),
+            bottom: rects.iter().map(|r| r.bottom).max().unwrap_or(0),
+        },
+    };
+    Ok(RECT {
+        left: r.left - origin_x,
+        top: r.top - origin_y,
+        right: r.right - origin_x,
+        bottom: r.bottom - origin_y,
+    })
+}
+
+// ==============================================================================
+// Windows: Work Area
+// ==============================================================================
+
+/// (bounds, work area, primary) per monitor, screen coords, `DisplayInfo` order.
+#[cfg(target_os = "windows")]
+fn monitor_work_rects() -> Vec<(
+    windows::Win32::Foundation::RECT,
+    windows::Win32::Foundation::RECT,
+    bool,
+)> {
+    use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO,
+    };
+    const MONITORINFOF_PRIMARY: u32 = 1;
+
+    unsafe extern "system" fn cb(hm: HMONITOR, _hdc: HDC, _rect: *mut RECT, lp: LPARAM) -> BOOL {
+        let out = &mut *(lp.0 as *mut Vec<(RECT, RECT, bool)>);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if GetMonitorInfoW(hm, &mut info).as_bool() {
+            out.push((
+                info.rcMonitor,
+                info.rcWork,
+                info.dwFlags & MONITORINFOF_PRIMARY != 0,
+            ));
+        }
+        BOOL(1)
+    }
+    let mut out: Vec<(RECT, RECT, bool)> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            HDC::default(),
+            None,
+            Some(cb),
+            LPARAM(&mut out as *mut _ as isize),
+        );
+    }
+    out
+}
+
+/// Screen rectangle the layer window covers: the union of the monitor bounds,
+/// or of the work areas when fitting to the work area, limited to the
+/// `--monitor` one when set.
+#[cfg(target_os = "windows")]
+fn layer_target(
+    monitors: &[(
+        windows::Win32::Foundation::RECT,
+        windows::Win32::Foundation::RECT,
+        bool,
+    )],
+) -> windows::Win32::Foundation::RECT {
+    use windows::Win32::Foundation::RECT;
+    let fit = FIT_TO_WORK_AREA.load(Ordering::SeqCst);
+    // An unknown `--monitor` index falls back to all monitors
+    let only = usize::try_from(LAYER_MONITOR.load(Ordering::SeqCst))
+        .ok()
+        .filter(|i| *i < monitors.len());
+    let rects = || {
+        monitors
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| only.is_none_or(|o| o == *i))
+            .map(|(_, (b, w, _))| if fit { *w } else { *b })
+    };
+    RECT {
+        left: rects().map(|r| r.left).min().unwrap_or(0),
+        top: rects().map(|r| r.top).min().unwrap_or(0),
+        right: rects().map(|r| r.right).max().unwrap_or(0),
+        bottom: rects().map(|r| r.bottom).max().unwrap_or(0),
+    }
+}
+
+/// Screen position of the layer window's top-left corner, the origin of page
+/// pixels.
+#[cfg(target_os = "windows")]
+pub fn layer_origin() -> (i32, i32) {
+    let target = layer_target(&monitor_work_rects());
+    (target.left, target.top)
+}
+
+#[cfg(target_os = "windows")]
+fn to_work_areas(
+    monitors: &[(
+        windows::Win32::Foundation::RECT,
+        windows::Win32::Foundation::RECT,
+        bool,
+    )],
+    target: &windows::Win32::Foundation::RECT,
+) -> Vec<MonitorWorkArea> {
+    let page = |r: &windows::Win32::Foundation::RECT| LayerRect {
+        x: r.left - target.left,
+        y: r.top - target.top,
+        width: r.right - r.left,
+        height: r.bottom - r.top,
+    };
+    monitors
+        .iter()
+        .enumerate()
+        .map(|(i, (bounds, work, primary))| MonitorWorkArea {
+            monitor: i as u32,
+            primary: *primary,
+            bounds: page(bounds),
+            work_area: page(work),
+        })
+        .collect()
+}
+
+/// Resize the layer window to the current monitors (and work areas, when
+/// enabled) and report the new work areas as `work-area-changed`.
+#[cfg(target_os = "windows")]
+fn fit_layer() {
+    use crate::events::{AppEvent, EmitAppEvent};
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumChildWindows, PostMessageW, SetWindowPos, SWP_FRAMECHANGED, SWP_NOACTIVATE,
+        SWP_NOZORDER,
+    };
+
+    let monitors = monitor_work_rects();
+    if monitors.is_empty() {
+        return;
+    }
+    let target = layer_target(&monitors);
+    let (w, h) = (target.right - target.left, target.bottom - target.top);
+    if w <= 0 || h <= 0 {
+        return;
+    }
+    // The layer parent spans the virtual desktop; its client origin is the
+    // top-left of the monitor bounds.
+    let origin_x = monitors.iter().map(|(b, _, _)| b.left).min().unwrap_or(0);
+    let origin_y = monitors.iter().map(|(b, _, _)| b.top).min().unwrap_or(0);
+
+    let wv = mouse_hook::get_webview_hwnd();
+    if wv != 0 {
+        unsafe {
+            let wv_h = HWND(wv as *mut _);
+            let _ = SetWindowPos(
+                wv_h,
+                HWND::default(),
+                target.left - origin_x,
+                target.top - origin_y,
+                w,
+                h,
+                SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+            );
+            unsafe extern "system" fn resize_child(child: HWND, lp: LPARAM) -> BOOL {
+                let (w, h) = *(lp.0 as *const (i32, i32));
+                let _ = SetWindowPos(
+                    child,
+                    HWND::default(),
+                    0,
+                    0,
+                    w,
+                    h,
+                    SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+                );
+                BOOL(1)
+            }
+            let size = (w, h);
+            let _ = EnumChildWindows(
+                wv_h,
+                Some(resize_child),
+                LPARAM(&size as *const _ as isize),
+            );
+            // WebView2 bounds are set on the dispatch thread
+            let dh = mouse_hook::get_dispatch_hwnd();
+            if dh != 0 {
+                let _ = PostMessageW(
+                    HWND(dh as *mut _),
+                    mouse_hook::WM_MWP_SETBOUNDS_PUB,
+                    WPARAM(w as usize),
+                    LPARAM(h as isize),
+                );
+            }
+        }
+    }
+    info!(
+        "[window_layer] Layer fitted to {}x{} at ({}, {}) (work area: {})",
+        w,
+        h,
+        target.left,
+        target.top,
+        FIT_TO_WORK_AREA.load(Ordering::SeqCst)
+    );
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit_app_event(&AppEvent::WorkAreaChanged {
+            areas: to_work_areas(&monitors, &target),
+        });
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn work_area_wnd_proc(
+    hwnd: windows::Win32::Foundation::HWND,
+    msg: u32,
+    wp: windows::Win32::Foundation::WPARAM,
+    lp: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::System::Power::POWERBROADCAST_SETTING;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DefWindowProcW, PBT_APMRESUMEAUTOMATIC, PBT_POWERSETTINGCHANGE, SPI_SETWORKAREA,
+        WM_DISPLAYCHANGE, WM_FONTCHANGE, WM_POWERBROADCAST, WM_SETTINGCHANGE,
+    };
+    if msg == WM_FONTCHANGE {
+        if let Some(app) = APP_HANDLE.get() {
+            crate::fonts::on_fonts_changed(app);
+        }
+        return windows::Win32::Foundation::LRESULT(0);
+    }
+    if msg == WM_POWERBROADCAST {
+        match wp.0 as u32 {
+            // Sent for every resume from sleep or hibernation
+            PBT_APMRESUMEAUTOMATIC => schedule_resume_recovery("resume from sleep"),
+            // Modern standby never suspends: the console display coming back is the wake
+            PBT_POWERSETTINGCHANGE if lp.0 != 0 => {
+                let setting = &*(lp.0 as *const POWERBROADCAST_SETTING);
+                if setting.PowerSetting == GUID_CONSOLE_DISPLAY_STATE && setting.DataLength >= 4 {
+                    let state = u32::from_le_bytes(std::ptr::read_unaligned(
+                        setting.Data.as_ptr() as *const [u8; 4]
+                    ));
+                    let was_off = DISPLAY_OFF.swap(state == 0, Ordering::SeqCst);
+                    if was_off && state != 0 {
+                        schedule_resume_recovery("display on");
+                    }
+                }
+            }
+            _ => {}
+        }
+        return windows::Win32::Foundation::LRESULT(1);
+    }
+    // Taskbar moves, resizes and auto-hide toggles all broadcast SPI_SETWORKAREA
+    if (msg == WM_SETTINGCHANGE && wp.0 == SPI_SETWORKAREA.0 as usize) || msg == WM_DISPLAYCHANGE
+    {
+        fit_layer();
+    }
+    // Also sent for refresh-rate and HDR switches, not just resolution
+    if msg == WM_DISPLAYCHANGE {
+        report_monitor_changes();
+    }
+    DefWindowProcW(hwnd, msg, wp, lp)
+}
+
+/// Emit `monitors-changed` when the monitor set or a mode (resolution,
+/// refresh rate, HDR) differs from the last report.
+#[cfg(target_os = "windows")]
+fn report_monitor_changes() {
+    use crate::events::{AppEvent, EmitAppEvent};
+
+    let monitors = crate::system_monitor::collect_display_info().unwrap_or_default();
+    {
+        let Ok(mut known) = KNOWN_MONITORS.lock() else {
+            return;
+        };
+        if *known == monitors {
+            return;
+        }
+        for m in &monitors {
+            if let Some(old) = known.iter().find(|k| k.name == m.name) {
+                if old.refresh_rate != m.refresh_rate {
+                    info!(
+                        "[window_layer] {} refresh rate {:?} -> {:?} Hz",
+                        m.name, old.refresh_rate, m.refresh_rate
+                    );
+                }
+            }
+        }
+        *known = monitors.clone();
+    }
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit_app_event(&AppEvent::MonitorsChanged { monitors });
+    }
+}
+
+/// `GUID_CONSOLE_DISPLAY_STATE`: 0 = off, 1 = on, 2 = dimmed
+#[cfg(target_os = "windows")]
+const GUID_CONSOLE_DISPLAY_STATE: windows::core::GUID =
+    windows::core::GUID::from_u128(0x6fe69556_704a_47a0_8f24_c28d936fda47);
+/// Explorer and DWM rebuild their windows for a moment after a wake
+#[cfg(target_os = "windows")]
+const RESUME_SETTLE: std::time::Duration = std::time::Duration::from_secs(2);
+#[cfg(target_os = "windows")]
+static DISPLAY_OFF: AtomicBool = AtomicBool::new(false);
+#[cfg(target_os = "windows")]
+static RESUME_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Run `recover_after_resume` once things have settled (coalesces bursts).
+#[cfg(target_os = "windows")]
+fn schedule_resume_recovery(reason: &'static str) {
+    if RESUME_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("[resume] {}, validating the layer", reason);
+    crate::crash::breadcrumb("layer", reason);
+    std::thread::spawn(|| {
+        std::thread::sleep(RESUME_SETTLE);
+        RESUME_PENDING.store(false, Ordering::SeqCst);
+        recover_after_resume();
+    });
+}
+
+/// After a wake the layer can be detached or black and the hook's cached
+/// windows stale: re-inject when needed, reset the bounds, re-install the
+/// mouse hook and force a repaint.
+#[cfg(target_os = "windows")]
+fn recover_after_resume() {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Gdi::{
+        RedrawWindow, RDW_ALLCHILDREN, RDW_ERASE, RDW_FRAME, RDW_INVALIDATE, RDW_UPDATENOW,
+    };
+
+    // Detached on purpose (shutdown) or never injected (`--windowed`)
+    if WATCHDOG_PARENT.load(Ordering::SeqCst) == 0 || ICONS_RESTORED.load(Ordering::SeqCst) {
+        return;
+    }
+    let _span = crate::trace::span("layer", "resume_recovery");
+    if let Err(reason) = check_injection() {
+        info!("[resume] {}, re-injecting", reason);
+        if let Err(e) = reinject_layer() {
+            error!("[resume] Re-injection failed: {}", e);
+            return;
+        }
+    }
+    fit_layer();
+    mouse_hook::reinstall_mouse_hook();
+    let wv = mouse_hook::get_webview_hwnd();
+    if wv != 0 {
+        unsafe {
+            let _ = RedrawWindow(
+                HWND(wv as *mut _),
+                None,
+                None,
+                RDW_INVALIDATE | RDW_ERASE | RDW_FRAME | RDW_ALLCHILDREN | RDW_UPDATENOW,
+            );
+        }
+    }
+    info!("[resume] Layer validated and repainted");
+}
+
+/// Hidden top-level window for work-area, display, power and font broadcasts,
+/// which message-only windows (like the dispatch window) never receive.
+#[cfg(target_os = "windows")]
+fn start_work_area_watch() {
+    if WORK_AREA_WATCHING.swap(true, Ordering::SeqCst) {
+        return;
     }
-    expected
-        .encode_utf16()
-        .zip(buf[..len].iter())
-        .all(|(a, b)| a == *b)
+    if let Ok(mut known) = KNOWN_MONITORS.lock() {
+        *known = crate::system_monitor::collect_display_info().unwrap_or_default();
+    }
+    std::thread::spawn(|| unsafe {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::System::Power::{
+            RegisterPowerSettingNotification, REGISTER_NOTIFICATION_FLAGS,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::{
+            CreateWindowExW, DispatchMessageW, GetMessageW, RegisterClassW, TranslateMessage,
+            MSG, WINDOW_STYLE, WNDCLASSW, WS_EX_TOOLWINDOW,
+        };
+        let cls = windows::core::w!("MWP_WorkAreaWatch");
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(work_area_wnd_proc),
+            lpszClassName: cls,
+            ..Default::default()
+        };
+        let _ = RegisterClassW(&wc);
+        let Ok(hwnd) = CreateWindowExW(
+            WS_EX_TOOLWINDOW,
+            cls,
+            windows::core::w!(""),
+            WINDOW_STYLE(0),
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            None,
+            None,
+        ) else {
+            error!("[window_layer] Failed to create work-area watch window");
+            WORK_AREA_WATCHING.store(false, Ordering::SeqCst);
+            return;
+        };
+        // Display on/off, for wakes from modern standby
+        let _ = RegisterPowerSettingNotification(
+            windows::Win32::Foundation::HANDLE(hwnd.0),
+            &GUID_CONSOLE_DISPLAY_STATE,
+            REGISTER_NOTIFICATION_FLAGS(0), // DEVICE_NOTIFY_WINDOW_HANDLE
+        );
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).into() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    });
 }
 
 // ==============================================================================
@@ -171,10 +1221,103 @@ struct DesktopDetection {
     zorder_anchor: windows::Win32::Foundation::HWND,
     v_width: i32,
     v_height: i32,
+    /// Win11 24H2+ layout (SHELLDLL_DefView directly under Progman)
+    shell_in_progman: bool,
+}
+
+/// Layout found by the last full scan (`desktop_detection.json`). Reused while
+/// the OS build is unchanged so startup and recovery skip the WorkerW spawn
+/// and the top-level window enumeration.
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DetectionCache {
+    os_build: String,
+    layout: String,
+    parent_class: String,
+}
+
+#[cfg(target_os = "windows")]
+const DETECTION_CACHE_FILE: &str = "desktop_detection.json";
+
+/// `CurrentBuild.UBR`, empty when unreadable (disables the cache).
+#[cfg(target_os = "windows")]
+fn os_build() -> String {
+    use windows::core::w;
+    use windows::Win32::System::Registry::{
+        RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD, RRF_RT_REG_SZ,
+    };
+    let key = w!("SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion");
+    let mut build = [0u16; 32];
+    let mut size = std::mem::size_of_val(&build) as u32;
+    let mut ubr: u32 = 0;
+    let mut ubr_size = std::mem::size_of::<u32>() as u32;
+    unsafe {
+        if RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key,
+            w!("CurrentBuild"),
+            RRF_RT_REG_SZ,
+            None,
+            Some(build.as_mut_ptr() as *mut _),
+            Some(&mut size),
+        )
+        .is_err()
+        {
+            return String::new();
+        }
+        let _ = RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key,
+            w!("UBR"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut ubr as *mut u32 as *mut _),
+            Some(&mut ubr_size),
+        );
+    }
+    let len = build.iter().position(|c| *c == 0).unwrap_or(build.len());
+    format!("{}.{}", String::from_utf16_lossy(&build[..len]), ubr)
 }
 
+/// Verify the cached layout first (no WorkerW spawn, no settle delay), then
+/// fall back to a full scan and cache its result.
 #[cfg(target_os = "windows")]
 fn detect_desktop() -> Result<DesktopDetection, crate::error::AppError> {
+    let _span = crate::trace::span("layer", "detect_desktop");
+    let build = os_build();
+    let cache: DetectionCache = crate::storage::load_json(DETECTION_CACHE_FILE);
+    if !build.is_empty() && cache.os_build == build {
+        match scan_desktop(false) {
+            Ok(d)
+                if layout_name(&d) == cache.layout
+                    && class_name(d.target_parent) == cache.parent_class =>
+            {
+                info!("[detect_desktop] Cached {} layout verified", cache.layout);
+                return Ok(d);
+            }
+            _ => info!("[detect_desktop] Cached layout did not match, running full scan"),
+        }
+    }
+
+    let d = scan_desktop(true)?;
+    if !build.is_empty() {
+        let cache = DetectionCache {
+            os_build: build,
+            layout: layout_name(&d).to_string(),
+            parent_class: class_name(d.target_parent),
+        };
+        if let Err(e) = crate::storage::save_json(DETECTION_CACHE_FILE, &cache) {
+            log::warn!("[detect_desktop] Cannot save detection cache: {}", e);
+        }
+    }
+    Ok(d)
+}
+
+/// Locate the desktop windows. `spawn_worker_w` asks Progman to create the
+/// wallpaper WorkerW first; without it the scan only sees existing windows.
+#[cfg(target_os = "windows")]
+fn scan_desktop(spawn_worker_w: bool) -> Result<DesktopDetection, crate::error::AppError> {
     use crate::error::AppError;
     use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT, WPARAM};
     use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
@@ -191,17 +1334,19 @@ fn detect_desktop() -> Result<DesktopDetection, crate::error::AppError> {
         // This is an undocumented Progman message discovered via reverse engineering;
         // it triggers creation of the WorkerW window behind the desktop icons.
         const PROGMAN_SPAWN_WORKERW: u32 = 0x052C;
-        let mut msg_result: usize = 0;
-        let _ = SendMessageTimeoutW(
-            progman,
-            PROGMAN_SPAWN_WORKERW,
-            WPARAM(0x0D),
-            LPARAM(1),
-            SMTO_NORMAL,
-            1000,
-            Some(&mut msg_result),
-        );
-        std::thread::sleep(std::time::Duration::from_millis(150));
+        if spawn_worker_w {
+            let mut msg_result: usize = 0;
+            let _ = SendMessageTimeoutW(
+                progman,
+                PROGMAN_SPAWN_WORKERW,
+                WPARAM(0x0D),
+                LPARAM(1),
+                SMTO_NORMAL,
+                1000,
+                Some(&mut msg_result),
+            );
+            std::thread::sleep(std::time::Duration::from_millis(150));
+        }
 
         let mut target_parent;
         let shell_for_slv;
@@ -344,6 +1489,7 @@ fn detect_desktop() -> Result<DesktopDetection, crate::error::AppError> {
             zorder_anchor,
             v_width: width,
             v_height: height,
+            shell_in_progman: !shell_view.is_invalid(),
         })
     }
 }
@@ -387,6 +1533,8 @@ fn apply_injection(our_hwnd: windows::Win32::Foundation::HWND, detection: &Deskt
     use windows::Win32::Foundation::HWND;
     use windows::Win32::UI::WindowsAndMessaging::*;
 
+    let _span = crate::trace::span("layer", "apply_injection");
+    ZORDER_ANCHOR.store(detection.zorder_anchor.0 as isize, Ordering::SeqCst);
     unsafe {
         if GetParent(our_hwnd).unwrap_or_default() == detection.target_parent {
             return;
@@ -466,19 +1614,7 @@ fn apply_injection(our_hwnd: windows::Win32::Foundation::HWND, detection: &Deskt
         // 7. Ensure WorkerW is BEHIND the icon layer so WindowFromPoint
         //    returns SysListView32, enabling fully native icon interactions
         //    (drag & drop, double-click, context menus, selection rectangle).
-        if !detection.zorder_anchor.is_invalid()
-            && detection.zorder_anchor != detection.target_parent
-        {
-            let _ = SetWindowPos(
-                detection.target_parent,
-                detection.zorder_anchor,
-                0,
-                0,
-                0,
-                0,
-                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
-            );
-        }
+        place_behind_anchor(detection.target_parent, detection.zorder_anchor);
 
         info!(
             "[apply_injection] Done. Parent=0x{:X}, Size={}x{}",
@@ -487,6 +1623,115 @@ fn apply_injection(our_hwnd: windows::Win32::Foundation::HWND, detection: &Deskt
     }
 }
 
+#[cfg(target_os = "windows")]
+fn place_behind_anchor(
+    target_parent: windows::Win32::Foundation::HWND,
+    anchor: windows::Win32::Foundation::HWND,
+) {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SetWindowPos, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+    };
+    if anchor.is_invalid() || anchor == target_parent {
+        return;
+    }
+    unsafe {
+        let _ = SetWindowPos(
+            target_parent,
+            anchor,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+        );
+    }
+}
+
+// ==============================================================================
+// Windows: Show Desktop (Win+D)
+// ==============================================================================
+
+// Win+D / "Show desktop" hands the foreground to the shell's desktop window
+// (WorkerW, or Progman on 24H2). Explorer reorders its desktop children while
+// doing so, which can lift our layer over the icons; re-assert the z-order on
+// every transition and tell the page so scenes can animate the reveal.
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn show_desktop_event_proc(
+    _hook: windows::Win32::UI::Accessibility::HWINEVENTHOOK,
+    _event: u32,
+    hwnd: windows::Win32::Foundation::HWND,
+    id_object: i32,
+    _id_child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    use crate::events::{AppEvent, EmitAppEvent};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::OBJID_WINDOW;
+
+    if id_object != OBJID_WINDOW.0 || hwnd.is_invalid() {
+        return;
+    }
+    let active = is_class_name(hwnd, "WorkerW") || is_class_name(hwnd, "Progman");
+    if SHOW_DESKTOP_ACTIVE.swap(active, Ordering::SeqCst) == active {
+        return;
+    }
+
+    let parent = mouse_hook::get_target_parent_hwnd();
+    if parent != 0 {
+        place_behind_anchor(
+            HWND(parent as *mut _),
+            HWND(ZORDER_ANCHOR.load(Ordering::SeqCst) as *mut _),
+        );
+    }
+    info!(
+        "[window_layer] Show desktop {}",
+        if active { "entered" } else { "left" }
+    );
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit_app_event(&AppEvent::ShowDesktop { active });
+    }
+}
+
+/// Watch foreground changes for show-desktop transitions (once per process).
+#[cfg(target_os = "windows")]
+fn start_show_desktop_watch() {
+    if SHOW_DESKTOP_WATCHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| unsafe {
+        use windows::Win32::Foundation::{HMODULE, HWND};
+        use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            DispatchMessageW, GetMessageW, TranslateMessage, EVENT_SYSTEM_FOREGROUND, MSG,
+            WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS,
+        };
+
+        // Out-of-context hooks are delivered through this thread's message loop
+        let hook = SetWinEventHook(
+            EVENT_SYSTEM_FOREGROUND,
+            EVENT_SYSTEM_FOREGROUND,
+            HMODULE::default(),
+            Some(show_desktop_event_proc),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        );
+        if hook.is_invalid() {
+            error!("[window_layer] SetWinEventHook(EVENT_SYSTEM_FOREGROUND) failed");
+            SHOW_DESKTOP_WATCHING.store(false, Ordering::SeqCst);
+            return;
+        }
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).into() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+        let _ = UnhookWinEvent(hook);
+    });
+}
+
 // ==============================================================================
 // Windows: Initialization
 // ==============================================================================
@@ -495,11 +1740,13 @@ fn apply_injection(our_hwnd: windows::Win32::Foundation::HWND, detection: &Deskt
 fn ensure_in_worker_w(window: &tauri::WebviewWindow) -> crate::error::AppResult<()> {
     use windows::Win32::Foundation::HWND;
 
+    let _span = crate::trace::span("layer", "ensure_in_worker_w");
     let _ = window.set_ignore_cursor_events(false);
     let our_hwnd_raw = window.hwnd()?;
     let our_hwnd = HWND(our_hwnd_raw.0 as *mut _);
 
     let detection = detect_desktop()?;
+    record_detection(&detection);
 
     mouse_hook::set_webview_hwnd(our_hwnd.0 as isize);
     mouse_hook::set_target_parent_hwnd(detection.target_parent.0 as isize);
@@ -509,7 +1756,10 @@ fn ensure_in_worker_w(window: &tauri::WebviewWindow) -> crate::error::AppResult<
         mouse_hook::set_syslistview_hwnd(detection.syslistview.0 as isize);
     }
     apply_injection(our_hwnd, &detection);
-    mouse_hook::init_dispatch_window();
+    let first_attach = !LAYER_ATTACHED.swap(true, Ordering::SeqCst);
+    if first_attach {
+        mouse_hook::init_dispatch_window();
+    }
 
     let (w, h) = (detection.v_width, detection.v_height);
     let our_hwnd_isize = our_hwnd.0 as isize;
@@ -600,14 +1850,30 @@ fn ensure_in_worker_w(window: &tauri::WebviewWindow) -> crate::error::AppResult<
         }
         if !found {
             error!("[window_layer] Timed out waiting for composition controller (1s)");
+        } else if FIT_TO_WORK_AREA.load(Ordering::SeqCst)
+            || LAYER_MONITOR.load(Ordering::SeqCst) >= 0
+        {
+            fit_layer();
         }
     });
 
+    WATCHDOG_PARENT.store(detection.target_parent.0 as isize, Ordering::SeqCst);
+    if !first_attach {
+        LAST_RECOVERY.store(unix_now(), Ordering::SeqCst);
+        info!("[window_layer] Webview re-attached to the desktop layer");
+        return Ok(());
+    }
+
     mouse_hook::start_hook_thread();
+    {
+        use tauri::Manager;
+        let _ = APP_HANDLE.set(window.app_handle().clone());
+        mouse_hook::start_hook_watchdog(window.app_handle().clone());
+    }
+    start_show_desktop_watch();
+    start_work_area_watch();
 
     // Zombie window watchdog: re-detects desktop if parent HWND becomes stale
-    WATCHDOG_PARENT.store(detection.target_parent.0 as isize, Ordering::SeqCst);
-    let watchdog_our = our_hwnd.0 as isize;
     std::thread::spawn(move || {
         use std::time::Duration;
         use windows::Win32::UI::WindowsAndMessaging::IsWindow;
@@ -620,20 +1886,8 @@ fn ensure_in_worker_w(window: &tauri::WebviewWindow) -> crate::error::AppResult<
             unsafe {
                 if !IsWindow(HWND(parent_raw as *mut _)).as_bool() {
                     info!("[watchdog] Parent HWND stale, re-detecting desktop...");
-                    // Invalidate cached explorer handle (PID may have changed)
-                    mouse_hook::invalidate_proc_cache_pub();
-                    match detect_desktop() {
-                        Ok(d) => {
-                            mouse_hook::set_target_parent_hwnd(d.target_parent.0 as isize);
-                            mouse_hook::set_progman_hwnd(d.progman.0 as isize);
-                            mouse_hook::set_explorer_pid(d.explorer_pid);
-                            if !d.syslistview.is_invalid() {
-                                mouse_hook::set_syslistview_hwnd(d.syslistview.0 as isize);
-                            }
-                            apply_injection(HWND(watchdog_our as *mut _), &d);
-                            WATCHDOG_PARENT.store(d.target_parent.0 as isize, Ordering::SeqCst);
-                            info!("[watchdog] Re-injection done");
-                        }
+                    match reinject_layer() {
+                        Ok(()) => info!("[watchdog] Re-injection done"),
                         Err(e) => error!("[watchdog] Re-detection failed: {}", e),
                     }
                 }
@@ -644,6 +1898,27 @@ fn ensure_in_worker_w(window: &tauri::WebviewWindow) -> crate::error::AppResult<
     Ok(())
 }
 
+/// Re-detect the desktop and move the layer into the (new) desktop parent.
+#[cfg(target_os = "windows")]
+fn reinject_layer() -> crate::error::AppResult<()> {
+    use windows::Win32::Foundation::HWND;
+
+    // Invalidate cached explorer handle (PID may have changed)
+    mouse_hook::invalidate_proc_cache_pub();
+    let d = detect_desktop()?;
+    record_detection(&d);
+    mouse_hook::set_target_parent_hwnd(d.target_parent.0 as isize);
+    mouse_hook::set_progman_hwnd(d.progman.0 as isize);
+    mouse_hook::set_explorer_pid(d.explorer_pid);
+    if !d.syslistview.is_invalid() {
+        mouse_hook::set_syslistview_hwnd(d.syslistview.0 as isize);
+    }
+    apply_injection(HWND(mouse_hook::get_webview_hwnd() as *mut _), &d);
+    WATCHDOG_PARENT.store(d.target_parent.0 as isize, Ordering::SeqCst);
+    LAST_RECOVERY.store(unix_now(), Ordering::SeqCst);
+    Ok(())
+}
+
 // ==============================================================================
 // Windows: Mouse & Keyboard Hooks
 // ==============================================================================
@@ -683,6 +1958,8 @@ pub mod mouse_hook {
     static EXPLORER_PID: AtomicU32 = AtomicU32::new(0);
     static DESKTOP_CORE_HWND: AtomicIsize = AtomicIsize::new(0);
     static COMP_CONTROLLER_PTR: AtomicIsize = AtomicIsize::new(0);
+    /// WebView hidden under a native video; re-applied to a replacement webview
+    static WEBVIEW_HIDDEN: AtomicBool = AtomicBool::new(false);
     static DRAG_VK: AtomicIsize = AtomicIsize::new(0);
     static DISPATCH_HWND: AtomicIsize = AtomicIsize::new(0);
     static CHROME_RWHH: AtomicIsize = AtomicIsize::new(0);
@@ -709,6 +1986,42 @@ pub mod mouse_hook {
     // because ListView's hot-tracking checks real cursor pos via GetCursorPos)
     static CURRENT_HOT_ITEM: AtomicI32 = AtomicI32::new(-1);
     static LAST_HOVER_TICK: AtomicU64 = AtomicU64::new(0);
+    // Double-click on empty desktop → configurable action (see desktop_actions)
+    static DESKTOP_DBLCLICK_ENABLED: AtomicBool = AtomicBool::new(false);
+    static EMPTY_CLICK_TIME: AtomicU32 = AtomicU32::new(0);
+    static EMPTY_CLICK_X: AtomicI32 = AtomicI32::new(0);
+    static EMPTY_CLICK_Y: AtomicI32 = AtomicI32::new(0);
+    // Right-click on empty desktop → our own context menu (see context_menu)
+    static CUSTOM_CONTEXT_MENU_ENABLED: AtomicBool = AtomicBool::new(false);
+    static RCLICK_EMPTY: AtomicBool = AtomicBool::new(false);
+    // Desktop gestures: middle-button swipe in progress + L/R chord button-ups to swallow
+    static GESTURES_ENABLED: AtomicBool = AtomicBool::new(false);
+    static MIDDLE_SWIPE: AtomicBool = AtomicBool::new(false);
+    static SWIPE_START_X: AtomicI32 = AtomicI32::new(0);
+    static SWIPE_START_Y: AtomicI32 = AtomicI32::new(0);
+    static CHORD_PENDING_UPS: AtomicU32 = AtomicU32::new(0);
+    // Input backend: WH_MOUSE_LL (default) or Raw Input on the dispatch window
+    static RAW_INPUT_BACKEND: AtomicBool = AtomicBool::new(false);
+    static HOOK_THREAD_ID: AtomicU32 = AtomicU32::new(0);
+    // Hook health: last hook_proc call (GetTickCount) + cursor seen there,
+    // and the last ping answered by the hook thread's message loop
+    static HOOK_HEARTBEAT: AtomicU32 = AtomicU32::new(0);
+    static HOOK_LAST_X: AtomicI32 = AtomicI32::new(0);
+    static HOOK_LAST_Y: AtomicI32 = AtomicI32::new(0);
+    static HOOK_PONG: AtomicU32 = AtomicU32::new(0);
+    // Diagnostics counters (see get_input_diagnostics)
+    static EVENTS_SEEN: AtomicU64 = AtomicU64::new(0);
+    static SUPPRESSED: AtomicU64 = AtomicU64::new(0);
+    static ROUTE: AtomicU32 = AtomicU32::new(ROUTE_IDLE);
+    static ROUTE_TRANSITIONS: [AtomicU64; 3] =
+        [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+    static DISPATCH_POSTED: AtomicU64 = AtomicU64::new(0);
+    static DISPATCH_HANDLED: AtomicU64 = AtomicU64::new(0);
+    static DISPATCH_DROPPED: AtomicU64 = AtomicU64::new(0);
+    static HOOK_RECOVERIES: AtomicU64 = AtomicU64::new(0);
+    static LAST_ERRORS: std::sync::Mutex<std::collections::VecDeque<String>> =
+        std::sync::Mutex::new(std::collections::VecDeque::new());
+    const MAX_LAST_ERRORS: usize = 16;
     // Cached explorer process handle + remote buffer for cross-process LVM ops.
     // Avoids OpenProcess/VirtualAllocEx/VirtualFreeEx/CloseHandle per call.
     static CACHED_PROC_HANDLE: AtomicIsize = AtomicIsize::new(0);
@@ -719,10 +2032,36 @@ pub mod mouse_hook {
     const WM_APP: u32 = 0x8000;
     pub const WM_MWP_SETBOUNDS_PUB: u32 = WM_APP + 43;
     const WM_MWP_MOUSE: u32 = WM_APP + 42;
+    const WM_MWP_DESKTOP_DBLCLICK: u32 = WM_APP + 44;
+    const WM_MWP_DESKTOP_CONTEXTMENU: u32 = WM_APP + 45;
+    const WM_MWP_SET_INPUT_BACKEND: u32 = WM_APP + 46;
+    /// Thread message to the hook thread: wParam 1 = install mouse hook, 0 = remove
+    const WM_MWP_SET_MOUSE_HOOK: u32 = WM_APP + 47;
+    /// Thread message to the hook thread: watchdog liveness ping
+    const WM_MWP_HOOK_PING: u32 = WM_APP + 48;
+    /// wParam = DesktopGesture code
+    const WM_MWP_DESKTOP_GESTURE: u32 = WM_APP + 49;
+    /// wParam 1 = show the WebView, 0 = hide it
+    const WM_MWP_SET_WEBVIEW_VISIBLE: u32 = WM_APP + 50;
+    /// Minimum middle-button travel (px) for a swipe
+    const SWIPE_MIN_DISTANCE: i32 = 100;
+    const CHORD_UP_LEFT: u32 = 0x1;
+    const CHORD_UP_RIGHT: u32 = 0x2;
+    const HOOK_WATCHDOG_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+    /// Input seen by the system but not by the hook for this long → hook was removed
+    const HOOK_SILENCE_MS: u32 = 1500;
+
+    // Routing states of an input event
+    const ROUTE_IDLE: u32 = 0;
+    const ROUTE_NATIVE: u32 = 1;
+    const ROUTE_WEB: u32 = 2;
 
     pub fn set_webview_hwnd(h: isize) {
         WEBVIEW_HWND.store(h, Ordering::SeqCst);
     }
+    pub fn get_webview_hwnd() -> isize {
+        WEBVIEW_HWND.load(Ordering::SeqCst)
+    }
     pub fn set_syslistview_hwnd(h: isize) {
         SYSLISTVIEW_HWND.store(h, Ordering::SeqCst);
     }
@@ -738,8 +2077,14 @@ pub mod mouse_hook {
     pub fn get_syslistview_hwnd() -> isize {
         SYSLISTVIEW_HWND.load(Ordering::SeqCst)
     }
+    pub fn get_target_parent_hwnd() -> isize {
+        TARGET_PARENT_HWND.load(Ordering::SeqCst)
+    }
     pub fn set_comp_controller_ptr(p: isize) {
         COMP_CONTROLLER_PTR.store(p, Ordering::SeqCst);
+        if p != 0 && WEBVIEW_HIDDEN.load(Ordering::SeqCst) {
+            set_webview_visible(false);
+        }
     }
     pub fn get_comp_controller_ptr() -> isize {
         COMP_CONTROLLER_PTR.load(Ordering::SeqCst)
@@ -750,160 +2095,230 @@ pub mod mouse_hook {
     pub fn get_chrome_rwhh_raw() -> isize {
         CHROME_RWHH.load(Ordering::SeqCst)
     }
-    pub fn invalidate_proc_cache_pub() {
-        unsafe { invalidate_proc_cache() }
+    /// Forget the handles of a failed webview; the replacement's are picked up
+    /// when it is attached and first hovered.
+    pub fn reset_webview_handles() {
+        COMP_CONTROLLER_PTR.store(0, Ordering::SeqCst);
+        CHROME_RWHH.store(0, Ordering::SeqCst);
     }
-
-    pub fn unregister_session_notif() {
+    pub fn set_desktop_double_click_enabled(enabled: bool) {
+        DESKTOP_DBLCLICK_ENABLED.store(enabled, Ordering::SeqCst);
+    }
+    pub fn set_custom_context_menu_enabled(enabled: bool) {
+        CUSTOM_CONTEXT_MENU_ENABLED.store(enabled, Ordering::SeqCst);
+    }
+    pub fn set_desktop_gestures_enabled(enabled: bool) {
+        GESTURES_ENABLED.store(enabled, Ordering::SeqCst);
+        if !enabled {
+            MIDDLE_SWIPE.store(false, Ordering::SeqCst);
+        }
+    }
+    /// Show or hide the WebView on its thread (hidden while a native video covers it).
+    pub fn set_webview_visible(visible: bool) {
+        WEBVIEW_HIDDEN.store(!visible, Ordering::SeqCst);
         let dh = DISPATCH_HWND.load(Ordering::SeqCst);
         if dh != 0 {
             unsafe {
-                use windows::Win32::System::RemoteDesktop::WTSUnRegisterSessionNotification;
-                let _ = WTSUnRegisterSessionNotification(HWND(dh as *mut _));
+                let _ = PostMessageW(
+                    HWND(dh as *mut _),
+                    WM_MWP_SET_WEBVIEW_VISIBLE,
+                    WPARAM(visible as usize),
+                    LPARAM(0),
+                );
             }
         }
     }
-
-    /// Pack two i32 screen/client coordinates into a Windows lParam isize.
-    #[inline]
-    fn make_lparam(x: i32, y: i32) -> isize {
-        ((x as i16 as u16 as u32) | ((y as i16 as u16 as u32) << 16)) as isize
+    /// Switch between the WH_MOUSE_LL hook and the Raw Input backend at runtime.
+    pub fn set_raw_input_backend(enabled: bool) {
+        if RAW_INPUT_BACKEND.swap(enabled, Ordering::SeqCst) == enabled {
+            return;
+        }
+        log::info!(
+            "[input] Backend: {}",
+            if enabled { "raw input" } else { "mouse hook" }
+        );
+        unsafe {
+            let dh = DISPATCH_HWND.load(Ordering::SeqCst);
+            if dh != 0 {
+                let _ = PostMessageW(
+                    HWND(dh as *mut _),
+                    WM_MWP_SET_INPUT_BACKEND,
+                    WPARAM(enabled as usize),
+                    LPARAM(0),
+                );
+            }
+            let tid = HOOK_THREAD_ID.load(Ordering::SeqCst);
+            if tid != 0 {
+                let _ = PostThreadMessageW(
+                    tid,
+                    WM_MWP_SET_MOUSE_HOOK,
+                    WPARAM(!enabled as usize),
+                    LPARAM(0),
+                );
+            }
+        }
+    }
+    pub fn is_raw_input_backend() -> bool {
+        RAW_INPUT_BACKEND.load(Ordering::SeqCst)
     }
 
-    #[inline]
-    unsafe fn post_mouse(kind: i32, vk: i32, data: u32, x: i32, y: i32) {
-        // Encoding packs 3 fields into a single usize via bit shifts.
-        // The <<32 shift requires a 64-bit pointer width; on 32-bit it would silently lose data.
-        const _: () = assert!(
-            std::mem::size_of::<usize>() >= 8,
-            "mouse hook encoding requires 64-bit pointer width"
-        );
-        let dh = DISPATCH_HWND.load(Ordering::Relaxed);
-        if dh == 0 {
-            return;
+    /// Cursor position in screen coordinates: the last one seen by the hook
+    /// while it is installed, otherwise asked from the system.
+    pub fn cursor_pos() -> Option<(i32, i32)> {
+        let hooked = !is_raw_input_backend()
+            && crate::window_layer::HOOK_HANDLE_GLOBAL.load(Ordering::SeqCst) != 0;
+        if hooked {
+            return Some((
+                HOOK_LAST_X.load(Ordering::Relaxed),
+                HOOK_LAST_Y.load(Ordering::Relaxed),
+            ));
         }
-        let wp =
-            WPARAM((kind as u16 as usize) | ((vk as u16 as usize) << 16) | ((data as usize) << 32));
-        let lp = LPARAM(make_lparam(x, y));
-        let _ = PostMessageW(HWND(dh as *mut _), WM_MWP_MOUSE, wp, lp);
+        let mut pt = windows::Win32::Foundation::POINT::default();
+        unsafe { GetCursorPos(&mut pt) }.ok()?;
+        Some((pt.x, pt.y))
     }
 
-    const WM_WTSSESSION_CHANGE: u32 = 0x02B1;
-    const WTS_SESSION_LOCK: u32 = 0x7;
-    const WTS_SESSION_UNLOCK: u32 = 0x8;
-    const WM_DISPLAYCHANGE: u32 = 0x007E;
-    const WM_SETTINGCHANGE: u32 = 0x001A;
+    /// Log an input error and keep it for diagnostics.
+    fn record_error(message: String) {
+        log::error!("{}", message);
+        if let Ok(mut errors) = LAST_ERRORS.lock() {
+            if errors.len() == MAX_LAST_ERRORS {
+                errors.pop_front();
+            }
+            let ts = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            errors.push_back(format!("[{}] {}", ts, message));
+        }
+    }
 
-    /// Reload double-click / drag thresholds from system settings.
-    /// Called when WM_SETTINGCHANGE fires (user changed mouse prefs in Control Panel).
-    unsafe fn refresh_mouse_metrics() {
-        use windows::Win32::UI::Input::KeyboardAndMouse::GetDoubleClickTime;
-        use windows::Win32::UI::WindowsAndMessaging::{
-            GetSystemMetrics, SM_CXDOUBLECLK, SM_CXDRAG, SM_CYDOUBLECLK, SM_CYDRAG,
-        };
-        DBLCLICK_TIME.store(GetDoubleClickTime(), Ordering::Relaxed);
-        DBLCLICK_CX.store(GetSystemMetrics(SM_CXDOUBLECLK) / 2, Ordering::Relaxed);
-        DBLCLICK_CY.store(GetSystemMetrics(SM_CYDOUBLECLK) / 2, Ordering::Relaxed);
-        DRAG_THRESHOLD_CX.store(GetSystemMetrics(SM_CXDRAG), Ordering::Relaxed);
-        DRAG_THRESHOLD_CY.store(GetSystemMetrics(SM_CYDRAG), Ordering::Relaxed);
-        log::info!("[settings] Mouse metrics refreshed");
+    #[inline]
+    fn set_route(route: u32) {
+        if ROUTE.swap(route, Ordering::Relaxed) != route {
+            ROUTE_TRANSITIONS[route as usize].fetch_add(1, Ordering::Relaxed);
+        }
     }
 
-    /// Resize the WebView and update WebView2 controller bounds after a display change.
-    /// Called when WM_DISPLAYCHANGE fires (monitor plug/unplug or resolution change).
-    unsafe fn on_display_change() {
-        use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT};
-        use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
-        use windows::Win32::UI::WindowsAndMessaging::{
-            EnumChildWindows, SetWindowPos, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOZORDER,
-        };
+    /// Swallow the current hook event.
+    #[inline]
+    fn suppress() -> LRESULT {
+        SUPPRESSED.fetch_add(1, Ordering::Relaxed);
+        LRESULT(1)
+    }
 
-        // Compute virtual desktop bounds across all monitors
-        struct Bounds {
-            left: i32,
-            top: i32,
-            right: i32,
-            bottom: i32,
-        }
-        let mut b = Bounds {
-            left: i32::MAX,
-            top: i32::MAX,
-            right: i32::MIN,
-            bottom: i32::MIN,
+    pub fn diagnostics() -> crate::window_layer::InputDiagnostics {
+        use crate::window_layer::{
+            InputDiagnostics, HOOK_HANDLE_GLOBAL, INTERFACE_MODE, IS_SESSION_ACTIVE,
         };
-        unsafe extern "system" fn mon_cb(
-            _hm: HMONITOR,
-            _hdc: HDC,
-            rect: *mut RECT,
-            lp: LPARAM,
-        ) -> BOOL {
-            if lp.0 != 0 && !rect.is_null() {
-                let r = rect.read();
-                let b = &mut *(lp.0 as *mut Bounds);
-                b.left = b.left.min(r.left);
-                b.top = b.top.min(r.top);
-                b.right = b.right.max(r.right);
-                b.bottom = b.bottom.max(r.bottom);
+        let posted = DISPATCH_POSTED.load(Ordering::Relaxed);
+        let handled = DISPATCH_HANDLED.load(Ordering::Relaxed);
+        let heartbeat = HOOK_HEARTBEAT.load(Ordering::Relaxed);
+        let raw = RAW_INPUT_BACKEND.load(Ordering::SeqCst);
+        InputDiagnostics {
+            backend: if raw { "rawInput" } else { "mouseHook" }.to_string(),
+            hook_installed: HOOK_HANDLE_GLOBAL.load(Ordering::SeqCst) != 0,
+            hook_thread_alive: HOOK_THREAD_ID.load(Ordering::SeqCst) != 0,
+            session_active: IS_SESSION_ACTIVE.load(Ordering::Relaxed),
+            interface_mode: INTERFACE_MODE.load(Ordering::Relaxed),
+            events_seen: EVENTS_SEEN.load(Ordering::Relaxed),
+            suppressed: SUPPRESSED.load(Ordering::Relaxed),
+            transitions_to_idle: ROUTE_TRANSITIONS[ROUTE_IDLE as usize].load(Ordering::Relaxed),
+            transitions_to_native: ROUTE_TRANSITIONS[ROUTE_NATIVE as usize].load(Ordering::Relaxed),
+            transitions_to_web: ROUTE_TRANSITIONS[ROUTE_WEB as usize].load(Ordering::Relaxed),
+            route: match ROUTE.load(Ordering::Relaxed) {
+                ROUTE_NATIVE => "native",
+                ROUTE_WEB => "web",
+                _ => "idle",
             }
-            BOOL(1)
-        }
-        let _ = EnumDisplayMonitors(
-            HDC::default(),
-            None,
-            Some(mon_cb),
-            LPARAM(&mut b as *mut _ as isize),
-        );
-
-        let w = b.right - b.left;
-        let h = b.bottom - b.top;
-        if w <= 0 || h <= 0 {
-            return;
+            .to_string(),
+            dispatch_queue_depth: posted.saturating_sub(handled),
+            dispatch_dropped: DISPATCH_DROPPED.load(Ordering::Relaxed),
+            hook_recoveries: HOOK_RECOVERIES.load(Ordering::Relaxed),
+            last_hook_event_ms: (heartbeat != 0 && !raw).then(|| unsafe {
+                windows::Win32::System::SystemInformation::GetTickCount().wrapping_sub(heartbeat)
+            }),
+            last_errors: LAST_ERRORS
+                .lock()
+                .map(|e| e.iter().cloned().collect())
+                .unwrap_or_default(),
         }
-        log::info!("[display] Display changed: virtual screen {}x{}", w, h);
+    }
+    pub fn invalidate_proc_cache_pub() {
+        unsafe { invalidate_proc_cache() }
+    }
 
-        // Resize WebView HWND and all its children
-        let wv = WEBVIEW_HWND.load(Ordering::Relaxed);
-        if wv != 0 {
-            let wv_h = HWND(wv as *mut _);
-            let _ = SetWindowPos(
-                wv_h,
-                HWND::default(),
-                0,
-                0,
-                w,
-                h,
-                SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
-            );
-            struct S {
-                w: i32,
-                h: i32,
-            }
-            let s = S { w, h };
-            unsafe extern "system" fn resize_child(child: HWND, lp: LPARAM) -> BOOL {
-                if lp.0 != 0 {
-                    let s = &*(lp.0 as *const S);
-                    let _ = SetWindowPos(
-                        child,
-                        HWND::default(),
-                        0,
-                        0,
-                        s.w,
-                        s.h,
-                        SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
-                    );
-                }
-                BOOL(1)
+    pub fn unregister_session_notif() {
+        let dh = DISPATCH_HWND.load(Ordering::SeqCst);
+        if dh != 0 {
+            unsafe {
+                use windows::Win32::System::RemoteDesktop::WTSUnRegisterSessionNotification;
+                let _ = WTSUnRegisterSessionNotification(HWND(dh as *mut _));
             }
-            let _ = EnumChildWindows(wv_h, Some(resize_child), LPARAM(&s as *const _ as isize));
         }
+    }
+
+    /// Pack two i32 screen/client coordinates into a Windows lParam isize.
+    #[inline]
+    fn make_lparam(x: i32, y: i32) -> isize {
+        ((x as i16 as u16 as u32) | ((y as i16 as u16 as u32) << 16)) as isize
+    }
 
-        // Update WebView2 composition controller bounds
-        let ptr = get_comp_controller_ptr();
-        if ptr != 0 {
-            let _ = wry::set_controller_bounds_raw(ptr, w, h);
+    #[inline]
+    unsafe fn post_mouse(kind: i32, vk: i32, data: u32, x: i32, y: i32) {
+        // Encoding packs 3 fields into a single usize via bit shifts.
+        // The <<32 shift requires a 64-bit pointer width; on 32-bit it would silently lose data.
+        const _: () = assert!(
+            std::mem::size_of::<usize>() >= 8,
+            "mouse hook encoding requires 64-bit pointer width"
+        );
+        let dh = DISPATCH_HWND.load(Ordering::Relaxed);
+        if dh == 0 {
+            return;
+        }
+        let wp =
+            WPARAM((kind as u16 as usize) | ((vk as u16 as usize) << 16) | ((data as usize) << 32));
+        let lp = LPARAM(make_lparam(x, y));
+        if PostMessageW(HWND(dh as *mut _), WM_MWP_MOUSE, wp, lp).is_ok() {
+            DISPATCH_POSTED.fetch_add(1, Ordering::Relaxed);
+        } else if DISPATCH_DROPPED.fetch_add(1, Ordering::Relaxed) == 0 {
+            // Queue full or window gone; report once, the counter tracks the rest
+            record_error("[hook] Dispatch queue rejected a mouse message".to_string());
         }
     }
 
+    const WM_WTSSESSION_CHANGE: u32 = 0x02B1;
+    const WTS_CONSOLE_CONNECT: u32 = 0x1;
+    const WTS_REMOTE_CONNECT: u32 = 0x3;
+    const WTS_SESSION_LOGON: u32 = 0x5;
+    const WTS_SESSION_LOGOFF: u32 = 0x6;
+    const WTS_SESSION_LOCK: u32 = 0x7;
+    const WTS_SESSION_UNLOCK: u32 = 0x8;
+    const WM_DISPLAYCHANGE: u32 = 0x007E;
+    const WM_SETTINGCHANGE: u32 = 0x001A;
+
+    /// Reload double-click / drag thresholds from system settings.
+    /// Called when WM_SETTINGCHANGE fires (user changed mouse prefs in Control Panel).
+    unsafe fn refresh_mouse_metrics() {
+        use windows::Win32::UI::Input::KeyboardAndMouse::GetDoubleClickTime;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetSystemMetrics, SM_CXDOUBLECLK, SM_CXDRAG, SM_CYDOUBLECLK, SM_CYDRAG,
+        };
+        DBLCLICK_TIME.store(GetDoubleClickTime(), Ordering::Relaxed);
+        DBLCLICK_CX.store(GetSystemMetrics(SM_CXDOUBLECLK) / 2, Ordering::Relaxed);
+        DBLCLICK_CY.store(GetSystemMetrics(SM_CYDOUBLECLK) / 2, Ordering::Relaxed);
+        DRAG_THRESHOLD_CX.store(GetSystemMetrics(SM_CXDRAG), Ordering::Relaxed);
+        DRAG_THRESHOLD_CY.store(GetSystemMetrics(SM_CYDRAG), Ordering::Relaxed);
+        log::info!("[settings] Mouse metrics refreshed");
+    }
+
+    /// Resize the WebView and update WebView2 controller bounds after a display change.
+    /// Called when WM_DISPLAYCHANGE fires (monitor plug/unplug or resolution change).
+    unsafe fn on_display_change() {
+        log::info!("[display] Display changed");
+        crate::window_layer::fit_layer();
+    }
+
     unsafe extern "system" fn dispatch_wnd_proc(
         hwnd: HWND,
         msg: u32,
@@ -917,7 +2332,15 @@ pub mod mouse_hook {
             }
             return LRESULT(0);
         }
+        if msg == WM_MWP_SET_WEBVIEW_VISIBLE {
+            let ptr = get_comp_controller_ptr();
+            if ptr != 0 {
+                let _ = wry::set_controller_visible_raw(ptr, wp.0 != 0);
+            }
+            return LRESULT(0);
+        }
         if msg == WM_MWP_MOUSE {
+            DISPATCH_HANDLED.fetch_add(1, Ordering::Relaxed);
             let ptr = get_comp_controller_ptr();
             if ptr != 0 {
                 let kind = (wp.0 & 0xFFFF) as i32;
@@ -934,18 +2357,61 @@ pub mod mouse_hook {
             }
             return LRESULT(0);
         }
-        // WTS session lock/unlock notifications
+        if msg == WM_MWP_DESKTOP_DBLCLICK {
+            crate::desktop_actions::on_desktop_double_click();
+            return LRESULT(0);
+        }
+        if msg == WM_MWP_DESKTOP_GESTURE {
+            if let Some(g) = crate::desktop_actions::DesktopGesture::from_code(wp.0) {
+                crate::desktop_actions::on_desktop_gesture(g);
+            }
+            return LRESULT(0);
+        }
+        if msg == WM_MWP_SET_INPUT_BACKEND {
+            register_raw_mouse(hwnd, wp.0 != 0);
+            return LRESULT(0);
+        }
+        if msg == WM_INPUT {
+            handle_raw_input(lp);
+            // DefWindowProc performs the required raw input cleanup
+            return DefWindowProcW(hwnd, msg, wp, lp);
+        }
+        if msg == WM_MWP_DESKTOP_CONTEXTMENU {
+            let x = (lp.0 & 0xFFFF) as i16 as i32;
+            let y = ((lp.0 >> 16) & 0xFFFF) as i16 as i32;
+            crate::context_menu::show_at(x, y);
+            return LRESULT(0);
+        }
+        // WTS session notifications; disconnects are always followed by a connect
         if msg == WM_WTSSESSION_CHANGE {
-            match wp.0 as u32 {
+            use crate::window_layer::SessionState;
+            let state = match wp.0 as u32 {
                 WTS_SESSION_LOCK => {
                     crate::window_layer::IS_SESSION_ACTIVE.store(false, Ordering::SeqCst);
                     log::info!("[session] Screen locked, hook paused");
+                    Some(SessionState::Locked)
                 }
                 WTS_SESSION_UNLOCK => {
                     crate::window_layer::IS_SESSION_ACTIVE.store(true, Ordering::SeqCst);
                     log::info!("[session] Screen unlocked, hook resumed");
+                    Some(SessionState::Unlocked)
+                }
+                WTS_REMOTE_CONNECT => {
+                    crate::window_layer::update_remote_pause();
+                    Some(SessionState::Remote)
+                }
+                WTS_CONSOLE_CONNECT => {
+                    crate::window_layer::update_remote_pause();
+                    Some(SessionState::Console)
                 }
-                _ => {}
+                WTS_SESSION_LOGON => Some(SessionState::Logon),
+                WTS_SESSION_LOGOFF => Some(SessionState::Logoff),
+                _ => None,
+            };
+            if let (Some(state), Some(app)) = (state, crate::window_layer::APP_HANDLE.get()) {
+                use crate::events::{AppEvent, EmitAppEvent};
+                log::info!("[session] {:?}", state);
+                let _ = app.emit_app_event(&AppEvent::SessionState { state });
             }
             return LRESULT(0);
         }
@@ -989,6 +2455,9 @@ pub mod mouse_hook {
                 None,
             ) {
                 DISPATCH_HWND.store(h.0 as isize, Ordering::SeqCst);
+                if RAW_INPUT_BACKEND.load(Ordering::SeqCst) {
+                    register_raw_mouse(h, true);
+                }
 
                 // Register for session lock/unlock notifications
                 use windows::Win32::System::RemoteDesktop::WTSRegisterSessionNotification;
@@ -1032,6 +2501,26 @@ pub mod mouse_hook {
         None
     }
 
+    /// Whether a screen point falls in a passthrough region. Never blocks: a
+    /// region update in progress counts as no match.
+    #[inline]
+    unsafe fn in_passthrough_region(pt: windows::Win32::Foundation::POINT) -> bool {
+        let Ok(regions) = crate::window_layer::PASSTHROUGH_REGIONS.try_read() else {
+            return false;
+        };
+        if regions.is_empty() {
+            return false;
+        }
+        let mut cp = pt;
+        let wv = HWND(WEBVIEW_HWND.load(Ordering::Relaxed) as *mut _);
+        if !windows::Win32::Graphics::Gdi::ScreenToClient(wv, &mut cp).as_bool() {
+            return false;
+        }
+        regions
+            .iter()
+            .any(|r| cp.x >= r.x && cp.x < r.x + r.width && cp.y >= r.y && cp.y < r.y + r.height)
+    }
+
     #[inline]
     unsafe fn is_over_desktop(hwnd_under: HWND) -> bool {
         let tp = HWND(TARGET_PARENT_HWND.load(Ordering::Relaxed) as *mut _);
@@ -1404,6 +2893,296 @@ pub mod mouse_hook {
         }
     }
 
+    /// Detect a double-click on empty desktop space using the system double-click
+    /// time/rect, and hand it to the dispatch window (never act inside the hook).
+    #[inline]
+    unsafe fn track_empty_desktop_click(info_hook: &MSLLHOOKSTRUCT) {
+        if !DESKTOP_DBLCLICK_ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let now = info_hook.time;
+        let dt = now.saturating_sub(EMPTY_CLICK_TIME.load(Ordering::Relaxed));
+        let dx = (info_hook.pt.x - EMPTY_CLICK_X.load(Ordering::Relaxed)).abs();
+        let dy = (info_hook.pt.y - EMPTY_CLICK_Y.load(Ordering::Relaxed)).abs();
+
+        if dt > 0
+            && dt <= DBLCLICK_TIME.load(Ordering::Relaxed)
+            && dx <= DBLCLICK_CX.load(Ordering::Relaxed)
+            && dy <= DBLCLICK_CY.load(Ordering::Relaxed)
+        {
+            // Reset so a triple-click does not fire twice
+            EMPTY_CLICK_TIME.store(0, Ordering::Relaxed);
+            let dh = DISPATCH_HWND.load(Ordering::Relaxed);
+            if dh != 0 {
+                let _ = PostMessageW(
+                    HWND(dh as *mut _),
+                    WM_MWP_DESKTOP_DBLCLICK,
+                    WPARAM(0),
+                    LPARAM(0),
+                );
+            }
+        } else {
+            EMPTY_CLICK_TIME.store(now, Ordering::Relaxed);
+            EMPTY_CLICK_X.store(info_hook.pt.x, Ordering::Relaxed);
+            EMPTY_CLICK_Y.store(info_hook.pt.y, Ordering::Relaxed);
+        }
+    }
+
+    unsafe fn post_gesture(gesture: crate::desktop_actions::DesktopGesture) {
+        let dh = DISPATCH_HWND.load(Ordering::Relaxed);
+        if dh != 0 {
+            let _ = PostMessageW(
+                HWND(dh as *mut _),
+                WM_MWP_DESKTOP_GESTURE,
+                WPARAM(gesture as usize),
+                LPARAM(0),
+            );
+        }
+    }
+
+    /// Middle-button swipe on empty desktop: start on button-down, classify on
+    /// button-up by dominant direction. The events themselves still reach the webview.
+    unsafe fn track_middle_swipe(msg: u32, info_hook: &MSLLHOOKSTRUCT, slv_raw: isize) {
+        use crate::desktop_actions::DesktopGesture;
+        if msg == WM_MBUTTONDOWN {
+            let on_icon = slv_raw != 0
+                && IsWindowVisible(HWND(slv_raw as *mut _)).as_bool()
+                && get_hit_item_index(HWND(slv_raw as *mut _), &info_hook.pt) >= 0;
+            MIDDLE_SWIPE.store(!on_icon, Ordering::Relaxed);
+            SWIPE_START_X.store(info_hook.pt.x, Ordering::Relaxed);
+            SWIPE_START_Y.store(info_hook.pt.y, Ordering::Relaxed);
+            return;
+        }
+        if msg != WM_MBUTTONUP || !MIDDLE_SWIPE.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let dx = info_hook.pt.x - SWIPE_START_X.load(Ordering::Relaxed);
+        let dy = info_hook.pt.y - SWIPE_START_Y.load(Ordering::Relaxed);
+        if dx.abs().max(dy.abs()) < SWIPE_MIN_DISTANCE {
+            return;
+        }
+        // Diagonal drags are ambiguous: require one axis to dominate 2:1
+        let gesture = if dx.abs() >= dy.abs() * 2 {
+            if dx < 0 {
+                DesktopGesture::MiddleSwipeLeft
+            } else {
+                DesktopGesture::MiddleSwipeRight
+            }
+        } else if dy.abs() >= dx.abs() * 2 {
+            if dy < 0 {
+                DesktopGesture::MiddleSwipeUp
+            } else {
+                DesktopGesture::MiddleSwipeDown
+            }
+        } else {
+            return;
+        };
+        post_gesture(gesture);
+    }
+
+    /// Left+right chord on empty desktop. Returns true when `msg` completes a chord;
+    /// the caller swallows it, and both button-ups are swallowed later so neither
+    /// a click nor a context menu follows.
+    unsafe fn track_button_chord(msg: u32) -> bool {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{
+            GetAsyncKeyState, VK_LBUTTON, VK_RBUTTON,
+        };
+        if !GESTURES_ENABLED.load(Ordering::Relaxed) {
+            return false;
+        }
+        let other = match msg {
+            WM_LBUTTONDOWN => VK_RBUTTON,
+            WM_RBUTTONDOWN => VK_LBUTTON,
+            _ => return false,
+        };
+        if GetAsyncKeyState(other.0 as i32) as u16 & 0x8000 == 0 {
+            return false;
+        }
+        RCLICK_EMPTY.store(false, Ordering::Relaxed);
+        CHORD_PENDING_UPS.store(CHORD_UP_LEFT | CHORD_UP_RIGHT, Ordering::Relaxed);
+        post_gesture(crate::desktop_actions::DesktopGesture::LeftRightChord);
+        true
+    }
+
+    /// True when `msg` is a button-up left over from a recognized chord.
+    fn take_chord_up(msg: u32) -> bool {
+        let bit = match msg {
+            WM_LBUTTONUP => CHORD_UP_LEFT,
+            WM_RBUTTONUP => CHORD_UP_RIGHT,
+            _ => return false,
+        };
+        CHORD_PENDING_UPS.fetch_and(!bit, Ordering::Relaxed) & bit != 0
+    }
+
+    /// Interface mode: post a mouse event straight to Chrome_RWHH.
+    unsafe fn post_to_rwhh(msg: u32, info_hook: &MSLLHOOKSTRUCT) {
+        use windows::Win32::Graphics::Gdi::ScreenToClient;
+        let rwhh = CHROME_RWHH.load(Ordering::Relaxed);
+        if rwhh == 0 {
+            return;
+        }
+        let rwhh_hwnd = HWND(rwhh as *mut _);
+        match msg {
+            WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
+                let lp = make_lparam(info_hook.pt.x, info_hook.pt.y);
+                let delta = (info_hook.mouseData >> 16) as i16 as u16;
+                let wp = (delta as usize) << 16;
+                let _ = PostMessageW(rwhh_hwnd, msg, WPARAM(wp), LPARAM(lp));
+            }
+            _ => {
+                let mut cp = info_hook.pt;
+                let _ = ScreenToClient(rwhh_hwnd, &mut cp);
+                let lp = make_lparam(cp.x, cp.y);
+                // Include real modifier / button state so Shift+click,
+                // Ctrl+click and drag-move work correctly in the WebView.
+                use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
+                let mut mk: usize = 0;
+                if msg == WM_LBUTTONDOWN || GetAsyncKeyState(0x01) < 0 {
+                    mk |= 0x0001; // MK_LBUTTON
+                }
+                if msg == WM_RBUTTONDOWN || GetAsyncKeyState(0x02) < 0 {
+                    mk |= 0x0002; // MK_RBUTTON
+                }
+                if msg == WM_MBUTTONDOWN || GetAsyncKeyState(0x04) < 0 {
+                    mk |= 0x0010; // MK_MBUTTON
+                }
+                if GetAsyncKeyState(0x10) < 0 {
+                    mk |= 0x0004; // MK_SHIFT
+                }
+                if GetAsyncKeyState(0x11) < 0 {
+                    mk |= 0x0008; // MK_CONTROL
+                }
+                let _ = PostMessageW(rwhh_hwnd, msg, WPARAM(mk), LPARAM(lp));
+            }
+        }
+    }
+
+    // Raw Input button flags (RAWMOUSE.usButtonFlags)
+    const RI_MOUSE_LEFT_BUTTON_DOWN: u16 = 0x0001;
+    const RI_MOUSE_LEFT_BUTTON_UP: u16 = 0x0002;
+    const RI_MOUSE_RIGHT_BUTTON_DOWN: u16 = 0x0004;
+    const RI_MOUSE_RIGHT_BUTTON_UP: u16 = 0x0008;
+    const RI_MOUSE_MIDDLE_BUTTON_DOWN: u16 = 0x0010;
+    const RI_MOUSE_MIDDLE_BUTTON_UP: u16 = 0x0020;
+    const RI_MOUSE_WHEEL: u16 = 0x0400;
+    const RI_MOUSE_HWHEEL: u16 = 0x0800;
+
+    /// Register (or remove) mouse Raw Input on the dispatch window. Dispatch thread only.
+    unsafe fn register_raw_mouse(hwnd: HWND, enable: bool) {
+        use windows::Win32::UI::Input::{
+            RegisterRawInputDevices, RAWINPUTDEVICE, RIDEV_INPUTSINK, RIDEV_REMOVE,
+        };
+        let device = RAWINPUTDEVICE {
+            usUsagePage: 0x01, // HID_USAGE_PAGE_GENERIC
+            usUsage: 0x02,     // HID_USAGE_GENERIC_MOUSE
+            dwFlags: if enable {
+                RIDEV_INPUTSINK
+            } else {
+                RIDEV_REMOVE
+            },
+            // RIDEV_REMOVE requires a null target
+            hwndTarget: if enable { hwnd } else { HWND::default() },
+        };
+        match RegisterRawInputDevices(&[device], std::mem::size_of::<RAWINPUTDEVICE>() as u32) {
+            Ok(()) => log::info!(
+                "[raw_input] Mouse raw input {}",
+                if enable { "registered" } else { "removed" }
+            ),
+            Err(e) => record_error(format!(
+                "[raw_input] RegisterRawInputDevices failed: {:?}",
+                e
+            )),
+        }
+    }
+
+    /// Raw Input backend: translate WM_INPUT into the same forwarding path as the hook.
+    /// Raw Input cannot swallow events, so icon drag/context-menu emulation is
+    /// hook-only; the shell handles icons natively here.
+    unsafe fn handle_raw_input(lp: LPARAM) {
+        use windows::Win32::Foundation::POINT;
+        use windows::Win32::Graphics::Gdi::ScreenToClient;
+        use windows::Win32::UI::Input::{
+            GetRawInputData, HRAWINPUT, RAWINPUT, RAWINPUTHEADER, RID_INPUT, RIM_TYPEMOUSE,
+        };
+
+        if !RAW_INPUT_BACKEND.load(Ordering::Relaxed)
+            || !crate::window_layer::IS_SESSION_ACTIVE.load(Ordering::Relaxed)
+        {
+            return;
+        }
+        let wv_raw = WEBVIEW_HWND.load(Ordering::Relaxed);
+        if wv_raw == 0 {
+            return;
+        }
+
+        let mut raw = RAWINPUT::default();
+        let mut size = std::mem::size_of::<RAWINPUT>() as u32;
+        let read = GetRawInputData(
+            HRAWINPUT(lp.0 as *mut _),
+            RID_INPUT,
+            Some(&mut raw as *mut _ as *mut _),
+            &mut size,
+            std::mem::size_of::<RAWINPUTHEADER>() as u32,
+        );
+        if read == u32::MAX || raw.header.dwType != RIM_TYPEMOUSE.0 {
+            return;
+        }
+        EVENTS_SEEN.fetch_add(1, Ordering::Relaxed);
+        let buttons = raw.data.mouse.Anonymous.Anonymous;
+        let flags = buttons.usButtonFlags;
+
+        // Raw deltas are unaccelerated; the real cursor position is what the page expects
+        let mut pt = POINT::default();
+        if GetCursorPos(&mut pt).is_err()
+            || !is_over_desktop(WindowFromPoint(pt))
+            || in_passthrough_region(pt)
+        {
+            set_route(ROUTE_IDLE);
+            return;
+        }
+        set_route(ROUTE_WEB);
+
+        let mut events: [u32; 8] = [0; 8];
+        let mut n = 0;
+        let mut push = |m: u32| {
+            events[n] = m;
+            n += 1;
+        };
+        if flags == 0 {
+            push(WM_MOUSEMOVE);
+        }
+        for (bit, m) in [
+            (RI_MOUSE_LEFT_BUTTON_DOWN, WM_LBUTTONDOWN),
+            (RI_MOUSE_LEFT_BUTTON_UP, WM_LBUTTONUP),
+            (RI_MOUSE_RIGHT_BUTTON_DOWN, WM_RBUTTONDOWN),
+            (RI_MOUSE_RIGHT_BUTTON_UP, WM_RBUTTONUP),
+            (RI_MOUSE_MIDDLE_BUTTON_DOWN, WM_MBUTTONDOWN),
+            (RI_MOUSE_MIDDLE_BUTTON_UP, WM_MBUTTONUP),
+            (RI_MOUSE_WHEEL, WM_MOUSEWHEEL),
+            (RI_MOUSE_HWHEEL, WM_MOUSEHWHEEL),
+        ] {
+            if flags & bit != 0 {
+                push(m);
+            }
+        }
+
+        let info = MSLLHOOKSTRUCT {
+            pt,
+            mouseData: (buttons.usButtonData as u32) << 16,
+            ..Default::default()
+        };
+        let interface = crate::window_layer::INTERFACE_MODE.load(Ordering::Relaxed);
+        let mut cp = pt;
+        let _ = ScreenToClient(HWND(wv_raw as *mut _), &mut cp);
+        for &m in &events[..n] {
+            if interface {
+                post_to_rwhh(m, &info);
+            } else {
+                forward(m, &info, cp.x, cp.y);
+            }
+        }
+    }
+
     #[inline]
     unsafe fn forward(msg: u32, info_hook: &MSLLHOOKSTRUCT, cx: i32, cy: i32) {
         match msg {
@@ -1565,6 +3344,16 @@ pub mod mouse_hook {
                 );
                 let wv_raw = WEBVIEW_HWND.load(Ordering::Relaxed);
 
+                if code >= 0 {
+                    let pt = (*(lparam.0 as *const MSLLHOOKSTRUCT)).pt;
+                    HOOK_HEARTBEAT.store(
+                        windows::Win32::System::SystemInformation::GetTickCount(),
+                        Ordering::Relaxed,
+                    );
+                    HOOK_LAST_X.store(pt.x, Ordering::Relaxed);
+                    HOOK_LAST_Y.store(pt.y, Ordering::Relaxed);
+                }
+
                 if code < 0 || wv_raw == 0 {
                     return CallNextHookEx(hook_h, code, wparam, lparam);
                 }
@@ -1573,6 +3362,8 @@ pub mod mouse_hook {
                     return CallNextHookEx(hook_h, code, wparam, lparam);
                 }
 
+                EVENTS_SEEN.fetch_add(1, Ordering::Relaxed);
+                let _span = crate::trace::span("hook", "mouse_dispatch");
                 let info_hook = *(lparam.0 as *const MSLLHOOKSTRUCT);
                 let hwnd_under = WindowFromPoint(info_hook.pt);
                 let msg = wparam.0 as u32;
@@ -1584,6 +3375,7 @@ pub mod mouse_hook {
                 // and sees Chrome_RWHH. Instead: simulate a quick left-click to
                 // natively select the item, then send WM_CONTEXTMENU.
                 if RCLICK_ON_ICON.load(Ordering::Relaxed) {
+                    set_route(ROUTE_NATIVE);
                     if msg == WM_RBUTTONUP {
                         RCLICK_ON_ICON.store(false, Ordering::Relaxed);
                         if slv_raw != 0 {
@@ -1608,18 +3400,45 @@ pub mod mouse_hook {
                                 LPARAM(make_lparam(info_hook.pt.x, info_hook.pt.y)),
                             );
                         }
-                        return LRESULT(1);
+                        return suppress();
                     } else if msg == WM_MOUSEMOVE {
-                        return LRESULT(1);
+                        return suppress();
                     } else {
                         RCLICK_ON_ICON.store(false, Ordering::Relaxed);
                     }
                 }
 
+                // ── Button-ups of a desktop chord gesture: keep the webview's
+                // button state consistent, but hide them from Explorer ──
+                if take_chord_up(msg) {
+                    let mut cp = info_hook.pt;
+                    let _ = ScreenToClient(HWND(wv_raw as *mut _), &mut cp);
+                    forward(msg, &info_hook, cp.x, cp.y);
+                    return suppress();
+                }
+
+                // ── Right-click on empty desktop: custom menu on button-up ──
+                if RCLICK_EMPTY.load(Ordering::Relaxed) && msg != WM_MOUSEMOVE {
+                    RCLICK_EMPTY.store(false, Ordering::Relaxed);
+                    if msg == WM_RBUTTONUP {
+                        let dh = DISPATCH_HWND.load(Ordering::Relaxed);
+                        if dh != 0 {
+                            let _ = PostMessageW(
+                                HWND(dh as *mut _),
+                                WM_MWP_DESKTOP_CONTEXTMENU,
+                                WPARAM(0),
+                                LPARAM(make_lparam(info_hook.pt.x, info_hook.pt.y)),
+                            );
+                        }
+                        return suppress();
+                    }
+                }
+
                 // ── Left-click drag (icon repositioning with ghost image) ──
                 // Ghost follows cursor via ImageList drag APIs. Only a single
                 // LVM_SETITEMPOSITION fires at drop time (no grid-jumping).
                 if NATIVE_DRAG.load(Ordering::Relaxed) {
+                    set_route(ROUTE_NATIVE);
                     if msg == WM_LBUTTONUP {
                         NATIVE_DRAG.store(false, Ordering::Relaxed);
                         let was_dragging = DRAG_PAST_THRESHOLD.swap(false, Ordering::Relaxed);
@@ -1690,59 +3509,51 @@ pub mod mouse_hook {
                     return CallNextHookEx(hook_h, code, wparam, lparam);
                 }
 
-                // ── Not over desktop: pass through ──
-                if !is_over_desktop(hwnd_under) {
+                // ── Not over desktop, or in a passthrough region: pass through ──
+                if !is_over_desktop(hwnd_under) || in_passthrough_region(info_hook.pt) {
+                    set_route(ROUTE_IDLE);
                     return CallNextHookEx(hook_h, code, wparam, lparam);
                 }
 
                 // ── Interface mode: PostMessage direct à Chrome_RWHH ──
                 if crate::window_layer::INTERFACE_MODE.load(Ordering::Relaxed) {
-                    let rwhh = CHROME_RWHH.load(Ordering::Relaxed);
-                    if rwhh != 0 {
-                        let rwhh_hwnd = HWND(rwhh as *mut _);
-                        match msg {
-                            WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
-                                let lp = make_lparam(info_hook.pt.x, info_hook.pt.y);
-                                let delta = (info_hook.mouseData >> 16) as i16 as u16;
-                                let wp = (delta as usize) << 16;
-                                let _ = PostMessageW(rwhh_hwnd, msg, WPARAM(wp), LPARAM(lp));
-                            }
-                            _ => {
-                                let mut cp = info_hook.pt;
-                                let _ = ScreenToClient(rwhh_hwnd, &mut cp);
-                                let lp = make_lparam(cp.x, cp.y);
-                                // Include real modifier / button state so Shift+click,
-                                // Ctrl+click and drag-move work correctly in the WebView.
-                                use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
-                                let mut mk: usize = 0;
-                                if msg == WM_LBUTTONDOWN || GetAsyncKeyState(0x01) < 0 {
-                                    mk |= 0x0001; // MK_LBUTTON
-                                }
-                                if msg == WM_RBUTTONDOWN || GetAsyncKeyState(0x02) < 0 {
-                                    mk |= 0x0002; // MK_RBUTTON
-                                }
-                                if msg == WM_MBUTTONDOWN || GetAsyncKeyState(0x04) < 0 {
-                                    mk |= 0x0010; // MK_MBUTTON
-                                }
-                                if GetAsyncKeyState(0x10) < 0 {
-                                    mk |= 0x0004; // MK_SHIFT
-                                }
-                                if GetAsyncKeyState(0x11) < 0 {
-                                    mk |= 0x0008; // MK_CONTROL
-                                }
-                                let _ = PostMessageW(rwhh_hwnd, msg, WPARAM(mk), LPARAM(lp));
-                            }
-                        }
-                    }
+                    set_route(ROUTE_WEB);
+                    post_to_rwhh(msg, &info_hook);
                     return CallNextHookEx(hook_h, code, wparam, lparam);
                 }
 
+                if (msg == WM_MBUTTONDOWN || msg == WM_MBUTTONUP)
+                    && GESTURES_ENABLED.load(Ordering::Relaxed)
+                {
+                    track_middle_swipe(msg, &info_hook, slv_raw);
+                }
+
                 // ── Wallpaper mode: button-down on icon ──
                 // Single get_hit_item_index call (avoids duplicate cross-process op)
                 if (msg == WM_LBUTTONDOWN || msg == WM_RBUTTONDOWN) && slv_raw != 0 {
                     let slv_h = HWND(slv_raw as *mut _);
-                    let item_idx = get_hit_item_index(slv_h, &info_hook.pt);
+                    // Hidden icons cannot be hit (LVM_HITTEST still reports them)
+                    let item_idx = if IsWindowVisible(slv_h).as_bool() {
+                        get_hit_item_index(slv_h, &info_hook.pt)
+                    } else {
+                        -1
+                    };
+                    if item_idx < 0 && track_button_chord(msg) {
+                        return suppress();
+                    }
+                    if item_idx < 0 && msg == WM_LBUTTONDOWN {
+                        track_empty_desktop_click(&info_hook);
+                    }
+                    if item_idx < 0
+                        && msg == WM_RBUTTONDOWN
+                        && CUSTOM_CONTEXT_MENU_ENABLED.load(Ordering::Relaxed)
+                    {
+                        // Eat the native menu; ours opens on button-up
+                        RCLICK_EMPTY.store(true, Ordering::Relaxed);
+                        return suppress();
+                    }
                     if item_idx >= 0 {
+                        set_route(ROUTE_NATIVE);
                         if msg == WM_LBUTTONDOWN {
                             // Left-click: initiate drag tracking
                             NATIVE_DRAG.store(true, Ordering::Relaxed);
@@ -1779,7 +3590,7 @@ pub mod mouse_hook {
                             );
                             // Eat WM_RBUTTONDOWN — prevents native desktop menu.
                             // Selection + WM_CONTEXTMENU handled on button-up.
-                            return LRESULT(1);
+                            return suppress();
                         }
                     }
                 }
@@ -1807,6 +3618,7 @@ pub mod mouse_hook {
 
                 let mut cp = info_hook.pt;
                 let _ = ScreenToClient(HWND(wv_raw as *mut _), &mut cp);
+                set_route(ROUTE_WEB);
                 forward(msg, &info_hook, cp.x, cp.y);
 
                 CallNextHookEx(hook_h, code, wparam, lparam)
@@ -1878,21 +3690,201 @@ pub mod mouse_hook {
                 CallNextHookEx(hook_h, code, wparam, lparam)
             }
 
-            unsafe {
-                if let Ok(h) = SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), None, 0) {
-                    crate::window_layer::HOOK_HANDLE_GLOBAL.store(h.0 as isize, Ordering::SeqCst);
+            /// Install or remove the mouse hook. Must run on this thread (owns the hook).
+            unsafe fn set_mouse_hook(install: bool) {
+                let global = &crate::window_layer::HOOK_HANDLE_GLOBAL;
+                if install {
+                    if global.load(Ordering::SeqCst) != 0 {
+                        return;
+                    }
+                    match SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), None, 0) {
+                        Ok(h) => {
+                            global.store(h.0 as isize, Ordering::SeqCst);
+                            reset_hook_heartbeat();
+                        }
+                        Err(e) => record_error(format!(
+                            "[hook] SetWindowsHookEx(WH_MOUSE_LL) failed: {:?}",
+                            e
+                        )),
+                    }
+                } else {
+                    let h = global.swap(0, Ordering::SeqCst);
+                    if h != 0 {
+                        let _ = UnhookWindowsHookEx(HHOOK(h as *mut _));
+                    }
+                }
+            }
+
+            // Hooks die with their thread: clear the globals on any exit (incl. panic)
+            // so the watchdog sees the thread as gone and a new one can install
+            struct HookThreadGuard;
+            impl Drop for HookThreadGuard {
+                fn drop(&mut self) {
+                    HOOK_THREAD_ID.store(0, Ordering::SeqCst);
+                    crate::window_layer::HOOK_HANDLE_GLOBAL.store(0, Ordering::SeqCst);
+                    crate::window_layer::KB_HOOK_HANDLE_GLOBAL.store(0, Ordering::SeqCst);
                 }
+            }
+
+            unsafe {
+                // Create the message queue before publishing the thread id
+                let mut msg = MSG::default();
+                let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+                let _guard = HookThreadGuard;
+                HOOK_THREAD_ID.store(
+                    windows::Win32::System::Threading::GetCurrentThreadId(),
+                    Ordering::SeqCst,
+                );
+                HOOK_PONG.store(
+                    windows::Win32::System::SystemInformation::GetTickCount(),
+                    Ordering::SeqCst,
+                );
+
+                set_mouse_hook(!RAW_INPUT_BACKEND.load(Ordering::SeqCst));
                 if let Ok(h) = SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook_proc), None, 0)
                 {
                     crate::window_layer::KB_HOOK_HANDLE_GLOBAL
                         .store(h.0 as isize, Ordering::SeqCst);
                 }
-                let mut msg = MSG::default();
                 while GetMessageW(&mut msg, HWND::default(), 0, 0).into() {
+                    if msg.hwnd.is_invalid() && msg.message == WM_MWP_SET_MOUSE_HOOK {
+                        set_mouse_hook(msg.wParam.0 != 0);
+                        continue;
+                    }
+                    if msg.hwnd.is_invalid() && msg.message == WM_MWP_HOOK_PING {
+                        HOOK_PONG.store(
+                            windows::Win32::System::SystemInformation::GetTickCount(),
+                            Ordering::SeqCst,
+                        );
+                        continue;
+                    }
                     let _ = TranslateMessage(&msg);
                     DispatchMessageW(&msg);
                 }
             }
         });
     }
+
+    /// Drop and re-install the WH_MOUSE_LL hook on the hook thread.
+    pub fn reinstall_mouse_hook() {
+        let tid = HOOK_THREAD_ID.load(Ordering::SeqCst);
+        if tid == 0 || RAW_INPUT_BACKEND.load(Ordering::SeqCst) {
+            return;
+        }
+        unsafe {
+            let _ = PostThreadMessageW(tid, WM_MWP_SET_MOUSE_HOOK, WPARAM(0), LPARAM(0));
+            let _ = PostThreadMessageW(tid, WM_MWP_SET_MOUSE_HOOK, WPARAM(1), LPARAM(0));
+            reset_hook_heartbeat();
+        }
+    }
+
+    /// Start counting hook silence from now, at the current cursor position.
+    unsafe fn reset_hook_heartbeat() {
+        let mut pt = windows::Win32::Foundation::POINT::default();
+        let _ = GetCursorPos(&mut pt);
+        HOOK_LAST_X.store(pt.x, Ordering::Relaxed);
+        HOOK_LAST_Y.store(pt.y, Ordering::Relaxed);
+        HOOK_HEARTBEAT.store(
+            windows::Win32::System::SystemInformation::GetTickCount(),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// True when the system saw input after the hook's last call and the cursor
+    /// has moved since — i.e. mouse events are no longer reaching hook_proc.
+    /// Windows removes WH_MOUSE_LL hooks silently after repeated timeouts.
+    unsafe fn mouse_hook_silent() -> bool {
+        use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+        let mut lii = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !GetLastInputInfo(&mut lii).as_bool() {
+            return false;
+        }
+        let heartbeat = HOOK_HEARTBEAT.load(Ordering::Relaxed);
+        if (lii.dwTime.wrapping_sub(heartbeat) as i32) < HOOK_SILENCE_MS as i32 {
+            return false;
+        }
+        // Keyboard-only input also bumps the last-input time: require cursor movement
+        let mut pt = windows::Win32::Foundation::POINT::default();
+        GetCursorPos(&mut pt).is_ok()
+            && (pt.x != HOOK_LAST_X.load(Ordering::Relaxed)
+                || pt.y != HOOK_LAST_Y.load(Ordering::Relaxed))
+    }
+
+    /// Watchdog for the hook thread and the WH_MOUSE_LL hook. Restarts a dead
+    /// thread, re-installs a silently removed hook, and emits `hook-recovered`.
+    pub fn start_hook_watchdog(app: tauri::AppHandle) {
+        use crate::events::{AppEvent, EmitAppEvent};
+        use windows::Win32::System::SystemInformation::GetTickCount;
+
+        std::thread::spawn(move || {
+            let recovered = |reason: &str| {
+                log::warn!("[hook-watchdog] Recovered: {}", reason);
+                HOOK_RECOVERIES.fetch_add(1, Ordering::Relaxed);
+                crate::crash::breadcrumb("input", reason);
+                let _ = app.emit_app_event(&AppEvent::HookRecovered {
+                    reason: reason.to_string(),
+                });
+            };
+            let interval_ms = HOOK_WATCHDOG_INTERVAL.as_millis() as u32;
+            let mut stall_reported = false;
+
+            loop {
+                std::thread::sleep(HOOK_WATCHDOG_INTERVAL);
+                if crate::window_layer::ICONS_RESTORED.load(Ordering::SeqCst) {
+                    // Shutting down: hooks were removed on purpose
+                    return;
+                }
+                unsafe {
+                    let tid = HOOK_THREAD_ID.load(Ordering::SeqCst);
+                    if tid == 0
+                        || PostThreadMessageW(tid, WM_MWP_HOOK_PING, WPARAM(0), LPARAM(0)).is_err()
+                    {
+                        HOOK_THREAD_ID.store(0, Ordering::SeqCst);
+                        start_hook_thread();
+                        recovered("hook thread restarted");
+                        continue;
+                    }
+
+                    // The thread can't be replaced while it's alive; a stuck loop
+                    // also means Windows will time out (and drop) its hooks
+                    let pong_age = GetTickCount().wrapping_sub(HOOK_PONG.load(Ordering::SeqCst));
+                    if pong_age > interval_ms * 3 {
+                        if !stall_reported {
+                            record_error(format!(
+                                "[hook-watchdog] Hook thread unresponsive for {} ms",
+                                pong_age
+                            ));
+                            stall_reported = true;
+                        }
+                        continue;
+                    }
+                    stall_reported = false;
+
+                    if RAW_INPUT_BACKEND.load(Ordering::SeqCst)
+                        || !crate::window_layer::IS_SESSION_ACTIVE.load(Ordering::Relaxed)
+                    {
+                        continue;
+                    }
+                    let installed =
+                        crate::window_layer::HOOK_HANDLE_GLOBAL.load(Ordering::SeqCst) != 0;
+                    if !installed || mouse_hook_silent() {
+                        // Drop the stale handle, then install a fresh hook (in order on the hook thread)
+                        let _ =
+                            PostThreadMessageW(tid, WM_MWP_SET_MOUSE_HOOK, WPARAM(0), LPARAM(0));
+                        let _ =
+                            PostThreadMessageW(tid, WM_MWP_SET_MOUSE_HOOK, WPARAM(1), LPARAM(0));
+                        reset_hook_heartbeat();
+                        recovered(if installed {
+                            "mouse hook re-installed (removed by the system)"
+                        } else {
+                            "mouse hook re-installed (missing)"
+                        });
+                    }
+                }
+            }
+        });
+    }
 }